@@ -8,8 +8,9 @@ use crossbeam::channel::{bounded, select};
 use lsp_server::{Connection, Message, Notification, Request, Response};
 use lsp_types::{
     notification::Notification as _, request::Request as _, CompletionOptions, Diagnostic,
-    HoverProviderCapability, OneOf, SaveOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
-    TextDocumentSyncOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    HoverProviderCapability, OneOf, SaveOptions, SemanticTokensFullOptions, SemanticTokensOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TypeDefinitionProviderCapability, WorkDoneProgressOptions,
 };
 use std::{
     collections::BTreeMap,
@@ -21,7 +22,7 @@ use std::{
 use move_analyzer::{
     completion::on_completion_request,
     context::Context,
-    symbols,
+    formatting, inlay_hints, semantic_tokens, symbols,
     vfs::{on_text_document_sync_notification, VirtualFileSystem},
 };
 use move_symbol_pool::Symbol;
@@ -61,7 +62,7 @@ fn main() {
         .initialize_start()
         .expect("could not start connection initialization");
 
-    let capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
+    let mut capabilities = serde_json::to_value(lsp_types::ServerCapabilities {
         // The server receives notifications from the client as users open, close,
         // and modify documents.
         text_document_sync: Some(TextDocumentSyncCapability::Options(
@@ -107,9 +108,28 @@ fn main() {
         )),
         references_provider: Some(OneOf::Left(symbols::DEFS_AND_REFS_SUPPORT)),
         document_symbol_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: if semantic_tokens::SEMANTIC_TOKENS_SUPPORT {
+            Some(
+                SemanticTokensOptions {
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                    legend: semantic_tokens::legend(),
+                    range: None,
+                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                }
+                .into(),
+            )
+        } else {
+            None
+        },
         ..Default::default()
     })
     .expect("could not serialize server capabilities");
+    // The inlay hint capability is not (yet) a part of the server capabilities supported by the
+    // `lsp-types` crate, so it has to be added manually.
+    capabilities["inlayHintProvider"] = serde_json::json!(inlay_hints::INLAY_HINTS_SUPPORT);
 
     let (diag_sender, diag_receiver) = bounded::<Result<BTreeMap<Symbol, Vec<Diagnostic>>>>(0);
     let mut symbolicator_runner = symbols::SymbolicatorRunner::idle();
@@ -241,6 +261,19 @@ fn on_request(context: &Context, request: &Request) {
         lsp_types::request::DocumentSymbolRequest::METHOD => {
             symbols::on_document_symbol_request(context, request, &context.symbols.lock().unwrap());
         }
+        lsp_types::request::SemanticTokensFullRequest::METHOD => {
+            semantic_tokens::on_semantic_tokens_request(
+                context,
+                request,
+                &context.symbols.lock().unwrap(),
+            );
+        }
+        lsp_types::request::Formatting::METHOD => {
+            formatting::on_formatting_request(context, request);
+        }
+        inlay_hints::INLAY_HINT_REQUEST => {
+            inlay_hints::on_inlay_hint_request(context, request, &context.symbols.lock().unwrap());
+        }
         _ => eprintln!("handle request '{}' from client", request.method),
     }
 }
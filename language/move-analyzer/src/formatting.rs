@@ -0,0 +1,101 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module is responsible for handling document formatting requests using the source formatter
+//! of the compiler. The layout is configured in the `[format]` section of the manifest of the
+//! package the document belongs to, the same way as for `move fmt`.
+//!
+//! Formatting does not depend on symbolication information: the current content of the document is
+//! formatted as a whole and replaced by a single edit.
+
+use crate::context::Context;
+use lsp_server::Request;
+use lsp_types::{DocumentFormattingParams, Position, Range, TextEdit};
+use std::path::Path;
+
+use move_compiler::formatter::{format_source, FormatOptions};
+use move_package::source_package::{layout::SourcePackageLayout, manifest_parser};
+
+/// Handles document formatting request of the language server.
+pub fn on_formatting_request(context: &Context, request: &Request) {
+    let parameters = serde_json::from_value::<DocumentFormattingParams>(request.params.clone())
+        .expect("could not deserialize formatting request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    eprintln!("on_formatting_request: {:?}", fpath);
+
+    let text = match context.files.get(&fpath) {
+        Some(text) => Some(text.to_string()),
+        None => std::fs::read_to_string(&fpath).ok(),
+    };
+    // a document that cannot be formatted (e.g. because it does not parse) is left unchanged,
+    // parsing errors are reported to the IDE as diagnostics already
+    let edits = text.and_then(|text| format_document(&fpath, &text));
+
+    let response = lsp_server::Response::new_ok(request.id.clone(), edits);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send formatting response: {:?}", err);
+    }
+}
+
+/// Formats `text`, the content of the document at `fpath`, returning the edits to apply to the
+/// document or `None` if it cannot be formatted.
+pub fn format_document(fpath: &Path, text: &str) -> Option<Vec<TextEdit>> {
+    let formatted = format_source(text, &package_format_options(fpath)).ok()?;
+    if formatted == text {
+        return Some(vec![]);
+    }
+    let end = Position {
+        line: text.matches('\n').count() as u32,
+        character: text
+            .rsplit('\n')
+            .next()
+            .map_or(0, |last| last.encode_utf16().count()) as u32,
+    };
+    Some(vec![TextEdit {
+        range: Range {
+            start: Position::new(0, 0),
+            end,
+        },
+        new_text: formatted,
+    }])
+}
+
+/// The formatting options of the package containing the document at `fpath`, or the default ones
+/// if the document is not in a package or the manifest does not specify any.
+fn package_format_options(fpath: &Path) -> FormatOptions {
+    fpath
+        .parent()
+        .and_then(|dir| SourcePackageLayout::try_find_root(dir).ok())
+        .and_then(|root| manifest_parser::parse_move_manifest_from_file(&root).ok())
+        .and_then(|manifest| manifest.format)
+        .unwrap_or_default()
+}
+
+#[test]
+/// Tests if a document is replaced as a whole by its formatted content.
+fn formatting_test() {
+    use std::path::PathBuf;
+
+    let mut fpath = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fpath.push("tests/symbols/sources/Formatted.move");
+
+    let text = "module Symbols::Formatted {\n    fun foo(x: u64): u64 { x+1 }\n}";
+    let edits = format_document(&fpath, text).unwrap();
+    assert!(edits.len() == 1);
+    assert!(edits[0].range.end == Position::new(2, 1));
+    assert!(
+        edits[0].new_text == "module Symbols::Formatted {\n    fun foo(x: u64): u64 { x + 1 }\n}\n"
+    );
+
+    // formatted documents are not changed
+    let edits = format_document(&fpath, &edits[0].new_text).unwrap();
+    assert!(edits.is_empty());
+
+    // documents that do not parse are not formatted
+    assert!(format_document(&fpath, "module Symbols::Formatted {").is_none());
+}
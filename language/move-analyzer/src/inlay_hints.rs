@@ -0,0 +1,407 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module is responsible for computing inlay hints on top of compiler's typed AST, that is
+//! pieces of information that are not written in the source code but that are inferred by the
+//! compiler and can be displayed inline by the IDE:
+//! - types of local variables introduced in `let` bindings without a type annotation
+//! - type arguments of generic function calls where they are not explicitly provided
+//! - names of the parameters at function call sites
+//!
+//! Inlay hints are computed together with the rest of symbolication information (see the
+//! `symbols` module) and are stored with the symbols so that they can be served to the IDE without
+//! re-compiling the package.
+//!
+//! The inlay hint request has been introduced in version 3.17 of the Language Server Protocol which
+//! is not yet supported by the `lsp-types` version used by the language server, so the relevant
+//! request and response data structures are defined in this module following the protocol
+//! specification.
+
+use crate::{
+    context::Context,
+    symbols::{type_list_to_ide_string, type_to_ide_string, Symbols},
+    utils::{get_char_after_loc, get_loc},
+};
+use codespan_reporting::files::SimpleFiles;
+use lsp_server::Request;
+use lsp_types::{Position, Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    expansion::ast::ModuleIdent,
+    naming::ast::{Type, TypeName_, Type_},
+    shared::{unique_map::UniqueMap, Identifier},
+    typing::ast::{
+        Exp, ExpListItem, FunctionBody_, LValue, LValueList, LValue_, ModuleCall, ModuleDefinition,
+        SequenceItem, SequenceItem_, UnannotatedExp_,
+    },
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+
+/// Method name of the inlay hint request
+pub const INLAY_HINT_REQUEST: &str = "textDocument/inlayHint";
+
+/// Enabling/disabling the language server reporting readiness to support inlay hints to the IDE.
+pub const INLAY_HINTS_SUPPORT: bool = true;
+
+/// Kind of an inlay hint
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InlayHintKind(u8);
+
+impl InlayHintKind {
+    /// An inlay hint that is for a type annotation
+    pub const TYPE: InlayHintKind = InlayHintKind(1);
+    /// An inlay hint that is for a parameter
+    pub const PARAMETER: InlayHintKind = InlayHintKind(2);
+}
+
+/// Inlay hint information
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHint {
+    /// The position of this hint
+    pub position: Position,
+    /// The label of this hint
+    pub label: String,
+    /// The kind of this hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<InlayHintKind>,
+    /// Render padding before the hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_left: Option<bool>,
+    /// Render padding after the hint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub padding_right: Option<bool>,
+}
+
+/// Parameters of the inlay hint request
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlayHintParams {
+    /// The text document
+    pub text_document: TextDocumentIdentifier,
+    /// The visible document range for which inlay hints should be computed
+    pub range: Range,
+}
+
+/// Data used when computing inlay hints
+struct InlayHintCollector<'a> {
+    /// All modules of the typed AST (used to retrieve parameter names of called functions)
+    modules: &'a UniqueMap<ModuleIdent, ModuleDefinition>,
+    /// A mapping from file names to file content (used to obtain source file locations)
+    files: &'a SimpleFiles<Symbol, String>,
+    /// A mapping from file hashes to file IDs (used to obtain source file locations)
+    file_id_mapping: &'a HashMap<FileHash, usize>,
+    /// Computed inlay hints per file
+    hints: BTreeMap<FileHash, Vec<InlayHint>>,
+}
+
+/// Computes inlay hints for all modules of the typed AST (the resulting hints are grouped by the
+/// file they belong to)
+pub fn get_inlay_hints(
+    modules: &UniqueMap<ModuleIdent, ModuleDefinition>,
+    files: &SimpleFiles<Symbol, String>,
+    file_id_mapping: &HashMap<FileHash, usize>,
+) -> BTreeMap<FileHash, Vec<InlayHint>> {
+    let mut collector = InlayHintCollector {
+        modules,
+        files,
+        file_id_mapping,
+        hints: BTreeMap::new(),
+    };
+
+    for (_, _, mod_def) in modules {
+        for (_, _, fun) in &mod_def.functions {
+            if let FunctionBody_::Defined(sequence) = &fun.body.value {
+                for seq_item in sequence {
+                    collector.seq_item_hints(seq_item);
+                }
+            }
+        }
+    }
+
+    for hints in collector.hints.values_mut() {
+        hints.sort_by_key(|h| h.position);
+    }
+    collector.hints
+}
+
+impl<'a> InlayHintCollector<'a> {
+    /// Get hints for a sequence item
+    fn seq_item_hints(&mut self, seq_item: &SequenceItem) {
+        use SequenceItem_ as I;
+        match &seq_item.value {
+            I::Seq(e) => self.exp_hints(e),
+            I::Declare(lvalues) => self.lvalue_list_hints(lvalues),
+            I::Bind(lvalues, _, e) => {
+                self.exp_hints(e);
+                self.lvalue_list_hints(lvalues);
+            }
+        }
+    }
+
+    /// Get hints for a list of lvalues introducing new local variables
+    fn lvalue_list_hints(&mut self, lvalues: &LValueList) {
+        for lval in &lvalues.value {
+            self.lvalue_hints(lval);
+        }
+    }
+
+    /// Get hints for a single lvalue introducing new local variables
+    fn lvalue_hints(&mut self, lval: &LValue) {
+        match &lval.value {
+            LValue_::Var(var, t) => {
+                // no need for a hint if the type has been provided by the user
+                if get_char_after_loc(&var.loc(), self.files, self.file_id_mapping) == Some(':') {
+                    return;
+                }
+                if !is_displayable(t) {
+                    return;
+                }
+                self.add_hint(
+                    &var.loc(),
+                    false,
+                    format!(": {}", type_to_ide_string(t)),
+                    InlayHintKind::TYPE,
+                );
+            }
+            LValue_::Unpack(_, _, _, fields) | LValue_::BorrowUnpack(_, _, _, _, fields) => {
+                for (_, _, (_, (_, lvalue))) in fields {
+                    self.lvalue_hints(lvalue);
+                }
+            }
+            LValue_::Ignore => (),
+        }
+    }
+
+    /// Get hints for an expression
+    fn exp_hints(&mut self, exp: &Exp) {
+        use UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::ModuleCall(mod_call) => self.mod_call_hints(mod_call),
+            E::Builtin(_, exp)
+            | E::Vector(_, _, _, exp)
+            | E::VarCall(_, exp)
+            | E::Loop {
+                has_break: _,
+                body: exp,
+            }
+            | E::Return(exp)
+            | E::Abort(exp)
+            | E::Dereference(exp)
+            | E::UnaryExp(_, exp)
+            | E::Borrow(_, exp, _)
+            | E::TempBorrow(_, exp)
+            | E::Cast(exp, _)
+            | E::Annotate(exp, _) => self.exp_hints(exp),
+            E::IfElse(cond, t, f) => {
+                self.exp_hints(cond);
+                self.exp_hints(t);
+                self.exp_hints(f);
+            }
+            E::While(cond, body) => {
+                self.exp_hints(cond);
+                self.exp_hints(body);
+            }
+            E::Block(sequence) => {
+                for seq_item in sequence {
+                    self.seq_item_hints(seq_item);
+                }
+            }
+            E::Assign(_, _, e) => self.exp_hints(e),
+            E::Mutate(lhs, rhs) | E::BinopExp(lhs, _, _, rhs) => {
+                self.exp_hints(lhs);
+                self.exp_hints(rhs);
+            }
+            E::Pack(_, _, _, fields) => {
+                for (_, _, (_, (_, init_exp))) in fields {
+                    self.exp_hints(init_exp);
+                }
+            }
+            E::ExpList(list_items) => {
+                for item in list_items {
+                    let exp = match item {
+                        ExpListItem::Single(e, _) => e,
+                        ExpListItem::Splat(_, e, _) => e,
+                    };
+                    self.exp_hints(exp);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Get hints for a function call (inferred type arguments and parameter names)
+    fn mod_call_hints(&mut self, mod_call: &ModuleCall) {
+        let name_loc = mod_call.name.loc();
+        // type arguments are only displayed if they have been inferred by the compiler
+        if !mod_call.type_arguments.is_empty()
+            && mod_call.type_arguments.iter().all(is_displayable)
+            && get_char_after_loc(&name_loc, self.files, self.file_id_mapping) != Some('<')
+        {
+            self.add_hint(
+                &name_loc,
+                false,
+                format!("<{}>", type_list_to_ide_string(&mod_call.type_arguments)),
+                InlayHintKind::TYPE,
+            );
+        }
+
+        let param_names = self
+            .modules
+            .get_(&mod_call.module.value)
+            .and_then(|mod_def| mod_def.functions.get_(&mod_call.name.value()))
+            .map(|fun| {
+                fun.signature
+                    .parameters
+                    .iter()
+                    .map(|(v, _)| v.value())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let args: Vec<&Exp> = match &mod_call.arguments.exp.value {
+            _ if param_names.len() == 1 => vec![mod_call.arguments.as_ref()],
+            UnannotatedExp_::ExpList(list_items) => list_items
+                .iter()
+                .map(|item| match item {
+                    ExpListItem::Single(e, _) => e,
+                    ExpListItem::Splat(_, e, _) => e,
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        if args.len() == param_names.len() {
+            for (name, arg) in param_names.iter().zip(args.iter()) {
+                // no need for a hint if the argument is a local with the same name as the parameter
+                if name.starts_with('_') || local_name(arg) == Some(*name) {
+                    continue;
+                }
+                self.add_hint(
+                    &arg.exp.loc,
+                    true,
+                    format!("{}:", name),
+                    InlayHintKind::PARAMETER,
+                );
+            }
+        }
+
+        self.exp_hints(&mod_call.arguments);
+    }
+
+    /// Adds a hint either at the start or at the end of a given location
+    fn add_hint(&mut self, loc: &Loc, at_start: bool, label: String, kind: InlayHintKind) {
+        let pos = if at_start { loc.start() } else { loc.end() };
+        let position = match get_loc(&loc.file_hash(), pos, self.files, self.file_id_mapping) {
+            Some(p) => p,
+            None => return,
+        };
+        self.hints
+            .entry(loc.file_hash())
+            .or_default()
+            .push(InlayHint {
+                position,
+                label,
+                kind: Some(kind),
+                padding_left: Some(false),
+                padding_right: Some(at_start),
+            });
+    }
+}
+
+/// Checks if a type is fully resolved and can be presented to the user
+fn is_displayable(sp!(_, t): &Type) -> bool {
+    match t {
+        Type_::Unit | Type_::Param(_) => true,
+        Type_::Ref(_, t) => is_displayable(t),
+        Type_::Apply(_, sp!(_, TypeName_::Multiple(_)), _) => false,
+        Type_::Apply(_, _, ss) => ss.iter().all(is_displayable),
+        Type_::Fun(args, result) => args.iter().all(is_displayable) && is_displayable(result),
+        Type_::Anything | Type_::Var(_) | Type_::UnresolvedError => false,
+    }
+}
+
+/// Returns the name of a local variable if the expression is a plain use of this variable
+fn local_name(exp: &Exp) -> Option<Symbol> {
+    use UnannotatedExp_ as E;
+    match &exp.exp.value {
+        E::Move { from_user: _, var } | E::Copy { from_user: _, var } | E::Use(var) => {
+            Some(var.value())
+        }
+        E::BorrowLocal(_, var) => Some(var.value()),
+        _ => None,
+    }
+}
+
+/// Handles inlay hint request of the language server
+pub fn on_inlay_hint_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<InlayHintParams>(request.params.clone())
+        .expect("could not deserialize inlay hint request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let range = parameters.range;
+
+    let hints: Vec<InlayHint> = symbols
+        .file_inlay_hints()
+        .get(&fpath)
+        .map(|hints| {
+            hints
+                .iter()
+                .filter(|h| h.position >= range.start && h.position <= range.end)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let response = lsp_server::Response::new_ok(request.id.clone(), hints);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send inlay hint response: {:?}", err);
+    }
+}
+
+#[cfg(test)]
+fn assert_inlay_hint(hints: &[InlayHint], line: u32, col: u32, label: &str, kind: InlayHintKind) {
+    let hint = hints
+        .iter()
+        .find(|h| h.position.line == line && h.position.character == col)
+        .unwrap();
+    assert!(hint.label == label);
+    assert!(hint.kind == Some(kind));
+}
+
+#[test]
+/// Tests if inlay hints for local variable types and call parameter names are computed correctly.
+fn inlay_hints_test() {
+    use crate::symbols::Symbolicator;
+    use std::path::PathBuf;
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let hints = symbols.file_inlay_hints().get(&cpath).unwrap();
+
+    // type of a local variable
+    assert_inlay_hint(hints, 15, 15, ": u64", InlayHintKind::TYPE);
+    // names of the parameters of a call
+    assert_inlay_hint(hints, 40, 22, "p1:", InlayHintKind::PARAMETER);
+    assert_inlay_hint(hints, 40, 34, "p2:", InlayHintKind::PARAMETER);
+    // no hint for a local variable with a type annotation
+    assert!(!hints.iter().any(|h| h.position.line == 49));
+}
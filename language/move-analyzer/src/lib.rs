@@ -8,6 +8,9 @@ extern crate move_ir_types;
 pub mod completion;
 pub mod context;
 pub mod diagnostics;
+pub mod formatting;
+pub mod inlay_hints;
+pub mod semantic_tokens;
 pub mod symbols;
 pub mod utils;
 pub mod vfs;
@@ -0,0 +1,616 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module is responsible for computing semantic tokens on top of compiler's typed AST. Semantic
+//! tokens allow the IDE to highlight identifiers based on what they refer to rather than on
+//! syntax alone. In addition to the token types predefined by the Language Server Protocol
+//! (structs, functions, parameters, etc.), Move-specific token types are used to distinguish:
+//! - resources (structs with the `key` ability) from regular structs
+//! - abilities in struct declarations and type parameter constraints
+//! - address literals and named addresses
+//! - spec blocks
+//!
+//! Spec blocks are not a part of the typed AST and are found by lexing the source files instead.
+//!
+//! Similarly to other symbolication information, semantic tokens are computed for the whole
+//! package at once and stored with the symbols (sorted by their position in a file), so that they
+//! can be served to the IDE without re-compiling the package.
+
+use crate::{
+    context::Context,
+    symbols::Symbols,
+    utils::{get_loc, get_loc_text},
+};
+use codespan_reporting::files::{Files, SimpleFiles};
+use lsp_server::Request;
+use lsp_types::{
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensLegend,
+    SemanticTokensParams, SemanticTokensResult,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    expansion::ast::{AbilitySet, Address, ModuleIdent, ModuleIdent_, Value_},
+    naming::ast::{StructFields, TParam, Type, TypeName_, Type_},
+    parser::{
+        ast::{Ability_, StructName},
+        lexer::{Lexer, Tok},
+    },
+    shared::{unique_map::UniqueMap, Identifier},
+    typing::ast::{
+        BuiltinFunction_, Exp, ExpListItem, FunctionBody_, LValue, LValue_, ModuleDefinition,
+        SequenceItem, SequenceItem_, UnannotatedExp_,
+    },
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+
+/// Enabling/disabling the language server reporting readiness to support semantic tokens to the
+/// IDE.
+pub const SEMANTIC_TOKENS_SUPPORT: bool = true;
+
+/// Move-specific token type for structs with the `key` ability
+pub const RESOURCE: SemanticTokenType = SemanticTokenType::new("resource");
+/// Move-specific token type for abilities
+pub const ABILITY: SemanticTokenType = SemanticTokenType::new("ability");
+/// Move-specific token type for addresses
+pub const ADDRESS: SemanticTokenType = SemanticTokenType::new("address");
+/// Move-specific token type for spec blocks
+pub const SPEC: SemanticTokenType = SemanticTokenType::new("spec");
+
+/// Kind of a semantic token (the order of variants determines the index of the token type in the
+/// legend sent to the IDE)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum TokenKind {
+    Namespace,
+    Struct,
+    Resource,
+    TypeParameter,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+    Ability,
+    Address,
+    Spec,
+}
+
+impl TokenKind {
+    const ALL: [TokenKind; 11] = [
+        TokenKind::Namespace,
+        TokenKind::Struct,
+        TokenKind::Resource,
+        TokenKind::TypeParameter,
+        TokenKind::Function,
+        TokenKind::Parameter,
+        TokenKind::Variable,
+        TokenKind::Property,
+        TokenKind::Ability,
+        TokenKind::Address,
+        TokenKind::Spec,
+    ];
+
+    fn token_type(&self) -> SemanticTokenType {
+        match self {
+            TokenKind::Namespace => SemanticTokenType::NAMESPACE,
+            TokenKind::Struct => SemanticTokenType::STRUCT,
+            TokenKind::Resource => RESOURCE,
+            TokenKind::TypeParameter => SemanticTokenType::TYPE_PARAMETER,
+            TokenKind::Function => SemanticTokenType::FUNCTION,
+            TokenKind::Parameter => SemanticTokenType::PARAMETER,
+            TokenKind::Variable => SemanticTokenType::VARIABLE,
+            TokenKind::Property => SemanticTokenType::PROPERTY,
+            TokenKind::Ability => ABILITY,
+            TokenKind::Address => ADDRESS,
+            TokenKind::Spec => SPEC,
+        }
+    }
+}
+
+/// Bit representing the declaration modifier in the token modifiers bitset
+const DECLARATION_MODIFIER: u32 = 1;
+
+/// Legend describing token types and modifiers used by the language server
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TokenKind::ALL.iter().map(|k| k.token_type()).collect(),
+        token_modifiers: vec![SemanticTokenModifier::DECLARATION],
+    }
+}
+
+/// A semantic token at its absolute position in a file (the protocol encodes positions relative to
+/// the previous token, which is only done when responding to the IDE)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct FileToken {
+    /// Line where the token is located
+    line: u32,
+    /// Column where the token starts
+    col_start: u32,
+    /// Length of the token (tokens never span multiple lines)
+    length: u32,
+    /// Kind of the token
+    kind: TokenKind,
+    /// Token modifiers bitset
+    modifiers: u32,
+}
+
+/// Data used when computing semantic tokens
+struct TokenCollector<'a> {
+    /// All modules of the typed AST (used to check struct abilities)
+    modules: &'a UniqueMap<ModuleIdent, ModuleDefinition>,
+    /// A mapping from file names to file content (used to obtain source file locations)
+    files: &'a SimpleFiles<Symbol, String>,
+    /// A mapping from file hashes to file IDs (used to obtain source file locations)
+    file_id_mapping: &'a HashMap<FileHash, usize>,
+    /// Computed tokens per file
+    tokens: BTreeMap<FileHash, BTreeSet<FileToken>>,
+}
+
+/// Computes semantic tokens for all modules of the typed AST (the resulting tokens are grouped by
+/// the file they belong to)
+pub fn get_semantic_tokens(
+    modules: &UniqueMap<ModuleIdent, ModuleDefinition>,
+    files: &SimpleFiles<Symbol, String>,
+    file_id_mapping: &HashMap<FileHash, usize>,
+) -> BTreeMap<FileHash, BTreeSet<FileToken>> {
+    let mut collector = TokenCollector {
+        modules,
+        files,
+        file_id_mapping,
+        tokens: BTreeMap::new(),
+    };
+
+    for (_, mod_ident, mod_def) in modules {
+        collector.mod_tokens(mod_ident, mod_def);
+    }
+
+    for (fhash, id) in file_id_mapping {
+        if let Ok(source) = files.source(*id) {
+            collector.spec_tokens(*fhash, source);
+        }
+    }
+
+    collector.tokens
+}
+
+impl<'a> TokenCollector<'a> {
+    /// Get tokens for the whole module
+    fn mod_tokens(&mut self, mod_ident: &ModuleIdent_, mod_def: &ModuleDefinition) {
+        self.address_tokens(&mod_ident.address);
+        let name = &mod_ident.module;
+        self.add_token(&name.loc(), TokenKind::Namespace, true, Some(name.value()));
+
+        for (pos, name, struct_def) in &mod_def.structs {
+            let kind = if struct_def.abilities.has_ability_(Ability_::Key) {
+                TokenKind::Resource
+            } else {
+                TokenKind::Struct
+            };
+            self.add_token(&pos, kind, true, Some(*name));
+            self.abilities_tokens(&struct_def.abilities);
+            for stp in &struct_def.type_parameters {
+                self.type_param_tokens(&stp.param);
+            }
+            if let StructFields::Defined(fields) = &struct_def.fields {
+                for (fpos, fname, (_, t)) in fields {
+                    self.add_token(&fpos, TokenKind::Property, true, Some(*fname));
+                    self.type_tokens(t);
+                }
+            }
+        }
+
+        for (_, _, c) in &mod_def.constants {
+            self.type_tokens(&c.signature);
+            self.exp_tokens(&c.value);
+        }
+
+        for (pos, name, fun) in &mod_def.functions {
+            self.add_token(&pos, TokenKind::Function, true, Some(*name));
+            for tp in &fun.signature.type_parameters {
+                self.type_param_tokens(tp);
+            }
+            for (pname, ptype) in &fun.signature.parameters {
+                self.add_token(
+                    &pname.loc(),
+                    TokenKind::Parameter,
+                    true,
+                    Some(pname.value()),
+                );
+                self.type_tokens(ptype);
+            }
+            self.type_tokens(&fun.signature.return_type);
+            for (name, _) in &fun.acquires {
+                self.struct_name_tokens(mod_ident, name);
+            }
+            if let FunctionBody_::Defined(sequence) = &fun.body.value {
+                for seq_item in sequence {
+                    self.seq_item_tokens(seq_item);
+                }
+            }
+        }
+    }
+
+    /// Get tokens for spec blocks in a given file (spec blocks are not a part of the typed AST so
+    /// `spec` keywords are found by lexing the file)
+    fn spec_tokens(&mut self, fhash: FileHash, source: &str) {
+        let mut lexer = Lexer::new(source, fhash);
+        if lexer.advance().is_err() {
+            return;
+        }
+        while lexer.peek() != Tok::Eof {
+            if lexer.peek() == Tok::Spec {
+                let start = lexer.start_loc() as ByteIndex;
+                let loc = Loc::new(fhash, start, start + lexer.content().len() as ByteIndex);
+                self.add_token(&loc, TokenKind::Spec, false, None);
+            }
+            if lexer.advance().is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Get tokens for abilities
+    fn abilities_tokens(&mut self, abilities: &AbilitySet) {
+        for (loc, ability) in abilities {
+            let name = Symbol::from(ability.to_string());
+            self.add_token(&loc, TokenKind::Ability, false, Some(name));
+        }
+    }
+
+    /// Get tokens for a type parameter declaration
+    fn type_param_tokens(&mut self, tp: &TParam) {
+        let name = &tp.user_specified_name;
+        self.add_token(&name.loc, TokenKind::TypeParameter, true, Some(name.value));
+        self.abilities_tokens(&tp.abilities);
+    }
+
+    /// Get tokens for an address (named addresses are highlighted where they are written)
+    fn address_tokens(&mut self, addr: &Address) {
+        match addr {
+            Address::Numerical(Some(name), _) | Address::NamedUnassigned(name) => {
+                self.add_token(&name.loc, TokenKind::Address, false, Some(name.value))
+            }
+            Address::Numerical(None, _) => (),
+        }
+    }
+
+    /// Get tokens for a type written in the source code
+    fn type_tokens(&mut self, sp!(_, t): &Type) {
+        match t {
+            Type_::Ref(_, t) => self.type_tokens(t),
+            Type_::Param(tp) => {
+                let name = &tp.user_specified_name;
+                self.add_token(&name.loc, TokenKind::TypeParameter, false, Some(name.value));
+            }
+            Type_::Apply(_, sp!(_, type_name), ss) => {
+                if let TypeName_::ModuleType(sp!(_, mod_ident), struct_name) = type_name {
+                    self.struct_name_tokens(mod_ident, struct_name);
+                }
+                for t in ss {
+                    self.type_tokens(t);
+                }
+            }
+            Type_::Fun(args, result) => {
+                for t in args {
+                    self.type_tokens(t);
+                }
+                self.type_tokens(result);
+            }
+            Type_::Unit | Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => (),
+        }
+    }
+
+    /// Get tokens for a struct name (a resource if the struct has the `key` ability)
+    fn struct_name_tokens(&mut self, mod_ident: &ModuleIdent_, name: &StructName) {
+        let is_resource = self
+            .modules
+            .get_(mod_ident)
+            .and_then(|mod_def| mod_def.structs.get_(&name.value()))
+            .map(|struct_def| struct_def.abilities.has_ability_(Ability_::Key))
+            .unwrap_or(false);
+        let kind = if is_resource {
+            TokenKind::Resource
+        } else {
+            TokenKind::Struct
+        };
+        self.add_token(&name.loc(), kind, false, Some(name.value()));
+    }
+
+    /// Get tokens for a sequence item
+    fn seq_item_tokens(&mut self, seq_item: &SequenceItem) {
+        use SequenceItem_ as I;
+        match &seq_item.value {
+            I::Seq(e) => self.exp_tokens(e),
+            I::Declare(lvalues) => {
+                for lval in &lvalues.value {
+                    self.lvalue_tokens(lval, true);
+                }
+            }
+            I::Bind(lvalues, _, e) => {
+                self.exp_tokens(e);
+                for lval in &lvalues.value {
+                    self.lvalue_tokens(lval, true);
+                }
+            }
+        }
+    }
+
+    /// Get tokens for a single lvalue
+    fn lvalue_tokens(&mut self, lval: &LValue, define: bool) {
+        match &lval.value {
+            LValue_::Var(var, _) => {
+                self.add_token(&var.loc(), TokenKind::Variable, define, Some(var.value()))
+            }
+            LValue_::Unpack(ident, name, tparams, fields)
+            | LValue_::BorrowUnpack(_, ident, name, tparams, fields) => {
+                self.struct_name_tokens(&ident.value, name);
+                for (fpos, fname, (_, (_, lvalue))) in fields {
+                    self.add_token(&fpos, TokenKind::Property, false, Some(*fname));
+                    self.lvalue_tokens(lvalue, define);
+                }
+                for t in tparams {
+                    self.type_tokens(t);
+                }
+            }
+            LValue_::Ignore => (),
+        }
+    }
+
+    /// Get tokens for an expression
+    fn exp_tokens(&mut self, exp: &Exp) {
+        use UnannotatedExp_ as E;
+        match &exp.exp.value {
+            E::Value(sp!(loc, Value_::Address(_))) => {
+                self.add_token(loc, TokenKind::Address, false, None)
+            }
+            E::Move { from_user: _, var }
+            | E::Copy { from_user: _, var }
+            | E::Use(var)
+            | E::BorrowLocal(_, var) => {
+                self.add_token(&var.loc(), TokenKind::Variable, false, Some(var.value()))
+            }
+            E::ModuleCall(mod_call) => {
+                let name = &mod_call.name;
+                self.add_token(&name.loc(), TokenKind::Function, false, Some(name.value()));
+                for t in &mod_call.type_arguments {
+                    self.type_tokens(t);
+                }
+                self.exp_tokens(&mod_call.arguments);
+            }
+            E::Builtin(builtin_fun, exp) => {
+                use BuiltinFunction_ as BF;
+                match &builtin_fun.value {
+                    BF::MoveTo(t)
+                    | BF::MoveFrom(t)
+                    | BF::BorrowGlobal(_, t)
+                    | BF::Exists(t)
+                    | BF::Freeze(t) => self.type_tokens(t),
+                    BF::Assert(_) => (),
+                }
+                self.exp_tokens(exp);
+            }
+            E::Vector(_, _, t, exp) => {
+                self.type_tokens(t);
+                self.exp_tokens(exp);
+            }
+            E::Loop {
+                has_break: _,
+                body: exp,
+            }
+            | E::Return(exp)
+            | E::Abort(exp)
+            | E::Dereference(exp)
+            | E::UnaryExp(_, exp)
+            | E::VarCall(_, exp)
+            | E::TempBorrow(_, exp) => self.exp_tokens(exp),
+            E::IfElse(cond, t, f) => {
+                self.exp_tokens(cond);
+                self.exp_tokens(t);
+                self.exp_tokens(f);
+            }
+            E::While(cond, body) => {
+                self.exp_tokens(cond);
+                self.exp_tokens(body);
+            }
+            E::Block(sequence) => {
+                for seq_item in sequence {
+                    self.seq_item_tokens(seq_item);
+                }
+            }
+            E::Assign(lvalues, _, e) => {
+                for lval in &lvalues.value {
+                    self.lvalue_tokens(lval, false);
+                }
+                self.exp_tokens(e);
+            }
+            E::Mutate(lhs, rhs) | E::BinopExp(lhs, _, _, rhs) => {
+                self.exp_tokens(lhs);
+                self.exp_tokens(rhs);
+            }
+            E::Pack(ident, name, tparams, fields) => {
+                self.struct_name_tokens(&ident.value, name);
+                for (fpos, fname, (_, (_, init_exp))) in fields {
+                    self.add_token(&fpos, TokenKind::Property, false, Some(*fname));
+                    self.exp_tokens(init_exp);
+                }
+                for t in tparams {
+                    self.type_tokens(t);
+                }
+            }
+            E::ExpList(list_items) => {
+                for item in list_items {
+                    let exp = match item {
+                        ExpListItem::Single(e, _) => e,
+                        ExpListItem::Splat(_, e, _) => e,
+                    };
+                    self.exp_tokens(exp);
+                }
+            }
+            E::Borrow(_, exp, field) => {
+                self.exp_tokens(exp);
+                self.add_token(
+                    &field.loc(),
+                    TokenKind::Property,
+                    false,
+                    Some(field.value()),
+                );
+            }
+            E::Cast(exp, t) | E::Annotate(exp, t) => {
+                self.exp_tokens(exp);
+                self.type_tokens(t);
+            }
+            _ => (),
+        }
+    }
+
+    /// Adds a token for a given location. Locations of some AST elements (e.g., types inferred by
+    /// the compiler) do not correspond to the text they represent, so if the expected text of the
+    /// token is known, the token is only added if it matches the source text at the location.
+    fn add_token(&mut self, loc: &Loc, kind: TokenKind, declaration: bool, text: Option<Symbol>) {
+        let loc_text = match get_loc_text(loc, self.files, self.file_id_mapping) {
+            Some(t) => t,
+            None => return,
+        };
+        if loc_text.is_empty() || loc_text.contains('\n') {
+            return;
+        }
+        if let Some(text) = text {
+            if loc_text != text.as_str() {
+                return;
+            }
+        }
+        let start = match get_loc(
+            &loc.file_hash(),
+            loc.start(),
+            self.files,
+            self.file_id_mapping,
+        ) {
+            Some(s) => s,
+            None => return,
+        };
+        self.tokens
+            .entry(loc.file_hash())
+            .or_default()
+            .insert(FileToken {
+                line: start.line,
+                col_start: start.character,
+                length: loc_text.chars().count() as u32,
+                kind,
+                modifiers: if declaration { DECLARATION_MODIFIER } else { 0 },
+            });
+    }
+}
+
+/// Encodes tokens (sorted by their position in a file) in the format expected by the protocol, that
+/// is with positions relative to the previous token (overlapping tokens are skipped)
+fn encode_tokens(tokens: &BTreeSet<FileToken>) -> Vec<SemanticToken> {
+    let mut encoded = vec![];
+    let mut prev_line = 0;
+    let mut prev_start = 0;
+    let mut prev_end = None;
+    for t in tokens {
+        if let Some((line, col_end)) = prev_end {
+            if t.line == line && t.col_start < col_end {
+                continue;
+            }
+        }
+        let delta_line = t.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            t.col_start - prev_start
+        } else {
+            t.col_start
+        };
+        encoded.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: t.length,
+            token_type: t.kind as u32,
+            token_modifiers_bitset: t.modifiers,
+        });
+        prev_line = t.line;
+        prev_start = t.col_start;
+        prev_end = Some((t.line, t.col_start + t.length));
+    }
+    encoded
+}
+
+/// Handles semantic tokens request of the language server
+pub fn on_semantic_tokens_request(context: &Context, request: &Request, symbols: &Symbols) {
+    let parameters = serde_json::from_value::<SemanticTokensParams>(request.params.clone())
+        .expect("could not deserialize semantic tokens request");
+
+    let fpath = parameters.text_document.uri.to_file_path().unwrap();
+    let data = symbols
+        .file_semantic_tokens()
+        .get(&fpath)
+        .map(encode_tokens)
+        .unwrap_or_default();
+
+    let result = SemanticTokensResult::Tokens(SemanticTokens {
+        result_id: None,
+        data,
+    });
+    let response = lsp_server::Response::new_ok(request.id.clone(), result);
+    if let Err(err) = context
+        .connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+    {
+        eprintln!("could not send semantic tokens response: {:?}", err);
+    }
+}
+
+#[cfg(test)]
+fn assert_token(
+    tokens: &BTreeSet<FileToken>,
+    line: u32,
+    col: u32,
+    length: u32,
+    kind: TokenKind,
+    declaration: bool,
+) {
+    let token = tokens
+        .iter()
+        .find(|t| t.line == line && t.col_start == col)
+        .unwrap();
+    assert!(token.length == length);
+    assert!(token.kind == kind);
+    assert!((token.modifiers & DECLARATION_MODIFIER != 0) == declaration);
+}
+
+#[test]
+/// Tests if semantic tokens for Move-specific constructs are computed correctly.
+fn semantic_tokens_test() {
+    use crate::symbols::Symbolicator;
+    use std::path::PathBuf;
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    path.push("tests/symbols");
+
+    let (symbols_opt, _) = Symbolicator::get_symbols(path.as_path()).unwrap();
+    let symbols = symbols_opt.unwrap();
+
+    let mut fpath = path.clone();
+    fpath.push("sources/M1.move");
+    let cpath = dunce::canonicalize(&fpath).unwrap();
+
+    let tokens = symbols.file_semantic_tokens().get(&cpath).unwrap();
+
+    // resource declaration and its abilities
+    assert_token(tokens, 2, 11, 10, TokenKind::Resource, true);
+    assert_token(tokens, 2, 26, 3, TokenKind::Ability, false);
+    assert_token(tokens, 2, 31, 4, TokenKind::Ability, false);
+    assert_token(tokens, 2, 37, 5, TokenKind::Ability, false);
+    // function declaration, its parameter and the parameter's type
+    assert_token(tokens, 9, 8, 6, TokenKind::Function, true);
+    assert_token(tokens, 9, 15, 1, TokenKind::Parameter, true);
+    assert_token(tokens, 9, 18, 10, TokenKind::Resource, false);
+
+    // tokens are encoded relative to each other
+    let encoded = encode_tokens(tokens);
+    let first = tokens.iter().next().unwrap();
+    assert!(encoded[0].delta_line == first.line);
+    assert!(encoded[0].delta_start == first.col_start);
+}
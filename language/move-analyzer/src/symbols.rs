@@ -49,6 +49,8 @@
 use crate::{
     context::Context,
     diagnostics::{lsp_diagnostics, lsp_empty_diagnostics},
+    inlay_hints::{get_inlay_hints, InlayHint},
+    semantic_tokens::{get_semantic_tokens, FileToken},
     utils::get_loc,
 };
 use anyhow::{anyhow, Result};
@@ -246,6 +248,10 @@ pub struct Symbols {
     file_name_mapping: BTreeMap<FileHash, Symbol>,
     /// A mapping from filePath to ModuleDefs
     file_mods: BTreeMap<PathBuf, BTreeSet<ModuleDefs>>,
+    /// A mapping from file paths to inlay hints (sorted by position)
+    file_inlay_hints: BTreeMap<PathBuf, Vec<InlayHint>>,
+    /// A mapping from file paths to semantic tokens (sorted by position)
+    file_semantic_tokens: BTreeMap<PathBuf, BTreeSet<FileToken>>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -325,7 +331,7 @@ fn arg_list_to_ide_string(names: &[Symbol], types: &[Type]) -> String {
         .join(", ")
 }
 
-fn type_to_ide_string(sp!(_, t): &Type) -> String {
+pub(crate) fn type_to_ide_string(sp!(_, t): &Type) -> String {
     match t {
         Type_::Unit => "()".to_string(),
         Type_::Ref(m, r) => format!("&{} {}", if *m { "mut" } else { "" }, type_to_ide_string(r)),
@@ -358,6 +364,13 @@ fn type_to_ide_string(sp!(_, t): &Type) -> String {
                 )
             }
         },
+        Type_::Fun(args, result) => {
+            format!(
+                "|{}|{}",
+                type_list_to_ide_string(args),
+                type_to_ide_string(result)
+            )
+        }
         Type_::Anything => "_".to_string(),
         Type_::Var(_) => "invalid type (var)".to_string(),
         Type_::UnresolvedError => "invalid type (unresolved)".to_string(),
@@ -372,7 +385,7 @@ fn addr_to_ide_string(addr: &Address) -> String {
     }
 }
 
-fn type_list_to_ide_string(types: &[Type]) -> String {
+pub(crate) fn type_list_to_ide_string(types: &[Type]) -> String {
     types
         .iter()
         .map(type_to_ide_string)
@@ -611,11 +624,21 @@ impl Symbols {
         self.file_use_defs.extend(other.file_use_defs);
         self.file_name_mapping.extend(other.file_name_mapping);
         self.file_mods.extend(other.file_mods);
+        self.file_inlay_hints.extend(other.file_inlay_hints);
+        self.file_semantic_tokens.extend(other.file_semantic_tokens);
     }
 
     pub fn file_mods(&self) -> &BTreeMap<PathBuf, BTreeSet<ModuleDefs>> {
         &self.file_mods
     }
+
+    pub fn file_inlay_hints(&self) -> &BTreeMap<PathBuf, Vec<InlayHint>> {
+        &self.file_inlay_hints
+    }
+
+    pub fn file_semantic_tokens(&self) -> &BTreeMap<PathBuf, BTreeSet<FileToken>> {
+        &self.file_semantic_tokens
+    }
 }
 
 impl Symbolicator {
@@ -743,6 +766,22 @@ impl Symbolicator {
 
         eprintln!("get_symbols loaded file_mods length: {}", file_mods.len());
 
+        // inlay hints and semantic tokens are computed per file hash and are only needed for files
+        // that actually exist on disk
+        let file_path = |fhash: &FileHash| {
+            file_name_mapping.get(fhash).map(|path| {
+                dunce::canonicalize(path.as_str()).unwrap_or_else(|_| PathBuf::from(path.as_str()))
+            })
+        };
+        let file_inlay_hints = get_inlay_hints(modules, &files, &file_id_mapping)
+            .into_iter()
+            .filter_map(|(fhash, hints)| file_path(&fhash).map(|p| (p, hints)))
+            .collect();
+        let file_semantic_tokens = get_semantic_tokens(modules, &files, &file_id_mapping)
+            .into_iter()
+            .filter_map(|(fhash, tokens)| file_path(&fhash).map(|p| (p, tokens)))
+            .collect();
+
         let mut symbolicator = Symbolicator {
             mod_outer_defs,
             files,
@@ -785,6 +824,8 @@ impl Symbolicator {
             file_use_defs,
             file_name_mapping,
             file_mods,
+            file_inlay_hints,
+            file_semantic_tokens,
         };
 
         eprintln!("get_symbols load complete");
@@ -799,6 +840,8 @@ impl Symbolicator {
             references: BTreeMap::new(),
             file_name_mapping: BTreeMap::new(),
             file_mods: BTreeMap::new(),
+            file_inlay_hints: BTreeMap::new(),
+            file_semantic_tokens: BTreeMap::new(),
         }
     }
 
@@ -1408,6 +1451,9 @@ impl Symbolicator {
                 self.add_type_id_use_def(t, references, use_defs);
                 self.exp_symbols(exp, scope, references, use_defs);
             }
+            E::VarCall(_, exp) => {
+                self.exp_symbols(exp, scope, references, use_defs);
+            }
             E::IfElse(cond, t, f) => {
                 self.exp_symbols(cond, scope, references, use_defs);
                 self.exp_symbols(t, scope, references, use_defs);
@@ -29,3 +29,28 @@ pub fn get_loc(
         Err(_) => None,
     }
 }
+
+/// Returns the source text covered by a location (if the location maps to a known file).
+pub fn get_loc_text<'a>(
+    loc: &Loc,
+    files: &'a SimpleFiles<Symbol, String>,
+    file_id_mapping: &HashMap<FileHash, usize>,
+) -> Option<&'a str> {
+    let id = file_id_mapping.get(&loc.file_hash())?;
+    let source = files.source(*id).ok()?;
+    source.get(loc.start() as usize..loc.end() as usize)
+}
+
+/// Returns the first non-whitespace character following a location in the source text (if any).
+pub fn get_char_after_loc(
+    loc: &Loc,
+    files: &SimpleFiles<Symbol, String>,
+    file_id_mapping: &HashMap<FileHash, usize>,
+) -> Option<char> {
+    let id = file_id_mapping.get(&loc.file_hash())?;
+    let source = files.source(*id).ok()?;
+    source
+        .get(loc.end() as usize..)?
+        .chars()
+        .find(|c| !c.is_whitespace())
+}
@@ -39,15 +39,28 @@ impl<'a> CodeUnitVerifier<'a> {
         verifier_config: &VerifierConfig,
         module: &'a CompiledModule,
     ) -> VMResult<()> {
-        Self::verify_module_impl(verifier_config, module)
+        Self::verify_module_with_meter(
+            verifier_config,
+            module,
+            &mut BoundMeter::new(verifier_config),
+        )
+    }
+
+    /// Verifies the module as in `verify_module`, metering the work done with `meter`.
+    pub fn verify_module_with_meter(
+        verifier_config: &VerifierConfig,
+        module: &'a CompiledModule,
+        meter: &mut impl Meter,
+    ) -> VMResult<()> {
+        Self::verify_module_impl(verifier_config, module, meter)
             .map_err(|e| e.finish(Location::Module(module.self_id())))
     }
 
     fn verify_module_impl(
         verifier_config: &VerifierConfig,
         module: &CompiledModule,
+        meter: &mut impl Meter,
     ) -> PartialVMResult<()> {
-        let mut meter = BoundMeter::new(verifier_config);
         let mut name_def_map = HashMap::new();
         for (idx, func_def) in module.function_defs().iter().enumerate() {
             let fh = module.function_handle_at(func_def.function);
@@ -62,7 +75,7 @@ impl<'a> CodeUnitVerifier<'a> {
                 function_definition,
                 module,
                 &name_def_map,
-                &mut meter,
+                meter,
             )
             .map_err(|err| err.at_index(IndexKind::FunctionDefinition, index.0))?;
             total_back_edges += num_back_edges;
@@ -40,8 +40,8 @@ pub use script_signature::{
 pub use signature::SignatureChecker;
 pub use struct_defs::RecursiveStructDefChecker;
 pub use verifier::{
-    verify_module, verify_module_with_config, verify_module_with_config_for_test, verify_script,
-    verify_script_with_config, VerifierConfig,
+    verify_module, verify_module_with_config, verify_module_with_config_for_test,
+    verify_module_with_config_metered, verify_script, verify_script_with_config, VerifierConfig,
 };
 
 mod acquires_list_verifier;
@@ -64,6 +64,8 @@ pub trait Meter {
 pub struct BoundMeter {
     mod_bounds: Bounds,
     fun_bounds: Bounds,
+    // Units added in any scope, not counting the ones transferred between scopes.
+    total_units: u128,
 }
 
 struct Bounds {
@@ -81,18 +83,20 @@ impl Meter for BoundMeter {
 
     fn transfer(&mut self, from: Scope, to: Scope, factor: f32) -> PartialVMResult<()> {
         let units = (self.get_bounds(from).units as f32 * factor) as u128;
-        self.add(to, units)
+        self.get_bounds(to).add(units)
     }
 
     fn add(&mut self, scope: Scope, units: u128) -> PartialVMResult<()> {
-        self.get_bounds(scope).add(units)
+        self.get_bounds(scope).add(units)?;
+        self.total_units = self.total_units.saturating_add(units);
+        Ok(())
     }
 }
 
 impl Bounds {
     fn add(&mut self, units: u128) -> PartialVMResult<()> {
+        let new_units = self.units.saturating_add(units);
         if let Some(max) = self.max {
-            let new_units = self.units.saturating_add(units);
             if new_units > max {
                 // TODO: change to a new status PROGRAM_TOO_COMPLEX once this is rolled out. For
                 // now we use an existing code to avoid breaking changes on potential rollback.
@@ -102,8 +106,8 @@ impl Bounds {
                         self.name, self.units, units, max
                     )));
             }
-            self.units = new_units;
         }
+        self.units = new_units;
         Ok(())
     }
 }
@@ -114,16 +118,33 @@ impl BoundMeter {
             mod_bounds: Bounds {
                 name: "<unknown>".to_string(),
                 units: 0,
-                max: config.max_per_fun_meter_units,
+                max: config.max_per_mod_meter_units,
             },
             fun_bounds: Bounds {
                 name: "<unknown>".to_string(),
                 units: 0,
                 max: config.max_per_fun_meter_units,
             },
+            total_units: 0,
         }
     }
 
+    /// The units metered in the given scope since it was last entered. For the module scope,
+    /// these are the units of all functions verified so far.
+    pub fn get_usage(&self, scope: Scope) -> u128 {
+        if scope == Scope::Module {
+            self.mod_bounds.units
+        } else {
+            self.fun_bounds.units
+        }
+    }
+
+    /// The units metered so far in all scopes. Unlike the module scope, it includes the units of
+    /// a function whose verification failed before they were transferred to the module.
+    pub fn get_total_usage(&self) -> u128 {
+        self.total_units
+    }
+
     fn get_bounds(&mut self, scope: Scope) -> &mut Bounds {
         if scope == Scope::Module {
             &mut self.mod_bounds
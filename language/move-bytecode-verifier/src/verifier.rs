@@ -4,11 +4,17 @@
 
 //! This module contains the public APIs supported by the bytecode verifier.
 use crate::{
-    ability_field_requirements, check_duplication::DuplicationChecker,
-    code_unit_verifier::CodeUnitVerifier, constants, friends,
-    instantiation_loops::InstantiationLoopChecker, instruction_consistency::InstructionConsistency,
-    limits::LimitsVerifier, script_signature,
-    script_signature::no_additional_script_signature_checks, signature::SignatureChecker,
+    ability_field_requirements,
+    check_duplication::DuplicationChecker,
+    code_unit_verifier::CodeUnitVerifier,
+    constants, friends,
+    instantiation_loops::InstantiationLoopChecker,
+    instruction_consistency::InstructionConsistency,
+    limits::LimitsVerifier,
+    meter::{BoundMeter, Meter},
+    script_signature,
+    script_signature::no_additional_script_signature_checks,
+    signature::SignatureChecker,
     struct_defs::RecursiveStructDefChecker,
 };
 use move_binary_format::{
@@ -87,13 +93,24 @@ pub fn verify_module_with_config_for_test(
     result
 }
 
-#[cfg(feature = "std")]
 pub fn verify_module_with_config(config: &VerifierConfig, module: &CompiledModule) -> VMResult<()> {
+    verify_module_with_config_metered(config, module, &mut BoundMeter::new(config))
+}
+
+/// Verifies the module as in `verify_module_with_config`, metering the work done by the verifier
+/// with `meter`, e.g. so that it can be charged for.
+#[cfg(feature = "std")]
+pub fn verify_module_with_config_metered(
+    config: &VerifierConfig,
+    module: &CompiledModule,
+    meter: &mut impl Meter,
+) -> VMResult<()> {
     use move_binary_format::errors::PartialVMError;
     use move_core_types::{state::VMState, vm_status::StatusCode};
 
     let prev_state = move_core_types::state::set_state(VMState::VERIFIER);
-    let result = std::panic::catch_unwind(|| {
+    // The meter is not used after a panic, the module is rejected anyway.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         BoundsChecker::verify_module(module).map_err(|e| {
             // We can't point the error at the module, because if bounds-checking
             // failed, we cannot safely index into module's handle to itself.
@@ -108,13 +125,13 @@ pub fn verify_module_with_config(config: &VerifierConfig, module: &CompiledModul
         ability_field_requirements::verify_module(module)?;
         RecursiveStructDefChecker::verify_module(module)?;
         InstantiationLoopChecker::verify_module(module)?;
-        CodeUnitVerifier::verify_module(config, module)?;
+        CodeUnitVerifier::verify_module_with_meter(config, module, meter)?;
 
         // Add the failpoint injection to test the catch_unwind behavior.
         fail::fail_point!("verifier-failpoint-panic");
 
         script_signature::verify_module(module, no_additional_script_signature_checks)
-    })
+    }))
     .unwrap_or_else(|_| {
         Err(
             PartialVMError::new(StatusCode::VERIFIER_INVARIANT_VIOLATION)
@@ -128,7 +145,11 @@ pub fn verify_module_with_config(config: &VerifierConfig, module: &CompiledModul
 // Using an alternative approach for the no-std environment. See:
 // https://github.com/move-language/move/pull/750
 #[cfg(not(feature = "std"))]
-pub fn verify_module_with_config(config: &VerifierConfig, module: &CompiledModule) -> VMResult<()> {
+pub fn verify_module_with_config_metered(
+    config: &VerifierConfig,
+    module: &CompiledModule,
+    meter: &mut impl Meter,
+) -> VMResult<()> {
     BoundsChecker::verify_module(module).map_err(|e| {
         // We can't point the error at the module, because if bounds-checking
         // failed, we cannot safely index into module's handle to itself.
@@ -143,7 +164,7 @@ pub fn verify_module_with_config(config: &VerifierConfig, module: &CompiledModul
     ability_field_requirements::verify_module(module)?;
     RecursiveStructDefChecker::verify_module(module)?;
     InstantiationLoopChecker::verify_module(module)?;
-    CodeUnitVerifier::verify_module(config, module)?;
+    CodeUnitVerifier::verify_module_with_meter(config, module, meter)?;
     script_signature::verify_module(module, no_additional_script_signature_checks)
 }
 
@@ -49,6 +49,8 @@ pub const SOURCE_MAP_EXTENSION: &str = "mvsm";
 pub const MOVE_ERROR_DESC_EXTENSION: &str = "errmap";
 /// Extension for coverage maps
 pub const MOVE_COVERAGE_MAP_EXTENSION: &str = "mvcov";
+/// Extension for Move module bundle files
+pub const MOVE_BUNDLE_EXTENSION: &str = "mvb";
 
 /// - For each directory in `paths`, it will return all files that satisfy the predicate
 /// - Any file explicitly passed in `paths`, it will include that file in the result, regardless
@@ -418,6 +418,7 @@ fn function_body(
     assert!(context.loop_bounds.is_empty());
     assert!(context.loop_begin.is_none());
     assert!(context.loop_end.is_none());
+    let diags_start = context.env.count_diags();
     let b_ = match tb_ {
         HB::Native => GB::Native,
         HB::Defined { locals, body } => {
@@ -457,6 +458,7 @@ fn function_body(
             }
         }
     };
+    context.env.label_inlined_calls(loc, diags_start);
     sp(loc, b_)
 }
 
@@ -182,6 +182,10 @@ codes!(
                 (NOTE: this may become an error in the future)",
             severity: Warning
         },
+        InvalidLambda: { msg: "invalid use of lambda", severity: BlockingError },
+        RecursiveInline: { msg: "recursive inline function", severity: BlockingError },
+        InvalidInlineControl:
+            { msg: "invalid control flow in inline function", severity: BlockingError },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -235,10 +239,19 @@ codes!(
     Bug: [
         BytecodeGeneration: { msg: "BYTECODE GENERATION FAILED", severity: Bug },
         BytecodeVerification: { msg: "BYTECODE VERIFICATION FAILED", severity: Bug },
+        Formatting: { msg: "FORMATTING FAILED", severity: Bug },
     ],
     Derivation: [
         DeriveFailed: { msg: "attribute derivation failed", severity: BlockingError }
-    ]
+    ],
+    // lints, only reported for the source modules when enabled with the lint level
+    Linter: [
+        UnknownLint: { msg: "unknown lint", severity: Warning },
+        UncheckedTransfer: { msg: "unchecked transfer result", severity: Warning },
+        NativeAcquires: { msg: "acquires on a native function", severity: Warning },
+        PublicEntry: { msg: "public function could be entry", severity: Warning },
+        SelfTransfer: { msg: "transfer to the sending account", severity: Warning },
+    ],
 );
 
 //**************************************************************************************************
@@ -232,6 +232,12 @@ impl Diagnostics {
         self.diagnostics
     }
 
+    /// The diagnostics added after the first `start` ones
+    pub fn diagnostics_since_mut(&mut self, start: usize) -> &mut [Diagnostic] {
+        let start = start.min(self.diagnostics.len());
+        &mut self.diagnostics[start..]
+    }
+
     pub fn into_codespan_format(
         self,
     ) -> Vec<(
@@ -301,6 +307,20 @@ impl Diagnostic {
         self.secondary_labels.push((loc, msg.to_string()))
     }
 
+    pub fn primary_loc(&self) -> Loc {
+        self.primary_label.0
+    }
+
+    /// Replaces the primary label, keeping the previous one as a secondary label
+    pub fn set_primary_label(&mut self, (loc, msg): (Loc, impl ToString)) {
+        let previous = std::mem::replace(&mut self.primary_label, (loc, msg.to_string()));
+        self.secondary_labels.push(previous)
+    }
+
+    pub fn info(&self) -> &DiagnosticInfo {
+        &self.info
+    }
+
     pub fn extra_labels_len(&self) -> usize {
         self.secondary_labels.len() + self.notes.len()
     }
@@ -17,6 +17,7 @@ use crate::{
     FullyCompiledProgram,
 };
 use move_command_line_common::parser::{parse_u16, parse_u256, parse_u32};
+use move_core_types::{account_address::AccountAddress, u256::U256};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use std::{
@@ -439,7 +440,8 @@ fn module_(
             P::ModuleMember::Use(_) => unreachable!(),
             P::ModuleMember::Friend(f) => friend(context, &mut friends, f),
             P::ModuleMember::Function(mut f) => {
-                if !context.is_source_definition {
+                // inline functions are expanded at their call sites, which need their bodies
+                if !context.is_source_definition && !f.inline {
                     f.body.value = P::FunctionBody_::Native
                 }
                 function(context, &mut functions, f)
@@ -1200,6 +1202,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         name,
         visibility: pvisibility,
         entry,
+        inline,
         signature: psignature,
         body: pbody,
         acquires,
@@ -1207,7 +1210,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
     assert!(context.exp_specs.is_empty());
     let attributes = flatten_attributes(context, AttributePosition::Function, pattributes);
     let visibility = visibility(context, pvisibility);
-    let (old_aliases, signature) = function_signature(context, psignature);
+    let (old_aliases, signature) = function_signature_(context, inline, psignature);
     let acquires = acquires
         .into_iter()
         .flat_map(|a| name_access_chain(context, Access::Type, a))
@@ -1219,6 +1222,7 @@ fn function_(context: &mut Context, pfunction: P::Function) -> (FunctionName, E:
         loc,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -1243,6 +1247,14 @@ fn visibility(context: &mut Context, pvisibility: P::Visibility) -> E::Visibilit
 fn function_signature(
     context: &mut Context,
     psignature: P::FunctionSignature,
+) -> (OldAliasMap, E::FunctionSignature) {
+    function_signature_(context, false, psignature)
+}
+
+fn function_signature_(
+    context: &mut Context,
+    inline: bool,
+    psignature: P::FunctionSignature,
 ) -> (OldAliasMap, E::FunctionSignature) {
     let P::FunctionSignature {
         type_parameters: pty_params,
@@ -1255,7 +1267,7 @@ fn function_signature(
         .shadow_for_type_parameters(type_parameters.iter().map(|(name, _)| name));
     let parameters = pparams
         .into_iter()
-        .map(|(v, t)| (v, type_(context, t)))
+        .map(|(v, t)| (v, parameter_type(context, inline, t)))
         .collect::<Vec<_>>();
     for (v, _) in &parameters {
         check_valid_local_name(context, v)
@@ -1269,6 +1281,18 @@ fn function_signature(
     (old_aliases, signature)
 }
 
+// Parameters of inline functions can have function types, their arguments are lambdas
+fn parameter_type(context: &mut Context, inline: bool, pt: P::Type) -> E::Type {
+    match pt {
+        sp!(loc, P::Type_::Fun(args, result)) if inline => {
+            let args = types(context, args);
+            let result = type_(context, *result);
+            sp(loc, E::Type_::Fun(args, Box::new(result)))
+        }
+        pt => type_(context, pt),
+    }
+}
+
 fn function_body(context: &mut Context, sp!(loc, pbody_): P::FunctionBody) -> E::FunctionBody {
     use E::FunctionBody_ as EF;
     use P::FunctionBody_ as PF;
@@ -1594,7 +1618,11 @@ fn type_(context: &mut Context, sp!(loc, pt_): P::Type) -> E::Type {
             } else {
                 context.env.add_diag(diag!(
                     Syntax::SpecContextRestricted,
-                    (loc, "`|_|_` function type only allowed in specifications")
+                    (
+                        loc,
+                        "`|_|_` function type only allowed in specifications and as the type of \
+                         an inline function parameter"
+                    )
                 ));
                 ET::UnresolvedError
             }
@@ -1795,6 +1823,163 @@ fn exps(context: &mut Context, pes: Vec<P::Exp>) -> Vec<E::Exp> {
     pes.into_iter().map(|pe| exp_(context, pe)).collect()
 }
 
+// Lambdas are allowed as call arguments, calls of inline functions take them for their function
+// typed parameters
+fn call_args(context: &mut Context, pes: Vec<P::Exp>) -> Vec<E::Exp> {
+    pes.into_iter()
+        .map(|pe| match pe {
+            sp!(loc, P::Exp_::Lambda(pbs, pe)) => sp(loc, lambda(context, pbs, *pe)),
+            pe => exp_(context, pe),
+        })
+        .collect()
+}
+
+fn lambda(context: &mut Context, pbs: P::BindList, pe: P::Exp) -> E::Exp_ {
+    let bs_opt = bind_list(context, pbs);
+    let e = exp_(context, pe);
+    match bs_opt {
+        Some(bs) => E::Exp_::Lambda(bs, Box::new(e)),
+        None => {
+            assert!(context.env.has_errors());
+            E::Exp_::UnresolvedError
+        }
+    }
+}
+
+// For loops are desugared into while loops over fresh locals, which cannot be named in the
+// source as they start with '%'. The loop index is incremented before the body, so 'continue'
+// moves on to the next element. A loop over a range 'for (i in a..b) body' becomes
+//     {
+//         let %for_i = a;
+//         let %for_end = b;
+//         while (%for_i < %for_end) { let i = %for_i; %for_i = %for_i + 1; body }
+//     }
+// and a loop over a vector reference 'for (x in e) body' becomes
+//     {
+//         let %for_v = e;
+//         let %for_i = 0;
+//         let %for_len = 0x1::vector::length(%for_v);
+//         while (%for_i < %for_len) {
+//             let x = 0x1::vector::borrow(%for_v, %for_i); %for_i = %for_i + 1; body
+//         }
+//     }
+// using 'vector::borrow_mut' if e is a mutable borrow '&mut e'. A borrow of a dereference '&*r' or
+// '&mut *r' iterates over the vector 'r' refers to, instead of over a copy of it.
+fn for_loop(context: &mut Context, loc: Loc, v: Var, piter: P::Exp, pbody: P::Exp) -> E::Exp_ {
+    use E::{Exp_ as EE, SequenceItem_ as ES};
+    use P::{BinOp_ as PB, Exp_ as PE};
+
+    fn local(loc: Loc, name: &str) -> E::Exp {
+        let access = sp(loc, E::ModuleAccess_::Name(sp(loc, name.into())));
+        sp(loc, EE::Name(access, None))
+    }
+    fn local_lvalues(loc: Loc, name: &str) -> E::LValueList {
+        let access = sp(loc, E::ModuleAccess_::Name(sp(loc, name.into())));
+        sp(loc, vec![sp(loc, E::LValue_::Var(access, None))])
+    }
+    fn let_(loc: Loc, name: &str, e: E::Exp) -> E::SequenceItem {
+        sp(loc, ES::Bind(local_lvalues(loc, name), e))
+    }
+    fn num(loc: Loc, n: u8) -> E::Exp {
+        let value = sp(loc, E::Value_::InferredNum(U256::from(n)));
+        sp(loc, EE::Value(value))
+    }
+    fn binop(loc: Loc, lhs: E::Exp, op: PB, rhs: E::Exp) -> E::Exp {
+        sp(loc, EE::BinopExp(Box::new(lhs), sp(loc, op), Box::new(rhs)))
+    }
+    fn vector_call(loc: Loc, name: &str, args: Vec<E::Exp>) -> E::Exp {
+        let address = Address::anonymous(
+            loc,
+            NumericalAddress::new(AccountAddress::ONE.into_bytes(), NumberFormat::Hex),
+        );
+        let vector = ModuleName(sp(loc, "vector".into()));
+        let mident = sp(loc, ModuleIdent_::new(address, vector));
+        let access = sp(
+            loc,
+            E::ModuleAccess_::ModuleAccess(mident, sp(loc, name.into())),
+        );
+        sp(loc, EE::Call(access, false, None, sp(loc, args)))
+    }
+
+    const INDEX: &str = "%for_i";
+    const END: &str = "%for_end";
+    const VECTOR: &str = "%for_v";
+    const LENGTH: &str = "%for_len";
+
+    let iter_loc = piter.loc;
+    let (mut items, end, elem) = match piter.value {
+        PE::BinopExp(plo, sp!(_, PB::Range), phi) => {
+            let items = vec![
+                let_(plo.loc, INDEX, exp_(context, *plo)),
+                let_(phi.loc, END, exp_(context, *phi)),
+            ];
+            (items, END, local(v.loc(), INDEX))
+        }
+        piter_ => {
+            let (mut_, piter) = match piter_ {
+                PE::Borrow(mut_, pe) => match pe.value {
+                    PE::Dereference(pr) => (mut_, *pr),
+                    pe_ => (
+                        mut_,
+                        sp(iter_loc, PE::Borrow(mut_, Box::new(sp(pe.loc, pe_)))),
+                    ),
+                },
+                piter_ => (false, sp(iter_loc, piter_)),
+            };
+            let items = vec![
+                let_(iter_loc, VECTOR, exp_(context, piter)),
+                let_(iter_loc, INDEX, num(iter_loc, 0)),
+                let_(
+                    iter_loc,
+                    LENGTH,
+                    vector_call(iter_loc, "length", vec![local(iter_loc, VECTOR)]),
+                ),
+            ];
+            let borrow = if mut_ { "borrow_mut" } else { "borrow" };
+            let elem = vector_call(
+                v.loc(),
+                borrow,
+                vec![local(v.loc(), VECTOR), local(v.loc(), INDEX)],
+            );
+            (items, LENGTH, elem)
+        }
+    };
+
+    let body_loc = pbody.loc;
+    let bind_opt = bind(context, sp(v.loc(), P::Bind_::Var(v)));
+    let body = exp_(context, pbody);
+    let bind = match bind_opt {
+        Some(b) => b,
+        None => {
+            assert!(context.env.has_errors());
+            return EE::UnresolvedError;
+        }
+    };
+    let increment = EE::Assign(
+        local_lvalues(v.loc(), INDEX),
+        Box::new(binop(
+            v.loc(),
+            local(v.loc(), INDEX),
+            PB::Add,
+            num(v.loc(), 1),
+        )),
+    );
+    let loop_body = VecDeque::from(vec![
+        sp(v.loc(), ES::Bind(sp(v.loc(), vec![bind]), elem)),
+        sp(v.loc(), ES::Seq(sp(v.loc(), increment))),
+        sp(body_loc, ES::Seq(body)),
+    ]);
+    let cond = binop(
+        iter_loc,
+        local(iter_loc, INDEX),
+        PB::Lt,
+        local(iter_loc, end),
+    );
+    let while_ = EE::While(Box::new(cond), Box::new(sp(body_loc, EE::Block(loop_body))));
+    items.push(sp(loc, ES::Seq(sp(loc, while_))));
+    EE::Block(items.into())
+}
+
 fn exp(context: &mut Context, pe: P::Exp) -> Box<E::Exp> {
     Box::new(exp_(context, pe))
 }
@@ -1837,7 +2022,7 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
         }
         PE::Call(pn, is_macro, ptys_opt, sp!(rloc, prs)) => {
             let tys_opt = optional_types(context, ptys_opt);
-            let ers = sp(rloc, exps(context, prs));
+            let ers = sp(rloc, call_args(context, prs));
             let en_opt = name_access_chain(context, Access::ApplyPositional, pn);
             match en_opt {
                 Some(en) => EE::Call(en, is_macro, tys_opt, ers),
@@ -1879,24 +2064,21 @@ fn exp_(context: &mut Context, sp!(loc, pe_): P::Exp) -> E::Exp {
         }
         PE::While(pb, ploop) => EE::While(exp(context, *pb), exp(context, *ploop)),
         PE::Loop(ploop) => EE::Loop(exp(context, *ploop)),
+        PE::For(v, piter, ploop) => for_loop(context, loc, v, *piter, *ploop),
         PE::Block(seq) => EE::Block(sequence(context, loc, seq)),
         PE::Lambda(pbs, pe) => {
             if !context.in_spec_context {
                 context.env.add_diag(diag!(
                     Syntax::SpecContextRestricted,
-                    (loc, "lambda expression only allowed in specifications"),
+                    (
+                        loc,
+                        "lambda expression only allowed in specifications and as an argument of \
+                         an inline function call"
+                    ),
                 ));
                 EE::UnresolvedError
             } else {
-                let bs_opt = bind_list(context, pbs);
-                let e = exp_(context, *pe);
-                match bs_opt {
-                    Some(bs) => EE::Lambda(bs, Box::new(e)),
-                    None => {
-                        assert!(context.env.has_errors());
-                        EE::UnresolvedError
-                    }
-                }
+                lambda(context, pbs, *pe)
             }
         }
         PE::Quant(k, prs, ptrs, pc, pe) => {
@@ -0,0 +1,512 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formatter for Move source files. Only files that parse are formatted.
+//!
+//! The formatter works on the token stream of the lexer, so it keeps every token and comment of
+//! the file in order, and handles modules, scripts and specs alike. It keeps the line structure
+//! chosen by the author, and normalizes the rest of the layout:
+//! - lines are indented by the brackets (`{`, `(` and `[`) open at their start, plus one level
+//!   for lines continuing an expression, e.g. after a binary operator;
+//! - spaces between tokens on a line follow the usual Move style, e.g. one space around binary
+//!   operators and after commas, and none inside parentheses;
+//! - runs of blank lines are shortened, and blank lines after an opening bracket or before a
+//!   closing bracket are removed;
+//! - trailing whitespace is removed, and the file ends with a single newline.
+//!
+//! Tokens with an ambiguous role, e.g. `|` or `..`, keep whether they were separated by a space,
+//! as do the tokens of spec `apply` statements.
+//! Formatting a formatted file does not change it.
+
+use crate::{
+    diag,
+    diagnostics::Diagnostics,
+    parser::{comments::verify_string, lexer::Lexer, lexer::Tok, syntax::parse_file_string},
+    shared::{CompilationEnv, Flags},
+};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::Loc;
+
+/// Options of the formatter, set in the `[format]` section of a package manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// Number of spaces per indentation level
+    pub indent_width: usize,
+    /// Maximum number of consecutive blank lines
+    pub max_blank_lines: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            max_blank_lines: 1,
+        }
+    }
+}
+
+/// Formats the Move source file `source`. Returns the parsing diagnostics if it does not parse.
+pub fn format_source(source: &str, options: &FormatOptions) -> Result<String, Diagnostics> {
+    let source = source.replace("\r\n", "\n");
+    let file_hash = FileHash::new(&source);
+    verify_string(file_hash, &source)?;
+    let mut env = CompilationEnv::new(Flags::empty());
+    parse_file_string(&mut env, file_hash, &source)?;
+
+    let input = lex(file_hash, &source)?;
+    let mut formatter = Formatter::new(options);
+    for (token, gap) in input.tokens.iter().zip(&input.gaps) {
+        formatter.gap(gap);
+        formatter.token(token);
+    }
+    formatter.gap(&input.trailing_gap);
+    let formatted = formatter.finish();
+
+    // the output must parse, and lex to the same tokens and comments as the input
+    let output_hash = FileHash::new(&formatted);
+    let preserved = match lex(output_hash, &formatted) {
+        Ok(output) => {
+            same_tokens(&input, &output)
+                && parse_file_string(&mut env, output_hash, &formatted).is_ok()
+        }
+        Err(_) => false,
+    };
+    if !preserved {
+        let loc = Loc::new(file_hash, 0, 0);
+        return Err(Diagnostics::from(vec![diag!(
+            Bug::Formatting,
+            (loc, "Formatting changed the tokens or comments of the file")
+        )]));
+    }
+    Ok(formatted)
+}
+
+//**************************************************************************************************
+// Lexing
+//**************************************************************************************************
+
+struct Token<'a> {
+    tok: Tok,
+    content: &'a str,
+    // whether the token is a binary operator, for the tokens that can also be unary or delimit
+    // type arguments
+    binary: bool,
+    // whether the token is a brace of a group of `use` members, e.g. in `use 0x1::m::{Self, T}`
+    group: bool,
+    // whether the token is part of a spec `apply`, whose patterns are sensitive to spaces
+    apply: bool,
+}
+
+/// Whitespace and comments between tokens
+enum Trivia<'a> {
+    Whitespace { newlines: usize },
+    Comment(&'a str),
+}
+
+struct Input<'a> {
+    tokens: Vec<Token<'a>>,
+    // the trivia before each token
+    gaps: Vec<Vec<Trivia<'a>>>,
+    trailing_gap: Vec<Trivia<'a>>,
+}
+
+fn lex(file_hash: FileHash, source: &str) -> Result<Input, Diagnostics> {
+    let mut lexer = Lexer::new(source, file_hash);
+    let mut tokens: Vec<Token> = vec![];
+    let mut gaps = vec![];
+    // the number of open type argument lists, and whether each open brace is a `use` group
+    let mut type_args = 0;
+    let mut braces = vec![];
+    let mut apply = false;
+    loop {
+        lexer
+            .advance()
+            .map_err(|diag| Diagnostics::from(vec![*diag]))?;
+        let gap = trivia(&source[lexer.previous_end_loc()..lexer.start_loc()]);
+        let tok = lexer.peek();
+        if tok == Tok::Eof {
+            return Ok(Input {
+                tokens,
+                gaps,
+                trailing_gap: gap,
+            });
+        }
+        let binary = match tok {
+            // `*` and `&` are unary after an operator. After a `)`, they are unary if only preceded
+            // by a space, e.g. `if (c) &x`, but binary in `(a) & b` or `(a)&(b)`
+            Tok::Star | Tok::Amp => match tokens.last() {
+                Some(Token {
+                    tok: Tok::RParen, ..
+                }) => {
+                    let space_before = lexer.previous_end_loc() < lexer.start_loc();
+                    let space_after =
+                        source[lexer.start_loc() + 1..].starts_with(char::is_whitespace);
+                    space_after || !space_before
+                }
+                Some(prev) => is_operand_end(prev),
+                None => false,
+            },
+            // as in the parser, `<` starts type arguments right after a name
+            Tok::Less => {
+                let after_name = matches!(tokens.last(), Some(prev) if prev.tok == Tok::Identifier)
+                    && lexer.previous_end_loc() == lexer.start_loc();
+                type_args += after_name as usize;
+                !after_name
+            }
+            Tok::Greater | Tok::GreaterGreater => {
+                let closed = if tok == Tok::Greater { 1 } else { 2 };
+                let binary = type_args == 0;
+                type_args = type_args.saturating_sub(closed);
+                binary
+            }
+            _ => is_binary_operator(tok),
+        };
+        let group = match tok {
+            Tok::LBrace => {
+                let group = matches!(tokens.last(), Some(prev) if prev.tok == Tok::ColonColon);
+                braces.push(group);
+                group
+            }
+            Tok::RBrace => braces.pop().unwrap_or(false),
+            _ => false,
+        };
+        apply |= tok == Tok::Identifier
+            && lexer.content() == "apply"
+            && matches!(
+                tokens.last().map(|prev| prev.tok),
+                Some(Tok::LBrace | Tok::RBrace | Tok::Semicolon)
+            );
+        tokens.push(Token {
+            tok,
+            content: lexer.content(),
+            binary,
+            group,
+            apply,
+        });
+        apply &= tok != Tok::Semicolon;
+        gaps.push(gap);
+    }
+}
+
+/// Splits `gap`, the text between two tokens, into whitespace and comments
+fn trivia(mut gap: &str) -> Vec<Trivia> {
+    let mut trivia = vec![];
+    while !gap.is_empty() {
+        let len = if gap.starts_with("//") {
+            let len = gap.find('\n').unwrap_or(gap.len());
+            trivia.push(Trivia::Comment(gap[..len].trim_end()));
+            len
+        } else if gap.starts_with("/*") {
+            // block comments nest, and the lexer checked they are closed
+            let bytes = gap.as_bytes();
+            let mut depth = 0;
+            let mut len = 0;
+            loop {
+                if bytes[len..].starts_with(b"/*") {
+                    depth += 1;
+                    len += 2;
+                } else if bytes[len..].starts_with(b"*/") {
+                    depth -= 1;
+                    len += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    len += 1;
+                }
+            }
+            trivia.push(Trivia::Comment(&gap[..len]));
+            len
+        } else {
+            let len = gap.find(|c: char| !c.is_whitespace()).unwrap_or(gap.len());
+            trivia.push(Trivia::Whitespace {
+                newlines: gap[..len].matches('\n').count(),
+            });
+            len
+        };
+        gap = &gap[len..];
+    }
+    trivia
+}
+
+fn same_tokens(input: &Input, output: &Input) -> bool {
+    fn comments<'a>(input: &'a Input) -> impl Iterator<Item = &'a str> {
+        input
+            .gaps
+            .iter()
+            .chain(std::iter::once(&input.trailing_gap))
+            .flatten()
+            .filter_map(|trivia| match trivia {
+                Trivia::Comment(comment) => Some(*comment),
+                Trivia::Whitespace { .. } => None,
+            })
+    }
+    input.tokens.len() == output.tokens.len()
+        && input
+            .tokens
+            .iter()
+            .zip(&output.tokens)
+            .all(|(lhs, rhs)| lhs.tok == rhs.tok && lhs.content.trim() == rhs.content.trim())
+        && comments(input).eq(comments(output))
+}
+
+//**************************************************************************************************
+// Layout
+//**************************************************************************************************
+
+enum Item<'a> {
+    Token(&'a Token<'a>),
+    Comment(&'a str),
+}
+
+struct Formatter<'a> {
+    options: &'a FormatOptions,
+    out: String,
+    // the indentation of the lines on which the open brackets were opened
+    brackets: Vec<usize>,
+    line_indent: usize,
+    // the last token written, and whether anything was written after it
+    last_token: Option<&'a Token<'a>>,
+    last_is_token: bool,
+    // the whitespace since the last item written
+    newlines: usize,
+    space: bool,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(options: &'a FormatOptions) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            brackets: vec![],
+            line_indent: 0,
+            last_token: None,
+            last_is_token: false,
+            newlines: 0,
+            space: false,
+        }
+    }
+
+    fn gap(&mut self, gap: &'a [Trivia<'a>]) {
+        for trivia in gap {
+            match trivia {
+                Trivia::Whitespace { newlines } => {
+                    self.newlines += newlines;
+                    self.space = true;
+                }
+                Trivia::Comment(comment) => self.item(Item::Comment(comment)),
+            }
+        }
+    }
+
+    fn token(&mut self, token: &'a Token<'a>) {
+        self.item(Item::Token(token));
+        match token.tok {
+            Tok::LBrace | Tok::LParen | Tok::LBracket => self.brackets.push(self.line_indent),
+            Tok::RBrace | Tok::RParen | Tok::RBracket => {
+                self.brackets.pop();
+            }
+            _ => (),
+        }
+        self.last_token = Some(token);
+        self.last_is_token = true;
+    }
+
+    fn item(&mut self, item: Item<'a>) {
+        // `&mut` must be followed by a space to be lexed as such
+        let after_amp_mut = self.last_is_token && self.last_token.unwrap().tok == Tok::AmpMut;
+        if self.out.is_empty() {
+            self.line_indent = 0;
+        } else if self.newlines > 0 && !after_amp_mut {
+            self.new_line(&item);
+        } else if self.space_before(&item) {
+            self.out.push(' ');
+        }
+        match item {
+            Item::Token(token) => self.out.push_str(token.content.trim_end()),
+            Item::Comment(comment) => {
+                self.out.push_str(comment);
+                self.last_is_token = false;
+            }
+        }
+        self.newlines = 0;
+        self.space = false;
+    }
+
+    fn new_line(&mut self, item: &Item) {
+        let after_opener = self.last_is_token
+            && matches!(
+                self.last_token.unwrap().tok,
+                Tok::LBrace | Tok::LParen | Tok::LBracket
+            );
+        let closer = matches!(
+            item,
+            Item::Token(Token {
+                tok: Tok::RBrace | Tok::RParen | Tok::RBracket,
+                ..
+            })
+        );
+        let blank_lines = if after_opener || closer {
+            0
+        } else {
+            (self.newlines - 1).min(self.options.max_blank_lines)
+        };
+        for _ in 0..=blank_lines {
+            self.out.push('\n');
+        }
+
+        let continued = self.last_token.map_or(false, ends_continued_line)
+            || matches!(item, Item::Token(token) if starts_continued_line(token));
+        self.line_indent = if closer {
+            self.brackets.last().copied().unwrap_or(0)
+        } else {
+            self.brackets.last().map_or(0, |indent| indent + 1) + continued as usize
+        };
+        for _ in 0..self.line_indent * self.options.indent_width {
+            self.out.push(' ');
+        }
+    }
+
+    /// Whether `item` is separated from the previous one on the same line by a space
+    fn space_before(&self, item: &Item) -> bool {
+        match (self.last_is_token, item) {
+            (true, Item::Token(next)) => space_between(self.last_token.unwrap(), next, self.space),
+            _ => self.space,
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out
+    }
+}
+
+fn space_between(prev: &Token, next: &Token, space: bool) -> bool {
+    use Tok::*;
+    match (prev.tok, next.tok) {
+        _ if prev.apply && next.apply => space,
+        (AmpMut, _) => true,
+        (LBrace, RBrace) => false,
+        (LParen | LBracket, _) | (_, RParen | RBracket | Comma | Semicolon) => false,
+        (LBrace, _) => !prev.group,
+        (_, RBrace) => !next.group,
+        (ColonColon | Period, _) | (_, ColonColon | Period) => false,
+        (AtSign | NumSign | Exclaim, _) => false,
+        (Star | Amp | Less, _) if !prev.binary => false,
+        (_, Less | Greater | GreaterGreater) if !next.binary => false,
+        (_, Colon) => false,
+        (Colon | Comma | Semicolon, _) => true,
+        (Public, LParen) => false,
+        (_, LBrace) => true,
+        (Pipe | PeriodPeriod, _) | (_, Pipe | PeriodPeriod) => space,
+        _ if prev.binary || next.binary => true,
+        (Greater | GreaterGreater, n) => is_word(n),
+        (Identifier, Exclaim | LBracket) => false,
+        (Identifier, LParen) => is_spec_keyword(prev.content) && space,
+        (p, n) if is_keyword(p) || is_keyword(n) => true,
+        (p, n) if is_word(p) && is_word(n) => true,
+        _ => space,
+    }
+}
+
+/// Whether a line ending with `token` continues on the next line
+fn ends_continued_line(token: &Token) -> bool {
+    token.binary
+}
+
+/// Whether a line starting with `token` continues the previous line
+fn starts_continued_line(token: &Token) -> bool {
+    token.binary || matches!(token.tok, Tok::Period | Tok::Acquires)
+}
+
+/// Operators that are always binary
+fn is_binary_operator(tok: Tok) -> bool {
+    use Tok::*;
+    matches!(
+        tok,
+        Plus | Minus
+            | Slash
+            | Percent
+            | Caret
+            | LessLess
+            | EqualEqual
+            | ExclaimEqual
+            | LessEqual
+            | GreaterEqual
+            | AmpAmp
+            | PipePipe
+            | EqualEqualGreater
+            | LessEqualEqualGreater
+            | Equal
+            | As
+    )
+}
+
+/// Tokens that can end an operand, after which `*` and `&` are binary
+fn is_operand_end(token: &Token) -> bool {
+    use Tok::*;
+    match token.tok {
+        Identifier => token.content != "in" && !is_spec_keyword(token.content),
+        NumValue | NumTypedValue | ByteStringValue | True | False | RBracket => true,
+        _ => false,
+    }
+}
+
+fn is_word(tok: Tok) -> bool {
+    use Tok::*;
+    matches!(tok, Identifier | NumValue | NumTypedValue | ByteStringValue) || is_keyword(tok)
+}
+
+fn is_keyword(tok: Tok) -> bool {
+    use Tok::*;
+    matches!(
+        tok,
+        Abort
+            | Acquires
+            | As
+            | Break
+            | Continue
+            | Copy
+            | Else
+            | False
+            | If
+            | Invariant
+            | Let
+            | Loop
+            | Module
+            | Move
+            | Native
+            | Public
+            | Return
+            | Spec
+            | Struct
+            | True
+            | Use
+            | While
+            | For
+            | Fun
+            | Script
+            | Const
+            | Friend
+    )
+}
+
+/// Identifiers starting spec conditions, which can be followed by an expression instead of
+/// being called
+fn is_spec_keyword(name: &str) -> bool {
+    matches!(
+        name,
+        "aborts_if"
+            | "aborts_with"
+            | "assert"
+            | "assume"
+            | "decreases"
+            | "emits"
+            | "ensures"
+            | "modifies"
+            | "requires"
+            | "succeeds_if"
+    )
+}
@@ -225,7 +225,14 @@ fn module(
     let structs = tstructs.map(|name, s| struct_def(context, name, s));
 
     let constants = tconstants.map(|name, c| constant(context, name, c));
-    let functions = tfunctions.map(|name, f| function(context, name, f));
+    // inline functions are expanded at their call sites, so no code is generated for them
+    let functions = tfunctions.filter_map(|name, f| {
+        if f.inline {
+            None
+        } else {
+            Some(function(context, name, f))
+        }
+    });
     (
         module_ident,
         H::ModuleDefinition {
@@ -283,6 +290,7 @@ fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Fu
         attributes,
         visibility,
         entry,
+        inline: _,
         signature,
         acquires,
         body,
@@ -325,6 +333,7 @@ fn function_body(
 ) -> H::FunctionBody {
     use H::FunctionBody_ as HB;
     use T::FunctionBody_ as TB;
+    let diags_start = context.env.count_diags();
     let b_ = match tb_ {
         TB::Native => {
             context.extract_function_locals();
@@ -335,6 +344,7 @@ fn function_body(
             HB::Defined { locals, body }
         }
     };
+    context.env.label_inlined_calls(loc, diags_start);
     sp(loc, b_)
 }
 
@@ -481,7 +491,7 @@ fn base_type(context: &Context, sp!(loc, nb_): N::Type) -> H::BaseType {
         NT::Param(tp) => HB::Param(tp),
         NT::UnresolvedError => HB::UnresolvedError,
         NT::Anything => HB::Unreachable,
-        NT::Ref(_, _) | NT::Unit => {
+        NT::Ref(_, _) | NT::Unit | NT::Fun(_, _) => {
             panic!(
                 "ICE type constraints failed {}:{}-{}",
                 loc.file_hash(),
@@ -1061,6 +1071,8 @@ fn exp_impl(
     use T::UnannotatedExp_ as TE;
 
     let res = match e_ {
+        TE::VarCall(_, _) => panic!("ICE inline functions are not translated"),
+
         // Statement-like expressions
         TE::While(tb, loop_body) => {
             let mut cond_block = Block::new();
@@ -1740,6 +1752,7 @@ fn bind_for_short_circuit(e: &T::Exp) -> bool {
         | TE::Vector(_, _, _, _)
         | TE::BorrowLocal(_, _)
         | TE::ExpList(_)
+        | TE::VarCall(_, _)
         | TE::Cast(_, _) => panic!("ICE unexpected exp in short circuit check: {:?}", e),
     }
 }
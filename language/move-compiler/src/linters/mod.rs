@@ -0,0 +1,410 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lints run over the typed AST of the source modules and scripts, once typing found no errors.
+//! Each lint is a `TypingVisitor` registered in `LINTS` with the level it is enabled at. A lint
+//! can be silenced for a module, script or function with `#[allow(<lint>, ...)]`.
+
+use crate::{
+    diag,
+    diagnostics::Diagnostic,
+    expansion::ast::{self as E, ModuleIdent},
+    parser::ast::FunctionName,
+    shared::{
+        known_attributes::{KnownAttribute, LintAttribute},
+        CompilationEnv, Identifier,
+    },
+    typing::ast as T,
+};
+use move_core_types::account_address::AccountAddress;
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+mod native_acquires;
+mod public_entry;
+mod self_transfer;
+mod unchecked_transfer;
+
+//**************************************************************************************************
+// Lints
+//**************************************************************************************************
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum LintLevel {
+    // No lints are run
+    None,
+    // Lints that are unlikely to report false positives
+    Default,
+    // All lints, including the ones based on heuristics
+    All,
+}
+
+struct LintInfo {
+    name: &'static str,
+    level: LintLevel,
+    visitor: fn() -> Box<dyn TypingVisitor>,
+}
+
+const LINTS: &[LintInfo] = &[
+    LintInfo {
+        name: unchecked_transfer::NAME,
+        level: LintLevel::Default,
+        visitor: unchecked_transfer::visitor,
+    },
+    LintInfo {
+        name: self_transfer::NAME,
+        level: LintLevel::Default,
+        visitor: self_transfer::visitor,
+    },
+    LintInfo {
+        name: native_acquires::NAME,
+        level: LintLevel::Default,
+        visitor: native_acquires::visitor,
+    },
+    LintInfo {
+        name: public_entry::NAME,
+        level: LintLevel::All,
+        visitor: public_entry::visitor,
+    },
+];
+
+// The Substrate standard library and the Move standard library share this address
+const STDLIB_ADDRESS: AccountAddress = AccountAddress::ONE;
+
+const BALANCE_MODULE: &str = "balance";
+const TRANSFER_FUNCTION: &str = "transfer";
+
+const SIGNER_MODULE: &str = "signer";
+const ADDRESS_OF_FUNCTION: &str = "address_of";
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub fn program(compilation_env: &mut CompilationEnv, prog: &T::Program) {
+    if compilation_env.has_errors() {
+        return;
+    }
+    let level = compilation_env.flags().lint_level();
+    let mut context = LintContext::new(compilation_env, prog);
+    let items = source_functions(&mut context, prog);
+    for lint in LINTS {
+        if level < lint.level {
+            continue;
+        }
+        context.lint = lint.name;
+        let mut visitor = (lint.visitor)();
+        for (module, name, fdef, allowed) in &items {
+            context.current_module = *module;
+            context.allowed = allowed.clone();
+            visitor.visit_function(&mut context, *name, fdef)
+        }
+    }
+}
+
+type SourceFunction<'a> = (
+    Option<ModuleIdent>,
+    FunctionName,
+    &'a T::Function,
+    BTreeSet<Symbol>,
+);
+
+/// Collects the functions to lint, along with the lints allowed for each of them
+fn source_functions<'a>(
+    context: &mut LintContext,
+    prog: &'a T::Program,
+) -> Vec<SourceFunction<'a>> {
+    let mut functions = vec![];
+    for (mident, mdef) in prog.modules.key_cloned_iter() {
+        if !mdef.is_source_module {
+            continue;
+        }
+        let module_allowed = allowed_lints(context, &mdef.attributes);
+        for (name, fdef) in mdef.functions.key_cloned_iter() {
+            let mut allowed = allowed_lints(context, &fdef.attributes);
+            allowed.extend(module_allowed.iter().copied());
+            functions.push((Some(mident), name, fdef, allowed))
+        }
+    }
+    for script in prog.scripts.values() {
+        let mut allowed = allowed_lints(context, &script.attributes);
+        allowed.extend(allowed_lints(context, &script.function.attributes));
+        functions.push((None, script.function_name, &script.function, allowed))
+    }
+    // inline functions are linted where they are expanded
+    functions.retain(|(_, _, fdef, _)| !fdef.inline);
+    functions
+}
+
+/// The lints listed in the `#[allow(...)]` attribute, if any
+fn allowed_lints(context: &mut LintContext, attributes: &E::Attributes) -> BTreeSet<Symbol> {
+    let allow = E::AttributeName_::Known(KnownAttribute::Lint(LintAttribute::Allow));
+    let attr = match attributes.get_(&allow) {
+        Some(attr) => attr,
+        None => return BTreeSet::new(),
+    };
+    let lints = match &attr.value {
+        E::Attribute_::Parameterized(_, lints) => lints,
+        E::Attribute_::Name(_) | E::Attribute_::Assigned(_, _) => {
+            let msg = format!(
+                "Expected a list of lints, e.g. '#[{}({})]'",
+                LintAttribute::ALLOW,
+                LINTS[0].name
+            );
+            context
+                .env
+                .add_diag(diag!(Attributes::InvalidValue, (attr.loc, msg)));
+            return BTreeSet::new();
+        }
+    };
+    let mut allowed = BTreeSet::new();
+    for (loc, name_, _) in lints {
+        let name = match name_ {
+            E::AttributeName_::Unknown(name) => *name,
+            E::AttributeName_::Known(known) => Symbol::from(known.name()),
+        };
+        if !LINTS.iter().any(|lint| lint.name == name.as_str()) {
+            let msg = format!("Unknown lint '{}'", name);
+            let known = format!(
+                "Known lints are: {}",
+                LINTS
+                    .iter()
+                    .map(|lint| format!("'{}'", lint.name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            context
+                .env
+                .add_diag(diag!(Linter::UnknownLint, (loc, msg), (loc, known)));
+        }
+        allowed.insert(name);
+    }
+    allowed
+}
+
+//**************************************************************************************************
+// Context
+//**************************************************************************************************
+
+pub struct LintContext<'env, 'prog> {
+    env: &'env mut CompilationEnv,
+    pub program: &'prog T::Program,
+    // the lint being run
+    lint: &'static str,
+    // the lints allowed for the function being linted
+    allowed: BTreeSet<Symbol>,
+    // `None` for the function of a script
+    pub current_module: Option<ModuleIdent>,
+}
+
+impl<'env, 'prog> LintContext<'env, 'prog> {
+    fn new(env: &'env mut CompilationEnv, program: &'prog T::Program) -> Self {
+        Self {
+            env,
+            program,
+            lint: "",
+            allowed: BTreeSet::new(),
+            current_module: None,
+        }
+    }
+
+    /// Reports a finding of the lint being run, unless it is allowed for the current function
+    pub fn add_diag(&mut self, mut diag: Diagnostic) {
+        if self.allowed.contains(&Symbol::from(self.lint)) {
+            return;
+        }
+        diag.add_note(format!(
+            "This lint can be silenced with '#[{}({})]'",
+            LintAttribute::ALLOW,
+            self.lint
+        ));
+        self.env.add_diag(diag)
+    }
+}
+
+//**************************************************************************************************
+// Visitor
+//**************************************************************************************************
+
+/// A lint over the typed AST. Each method defaults to visiting the children of the node, so a
+/// lint overrides the nodes it is interested in and calls the matching `walk_*` function to keep
+/// visiting
+pub trait TypingVisitor {
+    fn visit_function(
+        &mut self,
+        context: &mut LintContext,
+        _name: FunctionName,
+        fdef: &T::Function,
+    ) {
+        walk_function(self, context, fdef)
+    }
+
+    fn visit_sequence(&mut self, context: &mut LintContext, seq: &T::Sequence) {
+        walk_sequence(self, context, seq)
+    }
+
+    fn visit_seq_item(&mut self, context: &mut LintContext, item: &T::SequenceItem) {
+        walk_seq_item(self, context, item)
+    }
+
+    fn visit_exp(&mut self, context: &mut LintContext, e: &T::Exp) {
+        walk_exp(self, context, e)
+    }
+}
+
+pub fn walk_function<V: TypingVisitor + ?Sized>(
+    visitor: &mut V,
+    context: &mut LintContext,
+    fdef: &T::Function,
+) {
+    match &fdef.body.value {
+        T::FunctionBody_::Native => (),
+        T::FunctionBody_::Defined(seq) => visitor.visit_sequence(context, seq),
+    }
+}
+
+pub fn walk_sequence<V: TypingVisitor + ?Sized>(
+    visitor: &mut V,
+    context: &mut LintContext,
+    seq: &T::Sequence,
+) {
+    for item in seq {
+        visitor.visit_seq_item(context, item)
+    }
+}
+
+pub fn walk_seq_item<V: TypingVisitor + ?Sized>(
+    visitor: &mut V,
+    context: &mut LintContext,
+    sp!(_, item_): &T::SequenceItem,
+) {
+    use T::SequenceItem_ as S;
+    match item_ {
+        S::Declare(_) => (),
+        S::Seq(e) | S::Bind(_, _, e) => visitor.visit_exp(context, e),
+    }
+}
+
+pub fn walk_exp<V: TypingVisitor + ?Sized>(visitor: &mut V, context: &mut LintContext, e: &T::Exp) {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Use(_)
+        | E::Constant(_, _)
+        | E::BorrowLocal(_, _)
+        | E::Break
+        | E::Continue
+        | E::Spec(_, _)
+        | E::UnresolvedError => (),
+
+        E::ModuleCall(call) => visitor.visit_exp(context, &call.arguments),
+        E::Builtin(_, e)
+        | E::Vector(_, _, _, e)
+        | E::VarCall(_, e)
+        | E::Loop { body: e, .. }
+        | E::Assign(_, _, e)
+        | E::Return(e)
+        | E::Abort(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _)
+        | E::TempBorrow(_, e)
+        | E::Cast(e, _)
+        | E::Annotate(e, _) => visitor.visit_exp(context, e),
+        E::IfElse(eb, et, ef) => {
+            visitor.visit_exp(context, eb);
+            visitor.visit_exp(context, et);
+            visitor.visit_exp(context, ef)
+        }
+        E::While(e1, e2) | E::Mutate(e1, e2) | E::BinopExp(e1, _, _, e2) => {
+            visitor.visit_exp(context, e1);
+            visitor.visit_exp(context, e2)
+        }
+        E::Block(seq) => visitor.visit_sequence(context, seq),
+        E::Pack(_, _, _, fields) => {
+            for (_, _, (_, (_, e))) in fields {
+                visitor.visit_exp(context, e)
+            }
+        }
+        E::ExpList(items) => {
+            for item in items {
+                match item {
+                    T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => {
+                        visitor.visit_exp(context, e)
+                    }
+                }
+            }
+        }
+    }
+}
+
+//**************************************************************************************************
+// Helpers
+//**************************************************************************************************
+
+/// Whether the call is to `<address>::<module>::<function>`
+fn is_call_to(call: &T::ModuleCall, address: AccountAddress, module: &str, function: &str) -> bool {
+    let E::ModuleIdent_ {
+        address: call_address,
+        module: call_module,
+    } = &call.module.value;
+    call_address.into_addr_bytes().into_inner() == address
+        && call_module.value().as_str() == module
+        && call.name.value().as_str() == function
+}
+
+/// The call to `balance::transfer` in `e`, if any
+fn balance_transfer(e: &T::Exp) -> Option<&T::ModuleCall> {
+    match &e.exp.value {
+        T::UnannotatedExp_::ModuleCall(call)
+            if is_call_to(call, STDLIB_ADDRESS, BALANCE_MODULE, TRANSFER_FUNCTION) =>
+        {
+            Some(call)
+        }
+        T::UnannotatedExp_::Annotate(e, _) => balance_transfer(e),
+        _ => None,
+    }
+}
+
+/// The arguments of a call, one expression per parameter
+fn call_arguments(call: &T::ModuleCall) -> Vec<&T::Exp> {
+    match &call.arguments.exp.value {
+        T::UnannotatedExp_::ExpList(items) => items
+            .iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) | T::ExpListItem::Splat(_, e, _) => e,
+            })
+            .collect(),
+        T::UnannotatedExp_::Unit { .. } => vec![],
+        _ => vec![&call.arguments],
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LintLevel::None => write!(f, "none"),
+            LintLevel::Default => write!(f, "default"),
+            LintLevel::All => write!(f, "all"),
+        }
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(LintLevel::None),
+            "default" => Ok(LintLevel::Default),
+            "all" => Ok(LintLevel::All),
+            _ => Err(format!(
+                "Unknown lint level '{}'. Expected one of: 'none', 'default', 'all'",
+                s
+            )),
+        }
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `acquires` annotations of native functions. Only natives are checked: typing already
+//! rejects the annotations of Move functions that are not needed (see `typing::globals`), but
+//! natives have no body to check them against, even though they never access the resources of
+//! the module. The annotation then needlessly propagates to every caller.
+
+use super::{LintContext, TypingVisitor};
+use crate::{diag, parser::ast::FunctionName, typing::ast as T};
+
+pub const NAME: &str = "native_acquires";
+
+pub fn visitor() -> Box<dyn TypingVisitor> {
+    Box::new(NativeAcquires)
+}
+
+struct NativeAcquires;
+
+impl TypingVisitor for NativeAcquires {
+    fn visit_function(
+        &mut self,
+        context: &mut LintContext,
+        name: FunctionName,
+        fdef: &T::Function,
+    ) {
+        if !matches!(fdef.body.value, T::FunctionBody_::Native) {
+            return;
+        }
+        for (resource, loc) in &fdef.acquires {
+            let msg = format!(
+                "Unneeded 'acquires' of '{}'. The native function '{}' does not access the \
+                 resources of its module, but all of its callers must list them as well",
+                resource, name
+            );
+            context.add_diag(diag!(Linter::NativeAcquires, (*loc, msg)))
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports `public` functions that look like transaction entry points: they take a signer, only
+//! take parameters a transaction can provide, return nothing and are not called anywhere in the
+//! program. Such functions can only be reached through a script unless they are `entry`.
+
+use super::{walk_exp, LintContext, TypingVisitor};
+use crate::{
+    diag,
+    expansion::ast::{AttributeName_, ModuleIdent, Visibility},
+    naming::ast::{BuiltinTypeName_, Type, TypeName_, Type_},
+    parser::ast::FunctionName,
+    shared::{known_attributes::KnownAttribute, Identifier},
+    typing::ast as T,
+};
+use std::collections::BTreeSet;
+
+pub const NAME: &str = "public_entry";
+
+pub fn visitor() -> Box<dyn TypingVisitor> {
+    Box::<PublicEntry>::default()
+}
+
+#[derive(Default)]
+struct PublicEntry {
+    // all functions called in the program, computed on the first visited function
+    called: Option<BTreeSet<(ModuleIdent, FunctionName)>>,
+}
+
+impl TypingVisitor for PublicEntry {
+    fn visit_function(
+        &mut self,
+        context: &mut LintContext,
+        name: FunctionName,
+        fdef: &T::Function,
+    ) {
+        let mident = match context.current_module {
+            Some(mident) => mident,
+            None => return,
+        };
+        if !matches!(fdef.visibility, Visibility::Public(_))
+            || fdef.entry.is_some()
+            || matches!(fdef.body.value, T::FunctionBody_::Native)
+            || !matches!(fdef.signature.return_type.value, Type_::Unit)
+            || is_test(fdef)
+        {
+            return;
+        }
+        let parameters = &fdef.signature.parameters;
+        if !parameters.iter().any(|(_, ty)| is_signer(ty))
+            || !parameters.iter().all(|(_, ty)| is_entry_parameter(ty))
+        {
+            return;
+        }
+        let called = self.called.get_or_insert_with(|| called_functions(context));
+        if called.contains(&(mident, name)) {
+            return;
+        }
+        let msg = format!(
+            "Public function '{}' takes a signer but is not called by any function. Consider \
+             making it 'public entry' so that it can be called in a transaction",
+            name
+        );
+        context.add_diag(diag!(Linter::PublicEntry, (name.loc(), msg)))
+    }
+}
+
+fn is_test(fdef: &T::Function) -> bool {
+    fdef.attributes
+        .iter()
+        .any(|(_, name_, _)| matches!(name_, AttributeName_::Known(KnownAttribute::Testing(_))))
+}
+
+fn is_signer(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Ref(false, inner) => is_signer(inner),
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, b))), _) => {
+            matches!(b, BuiltinTypeName_::Signer)
+        }
+        _ => false,
+    }
+}
+
+fn is_entry_parameter(ty: &Type) -> bool {
+    is_signer(ty) || is_entry_value(ty)
+}
+
+/// Primitive values and vectors of them
+fn is_entry_value(ty: &Type) -> bool {
+    match &ty.value {
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, b))), ty_args) => match b {
+            BuiltinTypeName_::Signer => false,
+            BuiltinTypeName_::Vector => ty_args.iter().all(is_entry_value),
+            _ => true,
+        },
+        _ => false,
+    }
+}
+
+fn called_functions(context: &mut LintContext) -> BTreeSet<(ModuleIdent, FunctionName)> {
+    let mut collector = CallCollector(BTreeSet::new());
+    let program = context.program;
+    let functions = program
+        .modules
+        .iter()
+        .flat_map(|(_, _, mdef)| mdef.functions.key_cloned_iter())
+        .chain(
+            program
+                .scripts
+                .values()
+                .map(|script| (script.function_name, &script.function)),
+        );
+    for (name, fdef) in functions {
+        collector.visit_function(context, name, fdef)
+    }
+    collector.0
+}
+
+struct CallCollector(BTreeSet<(ModuleIdent, FunctionName)>);
+
+impl TypingVisitor for CallCollector {
+    fn visit_exp(&mut self, context: &mut LintContext, e: &T::Exp) {
+        if let T::UnannotatedExp_::ModuleCall(call) = &e.exp.value {
+            self.0.insert((call.module, call.name));
+        }
+        walk_exp(self, context, e)
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports calls to `balance::transfer` that send the funds back to the signing account, i.e.
+//! where the destination is `signer::address_of` of the source signer.
+
+use super::{
+    balance_transfer, call_arguments, is_call_to, walk_exp, walk_function, walk_seq_item,
+    LintContext, TypingVisitor, ADDRESS_OF_FUNCTION, SIGNER_MODULE, STDLIB_ADDRESS,
+};
+use crate::{
+    diag,
+    parser::ast::{FunctionName, Var},
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+use std::collections::BTreeMap;
+
+pub const NAME: &str = "self_transfer";
+
+pub fn visitor() -> Box<dyn TypingVisitor> {
+    Box::<SelfTransfer>::default()
+}
+
+#[derive(Default)]
+struct SelfTransfer {
+    // locals holding the address of a signer, mapped to the local of that signer
+    signer_addresses: BTreeMap<Var, Var>,
+}
+
+impl TypingVisitor for SelfTransfer {
+    fn visit_function(
+        &mut self,
+        context: &mut LintContext,
+        _name: FunctionName,
+        fdef: &T::Function,
+    ) {
+        self.signer_addresses.clear();
+        walk_function(self, context, fdef)
+    }
+
+    fn visit_seq_item(&mut self, context: &mut LintContext, item: &T::SequenceItem) {
+        walk_seq_item(self, context, item);
+        if let T::SequenceItem_::Bind(lvalues, _, e) = &item.value {
+            self.assign(lvalues, e)
+        }
+    }
+
+    fn visit_exp(&mut self, context: &mut LintContext, e: &T::Exp) {
+        walk_exp(self, context, e);
+        match &e.exp.value {
+            T::UnannotatedExp_::Assign(lvalues, _, rhs) => self.assign(lvalues, rhs),
+            _ => {
+                if let Some(call) = balance_transfer(e) {
+                    self.check_transfer(context, e.exp.loc, call)
+                }
+            }
+        }
+    }
+}
+
+impl SelfTransfer {
+    fn assign(&mut self, sp!(_, lvalues): &T::LValueList, e: &T::Exp) {
+        for sp!(_, lvalue) in lvalues {
+            if let T::LValue_::Var(var, _) = lvalue {
+                self.signer_addresses.remove(var);
+            }
+        }
+        if let [sp!(_, T::LValue_::Var(var, _))] = lvalues.as_slice() {
+            if let Some(signer) = signer_address(e) {
+                self.signer_addresses.insert(*var, signer);
+            }
+        }
+    }
+
+    fn check_transfer(&self, context: &mut LintContext, loc: Loc, call: &T::ModuleCall) {
+        let (src, dst) = match call_arguments(call).as_slice() {
+            [src, dst, _] => (*src, *dst),
+            _ => return,
+        };
+        let src = match local(src) {
+            Some(src) => src,
+            None => return,
+        };
+        let dst_signer = match local(dst) {
+            Some(var) => self.signer_addresses.get(&var).copied(),
+            None => signer_address(dst),
+        };
+        if dst_signer == Some(src) {
+            let msg = format!(
+                "Invalid transfer. The funds are sent back to the account of '{}'",
+                src
+            );
+            context.add_diag(diag!(
+                Linter::SelfTransfer,
+                (loc, msg),
+                (
+                    dst.exp.loc,
+                    "The destination is the address of the source signer"
+                ),
+            ))
+        }
+    }
+}
+
+/// The signer local `e` is `signer::address_of` of, if any
+fn signer_address(e: &T::Exp) -> Option<Var> {
+    match &e.exp.value {
+        T::UnannotatedExp_::ModuleCall(call)
+            if is_call_to(call, STDLIB_ADDRESS, SIGNER_MODULE, ADDRESS_OF_FUNCTION) =>
+        {
+            match call_arguments(call).as_slice() {
+                [signer] => local(signer),
+                _ => None,
+            }
+        }
+        T::UnannotatedExp_::Annotate(e, _) => signer_address(e),
+        _ => None,
+    }
+}
+
+/// The local `e` reads or borrows, if any
+fn local(e: &T::Exp) -> Option<Var> {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+            Some(*var)
+        }
+        E::TempBorrow(_, e) | E::Dereference(e) | E::Annotate(e, _) => local(e),
+        E::Builtin(b, e) if matches!(b.value, T::BuiltinFunction_::Freeze(_)) => local(e),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Reports calls to `balance::transfer` whose result is dropped. The transfer returns `false`
+//! instead of aborting when it cannot be done, so ignoring the result hides failed transfers.
+
+use super::{balance_transfer, walk_exp, walk_seq_item, walk_sequence, LintContext, TypingVisitor};
+use crate::{diag, typing::ast as T};
+
+pub const NAME: &str = "unchecked_transfer";
+
+pub fn visitor() -> Box<dyn TypingVisitor> {
+    Box::new(UncheckedTransfer)
+}
+
+struct UncheckedTransfer;
+
+impl TypingVisitor for UncheckedTransfer {
+    fn visit_sequence(&mut self, context: &mut LintContext, seq: &T::Sequence) {
+        // the last item is the value of the sequence
+        for item in seq.iter().take(seq.len().saturating_sub(1)) {
+            if let T::SequenceItem_::Seq(e) = &item.value {
+                check_ignored(context, e)
+            }
+        }
+        walk_sequence(self, context, seq)
+    }
+
+    fn visit_seq_item(&mut self, context: &mut LintContext, item: &T::SequenceItem) {
+        if let T::SequenceItem_::Bind(sp!(_, lvalues), _, e) = &item.value {
+            if is_ignore(lvalues) {
+                check_ignored(context, e)
+            }
+        }
+        walk_seq_item(self, context, item)
+    }
+
+    fn visit_exp(&mut self, context: &mut LintContext, e: &T::Exp) {
+        if let T::UnannotatedExp_::Assign(sp!(_, lvalues), _, rhs) = &e.exp.value {
+            if is_ignore(lvalues) {
+                check_ignored(context, rhs)
+            }
+        }
+        walk_exp(self, context, e)
+    }
+}
+
+fn is_ignore(lvalues: &T::LValueList_) -> bool {
+    matches!(lvalues.as_slice(), [sp!(_, T::LValue_::Ignore)])
+}
+
+fn check_ignored(context: &mut LintContext, e: &T::Exp) {
+    if balance_transfer(e).is_some() {
+        let msg = "The result of 'balance::transfer' is ignored. The transfer returns 'false' \
+                   instead of aborting if it could not be done";
+        context.add_diag(diag!(
+            Linter::UncheckedTransfer,
+            (e.exp.loc, msg),
+            (e.exp.loc, "Check the result, e.g. with 'assert!'"),
+        ))
+    }
+}
@@ -50,6 +50,12 @@ struct Context<'env> {
     scoped_functions: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
     unscoped_constants: BTreeMap<Symbol, Loc>,
     scoped_constants: BTreeMap<ModuleIdent, BTreeMap<Symbol, Loc>>,
+    // function typed parameters of the current inline function, called with `VarCall`
+    // function typed parameters of the current inline function, with their arity
+    lambda_params: BTreeMap<Symbol, usize>,
+    in_inline_function: bool,
+    // number of loops entered in the innermost enclosing lambda, if any
+    lambda_loop_depth: Option<usize>,
 }
 
 impl<'env> Context<'env> {
@@ -117,6 +123,9 @@ impl<'env> Context<'env> {
             scoped_constants,
             unscoped_types,
             unscoped_constants: BTreeMap::new(),
+            lambda_params: BTreeMap::new(),
+            in_inline_function: false,
+            lambda_loop_depth: None,
         }
     }
 
@@ -305,6 +314,32 @@ impl<'env> Context<'env> {
         }
     }
 
+    fn enter_loop(&mut self) {
+        if let Some(depth) = &mut self.lambda_loop_depth {
+            *depth += 1
+        }
+    }
+
+    fn exit_loop(&mut self) {
+        if let Some(depth) = &mut self.lambda_loop_depth {
+            *depth -= 1
+        }
+    }
+
+    // A lambda is expanded inside of the inline function body, so its loop control must not
+    // refer to a loop outside of the lambda
+    fn check_lambda_loop_control(&mut self, loc: Loc, case: &str) {
+        if self.lambda_loop_depth == Some(0) {
+            let msg = format!(
+                "Invalid usage of '{}'. '{}' in a lambda can only be used inside a loop of the \
+                 lambda body",
+                case, case
+            );
+            self.env
+                .add_diag(diag!(TypeSafety::InvalidLoopControl, (loc, msg)));
+        }
+    }
+
     fn bind_type(&mut self, s: Symbol, rt: ResolvedType) {
         self.unscoped_types.insert(s, rt);
     }
@@ -490,6 +525,7 @@ fn function(
         loc: _,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -497,11 +533,25 @@ fn function(
     } = ef;
     let signature = function_signature(context, signature);
     let acquires = function_acquires(context, acquires);
+    if inline {
+        context.lambda_params = signature
+            .parameters
+            .iter()
+            .filter_map(|(v, ty)| match &ty.value {
+                N::Type_::Fun(params, _) => Some((v.value(), params.len())),
+                _ => None,
+            })
+            .collect();
+    }
+    context.in_inline_function = inline;
     let body = function_body(context, body);
+    context.in_inline_function = false;
+    context.lambda_params.clear();
     let f = N::Function {
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -794,7 +844,8 @@ fn type_(context: &mut Context, sp!(loc, ety_): E::Type) -> N::Type {
                 }
             }
         }
-        ET::Fun(_, _) => panic!("ICE only allowed in spec context"),
+        // only allowed as the type of an inline function parameter, checked in expansion
+        ET::Fun(args, result) => NT::Fun(types(context, args), Box::new(type_(context, *result))),
     };
     sp(loc, ty_)
 }
@@ -906,9 +957,32 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
         EE::IfElse(eb, et, ef) => {
             NE::IfElse(exp(context, *eb), exp(context, *et), exp(context, *ef))
         }
-        EE::While(eb, el) => NE::While(exp(context, *eb), exp(context, *el)),
-        EE::Loop(el) => NE::Loop(exp(context, *el)),
+        EE::While(eb, el) => {
+            context.enter_loop();
+            let e = NE::While(exp(context, *eb), exp(context, *el));
+            context.exit_loop();
+            e
+        }
+        EE::Loop(el) => {
+            context.enter_loop();
+            let e = NE::Loop(exp(context, *el));
+            context.exit_loop();
+            e
+        }
         EE::Block(seq) => NE::Block(sequence(context, seq)),
+        EE::Lambda(eb, ebody) => {
+            let outer_depth = context.lambda_loop_depth.replace(0);
+            let nb_opt = bind_list(context, eb);
+            let nbody = exp(context, *ebody);
+            context.lambda_loop_depth = outer_depth;
+            match nb_opt {
+                None => {
+                    assert!(context.env.has_errors());
+                    NE::UnresolvedError
+                }
+                Some(nb) => NE::Lambda(nb, nbody),
+            }
+        }
 
         EE::Assign(a, e) => {
             let na_opt = assign_list(context, a);
@@ -938,10 +1012,25 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             NE::Mutate(nel, ner)
         }
 
-        EE::Return(es) => NE::Return(exp(context, *es)),
+        EE::Return(es) => {
+            if context.in_inline_function || context.lambda_loop_depth.is_some() {
+                let msg = "Invalid usage of 'return'. Inline function bodies and lambdas are \
+                           expanded at their call sites and cannot return";
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::InvalidInlineControl, (eloc, msg)));
+            }
+            NE::Return(exp(context, *es))
+        }
         EE::Abort(es) => NE::Abort(exp(context, *es)),
-        EE::Break => NE::Break,
-        EE::Continue => NE::Continue,
+        EE::Break => {
+            context.check_lambda_loop_control(eloc, "break");
+            NE::Break
+        }
+        EE::Continue => {
+            context.check_lambda_loop_control(eloc, "continue");
+            NE::Continue
+        }
 
         EE::Dereference(e) => NE::Dereference(exp(context, *e)),
         EE::UnaryExp(uop, e) => NE::UnaryExp(uop, exp(context, *e)),
@@ -1024,6 +1113,36 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                     }
                 }
 
+                EA::Name(n) if context.lambda_params.contains_key(&n.value) => {
+                    if let Some(ty_args) = ty_args {
+                        let loc = ty_args.first().map(|ty| ty.loc).unwrap_or(n.loc);
+                        context.env.add_diag(diag!(
+                            NameResolution::TooManyTypeArguments,
+                            (loc, "Lambda parameters cannot take type arguments"),
+                        ));
+                    }
+                    let arity = context.lambda_params[&n.value];
+                    let given = nes.value.len();
+                    if given != arity {
+                        let code = if given < arity {
+                            TypeSafety::TooFewArguments
+                        } else {
+                            TypeSafety::TooManyArguments
+                        };
+                        let msg = format!(
+                            "Invalid call of '{}'. The call expected {} argument(s) but got {}",
+                            n, arity, given
+                        );
+                        context.env.add_diag(diag!(
+                            code,
+                            (eloc, msg),
+                            (nes.loc, format!("Found {} argument(s) here", given)),
+                        ));
+                        NE::UnresolvedError
+                    } else {
+                        NE::VarCall(Var(n), nes)
+                    }
+                }
                 EA::Name(n) => {
                     context.env.add_diag(diag!(
                         NameResolution::UnboundUnscopedName,
@@ -1068,7 +1187,7 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             NE::UnresolvedError
         }
         // `Name` matches name variants only allowed in specs (we handle the allowed ones above)
-        EE::Index(..) | EE::Lambda(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
+        EE::Index(..) | EE::Quant(..) | EE::Name(_, Some(_)) => {
             panic!("ICE unexpected specification construct")
         }
     };
@@ -232,6 +232,7 @@ new_name!(FunctionName);
 
 pub const NATIVE_MODIFIER: &str = "native";
 pub const ENTRY_MODIFIER: &str = "entry";
+pub const INLINE_MODIFIER: &str = "inline";
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct FunctionSignature {
@@ -265,6 +266,8 @@ pub struct Function {
     pub loc: Loc,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    // inline functions are expanded at their call sites and take lambda arguments
+    pub inline: bool,
     pub signature: FunctionSignature,
     pub acquires: Vec<NameAccessChain>,
     pub name: FunctionName,
@@ -589,11 +592,14 @@ pub enum Exp_ {
     While(Box<Exp>, Box<Exp>),
     // loop eloop
     Loop(Box<Exp>),
+    // for (x in e1..e2) eloop
+    // for (x in e) eloop, where e is a vector reference
+    For(Var, Box<Exp>, Box<Exp>),
 
     // { seq }
     Block(Sequence),
     // fun (x1, ..., xn) e
-    Lambda(BindList, Box<Exp>), // spec only
+    Lambda(BindList, Box<Exp>), // spec only, or as an argument of an inline function
     // forall/exists x1 : e1, ..., xn [{ t1, .., tk } *] [where cond]: en.
     Quant(
         QuantKind,
@@ -1462,6 +1468,7 @@ impl AstDebug for Function {
             loc: _loc,
             visibility,
             entry,
+            inline,
             signature,
             acquires,
             name,
@@ -1472,6 +1479,9 @@ impl AstDebug for Function {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -1761,6 +1771,12 @@ impl AstDebug for Exp_ {
                 w.write("loop ");
                 e.ast_debug(w);
             }
+            E::For(v, iter, e) => {
+                w.write(&format!("for ({} in ", v));
+                iter.ast_debug(w);
+                w.write(")");
+                e.ast_debug(w);
+            }
             E::Block(seq) => w.block(|w| seq.ast_debug(w)),
             E::Lambda(sp!(_, bs), e) => {
                 w.write("fun ");
@@ -12,6 +12,7 @@ pub const KEYWORDS: &[&str] = &[
     "copy",
     "else",
     "false",
+    "for",
     "friend",
     "fun",
     "has",
@@ -70,6 +70,7 @@ pub enum Tok {
     True,
     Use,
     While,
+    For,
     LBrace,
     Pipe,
     PipePipe,
@@ -144,6 +145,7 @@ impl fmt::Display for Tok {
             True => "true",
             Use => "use",
             While => "while",
+            For => "for",
             LBrace => "{",
             Pipe => "|",
             PipePipe => "||",
@@ -631,6 +633,7 @@ fn get_name_token(name: &str) -> Tok {
         "copy" => Tok::Copy,
         "else" => Tok::Else,
         "false" => Tok::False,
+        "for" => Tok::For,
         "fun" => Tok::Fun,
         "friend" => Tok::Friend,
         "if" => Tok::If,
@@ -428,6 +428,7 @@ struct Modifiers {
     visibility: Option<Visibility>,
     entry: Option<Loc>,
     native: Option<Loc>,
+    inline: Option<Loc>,
 }
 
 impl Modifiers {
@@ -436,14 +437,15 @@ impl Modifiers {
             visibility: None,
             entry: None,
             native: None,
+            inline: None,
         }
     }
 }
 
-// Parse module member modifiers: visiblility and native.
+// Parse module member modifiers: visiblility, native, entry and inline.
 // The modifiers are also used for script-functions
 //      ModuleMemberModifiers = <ModuleMemberModifier>*
-//      ModuleMemberModifier = <Visibility> | "native"
+//      ModuleMemberModifier = <Visibility> | "native" | "entry" | "inline"
 // ModuleMemberModifiers checks for uniqueness, meaning each individual ModuleMemberModifier can
 // appear only once
 fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box<Diagnostic>> {
@@ -491,6 +493,20 @@ fn parse_module_member_modifiers(context: &mut Context) -> Result<Modifiers, Box
                 }
                 mods.entry = Some(loc)
             }
+            Tok::Identifier if context.tokens.content() == INLINE_MODIFIER => {
+                let loc = current_token_loc(context.tokens);
+                context.tokens.advance()?;
+                if let Some(prev_loc) = mods.inline {
+                    let msg = format!("Duplicate '{}' modifier", INLINE_MODIFIER);
+                    let prev_msg = format!("'{}' modifier previously given here", INLINE_MODIFIER);
+                    context.env.add_diag(diag!(
+                        Declarations::DuplicateItem,
+                        (loc, msg),
+                        (prev_loc, prev_msg)
+                    ))
+                }
+                mods.inline = Some(loc)
+            }
             _ => break,
         }
     }
@@ -703,15 +719,20 @@ fn parse_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
 // Parse a list of bindings for lambda.
 //      LambdaBindList =
 //          "|" Comma<Bind> "|"
+//          | "||"
 fn parse_lambda_bind_list(context: &mut Context) -> Result<BindList, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
-    let b = parse_comma_list(
-        context,
-        Tok::Pipe,
-        Tok::Pipe,
-        parse_bind,
-        "a variable or structure binding",
-    )?;
+    let b = if match_token(context.tokens, Tok::PipePipe)? {
+        vec![]
+    } else {
+        parse_comma_list(
+            context,
+            Tok::Pipe,
+            Tok::Pipe,
+            parse_bind,
+            "a variable or structure binding",
+        )?
+    };
     let end_loc = context.tokens.previous_end_loc();
     Ok(spanned(context.tokens.file_hash(), start_loc, end_loc, b))
 }
@@ -890,6 +911,8 @@ fn parse_sequence(context: &mut Context) -> Result<Sequence, Box<Diagnostic>> {
 //          | "if" "(" <Exp> ")" <Exp> ("else" <Exp>)?
 //          | "while" "(" <Exp> ")" "{" <Exp> "}"
 //          | "while" "(" <Exp> ")" <Exp> (SpecBlock)?
+//          | "for" "(" <Var> "in" <Exp> ")" "{" <Exp> "}"
+//          | "for" "(" <Var> "in" <Exp> ")" <Exp>
 //          | "loop" <Exp>
 //          | "loop" "{" <Exp> "}"
 //          | "return" "{" <Exp> "}"
@@ -1039,7 +1062,7 @@ fn parse_term(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
 fn is_control_exp(tok: Tok) -> bool {
     matches!(
         tok,
-        Tok::If | Tok::While | Tok::Loop | Tok::Return | Tok::Abort
+        Tok::If | Tok::While | Tok::For | Tok::Loop | Tok::Return | Tok::Abort
     )
 }
 
@@ -1122,6 +1145,19 @@ fn parse_control_exp(context: &mut Context) -> Result<(Exp, bool), Box<Diagnosti
             };
             (Exp_::While(Box::new(econd), Box::new(eloop)), ends_in_block)
         }
+        Tok::For => {
+            context.tokens.advance()?;
+            consume_token(context.tokens, Tok::LParen)?;
+            let var = parse_var(context)?;
+            consume_identifier(context.tokens, "in")?;
+            let eiter = parse_exp(context)?;
+            consume_token(context.tokens, Tok::RParen)?;
+            let (eloop, ends_in_block) = parse_exp_or_sequence(context)?;
+            (
+                Exp_::For(var, Box::new(eiter), Box::new(eloop)),
+                ends_in_block,
+            )
+        }
         Tok::Loop => {
             context.tokens.advance()?;
             let (eloop, ends_in_block) = parse_exp_or_sequence(context)?;
@@ -1261,19 +1297,20 @@ fn at_start_of_exp(context: &mut Context) -> bool {
             | Tok::Loop
             | Tok::Return
             | Tok::While
+            | Tok::For
     )
 }
 
 // Parse an expression:
 //      Exp =
-//            <LambdaBindList> <Exp>        spec only
+//            <LambdaBindList> <Exp>        spec only, or as an argument of an inline function
 //          | <Quantifier>                  spec only
 //          | <BinOpExp>
 //          | <UnaryExp> "=" <Exp>
 fn parse_exp(context: &mut Context) -> Result<Exp, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
     let exp = match context.tokens.peek() {
-        Tok::Pipe => {
+        Tok::Pipe | Tok::PipePipe => {
             let bindings = parse_lambda_bind_list(context)?;
             let body = Box::new(parse_exp(context)?);
             Exp_::Lambda(bindings, body)
@@ -1671,7 +1708,8 @@ fn make_builtin_call(loc: Loc, name: Symbol, type_args: Option<Vec<Type>>, args:
 //          <NameAccessChain> ('<' Comma<Type> ">")?
 //          | "&" <Type>
 //          | "&mut" <Type>
-//          | "|" Comma<Type> "|" Type   (spec only)
+//          | "|" Comma<Type> "|" Type?  (spec only, or as an inline function parameter type)
+//          | "||" Type?                 (spec only, or as an inline function parameter type)
 //          | "(" Comma<Type> ")"
 fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
     let start_loc = context.tokens.start_loc();
@@ -1694,9 +1732,24 @@ fn parse_type(context: &mut Context) -> Result<Type, Box<Diagnostic>> {
             let t = parse_type(context)?;
             Type_::Ref(true, Box::new(t))
         }
-        Tok::Pipe => {
-            let args = parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?;
-            let result = parse_type(context)?;
+        Tok::Pipe | Tok::PipePipe => {
+            let args = if match_token(context.tokens, Tok::PipePipe)? {
+                vec![]
+            } else {
+                parse_comma_list(context, Tok::Pipe, Tok::Pipe, parse_type, "a type")?
+            };
+            // the result type is unit if omitted
+            let result = match context.tokens.peek() {
+                Tok::Comma | Tok::RParen | Tok::Greater | Tok::LBrace | Tok::Semicolon => {
+                    let loc = make_loc(
+                        context.tokens.file_hash(),
+                        context.tokens.previous_end_loc(),
+                        context.tokens.previous_end_loc(),
+                    );
+                    sp(loc, Type_::Unit)
+                }
+                _ => parse_type(context)?,
+            };
             return Ok(spanned(
                 context.tokens.file_hash(),
                 start_loc,
@@ -1880,7 +1933,23 @@ fn parse_function_decl(
         visibility,
         mut entry,
         native,
+        inline,
     } = modifiers;
+    if let Some(loc) = inline {
+        let conflict = native
+            .map(|_| NATIVE_MODIFIER)
+            .or_else(|| entry.map(|_| ENTRY_MODIFIER));
+        if let Some(modifier) = conflict {
+            let msg = format!(
+                "Invalid function declaration. '{}' functions cannot be '{}' as they are \
+                 expanded at their call sites",
+                modifier, INLINE_MODIFIER
+            );
+            context
+                .env
+                .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+        }
+    }
 
     if let Some(Visibility::Script(vloc)) = visibility {
         let msg = format!(
@@ -1970,6 +2039,7 @@ fn parse_function_decl(
         loc,
         visibility: visibility.unwrap_or(Visibility::Internal),
         entry,
+        inline: inline.is_some(),
         signature,
         acquires,
         name,
@@ -2006,6 +2076,7 @@ fn parse_struct_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = format!(
@@ -2026,6 +2097,15 @@ fn parse_struct_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid struct declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
 
     consume_token(context.tokens, Tok::Struct)?;
 
@@ -2118,6 +2198,7 @@ fn parse_constant_decl(
         visibility,
         entry,
         native,
+        inline,
     } = modifiers;
     if let Some(vis) = visibility {
         let msg = "Invalid constant declaration. Constants cannot have visibility modifiers as \
@@ -2141,6 +2222,15 @@ fn parse_constant_decl(
             .env
             .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
     }
+    if let Some(loc) = inline {
+        let msg = format!(
+            "Invalid constant declaration. '{}' is used only on functions",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
     consume_token(context.tokens, Tok::Const)?;
     let name = ConstantName(parse_identifier(context)?);
     consume_token(context.tokens, Tok::Colon)?;
@@ -2481,6 +2571,15 @@ fn parse_script(
     let function_start_loc = context.tokens.start_loc();
     let modifiers = parse_module_member_modifiers(context)?;
     // don't need to check native modifier, it is checked later
+    if let Some(loc) = modifiers.inline {
+        let msg = format!(
+            "Invalid 'script' function declaration. Script functions cannot be '{}'",
+            INLINE_MODIFIER
+        );
+        context
+            .env
+            .add_diag(diag!(Syntax::InvalidModifier, (loc, msg)));
+    }
     let function =
         parse_function_decl(next_item_attributes, function_start_loc, modifiers, context)?;
 
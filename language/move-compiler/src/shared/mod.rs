@@ -4,7 +4,11 @@
 
 use crate::{
     command_line as cli,
-    diagnostics::{codes::Severity, Diagnostic, Diagnostics},
+    diagnostics::{
+        codes::{DiagnosticCode, Severity, TypeSafety},
+        Diagnostic, Diagnostics,
+    },
+    linters::LintLevel,
     naming::ast::ModuleDefinition,
 };
 use clap::*;
@@ -12,7 +16,7 @@ use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
@@ -176,10 +180,30 @@ pub struct IndexedPackagePath {
 
 pub type AttributeDeriver = dyn Fn(&mut CompilationEnv, &mut ModuleDefinition);
 
+/// A call of an inline function, expanded in the body of the calling function
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InlinedCall {
+    pub loc: Loc,
+    // name of the called function
+    pub function: String,
+    // whether the called function is declared in another module than the calling function
+    pub foreign: bool,
+    // the body of the called function and the lambdas given as arguments, expanded with the call
+    pub expanded: Vec<Loc>,
+}
+
+impl InlinedCall {
+    fn expands(&self, loc: Loc) -> bool {
+        self.expanded.iter().any(|expanded| expanded.contains(loc))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CompilationEnv {
     flags: Flags,
     diags: Diagnostics,
+    // calls of inline functions expanded in a function, keyed by the body of that function
+    inlined_calls: BTreeMap<Loc, Vec<InlinedCall>>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -189,6 +213,7 @@ impl CompilationEnv {
         Self {
             flags,
             diags: Diagnostics::new(),
+            inlined_calls: BTreeMap::new(),
         }
     }
 
@@ -244,6 +269,81 @@ impl CompilationEnv {
     pub fn flags(&self) -> &Flags {
         &self.flags
     }
+
+    pub fn add_inlined_calls(&mut self, function_body: Loc, calls: Vec<InlinedCall>) {
+        if !calls.is_empty() {
+            self.inlined_calls
+                .entry(function_body)
+                .or_default()
+                .extend(calls)
+        }
+    }
+
+    /// Points the diagnostics reported since the first `start` ones, and located in the code of
+    /// an inline function or lambda expanded in `function_body`, to the call that was expanded.
+    /// The location of the error in the expanded code is kept as a secondary label
+    pub fn label_inlined_calls(&mut self, function_body: Loc, start: usize) {
+        if let Some(calls) = self.inlined_calls.get(&function_body) {
+            label_inlined_calls(self.diags.diagnostics_since_mut(start), calls)
+        }
+    }
+
+    /// Points the diagnostics reported since the first `start` ones, while typing the expansion
+    /// of `calls`, to the call that was expanded, as in `label_inlined_calls`
+    pub fn label_inline_expansion(&mut self, calls: &[InlinedCall], start: usize) {
+        label_inlined_calls(self.diags.diagnostics_since_mut(start), calls)
+    }
+}
+
+fn label_inlined_calls(diags: &mut [Diagnostic], calls: &[InlinedCall]) {
+    for diag in diags {
+        let mut locs = vec![diag.primary_loc()];
+        let mut expanded = BTreeSet::new();
+        // follow chains of inline functions expanded in the bodies of other inline functions
+        while let Some(loc) = locs.pop() {
+            for (idx, call) in calls.iter().enumerate() {
+                if call.expands(loc) && !call.expands(call.loc) && expanded.insert(idx) {
+                    locs.push(call.loc);
+                }
+            }
+        }
+        // the first call written in the function itself, and not expanded from another one
+        let outermost = expanded
+            .iter()
+            .map(|idx| &calls[*idx])
+            .filter(|call| !calls.iter().any(|other| other.expands(call.loc)))
+            .min_by_key(|call| call.loc);
+        let outermost = match outermost {
+            Some(call) => call,
+            None => continue,
+        };
+
+        let msg = format!(
+            "Invalid call of '{}'. The code expanded at this call is invalid",
+            outermost.function
+        );
+        diag.set_primary_label((outermost.loc, msg));
+        for call in expanded.iter().map(|idx| &calls[*idx]) {
+            if call.loc != outermost.loc {
+                diag.add_secondary_label((call.loc, "Inlined from this call"));
+            }
+        }
+        let foreign = Some(outermost).filter(|call| call.foreign).or_else(|| {
+            expanded
+                .iter()
+                .map(|idx| &calls[*idx])
+                .find(|call| call.foreign)
+        });
+        if let Some(call) = foreign {
+            if diag.info() == &TypeSafety::Visibility.into_info() {
+                diag.add_note(format!(
+                    "Inline functions are expanded in the calling module. '{}' can only use the \
+                     functions and structs of its module which are visible from there",
+                    call.function
+                ));
+            }
+        }
+    }
 }
 
 //**************************************************************************************************
@@ -309,6 +409,13 @@ pub struct Flags {
     )]
     bytecode_version: Option<u32>,
 
+    /// Lints to run on the source modules: 'none', 'default' or 'all'.
+    #[clap(
+        long = cli::LINT,
+        default_value = "none",
+    )]
+    lint: LintLevel,
+
     /// If set, source files will not shadow dependency files. If the same file is passed to both,
     /// an error will be raised
     #[clap(
@@ -332,6 +439,7 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: LintLevel::None,
             keep_testing_functions: false,
         }
     }
@@ -343,6 +451,7 @@ impl Flags {
             shadow: false,
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: LintLevel::None,
             keep_testing_functions: false,
         }
     }
@@ -354,6 +463,7 @@ impl Flags {
             shadow: true, // allows overlapping between sources and deps
             flavor: "".to_string(),
             bytecode_version: None,
+            lint: LintLevel::None,
             keep_testing_functions: false,
         }
     }
@@ -372,6 +482,10 @@ impl Flags {
         }
     }
 
+    pub fn set_lint_level(self, lint: LintLevel) -> Self {
+        Self { lint, ..self }
+    }
+
     pub fn set_sources_shadow_deps(self, sources_shadow_deps: bool) -> Self {
         Self {
             shadow: sources_shadow_deps,
@@ -406,6 +520,10 @@ impl Flags {
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
+
+    pub fn lint_level(&self) -> LintLevel {
+        self.lint
+    }
 }
 
 //**************************************************************************************************
@@ -434,6 +552,7 @@ pub mod known_attributes {
         Testing(TestingAttribute),
         Verification(VerificationAttribute),
         Native(NativeAttribute),
+        Lint(LintAttribute),
     }
 
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -458,6 +577,12 @@ pub mod known_attributes {
         BytecodeInstruction,
     }
 
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum LintAttribute {
+        // Silences the listed lints for the annotated item
+        Allow,
+    }
+
     impl fmt::Display for AttributePosition {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
@@ -488,6 +613,7 @@ pub mod known_attributes {
                 NativeAttribute::BYTECODE_INSTRUCTION => {
                     Self::Native(NativeAttribute::BytecodeInstruction)
                 }
+                LintAttribute::ALLOW => Self::Lint(LintAttribute::Allow),
                 _ => return None,
             })
         }
@@ -497,6 +623,7 @@ pub mod known_attributes {
                 Self::Testing(a) => a.name(),
                 Self::Verification(a) => a.name(),
                 Self::Native(a) => a.name(),
+                Self::Lint(a) => a.name(),
             }
         }
 
@@ -505,6 +632,7 @@ pub mod known_attributes {
                 Self::Testing(a) => a.expected_positions(),
                 Self::Verification(a) => a.expected_positions(),
                 Self::Native(a) => a.expected_positions(),
+                Self::Lint(a) => a.expected_positions(),
             }
         }
     }
@@ -609,4 +737,28 @@ pub mod known_attributes {
             }
         }
     }
+
+    impl LintAttribute {
+        pub const ALLOW: &'static str = "allow";
+
+        pub const fn name(&self) -> &str {
+            match self {
+                Self::Allow => Self::ALLOW,
+            }
+        }
+
+        pub fn expected_positions(&self) -> &'static BTreeSet<AttributePosition> {
+            static ALLOW_POSITIONS: Lazy<BTreeSet<AttributePosition>> = Lazy::new(|| {
+                IntoIterator::into_iter([
+                    AttributePosition::Module,
+                    AttributePosition::Script,
+                    AttributePosition::Function,
+                ])
+                .collect()
+            });
+            match self {
+                Self::Allow => &ALLOW_POSITIONS,
+            }
+        }
+    }
 }
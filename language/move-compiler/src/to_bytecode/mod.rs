@@ -5,4 +5,5 @@
 #[macro_use]
 mod context;
 mod optimize;
+pub mod size_optimize;
 pub mod translate;
@@ -0,0 +1,366 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::{
+    Bytecode, CompiledModule, ModuleHandle, Signature, SignatureToken, StructFieldInformation,
+    StructHandleIndex, TableIndex,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    hash::Hash,
+};
+
+// Rebuilds the pools and tables of the module keeping only the entries referenced, directly or
+// not, by its definitions. Entries keep their relative order and equal entries are merged, so that
+// unused constants, signatures, handles and identifiers are removed and the pools are deduplicated.
+// Error constants are kept even when unused, for the error maps built from the modules.
+
+// The prefix of the names of the `u64` constants listed in error maps, as in `move-errmapgen`
+const ERROR_CONSTANT_PREFIX: &str = "E";
+
+/// One value per pool or table of a module holding entries that can be removed
+#[derive(Default)]
+struct Tables<T> {
+    identifiers: T,
+    address_identifiers: T,
+    module_handles: T,
+    struct_handles: T,
+    function_handles: T,
+    field_handles: T,
+    struct_def_instantiations: T,
+    function_instantiations: T,
+    field_instantiations: T,
+    signatures: T,
+    constants: T,
+}
+
+/// The indices of the entries referenced in each table
+type Used = Tables<BTreeSet<TableIndex>>;
+
+/// The new index of each entry kept in each table
+type Remapping = Tables<BTreeMap<TableIndex, TableIndex>>;
+
+pub fn optimize(module: &mut CompiledModule, source_map: &mut SourceMap) {
+    let used = used_entries(module, source_map);
+    let remap = compact_tables(module, &used);
+
+    module.self_module_handle_idx.0 = remap.module_handles[&module.self_module_handle_idx.0];
+    for friend in &mut module.friend_decls {
+        *friend = remap.module_handle(friend);
+    }
+    for def in &mut module.struct_defs {
+        def.struct_handle.0 = remap.struct_handles[&def.struct_handle.0];
+        if let StructFieldInformation::Declared(fields) = &mut def.field_information {
+            for field in fields {
+                field.name.0 = remap.identifiers[&field.name.0];
+                field.signature.0 = remap.token(&field.signature.0);
+            }
+        }
+    }
+    for def in &mut module.function_defs {
+        def.function.0 = remap.function_handles[&def.function.0];
+        if let Some(code) = &mut def.code {
+            code.locals.0 = remap.signatures[&code.locals.0];
+            for instr in &mut code.code {
+                remap.instruction(instr);
+            }
+        }
+    }
+    source_map.constant_map = std::mem::take(&mut source_map.constant_map)
+        .into_iter()
+        .filter_map(|(name, idx)| Some((name, *remap.constants.get(&idx)?)))
+        .collect();
+}
+
+//**************************************************************************************************
+// Used entries
+//**************************************************************************************************
+
+fn used_entries(module: &CompiledModule, source_map: &SourceMap) -> Used {
+    let mut used = Used::default();
+    used.module_handles.insert(module.self_module_handle_idx.0);
+    for friend in &module.friend_decls {
+        used.address_identifiers.insert(friend.address.0);
+        used.identifiers.insert(friend.name.0);
+    }
+    for def in &module.struct_defs {
+        used.struct_handles.insert(def.struct_handle.0);
+        if let StructFieldInformation::Declared(fields) = &def.field_information {
+            for field in fields {
+                used.identifiers.insert(field.name.0);
+                mark_token(&mut used.struct_handles, &field.signature.0);
+            }
+        }
+    }
+    for def in &module.function_defs {
+        used.function_handles.insert(def.function.0);
+        if let Some(code) = &def.code {
+            used.signatures.insert(code.locals.0);
+            for instr in &code.code {
+                mark_instruction(&mut used, instr);
+            }
+        }
+    }
+    for (name, idx) in &source_map.constant_map {
+        let constant = &module.constant_pool[*idx as usize];
+        if constant.type_ == SignatureToken::U64
+            && name.0.as_str().starts_with(ERROR_CONSTANT_PREFIX)
+        {
+            used.constants.insert(*idx);
+        }
+    }
+
+    // entries of a table only refer to entries of the tables processed after it
+    for idx in &used.function_instantiations {
+        let inst = &module.function_instantiations[*idx as usize];
+        used.function_handles.insert(inst.handle.0);
+        used.signatures.insert(inst.type_parameters.0);
+    }
+    for idx in &used.field_instantiations {
+        let inst = &module.field_instantiations[*idx as usize];
+        used.field_handles.insert(inst.handle.0);
+        used.signatures.insert(inst.type_parameters.0);
+    }
+    for idx in &used.struct_def_instantiations {
+        let inst = &module.struct_def_instantiations[*idx as usize];
+        used.signatures.insert(inst.type_parameters.0);
+    }
+    for idx in &used.function_handles {
+        let handle = &module.function_handles[*idx as usize];
+        used.module_handles.insert(handle.module.0);
+        used.identifiers.insert(handle.name.0);
+        used.signatures.insert(handle.parameters.0);
+        used.signatures.insert(handle.return_.0);
+    }
+    for idx in &used.signatures {
+        for token in &module.signatures[*idx as usize].0 {
+            mark_token(&mut used.struct_handles, token);
+        }
+    }
+    for idx in &used.struct_handles {
+        let handle = &module.struct_handles[*idx as usize];
+        used.module_handles.insert(handle.module.0);
+        used.identifiers.insert(handle.name.0);
+    }
+    for idx in &used.module_handles {
+        let handle = &module.module_handles[*idx as usize];
+        used.address_identifiers.insert(handle.address.0);
+        used.identifiers.insert(handle.name.0);
+    }
+    used
+}
+
+fn mark_token(struct_handles: &mut BTreeSet<TableIndex>, token: &SignatureToken) {
+    for token in token.preorder_traversal() {
+        match token {
+            SignatureToken::Struct(idx) | SignatureToken::StructInstantiation(idx, _) => {
+                struct_handles.insert(idx.0);
+            }
+            _ => (),
+        }
+    }
+}
+
+fn mark_instruction(used: &mut Used, instr: &Bytecode) {
+    use Bytecode as B;
+    match instr {
+        B::LdConst(idx) => {
+            used.constants.insert(idx.0);
+        }
+        B::Call(idx) => {
+            used.function_handles.insert(idx.0);
+        }
+        B::CallGeneric(idx) => {
+            used.function_instantiations.insert(idx.0);
+        }
+        B::PackGeneric(idx)
+        | B::UnpackGeneric(idx)
+        | B::MutBorrowGlobalGeneric(idx)
+        | B::ImmBorrowGlobalGeneric(idx)
+        | B::ExistsGeneric(idx)
+        | B::MoveFromGeneric(idx)
+        | B::MoveToGeneric(idx) => {
+            used.struct_def_instantiations.insert(idx.0);
+        }
+        B::MutBorrowField(idx) | B::ImmBorrowField(idx) => {
+            used.field_handles.insert(idx.0);
+        }
+        B::MutBorrowFieldGeneric(idx) | B::ImmBorrowFieldGeneric(idx) => {
+            used.field_instantiations.insert(idx.0);
+        }
+        B::VecPack(idx, _)
+        | B::VecLen(idx)
+        | B::VecImmBorrow(idx)
+        | B::VecMutBorrow(idx)
+        | B::VecPushBack(idx)
+        | B::VecPopBack(idx)
+        | B::VecUnpack(idx, _)
+        | B::VecSwap(idx) => {
+            used.signatures.insert(idx.0);
+        }
+        _ => (),
+    }
+}
+
+//**************************************************************************************************
+// Compaction
+//**************************************************************************************************
+
+/// Compacts the tables of `module` referred to by the other parts of the module, in an order such
+/// that the entries of a table are rewritten with the new indices of the tables they refer to.
+fn compact_tables(module: &mut CompiledModule, used: &Used) -> Remapping {
+    let identifiers = compact(&mut module.identifiers, &used.identifiers, Clone::clone);
+    let address_identifiers = compact(
+        &mut module.address_identifiers,
+        &used.address_identifiers,
+        Clone::clone,
+    );
+    let mut remap = Remapping {
+        identifiers,
+        address_identifiers,
+        ..Remapping::default()
+    };
+    remap.module_handles = compact(&mut module.module_handles, &used.module_handles, |handle| {
+        remap.module_handle(handle)
+    });
+    remap.struct_handles = compact(&mut module.struct_handles, &used.struct_handles, |handle| {
+        let mut handle = handle.clone();
+        handle.module.0 = remap.module_handles[&handle.module.0];
+        handle.name.0 = remap.identifiers[&handle.name.0];
+        handle
+    });
+    remap.signatures = compact(&mut module.signatures, &used.signatures, |signature| {
+        Signature(signature.0.iter().map(|token| remap.token(token)).collect())
+    });
+    // constants cannot refer to structs, their types do not need to be rewritten
+    remap.constants = compact(&mut module.constant_pool, &used.constants, Clone::clone);
+    remap.function_handles = compact(
+        &mut module.function_handles,
+        &used.function_handles,
+        |handle| {
+            let mut handle = handle.clone();
+            handle.module.0 = remap.module_handles[&handle.module.0];
+            handle.name.0 = remap.identifiers[&handle.name.0];
+            handle.parameters.0 = remap.signatures[&handle.parameters.0];
+            handle.return_.0 = remap.signatures[&handle.return_.0];
+            handle
+        },
+    );
+    // struct definitions are not removed, field handles do not need to be rewritten
+    remap.field_handles = compact(&mut module.field_handles, &used.field_handles, Clone::clone);
+    remap.struct_def_instantiations = compact(
+        &mut module.struct_def_instantiations,
+        &used.struct_def_instantiations,
+        |inst| {
+            let mut inst = inst.clone();
+            inst.type_parameters.0 = remap.signatures[&inst.type_parameters.0];
+            inst
+        },
+    );
+    remap.function_instantiations = compact(
+        &mut module.function_instantiations,
+        &used.function_instantiations,
+        |inst| {
+            let mut inst = inst.clone();
+            inst.handle.0 = remap.function_handles[&inst.handle.0];
+            inst.type_parameters.0 = remap.signatures[&inst.type_parameters.0];
+            inst
+        },
+    );
+    remap.field_instantiations = compact(
+        &mut module.field_instantiations,
+        &used.field_instantiations,
+        |inst| {
+            let mut inst = inst.clone();
+            inst.handle.0 = remap.field_handles[&inst.handle.0];
+            inst.type_parameters.0 = remap.signatures[&inst.type_parameters.0];
+            inst
+        },
+    );
+    remap
+}
+
+/// Keeps the `used` entries of `table`, rewritten by `rewrite`, merging the ones that are equal.
+/// Returns the new index of each kept entry.
+fn compact<T: Clone + Eq + Hash>(
+    table: &mut Vec<T>,
+    used: &BTreeSet<TableIndex>,
+    rewrite: impl Fn(&T) -> T,
+) -> BTreeMap<TableIndex, TableIndex> {
+    let mut compacted = vec![];
+    let mut indices = HashMap::new();
+    let mut remap = BTreeMap::new();
+    for idx in used {
+        let entry = rewrite(&table[*idx as usize]);
+        let new_idx = *indices.entry(entry.clone()).or_insert_with(|| {
+            compacted.push(entry);
+            (compacted.len() - 1) as TableIndex
+        });
+        remap.insert(*idx, new_idx);
+    }
+    *table = compacted;
+    remap
+}
+
+impl Remapping {
+    fn module_handle(&self, handle: &ModuleHandle) -> ModuleHandle {
+        let mut handle = handle.clone();
+        handle.address.0 = self.address_identifiers[&handle.address.0];
+        handle.name.0 = self.identifiers[&handle.name.0];
+        handle
+    }
+
+    fn token(&self, token: &SignatureToken) -> SignatureToken {
+        use SignatureToken as S;
+        match token {
+            S::Struct(idx) => S::Struct(StructHandleIndex(self.struct_handles[&idx.0])),
+            S::StructInstantiation(idx, args) => S::StructInstantiation(
+                StructHandleIndex(self.struct_handles[&idx.0]),
+                args.iter().map(|arg| self.token(arg)).collect(),
+            ),
+            S::Vector(inner) => S::Vector(Box::new(self.token(inner))),
+            S::Reference(inner) => S::Reference(Box::new(self.token(inner))),
+            S::MutableReference(inner) => S::MutableReference(Box::new(self.token(inner))),
+            S::Bool
+            | S::U8
+            | S::U16
+            | S::U32
+            | S::U64
+            | S::U128
+            | S::U256
+            | S::Address
+            | S::Signer
+            | S::TypeParameter(_) => token.clone(),
+        }
+    }
+
+    fn instruction(&self, instr: &mut Bytecode) {
+        use Bytecode as B;
+        match instr {
+            B::LdConst(idx) => idx.0 = self.constants[&idx.0],
+            B::Call(idx) => idx.0 = self.function_handles[&idx.0],
+            B::CallGeneric(idx) => idx.0 = self.function_instantiations[&idx.0],
+            B::PackGeneric(idx)
+            | B::UnpackGeneric(idx)
+            | B::MutBorrowGlobalGeneric(idx)
+            | B::ImmBorrowGlobalGeneric(idx)
+            | B::ExistsGeneric(idx)
+            | B::MoveFromGeneric(idx)
+            | B::MoveToGeneric(idx) => idx.0 = self.struct_def_instantiations[&idx.0],
+            B::MutBorrowField(idx) | B::ImmBorrowField(idx) => idx.0 = self.field_handles[&idx.0],
+            B::MutBorrowFieldGeneric(idx) | B::ImmBorrowFieldGeneric(idx) => {
+                idx.0 = self.field_instantiations[&idx.0]
+            }
+            B::VecPack(idx, _)
+            | B::VecLen(idx)
+            | B::VecImmBorrow(idx)
+            | B::VecMutBorrow(idx)
+            | B::VecPushBack(idx)
+            | B::VecPopBack(idx)
+            | B::VecUnpack(idx, _)
+            | B::VecSwap(idx) => idx.0 = self.signatures[&idx.0],
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        Bytecode, CompiledModule, FunctionDefinition, FunctionDefinitionIndex, SignatureToken,
+        TableIndex, Visibility,
+    },
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Removes the private, non-entry, functions that are not called (transitively) by a public,
+// friend or entry function of the module, or by its initializer. Their handles and the pool
+// entries used only by their code are left for `compact` to remove.
+
+// The private function run by the VM when the module is published, if it takes the publisher as
+// its only parameter
+const INIT_MODULE: &str = "init_module";
+
+pub fn optimize(module: &mut CompiledModule, source_map: &mut SourceMap) {
+    let defs_by_handle = module
+        .function_defs
+        .iter()
+        .enumerate()
+        .map(|(idx, def)| (def.function, idx))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut reachable = BTreeSet::new();
+    let mut stack = module
+        .function_defs
+        .iter()
+        .enumerate()
+        .filter(|(_, def)| {
+            def.visibility != Visibility::Private || def.is_entry || is_init_module(module, def)
+        })
+        .map(|(idx, _)| idx)
+        .collect::<Vec<_>>();
+    while let Some(idx) = stack.pop() {
+        if !reachable.insert(idx) {
+            continue;
+        }
+        let code = match &module.function_defs[idx].code {
+            Some(code) => &code.code,
+            None => continue,
+        };
+        for instr in code {
+            let handle = match instr {
+                Bytecode::Call(handle) => *handle,
+                Bytecode::CallGeneric(inst) => {
+                    module.function_instantiations[inst.0 as usize].handle
+                }
+                _ => continue,
+            };
+            // only the functions of the module have a definition
+            if let Some(callee) = defs_by_handle.get(&handle) {
+                stack.push(*callee)
+            }
+        }
+    }
+    if reachable.len() == module.function_defs.len() {
+        return;
+    }
+
+    let remap = reachable
+        .iter()
+        .enumerate()
+        .map(|(new_idx, old_idx)| (*old_idx as TableIndex, new_idx as TableIndex))
+        .collect::<BTreeMap<_, _>>();
+    module.function_defs = std::mem::take(&mut module.function_defs)
+        .into_iter()
+        .enumerate()
+        .filter(|(idx, _)| reachable.contains(idx))
+        .map(|(_, def)| def)
+        .collect();
+    source_map.remap_functions(|idx| remap.get(&idx.0).copied().map(FunctionDefinitionIndex));
+}
+
+fn is_init_module(module: &CompiledModule, def: &FunctionDefinition) -> bool {
+    let handle = module.function_handle_at(def.function);
+    let is_signer = |token: &SignatureToken| match token {
+        SignatureToken::Signer => true,
+        SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+        _ => false,
+    };
+    module.identifier_at(handle.name).as_str() == INIT_MODULE
+        && matches!(&module.signature_at(handle.parameters).0[..], [param] if is_signer(param))
+}
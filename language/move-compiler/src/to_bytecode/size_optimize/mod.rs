@@ -0,0 +1,114 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Module-level optimizations reducing the size of compiled modules, which are paid for per byte
+//! when published. Unlike the optimizations in `optimize`, which run on the code of each function
+//! during code generation, these run on the compiled module as a whole:
+//! - private (non-entry) functions that cannot be reached from the public, friend or entry
+//!   functions of the module, or from its `init_module` initializer, are removed
+//! - every pool and table of the module is rebuilt with only the entries still referenced, equal
+//!   entries being merged: this removes unused constants, signatures, handles and identifiers, and
+//!   deduplicates the constant and signature pools
+//!
+//! Error constants, the `u64` constants whose name starts with `E`, are kept even when unused, so
+//! that error maps built from the optimized modules still know them.
+
+mod compact;
+mod dead_code;
+
+use crate::compiled_unit::NamedCompiledModule;
+use anyhow::{anyhow, Result};
+use move_binary_format::file_format::CompiledModule;
+use std::{fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum OptLevel {
+    // Only the optimizations run on each function during code generation
+    Default,
+    // Module-level optimizations reducing the size of the modules as well
+    Size,
+}
+
+/// The serialized size, in bytes, of a module before and after optimization
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SizeReport {
+    pub original_size: usize,
+    pub optimized_size: usize,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+/// Optimizes `unit` according to `level`, keeping its source map in sync with the module. The
+/// optimized module is verified, and `unit` is left unchanged if the verification fails.
+pub fn module(unit: &mut NamedCompiledModule, level: OptLevel) -> Result<SizeReport> {
+    let original_size = serialized_size(&unit.module)?;
+    if level < OptLevel::Size {
+        return Ok(SizeReport {
+            original_size,
+            optimized_size: original_size,
+        });
+    }
+
+    let mut module = unit.module.clone();
+    let mut source_map = unit.source_map.clone();
+    dead_code::optimize(&mut module, &mut source_map);
+    compact::optimize(&mut module, &mut source_map);
+    move_bytecode_verifier::verifier::verify_module(&module).map_err(|e| {
+        anyhow!(
+            "Optimized module '{}' failed bytecode verification: {:?}",
+            unit.name,
+            e
+        )
+    })?;
+
+    let optimized_size = serialized_size(&module)?;
+    unit.module = module;
+    unit.source_map = source_map;
+    Ok(SizeReport {
+        original_size,
+        optimized_size,
+    })
+}
+
+fn serialized_size(module: &CompiledModule) -> Result<usize> {
+    let mut bytes = vec![];
+    module.serialize(&mut bytes)?;
+    Ok(bytes.len())
+}
+
+//**************************************************************************************************
+// impls
+//**************************************************************************************************
+
+impl SizeReport {
+    /// The number of bytes saved by the optimization
+    pub fn savings(&self) -> usize {
+        self.original_size.saturating_sub(self.optimized_size)
+    }
+}
+
+impl fmt::Display for OptLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptLevel::Default => write!(f, "default"),
+            OptLevel::Size => write!(f, "size"),
+        }
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => Ok(OptLevel::Default),
+            "size" => Ok(OptLevel::Size),
+            _ => Err(format!(
+                "Unknown optimization level '{}'. Expected one of: 'default', 'size'",
+                s
+            )),
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
     naming::ast::{FunctionSignature, StructDefinition, Type, TypeName_, Type_},
     parser::ast::{
         BinOp, ConstantName, Field, FunctionName, StructName, UnaryOp, Var, ENTRY_MODIFIER,
+        INLINE_MODIFIER,
     },
     shared::{ast_debug::*, unique_map::UniqueMap},
 };
@@ -76,6 +77,7 @@ pub struct Function {
     pub attributes: Attributes,
     pub visibility: Visibility,
     pub entry: Option<Loc>,
+    pub inline: bool,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
     pub body: FunctionBody,
@@ -149,6 +151,8 @@ pub enum UnannotatedExp_ {
     ModuleCall(Box<ModuleCall>),
     Builtin(Box<BuiltinFunction>, Box<Exp>),
     Vector(Loc, usize, Box<Type>, Box<Exp>),
+    // only in inline functions, which are expanded at their call sites
+    VarCall(Var, Box<Exp>),
 
     IfElse(Box<Exp>, Box<Exp>, Box<Exp>),
     While(Box<Exp>, Box<Exp>),
@@ -341,6 +345,7 @@ impl AstDebug for (FunctionName, &Function) {
                 attributes,
                 visibility,
                 entry,
+                inline,
                 signature,
                 acquires,
                 body,
@@ -351,6 +356,9 @@ impl AstDebug for (FunctionName, &Function) {
         if entry.is_some() {
             w.write(&format!("{} ", ENTRY_MODIFIER));
         }
+        if *inline {
+            w.write(&format!("{} ", INLINE_MODIFIER));
+        }
         if let FunctionBody_::Native = &body.value {
             w.write("native ");
         }
@@ -462,6 +470,12 @@ impl AstDebug for UnannotatedExp_ {
                 elems.ast_debug(w);
                 w.write("]");
             }
+            E::VarCall(v, rhs) => {
+                w.write(&format!("{}", v));
+                w.write("(");
+                rhs.ast_debug(w);
+                w.write(")");
+            }
             E::Pack(m, s, tys, fields) => {
                 w.write(&format!("{}::{}", m, s));
                 w.write("<");
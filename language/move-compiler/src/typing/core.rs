@@ -42,6 +42,8 @@ pub struct FunctionInfo {
     pub visibility: Visibility,
     pub signature: FunctionSignature,
     pub acquires: BTreeMap<StructName, Loc>,
+    // body of an inline function, expanded at its call sites
+    pub inline_body: Option<N::FunctionBody>,
 }
 
 pub struct ConstantInfo {
@@ -78,6 +80,11 @@ pub struct Context<'env> {
     pub constraints: Constraints,
 
     loop_info: LoopInfo,
+
+    // inline functions currently being expanded, with their bodies
+    inline_expansions: Vec<(ModuleIdent, FunctionName, Loc)>,
+    // calls of inline functions expanded in the current function
+    pub inlined_calls: Vec<InlinedCall>,
 }
 
 impl<'env> Context<'env> {
@@ -103,6 +110,7 @@ impl<'env> Context<'env> {
                 visibility: fdef.visibility.clone(),
                 signature: fdef.signature.clone(),
                 acquires: fdef.acquires.clone(),
+                inline_body: fdef.inline.then(|| fdef.body.clone()),
             });
             let constants = mdef.constants.ref_map(|cname, cdef| ConstantInfo {
                 defined_loc: cname.loc(),
@@ -126,6 +134,8 @@ impl<'env> Context<'env> {
             constraints: vec![],
             locals: UniqueMap::new(),
             loop_info: LoopInfo(LoopInfo_::NotInLoop),
+            inline_expansions: vec![],
+            inlined_calls: vec![],
             modules,
             env,
         }
@@ -312,7 +322,7 @@ impl<'env> Context<'env> {
         &self.struct_definition(m, n).type_parameters
     }
 
-    fn function_info(&self, m: &ModuleIdent, n: &FunctionName) -> &FunctionInfo {
+    pub fn function_info(&self, m: &ModuleIdent, n: &FunctionName) -> &FunctionInfo {
         self.module_info(m)
             .functions
             .get(n)
@@ -327,6 +337,44 @@ impl<'env> Context<'env> {
         constants.get(n).expect("ICE should have failed in naming")
     }
 
+    pub fn inline_function_body(
+        &self,
+        m: &ModuleIdent,
+        f: &FunctionName,
+    ) -> Option<&N::FunctionBody> {
+        self.function_info(m, f).inline_body.as_ref()
+    }
+
+    /// Whether the call of the inline function `m::f` at `loc` is recursive. The arguments of an
+    /// expanded call are written outside of the expanded body, so only the expansions enclosing
+    /// the code containing the call are considered
+    pub fn is_recursive_inline_call(&self, loc: Loc, m: &ModuleIdent, f: &FunctionName) -> bool {
+        let enclosing = match self
+            .inline_expansions
+            .iter()
+            .rposition(|(_, _, body)| body.contains(loc))
+        {
+            Some(idx) => &self.inline_expansions[..=idx],
+            None => &[],
+        };
+        self.is_current_function(m, f)
+            || enclosing
+                .iter()
+                .any(|(expanded_m, expanded_f, _)| expanded_m == m && expanded_f == f)
+    }
+
+    pub fn enter_inline_expansion(&mut self, m: ModuleIdent, f: FunctionName, body: Loc) {
+        self.inline_expansions.push((m, f, body))
+    }
+
+    pub fn exit_inline_expansion(&mut self) {
+        self.inline_expansions.pop();
+    }
+
+    pub fn is_expanding_inline_function(&self) -> bool {
+        !self.inline_expansions.is_empty()
+    }
+
     pub fn in_loop(&self) -> bool {
         match &self.loop_info.0 {
             LoopInfo_::NotInLoop => false,
@@ -485,6 +533,11 @@ fn error_format_impl_(b_: &Type_, subst: &Subst, nested: bool) -> String {
             format!("{}{}", n, tys_str)
         }
         Param(tp) => tp.user_specified_name.value.to_string(),
+        Fun(args, result) => format!(
+            "|{}|{}",
+            format_comma(args.iter().map(|t| error_format_nested(t, subst))),
+            error_format_nested(result, subst)
+        ),
         Ref(mut_, ty) => format!(
             "&{}{}",
             if *mut_ { "mut " } else { "" },
@@ -510,6 +563,8 @@ pub fn infer_abilities(context: &Context, subst: &Subst, ty: Type) -> AbilitySet
         T::Ref(_, _) => AbilitySet::references(loc),
         T::Var(_) => unreachable!("ICE unfold_type failed, which is impossible"),
         T::UnresolvedError | T::Anything => AbilitySet::all(loc),
+        // lambdas are not values
+        T::Fun(_, _) => AbilitySet::empty(),
         T::Param(TParam { abilities, .. }) | T::Apply(Some(abilities), _, _) => abilities,
         T::Apply(None, n, ty_args) => {
             let (declared_abilities, ty_args) = match &n.value {
@@ -552,6 +607,7 @@ fn debug_abilities_info(context: &Context, ty: &Type) -> (Option<Loc>, AbilitySe
         T::Unit | T::Ref(_, _) => (None, AbilitySet::references(loc), vec![]),
         T::Var(_) => panic!("ICE call unfold_type before debug_abilities_info"),
         T::UnresolvedError | T::Anything => (None, AbilitySet::all(loc), vec![]),
+        T::Fun(_, _) => (None, AbilitySet::empty(), vec![]),
         T::Param(TParam {
             abilities,
             user_specified_name,
@@ -1044,7 +1100,7 @@ fn solve_base_type_constraint(context: &mut Context, loc: Loc, msg: String, ty:
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Param(_) | Apply(_, _, _) => (),
+        UnresolvedError | Anything | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1065,7 +1121,7 @@ fn solve_single_type_constraint(context: &mut Context, loc: Loc, msg: String, ty
                 (tyloc, tmsg)
             ))
         }
-        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) => (),
+        UnresolvedError | Anything | Ref(_, _) | Param(_) | Apply(_, _, _) | Fun(_, _) => (),
     }
 }
 
@@ -1139,6 +1195,10 @@ pub fn subst_tparams(subst: &TParamSubst, sp!(loc, t_): Type) -> Type {
                 .collect();
             sp(loc, Apply(k, n, ftys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| subst_tparams(subst, t)).collect();
+            sp(loc, Fun(args, Box::new(subst_tparams(subst, *result))))
+        }
     }
 }
 
@@ -1151,6 +1211,10 @@ pub fn ready_tvars(subst: &Subst, sp!(loc, t_): Type) -> Type {
             let tys = tys.into_iter().map(|t| ready_tvars(subst, t)).collect();
             sp(loc, Apply(k, n, tys))
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| ready_tvars(subst, t)).collect();
+            sp(loc, Fun(args, Box::new(ready_tvars(subst, *result))))
+        }
         Var(i) => {
             let last_var = forward_tvar(subst, i);
             match subst.get(last_var) {
@@ -1180,8 +1244,14 @@ pub fn instantiate(context: &mut Context, sp!(loc, t_): Type) -> Type {
         Apply(abilities_opt, n, ty_args) => {
             instantiate_apply(context, loc, abilities_opt, n, ty_args)
         }
+        Fun(args, result) => {
+            let args = args.into_iter().map(|t| instantiate(context, t)).collect();
+            Fun(args, Box::new(instantiate(context, *result)))
+        }
         x @ Param(_) => x,
-        Var(_) => panic!("ICE instantiate type variable"),
+        // only in the bodies of expanded inline functions, where type parameters were substituted
+        // with the already instantiated type arguments of the call
+        x @ Var(_) => x,
     };
     sp(loc, it_)
 }
@@ -1408,6 +1478,13 @@ fn join_impl(
             let (subst, tys) = join_impl_types(subst, case, tys1, tys2)?;
             Ok((subst, sp(*loc, Apply(k2.clone(), n2.clone(), tys))))
         }
+        (sp!(_, Fun(args1, result1)), sp!(loc, Fun(args2, result2)))
+            if args1.len() == args2.len() =>
+        {
+            let (subst, args) = join_impl_types(subst, Join, args1, args2)?;
+            let (subst, result) = join_impl(subst, Join, result1, result2)?;
+            Ok((subst, sp(*loc, Fun(args, Box::new(result)))))
+        }
         (sp!(loc1, Var(id1)), sp!(loc2, Var(id2))) => {
             if *id1 == *id2 {
                 Ok((subst, sp(*loc2, Var(*id2))))
@@ -1554,6 +1631,10 @@ fn join_bind_tvar(subst: &mut Subst, loc: Loc, tvar: TVar, ty: Type) -> Result<b
                 .iter()
                 .rev()
                 .for_each(|inner| used_tvars(used, inner)),
+            T::Fun(args, result) => {
+                args.iter().for_each(|arg| used_tvars(used, arg));
+                used_tvars(used, result)
+            }
             T::Unit | T::Param(_) | T::Anything | T::UnresolvedError => (),
         }
     }
@@ -52,6 +52,10 @@ pub fn type_(context: &mut Context, ty: &mut Type) {
     match &mut ty.value {
         Anything | UnresolvedError | Param(_) | Unit => (),
         Ref(_, b) => type_(context, b),
+        Fun(args, result) => {
+            types(context, args);
+            type_(context, result);
+        }
         Var(tvar) => {
             let ty_tvar = sp(ty.loc, Var(*tvar));
             let replacement = core::unfold_type(&context.subst, ty_tvar);
@@ -238,6 +242,7 @@ pub fn exp(context: &mut Context, e: &mut T::Exp) {
             type_(context, ty_arg);
             exp(context, args);
         }
+        E::VarCall(_, args) => exp(context, args),
 
         E::IfElse(eb, et, ef) => {
             exp(context, eb);
@@ -116,6 +116,7 @@ fn exp(
             exp(context, annotated_acquires, seen, args);
         }
         E::Vector(_vec_loc, _n, _targ, args) => exp(context, annotated_acquires, seen, args),
+        E::VarCall(_, args) => exp(context, annotated_acquires, seen, args),
 
         E::IfElse(eb, et, ef) => {
             exp(context, annotated_acquires, seen, eb);
@@ -268,7 +269,7 @@ where
         T::Anything | T::UnresolvedError => {
             return None;
         }
-        T::Ref(_, _) | T::Unit => {
+        T::Ref(_, _) | T::Unit | T::Fun(_, _) => {
             // Key ability is checked by constraints, and these types do not have Key
             assert!(context.env.has_errors());
             return None;
@@ -0,0 +1,325 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Expansion of inline functions at their call sites.
+//!
+//! The body of the inline function is copied into the caller before it is typed. The locals of
+//! the body are renamed so they cannot capture the locals of the caller, its type parameters are
+//! replaced with the type arguments of the call, and calls of its function typed parameters are
+//! replaced with the bodies of the lambdas given as arguments.
+
+use super::core::{self, Context, TParamSubst};
+use crate::{
+    diag,
+    naming::ast::{self as N, Type, Type_},
+    parser::ast::Var,
+    shared::*,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, VecDeque};
+
+/// Delimiter of the suffix added to the locals of an expanded inline function
+const INLINE_NAME_DELIM: &str = "#i";
+
+/// Argument of a function typed parameter
+#[derive(Clone)]
+enum LambdaArg {
+    Lambda(N::LValueList, Box<N::Exp>),
+    // function typed parameter of the function being typed, forwarded to another inline function
+    Forward(Var),
+    Invalid,
+}
+
+struct Inliner<'a> {
+    tparam_subst: &'a TParamSubst,
+    suffix: u64,
+    lambdas: BTreeMap<Symbol, (Vec<Type>, Type, LambdaArg)>,
+}
+
+/// The expression replacing the call of an inline function with the given `parameters` and
+/// `return_type`, whose types are already instantiated for the call
+pub fn expand_call(
+    context: &mut Context,
+    tparam_subst: &TParamSubst,
+    parameters: Vec<(Var, Type)>,
+    return_type: Type,
+    args: Vec<N::Exp>,
+    body_loc: Loc,
+    body: N::Sequence,
+) -> N::Exp {
+    use N::{Exp_ as NE, SequenceItem_ as NS};
+    let mut inliner = Inliner {
+        tparam_subst,
+        suffix: Counter::next(),
+        lambdas: BTreeMap::new(),
+    };
+
+    let mut seq = VecDeque::new();
+    for ((param, param_ty), arg) in parameters.into_iter().zip(args) {
+        let local = inliner.var(param);
+        match (param_ty.value, arg) {
+            (Type_::Fun(param_tys, result), sp!(lloc, NE::Lambda(binds, lbody))) => {
+                let lambda = if binds.value.len() == param_tys.len() {
+                    LambdaArg::Lambda(binds, lbody)
+                } else {
+                    let msg = format!(
+                        "Invalid lambda for parameter '{}'. Expected a lambda with {} \
+                         parameter(s), but got {}",
+                        param,
+                        param_tys.len(),
+                        binds.value.len(),
+                    );
+                    context
+                        .env
+                        .add_diag(diag!(TypeSafety::InvalidLambda, (lloc, msg)));
+                    LambdaArg::Invalid
+                };
+                inliner
+                    .lambdas
+                    .insert(local.value(), (param_tys, *result, lambda));
+            }
+            (Type_::Fun(param_tys, result), sp!(_, NE::Use(v) | NE::Move(v) | NE::Copy(v)))
+                if is_lambda_local(context, &v) =>
+            {
+                let forward = LambdaArg::Forward(v);
+                inliner
+                    .lambdas
+                    .insert(local.value(), (param_tys, *result, forward));
+            }
+            (Type_::Fun(param_tys, result), sp!(aloc, _)) => {
+                let msg = format!(
+                    "Invalid argument for parameter '{}'. Expected a lambda",
+                    param
+                );
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::InvalidLambda, (aloc, msg)));
+                let invalid = LambdaArg::Invalid;
+                inliner
+                    .lambdas
+                    .insert(local.value(), (param_tys, *result, invalid));
+            }
+            (_, sp!(aloc, NE::Lambda(_, _))) => {
+                let msg = format!(
+                    "Invalid argument for parameter '{}'. Lambdas can only be passed for \
+                     function typed parameters",
+                    param
+                );
+                context
+                    .env
+                    .add_diag(diag!(TypeSafety::InvalidLambda, (aloc, msg)));
+                let arg = sp(aloc, NE::UnresolvedError);
+                seq.push_back(sp(aloc, NS::Bind(bind_var(local), arg)))
+            }
+            (ty_, arg) => {
+                let aloc = arg.loc;
+                let arg = sp(aloc, NE::Annotate(Box::new(arg), sp(param_ty.loc, ty_)));
+                seq.push_back(sp(aloc, NS::Bind(bind_var(local), arg)))
+            }
+        }
+    }
+
+    // the body is typed in the scope of the parameters, its last expression being the result
+    seq.extend(inliner.sequence(body));
+    match seq.back_mut() {
+        Some(sp!(_, NS::Seq(result))) => {
+            let rloc = result.loc;
+            let e = std::mem::replace(result, sp(rloc, NE::UnresolvedError));
+            *result = sp(rloc, NE::Annotate(Box::new(e), return_type));
+        }
+        _ => panic!("ICE sequences end with an expression"),
+    }
+    sp(body_loc, NE::Block(seq))
+}
+
+fn is_lambda_local(context: &mut Context, v: &Var) -> bool {
+    matches!(context.get_local_(v), Some(sp!(_, Type_::Fun(_, _))))
+}
+
+fn bind_var(v: Var) -> N::LValueList {
+    let loc = v.loc();
+    sp(loc, vec![sp(loc, N::LValue_::Var(v))])
+}
+
+impl<'a> Inliner<'a> {
+    fn var(&self, v: Var) -> Var {
+        let renamed = format!("{}{}{}", v.value(), INLINE_NAME_DELIM, self.suffix);
+        Var(sp(v.loc(), renamed.into()))
+    }
+
+    fn type_(&self, ty: Type) -> Type {
+        core::subst_tparams(self.tparam_subst, ty)
+    }
+
+    fn types_opt(&self, tys_opt: Option<Vec<Type>>) -> Option<Vec<Type>> {
+        tys_opt.map(|tys| tys.into_iter().map(|ty| self.type_(ty)).collect())
+    }
+
+    fn sequence(&mut self, seq: N::Sequence) -> N::Sequence {
+        use N::SequenceItem_ as S;
+        seq.into_iter()
+            .map(|sp!(loc, item_)| {
+                let item_ = match item_ {
+                    S::Seq(e) => S::Seq(self.exp(e)),
+                    S::Declare(binds, ty_opt) => {
+                        S::Declare(self.lvalues(binds), ty_opt.map(|ty| self.type_(ty)))
+                    }
+                    S::Bind(binds, e) => S::Bind(self.lvalues(binds), self.exp(e)),
+                };
+                sp(loc, item_)
+            })
+            .collect()
+    }
+
+    fn lvalues(&mut self, sp!(loc, binds): N::LValueList) -> N::LValueList {
+        sp(loc, binds.into_iter().map(|b| self.lvalue(b)).collect())
+    }
+
+    fn lvalue(&mut self, sp!(loc, b_): N::LValue) -> N::LValue {
+        use N::LValue_ as L;
+        let b_ = match b_ {
+            L::Ignore => L::Ignore,
+            L::Var(v) => L::Var(self.var(v)),
+            L::Unpack(m, s, tys_opt, fields) => {
+                let tys_opt = self.types_opt(tys_opt);
+                let fields = fields.map(|_, (idx, b)| (idx, self.lvalue(b)));
+                L::Unpack(m, s, tys_opt, fields)
+            }
+        };
+        sp(loc, b_)
+    }
+
+    fn exp_vec(&mut self, sp!(loc, es): Spanned<Vec<N::Exp>>) -> Spanned<Vec<N::Exp>> {
+        sp(loc, es.into_iter().map(|e| self.exp(e)).collect())
+    }
+
+    fn exp_box(&mut self, e: Box<N::Exp>) -> Box<N::Exp> {
+        Box::new(self.exp(*e))
+    }
+
+    fn exp_dotted(&mut self, sp!(loc, ed_): N::ExpDotted) -> N::ExpDotted {
+        use N::ExpDotted_ as D;
+        let ed_ = match ed_ {
+            D::Exp(e) => D::Exp(self.exp_box(e)),
+            D::Dot(ed, f) => D::Dot(Box::new(self.exp_dotted(*ed)), f),
+        };
+        sp(loc, ed_)
+    }
+
+    // Use of a local, replaced with the argument when it is a function typed parameter
+    fn local(&mut self, v: Var, mk: impl Fn(Var) -> N::Exp_) -> N::Exp_ {
+        let v = self.var(v);
+        match self.lambdas.get(&v.value()) {
+            None => mk(v),
+            Some((_, _, LambdaArg::Lambda(binds, body))) => {
+                N::Exp_::Lambda(binds.clone(), body.clone())
+            }
+            Some((_, _, LambdaArg::Forward(fv))) => mk(*fv),
+            Some((_, _, LambdaArg::Invalid)) => N::Exp_::UnresolvedError,
+        }
+    }
+
+    fn exp(&mut self, sp!(loc, e_): N::Exp) -> N::Exp {
+        use N::{Exp_ as E, SequenceItem_ as S};
+        let e_ = match e_ {
+            e_ @ (E::Value(_)
+            | E::Constant(_, _)
+            | E::Break
+            | E::Continue
+            | E::Unit { .. }
+            | E::UnresolvedError) => e_,
+            // specs are not expanded with the inline function
+            E::Spec(_, _) => E::Unit { trailing: false },
+
+            E::Move(v) => self.local(v, E::Move),
+            E::Copy(v) => self.local(v, E::Copy),
+            E::Use(v) => self.local(v, E::Use),
+
+            E::ModuleCall(m, f, tys_opt, args) => {
+                E::ModuleCall(m, f, self.types_opt(tys_opt), self.exp_vec(args))
+            }
+            E::Builtin(sp!(bloc, b_), args) => {
+                use N::BuiltinFunction_ as B;
+                let b_ = match b_ {
+                    B::MoveTo(ty_opt) => B::MoveTo(ty_opt.map(|ty| self.type_(ty))),
+                    B::MoveFrom(ty_opt) => B::MoveFrom(ty_opt.map(|ty| self.type_(ty))),
+                    B::BorrowGlobal(mut_, ty_opt) => {
+                        B::BorrowGlobal(mut_, ty_opt.map(|ty| self.type_(ty)))
+                    }
+                    B::Exists(ty_opt) => B::Exists(ty_opt.map(|ty| self.type_(ty))),
+                    B::Freeze(ty_opt) => B::Freeze(ty_opt.map(|ty| self.type_(ty))),
+                    b_ @ B::Assert(_) => b_,
+                };
+                E::Builtin(sp(bloc, b_), self.exp_vec(args))
+            }
+            E::Vector(vloc, ty_opt, args) => {
+                E::Vector(vloc, ty_opt.map(|ty| self.type_(ty)), self.exp_vec(args))
+            }
+            E::VarCall(v, args) => {
+                let sp!(argloc, mut args) = self.exp_vec(args);
+                let v = self.var(v);
+                let (param_tys, result, lambda) = match self.lambdas.get(&v.value()) {
+                    Some(lambda) => lambda.clone(),
+                    // a shadowed parameter, already reported when typing the inline function
+                    None => return sp(loc, E::VarCall(v, sp(argloc, args))),
+                };
+                match lambda {
+                    LambdaArg::Invalid => E::UnresolvedError,
+                    LambdaArg::Forward(fv) => {
+                        let call = sp(loc, E::VarCall(fv, sp(argloc, args)));
+                        E::Annotate(Box::new(call), result)
+                    }
+                    LambdaArg::Lambda(binds, body) => {
+                        let mut seq = VecDeque::new();
+                        if !param_tys.is_empty() {
+                            let (arg_, arg_ty) = if param_tys.len() == 1 {
+                                (args.pop().unwrap().value, param_tys[0].clone())
+                            } else {
+                                (E::ExpList(args), Type_::multiple(argloc, param_tys))
+                            };
+                            let arg = sp(argloc, E::Annotate(Box::new(sp(argloc, arg_)), arg_ty));
+                            seq.push_back(sp(argloc, S::Bind(binds, arg)));
+                        }
+                        let bloc = body.loc;
+                        seq.push_back(sp(bloc, S::Seq(sp(bloc, E::Annotate(body, result)))));
+                        E::Block(seq)
+                    }
+                }
+            }
+
+            E::IfElse(eb, et, ef) => {
+                E::IfElse(self.exp_box(eb), self.exp_box(et), self.exp_box(ef))
+            }
+            E::While(eb, eloop) => E::While(self.exp_box(eb), self.exp_box(eloop)),
+            E::Loop(eloop) => E::Loop(self.exp_box(eloop)),
+            E::Block(seq) => E::Block(self.sequence(seq)),
+            E::Lambda(binds, body) => E::Lambda(self.lvalues(binds), self.exp_box(body)),
+
+            E::Assign(binds, e) => E::Assign(self.lvalues(binds), self.exp_box(e)),
+            E::FieldMutate(ed, e) => E::FieldMutate(self.exp_dotted(ed), self.exp_box(e)),
+            E::Mutate(el, er) => E::Mutate(self.exp_box(el), self.exp_box(er)),
+
+            E::Return(e) => E::Return(self.exp_box(e)),
+            E::Abort(e) => E::Abort(self.exp_box(e)),
+
+            E::Dereference(e) => E::Dereference(self.exp_box(e)),
+            E::UnaryExp(op, e) => E::UnaryExp(op, self.exp_box(e)),
+            E::BinopExp(el, op, er) => E::BinopExp(self.exp_box(el), op, self.exp_box(er)),
+
+            E::Pack(m, s, tys_opt, fields) => {
+                let tys_opt = self.types_opt(tys_opt);
+                let fields = fields.map(|_, (idx, e)| (idx, self.exp(e)));
+                E::Pack(m, s, tys_opt, fields)
+            }
+            E::ExpList(es) => E::ExpList(es.into_iter().map(|e| self.exp(e)).collect()),
+
+            E::DerefBorrow(ed) => E::DerefBorrow(self.exp_dotted(ed)),
+            E::Borrow(mut_, ed) => E::Borrow(mut_, self.exp_dotted(ed)),
+
+            E::Cast(e, ty) => E::Cast(self.exp_box(e), self.type_(ty)),
+            E::Annotate(e, ty) => E::Annotate(self.exp_box(e), self.type_(ty)),
+        };
+        sp(loc, e_)
+    }
+}
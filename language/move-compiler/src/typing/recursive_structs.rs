@@ -99,6 +99,10 @@ fn type_(context: &mut Context, sp!(loc, ty_): &N::Type) {
         Var(_) => panic!("ICE tvar in struct field type"),
         Unit | Anything | UnresolvedError | Param(_) => (),
         Ref(_, t) => type_(context, t),
+        Fun(args, result) => {
+            args.iter().for_each(|t| type_(context, t));
+            type_(context, result)
+        }
         Apply(_, sp!(_, tn_), tys) => {
             if let TypeName_::ModuleType(m, s) = tn_ {
                 context.add_usage(*loc, m, s)
@@ -4,7 +4,7 @@
 
 use super::{
     core::{self, Context, Subst},
-    expand, globals, infinite_instantiations, recursive_structs,
+    expand, globals, infinite_instantiations, inline, recursive_structs,
 };
 use crate::{
     diag,
@@ -135,6 +135,7 @@ fn function(
         attributes,
         visibility,
         entry,
+        inline,
         mut signature,
         body: n_body,
         acquires,
@@ -164,10 +165,13 @@ fn function(
 
     let body = function_body(context, &acquires, n_body);
     context.current_function = None;
+    let inlined_calls = std::mem::take(&mut context.inlined_calls);
+    context.env.add_inlined_calls(body.loc, inlined_calls);
     T::Function {
         attributes,
         visibility,
         entry,
+        inline,
         signature,
         acquires,
         body,
@@ -396,6 +400,10 @@ mod check_valid_constant {
             //*****************************************
             E::Spec(_, _) => "Spec blocks are",
             E::BorrowLocal(_, _) => REFERENCE_CASE,
+            E::VarCall(_, args) => {
+                exp(context, args);
+                "Lambda calls are"
+            }
             E::ModuleCall(call) => {
                 exp(context, &call.arguments);
                 "Module calls are"
@@ -673,6 +681,12 @@ fn visit_type_params(
                 }
             }
         },
+        // Function types cannot appear in structs
+        Type_::Fun(args, result) => {
+            for ty in args.iter().chain(std::iter::once(&**result)) {
+                visit_type_params(context, ty, ParamPos::NonPhantom(NonPhantomPos::TypeArg), f)
+            }
+        }
         Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => {}
         Type_::Unit => {}
     }
@@ -733,6 +747,9 @@ fn has_unresolved_error_type(ty: &Type) -> bool {
         Type_::UnresolvedError => true,
         Type_::Ref(_, ty) => has_unresolved_error_type(ty),
         Type_::Apply(_, _, ty_args) => ty_args.iter().any(has_unresolved_error_type),
+        Type_::Fun(args, result) => {
+            args.iter().any(has_unresolved_error_type) || has_unresolved_error_type(result)
+        }
         Type_::Param(_) | Type_::Var(_) | Type_::Anything | Type_::Unit => false,
     }
 }
@@ -1210,12 +1227,12 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
         }
 
         NE::Move(var) => {
-            let ty = context.get_local(eloc, "move", &var);
+            let ty = non_lambda_local(context, eloc, "move", &var);
             let from_user = true;
             (ty, TE::Move { var, from_user })
         }
         NE::Copy(var) => {
-            let ty = context.get_local(eloc, "copy", &var);
+            let ty = non_lambda_local(context, eloc, "copy", &var);
             context.add_ability_constraint(
                 eloc,
                 Some(format!(
@@ -1229,10 +1246,15 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             (ty, TE::Copy { var, from_user })
         }
         NE::Use(var) => {
-            let ty = context.get_local(eloc, "variable usage", &var);
+            let ty = non_lambda_local(context, eloc, "variable usage", &var);
             (ty, TE::Use(var))
         }
 
+        NE::ModuleCall(m, f, ty_args_opt, sp!(argloc, nargs_))
+            if context.inline_function_body(&m, &f).is_some() =>
+        {
+            inline_call(context, eloc, m, f, ty_args_opt, argloc, nargs_)
+        }
         NE::ModuleCall(m, f, ty_args_opt, sp!(argloc, nargs_)) => {
             let args = exp_vec(context, nargs_);
             module_call(context, eloc, m, f, ty_args_opt, argloc, args)
@@ -1245,6 +1267,10 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             let args_ = exp_vec(context, nargs_);
             vector_pack(context, eloc, vec_loc, ty_opt, argloc, args_)
         }
+        NE::VarCall(var, sp!(argloc, nargs_)) => {
+            let args = exp_vec(context, nargs_);
+            var_call(context, eloc, var, argloc, args)
+        }
 
         NE::IfElse(nb, nt, nf) => {
             let eb = exp(context, nb);
@@ -1289,6 +1315,14 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
             let seq = sequence(context, nseq);
             (sequence_type(&seq).clone(), TE::Block(seq))
         }
+        NE::Lambda(_, _) => {
+            let msg = "Invalid lambda. Lambdas can only be passed directly as the arguments of \
+                       inline functions";
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (eloc, msg)));
+            (context.error_type(eloc), TE::UnresolvedError)
+        }
 
         NE::Assign(na, nr) => {
             let er = exp(context, nr);
@@ -1509,6 +1543,22 @@ fn exp_inner(context: &mut Context, sp!(eloc, ne_): N::Exp) -> T::Exp {
     T::exp(ty, sp(eloc, e_))
 }
 
+fn non_lambda_local(context: &mut Context, loc: Loc, verb: &str, var: &Var) -> Type {
+    let ty = context.get_local(loc, verb, var);
+    if let Type_::Fun(_, _) = &ty.value {
+        let msg = format!(
+            "Invalid usage of '{}'. Function typed parameters can only be called, or passed to \
+             inline functions",
+            var
+        );
+        context
+            .env
+            .add_diag(diag!(TypeSafety::InvalidLambda, (loc, msg)));
+        return context.error_type(loc);
+    }
+    ty
+}
+
 fn loop_body(
     context: &mut Context,
     eloc: Loc,
@@ -2088,6 +2138,132 @@ fn module_call(
     (ret_ty, T::UnannotatedExp_::ModuleCall(Box::new(call)))
 }
 
+fn inline_call(
+    context: &mut Context,
+    loc: Loc,
+    m: ModuleIdent,
+    f: FunctionName,
+    ty_args_opt: Option<Vec<Type>>,
+    argloc: Loc,
+    args: Vec<N::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    if context.is_recursive_inline_call(loc, &m, &f) {
+        // The recursion is reported once, when checking the definition of the called function,
+        // and not again for every function it is expanded in
+        if context.is_current_function(&m, &f) {
+            let msg = format!(
+                "Invalid call of '{}::{}'. Inline functions cannot be called recursively",
+                &m, &f
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::RecursiveInline, (loc, msg)));
+        }
+        return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+    }
+
+    let (_, ty_args, parameters, _, ret_ty) =
+        core::make_function_type(context, loc, &m, &f, ty_args_opt);
+    if args.len() != parameters.len() {
+        let given = args.iter().map(|e| context.error_type(e.loc)).collect();
+        make_arg_types(
+            context,
+            loc,
+            || format!("Invalid call of '{}::{}'", &m, &f),
+            parameters.len(),
+            argloc,
+            given,
+        );
+        return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+    }
+    let tparam_subst = core::make_tparam_subst(
+        &context.function_info(&m, &f).signature.type_parameters,
+        ty_args,
+    );
+    let (body_loc, body) = match context.inline_function_body(&m, &f).cloned() {
+        Some(sp!(body_loc, N::FunctionBody_::Defined(body))) => (body_loc, body),
+        _ => panic!("ICE inline functions are not native"),
+    };
+    let lambdas = args
+        .iter()
+        .filter(|arg| matches!(arg.value, N::Exp_::Lambda(_, _)))
+        .map(|arg| arg.loc);
+    let inlined_call = InlinedCall {
+        loc,
+        function: format!("{}::{}", &m, &f),
+        foreign: !context.is_current_module(&m),
+        expanded: std::iter::once(body_loc).chain(lambdas).collect(),
+    };
+    let expanded = inline::expand_call(
+        context,
+        &tparam_subst,
+        parameters,
+        ret_ty,
+        args,
+        body_loc,
+        body,
+    );
+
+    let diags_start = context.env.count_diags();
+    let calls_start = context.inlined_calls.len();
+    context.enter_inline_expansion(m, f, body_loc);
+    let e = exp_(context, expanded);
+    context.exit_inline_expansion();
+    context.inlined_calls.push(inlined_call);
+    // The errors in the expanded code are pointed to the call written in the function, once its
+    // expansion, including the nested ones, is typed
+    if !context.is_expanding_inline_function() {
+        let calls = &context.inlined_calls[calls_start..];
+        context.env.label_inline_expansion(calls, diags_start);
+    }
+    (e.ty, e.exp.value)
+}
+
+fn var_call(
+    context: &mut Context,
+    loc: Loc,
+    var: Var,
+    argloc: Loc,
+    args: Vec<T::Exp>,
+) -> (Type, T::UnannotatedExp_) {
+    let (params_ty, ret_ty) = match context.get_local(loc, "call", &var) {
+        sp!(_, Type_::Fun(params_ty, ret_ty)) => (params_ty, *ret_ty),
+        sp!(_, Type_::UnresolvedError) => {
+            assert!(context.env.has_errors());
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+        _ => {
+            let msg = format!(
+                "Invalid call of '{}'. Only the function typed parameters of inline functions \
+                 can be called",
+                var
+            );
+            context
+                .env
+                .add_diag(diag!(TypeSafety::InvalidLambda, (loc, msg)));
+            return (context.error_type(loc), T::UnannotatedExp_::UnresolvedError);
+        }
+    };
+    let (arguments, arg_tys) = call_args(
+        context,
+        loc,
+        || format!("Invalid call of '{}'", var),
+        params_ty.len(),
+        argloc,
+        args,
+    );
+    for ((idx, arg_ty), param_ty) in arg_tys.into_iter().enumerate().zip(params_ty) {
+        let msg = || {
+            format!(
+                "Invalid call of '{}'. Invalid argument for parameter '{}'",
+                var, idx
+            )
+        };
+        subtype(context, loc, msg, arg_ty, param_ty);
+    }
+    (ret_ty, T::UnannotatedExp_::VarCall(var, arguments))
+}
+
 fn builtin_call(
     context: &mut Context,
     loc: Loc,
@@ -0,0 +1,17 @@
+// A module with comments
+/* block comment
+   over two lines */
+module 0x42::comments {
+        /// Documentation of the struct
+    struct S has drop { x: u64 } // trailing comment
+
+    /** Documentation in a block */
+  fun f(/* no arguments */): u64 {
+            // a comment before a statement
+        let x = 1;  /* after a statement */
+      x /* nested /* comment */ */ + 1
+        // a comment before the closing brace
+    }
+      // a comment at the end of the module
+}
+// a comment at the end of the file
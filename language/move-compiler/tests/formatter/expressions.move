@@ -0,0 +1,60 @@
+module 0x42::expressions {
+    use std::vector;
+
+    struct Box<T> has copy, drop, store { value: T }
+
+    #[test_only]
+    const ADDR:address=@0x42;
+
+    fun generic<T:copy+drop>(x:T):Box<T> { Box<T>{value:x} }
+
+    fun casts(x:u8):u128 { (x as u128)*(x as u128)<<2 }
+
+    fun references(v:&mut vector<u64>, r:&mut u64) {
+        *r=*r+1;
+        let x=&mut *r;
+        *x=( *x )&0xff;
+        vector::push_back(v,*x);
+        let y=&v;
+        let _ = y;
+    }
+
+    fun conditions(a:bool,b:bool):bool { !a||(a&&!b)||a!=b }
+
+    fun long_expression(a:u64,b:u64):u64 {
+        let x=a+
+        b;
+        let y=a
+        *b;
+        x+
+        y
+    }
+
+    fun multi_line_call(a:u64,b:u64):u64 {
+        generic(
+        a+b
+        ).value
+    }
+
+    inline fun apply(x:u64,f:|u64|u64):u64 { f(x) }
+
+    fun lambdas(x:u64):u64 { apply(x,|y|y+1) }
+
+    fun ranges(n:u64):u64 {
+        let sum=0;
+        for(i in 0..n) { sum=sum+i };
+        sum
+    }
+
+    fun control(x:u64):u64 {
+        let y=if(x>0) { x } else { return 0 };
+        loop { if(y>10) break; y=y+1; continue };
+        y
+    }
+
+    #[test(account=@0x1)]
+    fun test(account:signer) { let _ = account; abort 0 }
+
+    fun bytes():vector<u8> { x"00ff" }
+    fun strings():vector<u8> { b"hello" }
+}
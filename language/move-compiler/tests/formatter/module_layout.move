@@ -0,0 +1,44 @@
+module 0x42::layout {
+use std::vector;
+  use std::option::{Self,Option};
+
+
+
+      struct Coin has key,store { value:u64 }
+    struct Pair<T:copy+drop> has copy,drop {
+   first:T,
+         second:T,
+    }
+
+  const MAX_VALUE:u64=100;
+
+
+  public fun new(value:u64):Coin{
+        assert!(value<=MAX_VALUE,1);
+      Coin{value}
+  }
+
+    public(friend) fun value(coin:&Coin):u64 {
+
+        coin.value
+
+    }
+
+  fun add(a:u64,b:u64):u64{a+b}
+
+fun nested(v:&vector<u64>):u64 {
+let sum=0;
+let i=0;
+while(i <vector::length(v)){
+if(*vector::borrow(v,i)>10){
+sum=sum+*vector::borrow(v,i);
+} else {
+sum = sum + 1;
+};
+i=i+1;
+};
+sum
+}
+
+    fun first(o:&Option<u64>):u64 { *option::borrow(o) }
+}
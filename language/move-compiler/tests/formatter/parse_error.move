@@ -0,0 +1,4 @@
+module 0x42::broken {
+    fun f( {
+    }
+}
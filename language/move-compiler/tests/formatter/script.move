@@ -0,0 +1,11 @@
+script {
+use std::vector;
+    fun main(account:signer,amounts:vector<u64>) {
+  let total=0;
+    for(amount in &amounts){
+            total=total+*amount;
+    };
+        let _ = account;
+     assert!(total>0&&vector::length(&amounts)!=0,0)
+    }
+}
@@ -0,0 +1,27 @@
+module 0x42::specs {
+    struct Counter has key { value: u64 }
+
+    fun increment(counter:&mut Counter) {
+        counter.value=counter.value+1;
+    }
+    spec increment {
+      requires counter.value <MAX_U64;
+        ensures counter.value==old(counter.value)+1;
+    aborts_if false;
+    }
+
+    spec Counter {
+        invariant value<=1000;
+    }
+
+    spec module {
+        pragma verify=true;
+    }
+
+    spec schema Bounded {
+            x:u64;
+        requires x>0 ==> x < 10;
+    }
+
+    spec fun is_small(x:u64):bool { x < 10 }
+}
@@ -0,0 +1,58 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::HashMap, fs, path::Path};
+
+use move_command_line_common::{
+    files::FileHash,
+    testing::{add_update_baseline_fix, format_diff, read_env_update_baseline, EXP_EXT},
+};
+use move_compiler::{
+    diagnostics::report_diagnostics_to_buffer,
+    formatter::{format_source, FormatOptions},
+};
+use move_symbol_pool::Symbol;
+
+fn formatter_testsuite(path: &Path) -> datatest_stable::Result<()> {
+    run_test(path)?;
+    Ok(())
+}
+
+/// Formats the file at `path` and compares the result, or the diagnostics if it does not parse,
+/// with the `.exp` file. The formatted file must not change when formatted again.
+fn run_test(path: &Path) -> anyhow::Result<()> {
+    let exp_path = path.with_extension(EXP_EXT);
+    let source = fs::read_to_string(path)?;
+    let options = FormatOptions::default();
+    let output = match format_source(&source, &options) {
+        Ok(formatted) => {
+            if format_source(&formatted, &options).ok().as_ref() != Some(&formatted) {
+                anyhow::bail!("Formatting is not idempotent:\n{}", formatted)
+            }
+            formatted
+        }
+        Err(diags) => {
+            let files = HashMap::from([(
+                FileHash::new(&source),
+                (Symbol::from(path.to_string_lossy().as_ref()), source.clone()),
+            )]);
+            String::from_utf8(report_diagnostics_to_buffer(&files, diags))?
+        }
+    };
+
+    if read_env_update_baseline() {
+        fs::write(exp_path, &output)?;
+        return Ok(());
+    }
+    let expected = fs::read_to_string(&exp_path)?;
+    if output != expected {
+        let msg = format!(
+            "Expected output differs from actual output:\n{}",
+            format_diff(expected, output),
+        );
+        anyhow::bail!(add_update_baseline_fix(msg))
+    }
+    Ok(())
+}
+
+datatest_stable::harness!(formatter_testsuite, "tests/formatter", r".*\.move$");
@@ -0,0 +1,11 @@
+module 0x8675309::M {
+    use std::vector;
+
+    fun f(v: vector<u64>) {
+        // the vector is borrowed for the whole loop
+        for (x in &mut v) {
+            *x = *x + 1;
+            vector::push_back(&mut v, 0);
+        }
+    }
+}
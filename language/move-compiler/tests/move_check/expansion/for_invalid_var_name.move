@@ -0,0 +1,6 @@
+module 0x8675309::M {
+    fun f(n: u64) {
+        for (I in 0..n) ();
+        for (Self in 0..n) ();
+    }
+}
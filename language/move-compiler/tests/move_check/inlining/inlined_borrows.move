@@ -0,0 +1,12 @@
+module 0x42::m {
+    inline fun modify(r: &mut u64, f: |&mut u64|) {
+        f(r)
+    }
+
+    fun test() {
+        let v = 0;
+        let r = &mut v;
+        modify(&mut v, |x| *x = 1);
+        *r = 2;
+    }
+}
@@ -0,0 +1,19 @@
+module 0x42::a {
+    fun secret(): u64 {
+        0
+    }
+
+    public inline fun reveal(): u64 {
+        secret()
+    }
+
+    public inline fun reveal_twice(): u64 {
+        reveal() + reveal()
+    }
+}
+
+module 0x42::b {
+    fun test(): u64 {
+        0x42::a::reveal_twice()
+    }
+}
@@ -0,0 +1,43 @@
+module 0x42::a {
+    struct S has drop { f: u64 }
+
+    fun secret(): u64 {
+        0
+    }
+
+    public fun new(): S {
+        S { f: 0 }
+    }
+
+    public inline fun call_secret(): u64 {
+        secret()
+    }
+
+    public inline fun pack(f: u64): S {
+        S { f }
+    }
+
+    public inline fun unpack(s: S): u64 {
+        let S { f } = s;
+        f
+    }
+
+    public inline fun field(s: &S): u64 {
+        s.f
+    }
+
+    public inline fun map(s: &S, g: |u64| u64): u64 {
+        g(field(s))
+    }
+}
+
+module 0x42::b {
+    use 0x42::a;
+
+    fun test(): u64 {
+        let s = a::new();
+        let x = a::call_secret() + a::field(&s) + a::map(&s, |x| x + 1);
+        let s2 = a::pack(x);
+        a::unpack(s2)
+    }
+}
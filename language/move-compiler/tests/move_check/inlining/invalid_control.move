@@ -0,0 +1,19 @@
+module 0x42::m {
+    inline fun early(x: u64): u64 {
+        if (x == 0) return 1;
+        x
+    }
+
+    inline fun apply(f: |u64|) {
+        f(0)
+    }
+
+    fun test() {
+        apply(|_x| return);
+        loop {
+            apply(|_x| break);
+            apply(|_x| continue);
+        };
+        apply(|_x| loop { break });
+    }
+}
@@ -0,0 +1,25 @@
+module 0x42::m {
+    inline fun apply(x: u64, f: |u64| u64): u64 {
+        f(x)
+    }
+
+    inline fun leak(f: |u64| u64): u64 {
+        let g = f;
+        g(1)
+    }
+
+    inline fun too_many(f: |u64| u64): u64 {
+        f(1, 2)
+    }
+
+    fun not_inline(x: u64): u64 {
+        x
+    }
+
+    fun test() {
+        apply(1, 2);
+        apply(|x| x, |x| x);
+        apply(1, |x, y| x + y);
+        not_inline(|x| x);
+    }
+}
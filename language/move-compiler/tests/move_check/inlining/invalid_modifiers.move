@@ -0,0 +1,11 @@
+module 0x42::m {
+    native inline fun n(x: u64): u64;
+
+    entry inline fun e() {}
+
+    inline struct S {}
+
+    fun not_inline(f: |u64| u64): u64 {
+        f(0)
+    }
+}
@@ -0,0 +1,45 @@
+module 0x42::m {
+    struct S has drop { f: u64 }
+
+    inline fun apply(x: u64, f: |u64| u64): u64 {
+        f(x)
+    }
+
+    inline fun call(f: || u64): u64 {
+        f()
+    }
+
+    inline fun repeat(n: u64, f: |u64|) {
+        let i = 0;
+        while (i < n) {
+            f(i);
+            i = i + 1;
+        }
+    }
+
+    inline fun with_s<T: drop>(s: S, x: T, f: |S, T| u64): u64 {
+        f(s, x)
+    }
+
+    inline fun twice(x: u64, f: |u64| u64): u64 {
+        apply(apply(x, |y| f(y)), |y| f(y))
+    }
+
+    inline fun forward(x: u64, f: |u64| u64): u64 {
+        apply(x, f)
+    }
+
+    public fun test(): u64 {
+        let i = 10;
+        let sum = 0;
+        repeat(3, |x| sum = sum + x + i);
+        let k = apply(sum, |x| x * 2);
+        let k = twice(k, |x| x + 1);
+        let k = forward(k, |x| x + i);
+        let k = k + call(|| 42);
+        with_s(S { f: k }, true, |s, b| {
+            let S { f } = s;
+            if (b) f else 0
+        })
+    }
+}
@@ -0,0 +1,17 @@
+module 0x42::m {
+    inline fun self_recursive(x: u64): u64 {
+        if (x == 0) 0 else self_recursive(x - 1)
+    }
+
+    inline fun ping(x: u64): u64 {
+        pong(x)
+    }
+
+    inline fun pong(x: u64): u64 {
+        ping(x)
+    }
+
+    fun test(): u64 {
+        self_recursive(1) + ping(2)
+    }
+}
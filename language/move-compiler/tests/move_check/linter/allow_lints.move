@@ -0,0 +1,33 @@
+module 0x1::balance {
+    native public fun transfer(src: &signer, dst: address, amount: u128): bool;
+}
+
+#[allow(unchecked_transfer)]
+module 0x42::allowed_module {
+    use 0x1::balance;
+
+    fun ignored(src: &signer, dst: address) {
+        balance::transfer(src, dst, 1);
+    }
+}
+
+module 0x42::m {
+    use 0x1::balance;
+    use std::signer;
+
+    #[allow(unchecked_transfer, self_transfer)]
+    fun allowed(src: &signer) {
+        balance::transfer(src, signer::address_of(src), 1);
+    }
+
+    #[allow(self_transfer)]
+    fun partially_allowed(src: &signer) {
+        balance::transfer(src, signer::address_of(src), 1);
+    }
+
+    #[allow(unknown_lint)]
+    fun unknown() {}
+
+    #[allow]
+    fun malformed() {}
+}
@@ -0,0 +1,13 @@
+module 0x42::m {
+    struct R has key { f: u64 }
+
+    native fun read(addr: address): u64 acquires R;
+
+    fun used(addr: address): u64 acquires R {
+        borrow_global<R>(addr).f
+    }
+
+    fun calls_native(addr: address): u64 acquires R {
+        read(addr)
+    }
+}
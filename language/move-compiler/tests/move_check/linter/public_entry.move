@@ -0,0 +1,32 @@
+module 0x42::m {
+    struct S has drop {}
+
+    // a transaction entry point that is not 'entry'
+    public fun mint(account: signer, amount: u64, data: vector<u8>) {
+        helper(&account, amount, data)
+    }
+
+    public fun mint_ref(account: &signer, amount: u64) {
+        helper(account, amount, vector[])
+    }
+
+    // called in the package
+    public fun helper(_account: &signer, _amount: u64, _data: vector<u8>) {}
+
+    // already an entry
+    public entry fun entry_mint(account: signer) {
+        helper(&account, 0, vector[])
+    }
+
+    // no signer
+    public fun no_signer(_amount: u64) {}
+
+    // returns a value
+    public fun returns(_account: &signer): u64 { 0 }
+
+    // not a valid transaction argument
+    public fun struct_param(_account: &signer, _s: S) {}
+
+    // not public
+    fun internal(_account: &signer) {}
+}
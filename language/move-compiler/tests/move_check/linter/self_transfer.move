@@ -0,0 +1,31 @@
+module 0x1::balance {
+    native public fun transfer(src: &signer, dst: address, amount: u128): bool;
+}
+
+module 0x42::m {
+    use 0x1::balance;
+    use std::signer;
+
+    fun direct(src: &signer) {
+        assert!(balance::transfer(src, signer::address_of(src), 1), 0);
+    }
+
+    fun borrowed(src: signer) {
+        assert!(balance::transfer(&src, signer::address_of(&src), 1), 0);
+    }
+
+    fun through_local(src: &signer) {
+        let addr = signer::address_of(src);
+        assert!(balance::transfer(src, addr, 1), 0);
+    }
+
+    fun other_signer(src: &signer, other: &signer) {
+        assert!(balance::transfer(src, signer::address_of(other), 1), 0);
+    }
+
+    fun reassigned(src: &signer, dst: address, cond: bool) {
+        let addr = signer::address_of(src);
+        if (cond) addr = dst;
+        assert!(balance::transfer(src, addr, 1), 0);
+    }
+}
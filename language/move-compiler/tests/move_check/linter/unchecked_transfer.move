@@ -0,0 +1,35 @@
+module 0x1::balance {
+    native public fun transfer(src: &signer, dst: address, amount: u128): bool;
+}
+
+module 0x42::m {
+    use 0x1::balance;
+
+    fun ignored(src: &signer, dst: address) {
+        balance::transfer(src, dst, 1);
+    }
+
+    fun bound_to_underscore(src: &signer, dst: address) {
+        let _ = balance::transfer(src, dst, 1);
+    }
+
+    fun assigned_to_underscore(src: &signer, dst: address) {
+        _ = balance::transfer(src, dst, 1);
+    }
+
+    fun in_branch(src: &signer, dst: address, cond: bool) {
+        if (cond) {
+            balance::transfer(src, dst, 1);
+        }
+    }
+
+    fun checked(src: &signer, dst: address) {
+        assert!(balance::transfer(src, dst, 1), 0);
+        let ok = balance::transfer(src, dst, 1);
+        assert!(ok, 1);
+    }
+
+    fun returned(src: &signer, dst: address): bool {
+        balance::transfer(src, dst, 1)
+    }
+}
@@ -0,0 +1,8 @@
+module 0x8675309::M {
+    fun f(n: u64, v: &vector<u64>) {
+        for (i in 0..n) ();
+        for (x in v) ();
+        for (_i in 0..n) ();
+        for (_ in v) ();
+    }
+}
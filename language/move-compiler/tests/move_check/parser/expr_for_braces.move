@@ -0,0 +1,10 @@
+module 0x8675309::M {
+    fun f(n: u64, v: &vector<u64>): u64 {
+        let sum = 0;
+        // Braces or parenthesis are not required for the body of a "for" expression.
+        for (i in 0..n) { sum = sum + i };
+        for (i in 0..n) ( sum = sum + i );
+        for (x in v) sum = sum + *x;
+        sum
+    }
+}
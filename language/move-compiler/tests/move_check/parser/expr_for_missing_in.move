@@ -0,0 +1,6 @@
+module 0x8675309::M {
+    fun f(n: u64) {
+        // Test a "for" expression missing the "in" keyword
+        for (i 0..n) ()
+    }
+}
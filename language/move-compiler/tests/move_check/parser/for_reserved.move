@@ -0,0 +1,6 @@
+module 0x8675309::M {
+    fun f() {
+        // "for" is a keyword and cannot be used as a local name
+        let for = 0;
+    }
+}
@@ -0,0 +1,11 @@
+module 0x8675309::M {
+    fun range_mismatch(n: u64) {
+        for (i in 0u8..n) { let _ = i; }
+    }
+
+    fun range_value_type(n: u64): bool {
+        let b = false;
+        for (i in 0..n) b = i;
+        b
+    }
+}
@@ -11,6 +11,7 @@ use move_command_line_common::{
 use move_compiler::{
     compiled_unit::AnnotatedCompiledUnit,
     diagnostics::*,
+    linters::LintLevel,
     shared::{Flags, NumericalAddress},
     unit_test, CommentMap, Compiler, SteppedCompiler, PASS_CFGIR, PASS_PARSER,
 };
@@ -24,6 +25,9 @@ const VERIFICATION_EXT: &str = "verification";
 /// Root of tests which require to set flavor flags.
 const FLAVOR_PATH: &str = "flavors/";
 
+/// Root of tests which are compiled with all lints enabled.
+const LINTER_PATH: &str = "linter/";
+
 fn default_testing_addresses() -> BTreeMap<String, NumericalAddress> {
     let mapping = [
         ("std", "0x1"),
@@ -98,6 +102,7 @@ fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
                 .to_string();
             flags = flags.set_flavor(flavor)
         }
+        Some(p) if p.contains(LINTER_PATH) => flags = flags.set_lint_level(LintLevel::All),
         _ => {}
     };
     run_test(path, &exp_path, &out_path, flags)?;
@@ -0,0 +1,59 @@
+//# publish
+module 0x42::M {
+    use std::vector;
+
+    public fun sum_range(lo: u64, hi: u64): u64 {
+        let sum = 0;
+        for (i in lo..hi) sum = sum + i;
+        sum
+    }
+
+    public fun sum_of_odds(n: u64): u64 {
+        let sum = 0;
+        for (i in 0..n) {
+            if (i % 2 == 0) continue;
+            if (i > 10) break;
+            sum = sum + i;
+        };
+        sum
+    }
+
+    public fun sum(v: &vector<u64>): u64 {
+        let sum = 0;
+        for (x in v) sum = sum + *x;
+        sum
+    }
+
+    public fun double(v: &mut vector<u64>) {
+        for (x in &mut *v) *x = *x * 2;
+    }
+
+    public fun pairs(n: u64): vector<u64> {
+        let v = vector::empty();
+        for (i in 0..n) {
+            for (j in i..n) vector::push_back(&mut v, i * 10 + j);
+        };
+        v
+    }
+}
+
+//# run
+script {
+use 0x42::M;
+fun main() {
+    assert!(M::sum_range(0, 5) == 10, 42);
+    assert!(M::sum_range(3, 3) == 0, 43);
+    assert!(M::sum_range(5, 3) == 0, 44);
+    // the loop bound is exclusive, so the loop index cannot overflow
+    assert!(M::sum_range(18446744073709551614, 18446744073709551615) == 18446744073709551614, 45);
+    assert!(M::sum_of_odds(100) == 25, 46);
+
+    let v = vector[1, 2, 3];
+    assert!(M::sum(&v) == 6, 47);
+    M::double(&mut v);
+    assert!(v == vector[2, 4, 6], 48);
+    assert!(M::sum(&vector[]) == 0, 49);
+
+    assert!(M::pairs(3) == vector[0, 1, 2, 11, 12, 22], 50);
+}
+}
@@ -0,0 +1,89 @@
+//# publish
+module 0x42::M {
+    use std::vector;
+
+    struct Counter has drop { calls: u64 }
+
+    public inline fun for_each<T: copy>(v: &vector<T>, f: |T|) {
+        let i = 0;
+        let n = vector::length(v);
+        while (i < n) {
+            f(*vector::borrow(v, i));
+            i = i + 1;
+        }
+    }
+
+    public inline fun map<T: copy, U>(v: &vector<T>, f: |T| U): vector<U> {
+        let result = vector::empty();
+        for_each(v, |x| vector::push_back(&mut result, f(x)));
+        result
+    }
+
+    public inline fun fold<T: copy, A>(v: &vector<T>, init: A, f: |A, T| A): A {
+        let acc = init;
+        for_each(v, |x| acc = f(acc, x));
+        acc
+    }
+
+    inline fun count(counter: &mut Counter, f: || u64): u64 {
+        counter.calls = counter.calls + 1;
+        f()
+    }
+
+    public fun sum(v: &vector<u64>): u64 {
+        fold(v, 0, |acc, x| acc + x)
+    }
+
+    public fun scaled(v: &vector<u64>, k: u64): vector<u64> {
+        map(v, |x| x * k)
+    }
+
+    public fun count_calls(): u64 {
+        let counter = Counter { calls: 0 };
+        let x = count(&mut counter, || 1);
+        // the lambda borrows the counter again after the inlined body is done with it
+        let y = count(&mut counter, || counter.calls * 10);
+        x + y + counter.calls
+    }
+
+    public fun checked_sum(v: &vector<u64>): u64 {
+        fold(v, 0, |acc, x| {
+            assert!(x < 100, x);
+            acc + x
+        })
+    }
+}
+
+//# run
+script {
+use 0x42::M;
+fun main() {
+    assert!(M::sum(&vector[1, 2, 3]) == 6, 42);
+    assert!(M::sum(&vector[]) == 0, 43);
+    assert!(M::scaled(&vector[1, 2, 3], 2) == vector[2, 4, 6], 44);
+    assert!(M::count_calls() == 23, 45);
+    assert!(M::checked_sum(&vector[1, 99]) == 100, 46);
+}
+}
+
+//# run
+script {
+use 0x42::M;
+fun main() {
+    // public inline functions are expanded in other modules and scripts
+    let total = 0;
+    M::for_each(&vector[1, 2, 3], |x| total = total + x);
+    assert!(total == 6, 42);
+    let bools = M::map(&vector[1, 2, 3], |x| x % 2 == 1);
+    assert!(bools == vector[true, false, true], 43);
+}
+}
+
+//# run
+script {
+use 0x42::M;
+fun main() {
+    // aborts from the lambda
+    M::checked_sum(&vector[1, 100, 2]);
+}
+}
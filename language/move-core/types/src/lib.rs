@@ -24,6 +24,7 @@ pub mod parser;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
 pub mod resolver;
+pub mod scale;
 // Not needed for pallet-move and no-std env
 #[cfg(feature = "std")]
 pub mod state;
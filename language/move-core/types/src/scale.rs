@@ -0,0 +1,139 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+//! SCALE encoding of Move values, used to exchange values with the Substrate runtime and with
+//! the contracts of other VMs (e.g. ink! contracts of pallet-contracts).
+//!
+//! Move types are mapped to the SCALE encoding of the equivalent Rust types:
+//! - `bool` and the unsigned integers are encoded as `bool`, `u8`, ..., `u128`
+//! - `u256` is encoded as 32 little-endian bytes, like `primitive_types::U256`
+//! - `address` and `signer` are encoded as the raw address bytes, like `AccountId32`
+//! - `vector<T>` is encoded as `Vec<T>`: its compact length followed by its elements
+//! - structs are encoded as Rust structs and tuples: their fields one after the other
+//!
+//! Unlike BCS, the encoding isn't self-describing enough to be decoded without the layout of the
+//! value.
+
+use crate::{
+    account_address::AccountAddress,
+    u256,
+    value::{MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Result};
+use parity_scale_codec::{Compact, Decode, Encode, Input};
+
+/// Encodes the value in SCALE.
+pub fn encode_value(value: &MoveValue) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    encode_value_to(value, &mut bytes);
+    bytes
+}
+
+/// Encodes the value in SCALE at the end of `dest`.
+pub fn encode_value_to(value: &MoveValue, dest: &mut Vec<u8>) {
+    match value {
+        MoveValue::Bool(b) => b.encode_to(dest),
+        MoveValue::U8(n) => n.encode_to(dest),
+        MoveValue::U16(n) => n.encode_to(dest),
+        MoveValue::U32(n) => n.encode_to(dest),
+        MoveValue::U64(n) => n.encode_to(dest),
+        MoveValue::U128(n) => n.encode_to(dest),
+        MoveValue::U256(n) => dest.extend_from_slice(&n.to_le_bytes()),
+        MoveValue::Address(a) | MoveValue::Signer(a) => dest.extend_from_slice(a.as_ref()),
+        MoveValue::Vector(elems) => {
+            Compact(elems.len() as u32).encode_to(dest);
+            for elem in elems {
+                encode_value_to(elem, dest);
+            }
+        }
+        MoveValue::Struct(MoveStruct::Runtime(fields)) => {
+            for field in fields {
+                encode_value_to(field, dest);
+            }
+        }
+        MoveValue::Struct(
+            MoveStruct::WithFields(fields) | MoveStruct::WithTypes { fields, .. },
+        ) => {
+            for (_, field) in fields {
+                encode_value_to(field, dest);
+            }
+        }
+    }
+}
+
+/// Decodes a SCALE encoded value of the given layout. All the bytes must be consumed.
+pub fn decode_value(layout: &MoveTypeLayout, mut bytes: &[u8]) -> Result<MoveValue> {
+    let value = decode_value_from(layout, &mut bytes)?;
+    if !bytes.is_empty() {
+        bail!(
+            "{} trailing bytes after the SCALE encoded value",
+            bytes.len()
+        );
+    }
+    Ok(value)
+}
+
+/// Decodes a SCALE encoded value of the given layout from the start of `input`.
+pub fn decode_value_from(layout: &MoveTypeLayout, input: &mut &[u8]) -> Result<MoveValue> {
+    Ok(match layout {
+        MoveTypeLayout::Bool => MoveValue::Bool(decode(input)?),
+        MoveTypeLayout::U8 => MoveValue::U8(decode(input)?),
+        MoveTypeLayout::U16 => MoveValue::U16(decode(input)?),
+        MoveTypeLayout::U32 => MoveValue::U32(decode(input)?),
+        MoveTypeLayout::U64 => MoveValue::U64(decode(input)?),
+        MoveTypeLayout::U128 => MoveValue::U128(decode(input)?),
+        MoveTypeLayout::U256 => MoveValue::U256(u256::U256::from_le_bytes(&decode::<
+            [u8; u256::U256_NUM_BYTES],
+        >(input)?)),
+        MoveTypeLayout::Address => MoveValue::Address(decode_address(input)?),
+        MoveTypeLayout::Signer => MoveValue::Signer(decode_address(input)?),
+        MoveTypeLayout::Vector(elem) => {
+            let Compact(len) = decode::<Compact<u32>>(input)?;
+            // The length isn't trusted to preallocate: the input runs out first if it's wrong.
+            let mut elems = Vec::new();
+            for _ in 0..len {
+                elems.push(decode_value_from(elem, input)?);
+            }
+            MoveValue::Vector(elems)
+        }
+        MoveTypeLayout::Struct(layout) => MoveValue::Struct(decode_struct_from(layout, input)?),
+    })
+}
+
+// The field names and types of decorated layouts are kept, as in BCS deserialization.
+fn decode_struct_from(layout: &MoveStructLayout, input: &mut &[u8]) -> Result<MoveStruct> {
+    let decode_fields = |fields: &[MoveFieldLayout], input: &mut &[u8]| {
+        fields
+            .iter()
+            .map(|field| Ok((field.name.clone(), decode_value_from(&field.layout, input)?)))
+            .collect::<Result<Vec<_>>>()
+    };
+    Ok(match layout {
+        MoveStructLayout::Runtime(fields) => MoveStruct::Runtime(
+            fields
+                .iter()
+                .map(|field| decode_value_from(field, input))
+                .collect::<Result<_>>()?,
+        ),
+        MoveStructLayout::WithFields(fields) => {
+            MoveStruct::WithFields(decode_fields(fields, input)?)
+        }
+        MoveStructLayout::WithTypes { type_, fields } => MoveStruct::WithTypes {
+            type_: type_.clone(),
+            fields: decode_fields(fields, input)?,
+        },
+    })
+}
+
+fn decode_address(input: &mut &[u8]) -> Result<AccountAddress> {
+    let mut bytes = [0u8; AccountAddress::LENGTH];
+    input
+        .read(&mut bytes)
+        .map_err(|e| anyhow!("Invalid SCALE encoded address: {}", e))?;
+    Ok(AccountAddress::new(bytes))
+}
+
+fn decode<T: Decode>(input: &mut &[u8]) -> Result<T> {
+    T::decode(input).map_err(|e| anyhow!("Invalid SCALE encoded value: {}", e))
+}
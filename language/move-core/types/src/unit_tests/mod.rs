@@ -4,5 +4,6 @@
 
 mod identifier_test;
 mod language_storage_test;
+mod scale_test;
 mod value_test;
 mod vm_status_test;
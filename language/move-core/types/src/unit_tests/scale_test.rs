@@ -0,0 +1,98 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress,
+    ident_str,
+    scale::{decode_value, encode_value},
+    u256::U256,
+    value::{MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use parity_scale_codec::Encode;
+
+/// A value of every type, with the equivalent Rust value encoded by `parity-scale-codec`.
+fn values() -> Vec<(MoveTypeLayout, MoveValue, Vec<u8>)> {
+    let address = AccountAddress::new([7u8; AccountAddress::LENGTH]);
+    vec![
+        (MoveTypeLayout::Bool, MoveValue::Bool(true), true.encode()),
+        (MoveTypeLayout::U8, MoveValue::U8(7), 7u8.encode()),
+        (MoveTypeLayout::U16, MoveValue::U16(7), 7u16.encode()),
+        (MoveTypeLayout::U32, MoveValue::U32(7), 7u32.encode()),
+        (MoveTypeLayout::U64, MoveValue::U64(7), 7u64.encode()),
+        (MoveTypeLayout::U128, MoveValue::U128(7), 7u128.encode()),
+        (
+            MoveTypeLayout::U256,
+            MoveValue::U256(U256::from(7u8)),
+            [7u64, 0, 0, 0].encode(),
+        ),
+        (
+            MoveTypeLayout::Address,
+            MoveValue::Address(address),
+            address.into_bytes().encode(),
+        ),
+        (
+            MoveTypeLayout::Signer,
+            MoveValue::Signer(address),
+            address.into_bytes().encode(),
+        ),
+        (
+            MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U16)),
+            MoveValue::Vector(vec![MoveValue::U16(1), MoveValue::U16(2)]),
+            vec![1u16, 2].encode(),
+        ),
+        (
+            MoveTypeLayout::Struct(MoveStructLayout::new(vec![
+                MoveTypeLayout::U64,
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            ])),
+            MoveValue::Struct(MoveStruct::new(vec![
+                MoveValue::U64(7),
+                MoveValue::vector_u8(b"abc".to_vec()),
+            ])),
+            (7u64, b"abc".to_vec()).encode(),
+        ),
+    ]
+}
+
+#[test]
+fn values_are_encoded_as_the_equivalent_rust_values() {
+    for (layout, value, expected) in values() {
+        let encoded = encode_value(&value);
+        assert_eq!(encoded, expected, "encoding of {}", layout);
+        assert_eq!(decode_value(&layout, &encoded).unwrap(), value);
+    }
+}
+
+#[test]
+fn decorated_structs_keep_their_field_names() {
+    let layout = MoveTypeLayout::Struct(MoveStructLayout::with_fields(vec![
+        MoveFieldLayout::new(ident_str!("f").to_owned(), MoveTypeLayout::U64),
+        MoveFieldLayout::new(ident_str!("g").to_owned(), MoveTypeLayout::Bool),
+    ]));
+    let value = MoveValue::Struct(MoveStruct::with_fields(vec![
+        (ident_str!("f").to_owned(), MoveValue::U64(7)),
+        (ident_str!("g").to_owned(), MoveValue::Bool(true)),
+    ]));
+
+    let encoded = encode_value(&value);
+    assert_eq!(encoded, (7u64, true).encode());
+    assert_eq!(decode_value(&layout, &encoded).unwrap(), value);
+}
+
+#[test]
+fn invalid_encodings_are_rejected() {
+    let layout = MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U64));
+
+    // Fewer elements than the length.
+    let mut encoded = vec![1u64, 2].encode();
+    encoded.truncate(encoded.len() - 8);
+    assert!(decode_value(&layout, &encoded).is_err());
+
+    // Trailing bytes.
+    let mut encoded = vec![1u64, 2].encode();
+    encoded.push(0);
+    assert!(decode_value(&layout, &encoded).is_err());
+
+    // Booleans other than 0 and 1.
+    assert!(decode_value(&MoveTypeLayout::Bool, &[2]).is_err());
+}
@@ -480,6 +480,20 @@ impl SourceMap {
             .ok_or_else(|| format_err!("Unable to get function source map"))
     }
 
+    /// Re-indexes the function source maps after function definitions have been removed from the
+    /// module. `remap` gives the new index of a function definition, or `None` if it was removed.
+    pub fn remap_functions(
+        &mut self,
+        remap: impl Fn(FunctionDefinitionIndex) -> Option<FunctionDefinitionIndex>,
+    ) {
+        self.function_map = std::mem::take(&mut self.function_map)
+            .into_iter()
+            .filter_map(|(idx, function_map)| {
+                remap(FunctionDefinitionIndex(idx)).map(|new_idx| (new_idx.0, function_map))
+            })
+            .collect();
+    }
+
     pub fn get_struct_source_map(
         &self,
         struct_def_idx: StructDefinitionIndex,
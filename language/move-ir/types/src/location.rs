@@ -61,6 +61,10 @@ impl Loc {
             end: self.end as usize,
         }
     }
+    /// Whether `other` lies within this location
+    pub fn contains(self, other: Loc) -> bool {
+        self.file_hash == other.file_hash && self.start <= other.start && other.end <= self.end
+    }
 }
 
 impl PartialOrd for Loc {
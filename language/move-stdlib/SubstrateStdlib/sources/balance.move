@@ -0,0 +1,15 @@
+/// Access to the native balance of Substrate accounts.
+///
+/// Transfers done from Move are cheques: the amounts are only settled on the Substrate side
+/// once the script or function call finishes successfully.
+module substrate::balance {
+    /// Transfers `amount` from the `src` account to the `dst` account.
+    /// Returns `false` if the transfer could not be done, e.g. due to insufficient funds.
+    native public fun transfer(src: &signer, dst: address, amount: u128): bool;
+
+    /// Returns the amount the account is allowed to spend within the current call.
+    native public fun cheque_amount(account: address): u128;
+
+    /// Returns the total balance of the account.
+    native public fun total_amount(account: address): u128;
+}
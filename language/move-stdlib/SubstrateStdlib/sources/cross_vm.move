@@ -0,0 +1,26 @@
+/// Calls to the contracts of other VMs running on the chain, e.g. the ink! contracts of
+/// pallet-contracts.
+///
+/// The arguments of a call are SCALE encoded: `bool` and the unsigned integers as the equivalent
+/// Rust types, `address` and `signer` as their bytes, vectors as Rust vectors (compact length
+/// followed by the elements) and structs as their fields one after the other. A struct whose
+/// fields are the arguments of a message is thus encoded as the message arguments.
+///
+/// The effects of the calls are only kept if the script or function call finishes successfully.
+module substrate::cross_vm {
+    /// Calls the `contract` message identified by `selector` with the SCALE encoded `args`,
+    /// transferring `value` from the `caller` to the contract.
+    ///
+    /// The call may consume up to `gas_limit` of the remaining gas. Returns whether the contract
+    /// executed successfully, and its SCALE encoded output.
+    ///
+    /// Aborts with code 1 if the chain doesn't support calls to other VMs.
+    native public fun call<Args>(
+        caller: &signer,
+        contract: address,
+        selector: vector<u8>,
+        args: &Args,
+        value: u128,
+        gas_limit: u64,
+    ): (bool, vector<u8>);
+}
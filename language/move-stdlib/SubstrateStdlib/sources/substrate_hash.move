@@ -0,0 +1,22 @@
+/// Hash functions commonly used within the Substrate ecosystem.
+///
+/// These complement the SHA-2/SHA-3 functions provided by `std::hash`.
+module substrate::substrate_hash {
+    /// Returns the SipHash-2-4 of `data`.
+    native public fun sip_hash(data: vector<u8>): u64;
+
+    /// Returns the 256-bit BLAKE2b hash of `data`.
+    native public fun blake2b_256(data: vector<u8>): vector<u8>;
+
+    /// Returns the RIPEMD-160 hash of `data`.
+    native public fun ripemd160(data: vector<u8>): vector<u8>;
+
+    /// Returns the Keccak-256 hash of `data`.
+    native public fun keccak256(data: vector<u8>): vector<u8>;
+
+    /// Returns the SHA2-512 hash of `data`.
+    native public fun sha2_512(data: vector<u8>): vector<u8>;
+
+    /// Returns the SHA3-512 hash of `data`.
+    native public fun sha3_512(data: vector<u8>): vector<u8>;
+}
@@ -1,28 +1,110 @@
 use std::error::Error;
-#[cfg(feature = "stdlib-bytecode")]
-use std::process::Command;
 
 fn main() -> Result<(), Box<dyn Error>> {
     #[cfg(feature = "stdlib-bytecode")]
-    build_stdlib_with_smove()?;
+    stdlib_bytecode::build_bundles()?;
 
     Ok(())
 }
 
 #[cfg(feature = "stdlib-bytecode")]
-fn build_stdlib_with_smove() -> Result<(), Box<dyn Error>> {
-    let run_build_stdlib_script = Command::new("bash")
-        .args(["build_stdlid_with_smove.sh"])
-        .output()
-        .expect("failed to execute process");
-    if !run_build_stdlib_script.status.success() {
-        let stderr = std::str::from_utf8(&run_build_stdlib_script.stderr)?;
-
-        let e = Box::<dyn Error + Send + Sync>::from(stderr);
-        return Err(e);
+mod stdlib_bytecode {
+    use move_package::BuildConfig;
+    use sha2::{Digest, Sha256};
+    use std::{
+        env,
+        error::Error,
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    /// A vendored Move package and the digests pinning its bundle.
+    struct Package {
+        name: &'static str,
+        /// Digest of the sources, as computed by `move-package`.
+        source_digest: &'static str,
+        /// SHA-256 of the built bundle.
+        bundle_digest: &'static str,
     }
 
-    // Rerun in case Move source files are changed.
-    println!("cargo:rerun-if-changed=sources/");
-    Ok(())
+    /// The digests pin both the sources the bundles are built from and the bundles themselves, so
+    /// the bundles published at genesis are always known ones, even if the compiler changes. Any
+    /// change to the Move sources or to the produced bytecode requires updating the digests here.
+    const PACKAGES: [Package; 2] = [
+        Package {
+            name: "MoveStdlib",
+            source_digest: "2ED2C2D8185FAEFA553DED3F25BD5557AB4E6F30AB423F6B63CB1B1D9C9D1BB9",
+            bundle_digest: "43CF0D6654396EFF5E0BF405532F87E442758DE6824F6A186DFCBD4430EAA044",
+        },
+        Package {
+            name: "SubstrateStdlib",
+            source_digest: "EFC8ED6D6558D01C44497E16987F5A69FDA9D6AD1977D82047A28C5F1F795B5C",
+            bundle_digest: "A628B8EA623C6A15C7149492CBF2AE5399939F3FA230873D400805B596440DB6",
+        },
+    ];
+
+    pub fn build_bundles() -> Result<(), Box<dyn Error>> {
+        let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+        let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?);
+
+        for package in PACKAGES.iter() {
+            build_bundle(package, &crate_dir.join(package.name), &out_dir)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compiles the package at `package_path` into `<out_dir>/build/<package>/bundles/<package>.mvb`.
+    fn build_bundle(
+        package: &Package,
+        package_path: &Path,
+        out_dir: &Path,
+    ) -> Result<(), Box<dyn Error>> {
+        // Rerun in case Move source files are changed.
+        println!(
+            "cargo:rerun-if-changed={}",
+            package_path.join("Move.toml").display()
+        );
+        println!(
+            "cargo:rerun-if-changed={}",
+            package_path.join("sources").display()
+        );
+
+        let compiled_package = BuildConfig {
+            generate_bundle: true,
+            install_dir: Some(out_dir.to_path_buf()),
+            force_recompilation: true,
+            skip_fetch_latest_git_deps: true,
+            ..Default::default()
+        }
+        .compile_package(package_path, &mut std::io::stderr())?;
+
+        let package_info = &compiled_package.compiled_package_info;
+        let digest = package_info
+            .source_digest
+            .ok_or("missing source digest for the compiled package")?;
+        if digest.as_str() != package.source_digest {
+            return Err(format!(
+                "sources of package '{}' have changed: expected digest {}, found {}",
+                package_info.package_name, package.source_digest, digest
+            )
+            .into());
+        }
+
+        let bundle_path = out_dir
+            .join("build")
+            .join(package.name)
+            .join("bundles")
+            .join(format!("{}.mvb", package.name));
+        let bundle_digest = hex::encode_upper(Sha256::digest(fs::read(bundle_path)?));
+        if bundle_digest != package.bundle_digest {
+            return Err(format!(
+                "bundle of package '{}' has changed: expected digest {}, found {}",
+                package_info.package_name, package.bundle_digest, bundle_digest
+            )
+            .into());
+        }
+
+        Ok(())
+    }
 }
@@ -10,15 +10,15 @@
 /// using the `offer::create` function.
 /// Then account B, in a separate transaction, can move the struct `T` from the `Offer` at
 /// A's address to the desired destination. B accesses the resource using the `redeem` function,
-/// which aborts unless the `for` field is B's address (preventing other addresses from
+/// which aborts unless the `for_address` field is B's address (preventing other addresses from
 /// accessing the `T` that is intended only for B). A can also redeem the `T` value if B hasn't
 /// redeemed it.
 module std::offer {
   use std::signer;
   use std::error;
 
-  /// A wrapper around value `offered` that can be claimed by the address stored in `for`.
-  struct Offer<Offered> has key { offered: Offered, for: address }
+  /// A wrapper around value `offered` that can be claimed by the address stored in `for_address`.
+  struct Offer<Offered> has key { offered: Offered, for_address: address }
 
   /// An offer of the specified type for the account does not exist
   const EOFFER_DNE_FOR_ACCOUNT: u64 = 0;
@@ -30,28 +30,28 @@ module std::offer {
   const EOFFER_DOES_NOT_EXIST: u64 = 2;
 
   /// Publish a value of type `Offered` under the sender's account. The value can be claimed by
-  /// either the `for` address or the transaction sender.
-  public fun create<Offered: store>(account: &signer, offered: Offered, for: address) {
+  /// either the `for_address` or the transaction sender.
+  public fun create<Offered: store>(account: &signer, offered: Offered, for_address: address) {
     assert!(!exists<Offer<Offered>>(signer::address_of(account)), error::already_exists(EOFFER_ALREADY_CREATED));
-    move_to(account, Offer<Offered> { offered, for });
+    move_to(account, Offer<Offered> { offered, for_address });
   }
   spec create {
-    /// Offer a struct to the account under address `for` by
+    /// Offer a struct to the account under address `for_address` by
     /// placing the offer under the signer's address
     aborts_if exists<Offer<Offered>>(signer::address_of(account));
     ensures exists<Offer<Offered>>(signer::address_of(account));
-    ensures global<Offer<Offered>>(signer::address_of(account)) == Offer<Offered> { offered: offered, for: for };
+    ensures global<Offer<Offered>>(signer::address_of(account)) == Offer<Offered> { offered: offered, for_address: for_address };
   }
 
   /// Claim the value of type `Offered` published at `offer_address`.
-  /// Only succeeds if the sender is the intended recipient stored in `for` or the original
+  /// Only succeeds if the sender is the intended recipient stored in `for_address` or the original
   /// publisher `offer_address`.
   /// Also fails if there is no `Offer<Offered>` published.
   public fun redeem<Offered: store>(account: &signer, offer_address: address): Offered acquires Offer {
     assert!(exists<Offer<Offered>>(offer_address), error::not_found(EOFFER_DOES_NOT_EXIST));
-    let Offer<Offered> { offered, for } = move_from<Offer<Offered>>(offer_address);
+    let Offer<Offered> { offered, for_address } = move_from<Offer<Offered>>(offer_address);
     let sender = signer::address_of(account);
-    assert!(sender == for || sender == offer_address, error::invalid_argument(EOFFER_DNE_FOR_ACCOUNT));
+    assert!(sender == for_address || sender == offer_address, error::invalid_argument(EOFFER_DNE_FOR_ACCOUNT));
     offered
   }
   spec redeem {
@@ -79,14 +79,14 @@ module std::offer {
   // Fails if no such `Offer` exists.
   public fun address_of<Offered: store>(offer_address: address): address acquires Offer {
     assert!(exists<Offer<Offered>>(offer_address), error::not_found(EOFFER_DOES_NOT_EXIST));
-    borrow_global<Offer<Offered>>(offer_address).for
+    borrow_global<Offer<Offered>>(offer_address).for_address
   }
   spec address_of {
     /// Aborts is there is no offer resource `Offer` at the `offer_address`.
     /// Returns the address of the intended recipient of the Offer
     /// under the `offer_address`.
     aborts_if !exists<Offer<Offered>>(offer_address);
-    ensures result == global<Offer<Offered>>(offer_address).for;
+    ensures result == global<Offer<Offered>>(offer_address).for_address;
   }
 
 // =================================================================
@@ -126,7 +126,7 @@ module std::offer {
     /// Returns true if the recipient is allowed to redeem `Offer<Offered>` at `offer_address`
     /// and false otherwise.
     fun is_allowed_recipient<Offered>(offer_addr: address, recipient: address): bool {
-      recipient == global<Offer<Offered>>(offer_addr).for || recipient == offer_addr
+      recipient == global<Offer<Offered>>(offer_addr).for_address || recipient == offer_addr
     }
   }
 
@@ -18,11 +18,17 @@ pub mod doc;
 /// Provides a precompiled bundle of move-stdlib bytecode modules.
 #[cfg(feature = "stdlib-bytecode")]
 pub fn move_stdlib_bundle() -> &'static [u8] {
-    include_bytes!("../MoveStdlib/build/MoveStdlib/bundles/MoveStdlib.mvb")
+    include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/build/MoveStdlib/bundles/MoveStdlib.mvb"
+    ))
 }
 
 /// Provides a precompiled bundle of substrate-stdlib bytecode modules.
 #[cfg(feature = "stdlib-bytecode")]
 pub fn substrate_stdlib_bundle() -> &'static [u8] {
-    include_bytes!("../SubstrateStdlib/build/SubstrateStdlib/bundles/SubstrateStdlib.mvb")
+    include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/build/SubstrateStdlib/bundles/SubstrateStdlib.mvb"
+    ))
 }
@@ -0,0 +1,173 @@
+// Copyright (c) Eiger, Equilibrium Group
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::natives::helpers::make_module_natives;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{collections::VecDeque, sync::Arc};
+use better_any::{Tid, TidAble};
+use move_binary_format::errors::{PartialVMError, PartialVMResult};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::{InternalGas, InternalGasPerByte, NumBytes},
+    scale,
+    vm_status::StatusCode,
+};
+use move_vm_runtime::native_functions::{NativeContext, NativeFunction};
+use move_vm_types::{
+    loaded_data::runtime_types::Type,
+    natives::function::NativeResult,
+    pop_arg,
+    values::{values_impl::Reference, SignerRef, Value},
+};
+use smallvec::smallvec;
+
+/// Abort code of `cross_vm::call` when the VM isn't executed with a [`NativeCrossVmContext`].
+pub const ECROSS_VM_UNAVAILABLE: u64 = 1;
+
+/// A call from Move to a contract of another VM.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrossVmCall {
+    /// Address of the Move account making the call.
+    pub caller: AccountAddress,
+    /// Address of the called contract.
+    pub contract: AccountAddress,
+    /// Input of the contract: the selector of the message followed by its SCALE encoded
+    /// arguments.
+    pub input: Vec<u8>,
+    /// Amount transferred from the caller to the contract.
+    pub value: u128,
+    /// Maximum amount of gas, in Move gas units, the call may consume. The limit given in Move is
+    /// capped by the gas left to the execution.
+    pub gas_limit: u64,
+}
+
+/// Outcome of a call to a contract of another VM.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CrossVmOutput {
+    /// Whether the contract executed successfully, its changes being reverted otherwise.
+    pub success: bool,
+    /// Output of the contract, SCALE encoded.
+    pub data: Vec<u8>,
+    /// Gas consumed by the call, in Move gas units.
+    pub gas_used: u64,
+}
+
+/// A backend that can dispatch calls to the contracts of other VMs.
+pub trait CrossVmResolver {
+    /// Resolver should execute the call and return its outcome. Errors abort the Move execution
+    /// with the returned status code.
+    fn call(&self, call: CrossVmCall) -> Result<CrossVmOutput, StatusCode>;
+}
+
+/// The native context extension giving access to the contracts of other VMs.
+#[derive(Tid)]
+pub struct NativeCrossVmContext<'a> {
+    resolver: &'a dyn CrossVmResolver,
+}
+
+impl<'a> NativeCrossVmContext<'a> {
+    pub fn new(resolver: &'a dyn CrossVmResolver) -> Self {
+        Self { resolver }
+    }
+}
+
+/***************************************************************************************************
+ * native fun call
+ *
+ *   gas cost: base_cost + per_byte * (size_of(input) + size_of(output)) + gas used by the call
+ *
+ *             The call fails with out of gas before being made if the gas left doesn't cover the
+ *             base and input costs, and its gas limit is capped by the gas left once they are
+ *             charged. The gas used by the call is charged up to its gas limit, at `per_gas_unit`
+ *             internal gas per Move gas unit.
+ *
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct CallGasParameters {
+    pub base: InternalGas,
+    pub per_byte: InternalGasPerByte,
+    pub per_gas_unit: InternalGas,
+}
+
+/// Rust implementation of Move's `native public fun call<Args>(&signer, address, vector<u8>,
+/// &Args, u128, u64): (bool, vector<u8>)`
+fn native_call(
+    gas_params: &CallGasParameters,
+    context: &mut NativeContext,
+    mut ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.len() == 1);
+    debug_assert!(args.len() == 6);
+
+    let mut cost = gas_params.base;
+    if !context.extensions().contains::<NativeCrossVmContext>() {
+        return Ok(NativeResult::err(cost, ECROSS_VM_UNAVAILABLE));
+    }
+
+    let gas_limit = pop_arg!(args, u64);
+    let value = pop_arg!(args, u128);
+    let call_args = pop_arg!(args, Reference);
+    let selector = pop_arg!(args, Vec<u8>);
+    let contract = pop_arg!(args, AccountAddress);
+    let caller = pop_arg!(args, SignerRef).address()?;
+
+    let layout = context
+        .type_to_type_layout(&ty_args.pop().unwrap())?
+        .ok_or_else(|| {
+            PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                .with_message("Cross-VM call arguments without a layout".into())
+        })?;
+    let mut input = selector;
+    scale::encode_value_to(&call_args.read_ref()?.as_move_value(&layout), &mut input);
+    cost += gas_params.per_byte * NumBytes::new(input.len() as u64);
+
+    let Some(gas_left) = context.gas_balance().checked_sub(cost) else {
+        return Ok(NativeResult::out_of_gas(cost));
+    };
+    let per_gas_unit = u64::from(gas_params.per_gas_unit);
+    let gas_limit = u64::from(gas_left)
+        .checked_div(per_gas_unit)
+        .map_or(gas_limit, |gas_units_left| gas_limit.min(gas_units_left));
+
+    let resolver = context.extensions().get::<NativeCrossVmContext>().resolver;
+    let output = resolver
+        .call(CrossVmCall {
+            caller,
+            contract,
+            input,
+            value,
+            gas_limit,
+        })
+        .map_err(PartialVMError::new)?;
+    cost += gas_params.per_byte * NumBytes::new(output.data.len() as u64);
+    cost += InternalGas::new(output.gas_used.min(gas_limit).saturating_mul(per_gas_unit));
+
+    Ok(NativeResult::ok(
+        cost,
+        smallvec![Value::bool(output.success), Value::vector_u8(output.data)],
+    ))
+}
+
+pub fn make_native_call(gas_params: CallGasParameters) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_call(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
+/***************************************************************************************************
+ * module
+ **************************************************************************************************/
+#[derive(Debug, Clone)]
+pub struct GasParameters {
+    pub call: CallGasParameters,
+}
+
+pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
+    let natives = [("call", make_native_call(gas_params.call))];
+
+    make_module_natives(natives)
+}
@@ -4,6 +4,7 @@
 
 pub mod balance;
 pub mod bcs;
+pub mod cross_vm;
 pub mod debug;
 pub mod event;
 pub mod hash;
@@ -30,6 +31,7 @@ pub struct GasParameters {
     pub type_name: type_name::GasParameters,
     pub vector: vector::GasParameters,
     pub balance: balance::GasParameters,
+    pub cross_vm: cross_vm::GasParameters,
     pub substrate_hash: substrate_hash::GasParameters,
 
     #[cfg(feature = "testing")]
@@ -101,6 +103,13 @@ impl GasParameters {
                 cheque_amount: balance::ChequeAmountGasParameters { base: 0.into() },
                 total_amount: balance::TotalAmountGasParameters { base: 0.into() },
             },
+            cross_vm: cross_vm::GasParameters {
+                call: cross_vm::CallGasParameters {
+                    base: 0.into(),
+                    per_byte: 0.into(),
+                    per_gas_unit: 0.into(),
+                },
+            },
             substrate_hash: substrate_hash::GasParameters {
                 sip_hash: substrate_hash::SipHashGasParameters {
                     base: 0.into(),
@@ -159,6 +168,7 @@ pub fn all_natives(
     add_natives!("type_name", type_name::make_all(gas_params.type_name));
     add_natives!("vector", vector::make_all(gas_params.vector));
     add_natives!("balance", balance::make_all(gas_params.balance));
+    add_natives!("cross_vm", cross_vm::make_all(gas_params.cross_vm));
     add_natives!(
         "substrate_hash",
         substrate_hash::make_all(gas_params.substrate_hash)
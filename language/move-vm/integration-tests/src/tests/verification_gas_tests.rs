@@ -0,0 +1,89 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units};
+use move_bytecode_verifier::VerifierConfig;
+use move_core_types::{account_address::AccountAddress, vm_status::StatusCode};
+use move_vm_runtime::{config::VMConfig, move_vm::MoveVM};
+use move_vm_test_utils::{
+    gas_schedule::{zero_cost_schedule, Gas, GasStatus},
+    InMemoryStorage,
+};
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+const GAS_BUDGET: u64 = 1_000_000_000;
+
+fn module_blob() -> Vec<u8> {
+    let code = r#"
+        module {{ADDR}}::M {
+            fun foo(x: u64): u64 {
+                let i = 0;
+                while (i < x) {
+                    if (i % 2 == 0) x = x + 1 else x = x - 1;
+                    i = i + 1;
+                };
+                x
+            }
+
+            fun bar(v: &mut vector<u64>) {
+                *v = vector[foo(1), foo(2)];
+            }
+        }
+    "#;
+    let code = code.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let mut units = compile_units(&code).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+    blob
+}
+
+/// Publishes the module with the per-module verifier limit `max_per_mod_meter_units`, charging
+/// one gas unit per unit of verification work. Returns the status and the gas used.
+fn publish(blob: Vec<u8>, max_per_mod_meter_units: Option<u128>) -> (StatusCode, u64) {
+    let storage = InMemoryStorage::new();
+    let vm = MoveVM::new_with_config(
+        vec![],
+        VMConfig {
+            verifier: VerifierConfig {
+                max_per_mod_meter_units,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let cost_table = zero_cost_schedule();
+    let mut gas_status = GasStatus::new(&cost_table, Gas::new(GAS_BUDGET));
+    gas_status.set_verification_unit_cost(1000);
+
+    let mut sess = vm.new_session(&storage);
+    let status = match sess.publish_module(blob, TEST_ADDR, &mut gas_status) {
+        Ok(()) => StatusCode::EXECUTED,
+        Err(err) => err.major_status(),
+    };
+    let gas_used = GAS_BUDGET - u64::from(gas_status.remaining_gas());
+    (status, gas_used)
+}
+
+#[test]
+fn rejected_modules_are_charged_for_verification() {
+    let blob = module_blob();
+    let (status, verification_units) = publish(blob.clone(), None);
+    assert_eq!(status, StatusCode::EXECUTED);
+    assert!(verification_units > 0, "verification wasn't charged");
+
+    // The module exceeds the limit once its last function is verified: all the work is done.
+    let (status, gas_used) = publish(blob.clone(), Some(verification_units as u128 - 1));
+    assert_eq!(status, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert_eq!(gas_used, verification_units);
+
+    // The work on the function exceeding the limit is charged too.
+    let (status, gas_used) = publish(blob, Some(1));
+    assert_eq!(status, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert!(
+        gas_used > 0,
+        "rejected module wasn't charged for verification"
+    );
+    assert!(gas_used < verification_units);
+}
@@ -1,6 +1,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::verified_module_cache::VerifiedModuleCache;
 use move_binary_format::file_format_common::VERSION_MAX;
 use move_bytecode_verifier::VerifierConfig;
 
@@ -15,6 +16,9 @@ pub struct VMConfig {
     pub paranoid_type_checks: bool,
     /// Maximum value nest depth for structs
     pub max_value_nest_depth: Option<u64>,
+    /// Cache of verified modules shared with other VMs. Modules found in it are not verified
+    /// again when they are loaded from storage.
+    pub verified_module_cache: Option<VerifiedModuleCache>,
 }
 
 impl Default for VMConfig {
@@ -24,6 +28,7 @@ impl Default for VMConfig {
             max_binary_format_version: VERSION_MAX,
             paranoid_type_checks: false,
             max_value_nest_depth: Some(DEFAULT_MAX_VALUE_NEST_DEPTH),
+            verified_module_cache: None,
         }
     }
 }
@@ -0,0 +1,185 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hooks for debuggers driving the interpreter.
+//!
+//! A debugger is attached to a session by adding a [`DebuggerExtension`] to the session's native
+//! context extensions. The interpreter then calls the debugger's [`DebugHook`] before executing
+//! each instruction, with a [`DebugState`] to inspect the call stack, the locals of each frame and
+//! global storage. Execution only continues once the hook returns, so a debugger pauses the
+//! program by blocking in the hook, e.g. until a client asks to step or continue.
+
+use crate::{
+    loader::{Function, Loader},
+    native_extensions::NativeContextExtensions,
+};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use better_any::{Tid, TidAble};
+use move_binary_format::{
+    errors::PartialVMResult,
+    file_format::{Bytecode, FunctionDefinitionIndex},
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TypeTag},
+};
+use move_vm_types::{
+    loaded_data::runtime_types::Type,
+    values::{self, Locals},
+};
+
+/// Called by the interpreter while it executes code of a session the debugger is attached to.
+pub trait DebugHook {
+    /// Called before the instruction at the top of the call stack is executed.
+    fn on_instruction(&mut self, state: &mut DebugState);
+}
+
+/// Attaches a [`DebugHook`] to a session through its native context extensions.
+#[derive(Tid)]
+pub struct DebuggerExtension {
+    hook: Box<dyn DebugHook>,
+}
+
+impl DebuggerExtension {
+    pub fn new(hook: impl DebugHook + 'static) -> Self {
+        Self {
+            hook: Box::new(hook),
+        }
+    }
+
+    /// Takes the debugger out of `extensions` if there is one, to be held by the interpreter while
+    /// it executes.
+    pub(crate) fn take_from(extensions: &mut NativeContextExtensions) -> Option<Self> {
+        if extensions.contains::<Self>() {
+            Some(extensions.remove::<Self>())
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn on_instruction(&mut self, state: &mut DebugState) {
+        self.hook.on_instruction(state)
+    }
+}
+
+/// A frame on the call stack, as seen by a debugger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackFrame {
+    /// The module of the function, or `None` for a script.
+    pub module: Option<ModuleId>,
+    pub function: String,
+    /// Index of the function definition in its module. Scripts have a single function at index 0.
+    pub function_index: FunctionDefinitionIndex,
+    pub type_args: Vec<TypeTag>,
+    pub pc: u16,
+}
+
+pub(crate) struct DebugFrame<'a> {
+    pub(crate) function: &'a Function,
+    pub(crate) pc: u16,
+    pub(crate) locals: &'a Locals,
+    pub(crate) ty_args: &'a [Type],
+}
+
+pub(crate) type GlobalInspector<'a> =
+    dyn FnMut(AccountAddress, &TypeTag) -> PartialVMResult<Option<String>> + 'a;
+
+/// The state of the interpreter before executing an instruction. Frames are numbered from the top
+/// of the call stack, i.e. frame 0 is executing the instruction.
+pub struct DebugState<'a> {
+    frames: Vec<DebugFrame<'a>>,
+    instruction: &'a Bytecode,
+    loader: &'a Loader,
+    global: &'a mut GlobalInspector<'a>,
+}
+
+impl<'a> DebugState<'a> {
+    pub(crate) fn new(
+        frames: Vec<DebugFrame<'a>>,
+        instruction: &'a Bytecode,
+        loader: &'a Loader,
+        global: &'a mut GlobalInspector<'a>,
+    ) -> Self {
+        Self {
+            frames,
+            instruction,
+            loader,
+            global,
+        }
+    }
+
+    /// Number of frames on the call stack.
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The instruction about to be executed.
+    pub fn instruction(&self) -> &Bytecode {
+        self.instruction
+    }
+
+    /// The module of the function about to execute an instruction, or `None` for a script.
+    pub fn module(&self) -> Option<&ModuleId> {
+        self.frames[0].function.module_id()
+    }
+
+    /// Index of the function about to execute an instruction.
+    pub fn function_index(&self) -> FunctionDefinitionIndex {
+        self.frames[0].function.index()
+    }
+
+    /// Offset of the instruction about to be executed.
+    pub fn pc(&self) -> u16 {
+        self.frames[0].pc
+    }
+
+    pub fn stack_trace(&self) -> Vec<StackFrame> {
+        self.frames
+            .iter()
+            .map(|frame| StackFrame {
+                module: frame.function.module_id().cloned(),
+                function: frame.function.name().into(),
+                function_index: frame.function.index(),
+                type_args: frame
+                    .ty_args
+                    .iter()
+                    .filter_map(|ty| self.loader.type_to_type_tag(ty).ok())
+                    .collect(),
+                pc: frame.pc,
+            })
+            .collect()
+    }
+
+    /// The locals of the given frame, rendered as text, in the order they are declared in. Locals
+    /// that are not initialized or have been moved out of are `None`. Returns `None` if there is
+    /// no such frame.
+    pub fn locals(&self, frame: usize) -> Option<Vec<Option<String>>> {
+        let frame = self.frames.get(frame)?;
+        let locals = frame.locals;
+        let rendered = (0..frame.function.local_count())
+            .map(|idx| {
+                if locals.is_invalid(idx).ok()? {
+                    return None;
+                }
+                let mut buf = String::new();
+                let value = locals.copy_loc(idx).ok()?;
+                values::debug::print_value(&mut buf, &value).ok()?;
+                Some(buf)
+            })
+            .collect();
+        Some(rendered)
+    }
+
+    /// The resource of type `tag` stored under `address`, rendered as text, or `None` if there is
+    /// no such resource.
+    ///
+    /// Inspecting a resource loads it into the data cache of the session, so an access to it by
+    /// the program is not charged for loading it again.
+    pub fn global(
+        &mut self,
+        address: AccountAddress,
+        tag: &TypeTag,
+    ) -> PartialVMResult<Option<String>> {
+        (self.global)(address, tag)
+    }
+}
@@ -0,0 +1,225 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured traces of the execution of Move code.
+//!
+//! Unlike the text trace written by the `debugging` feature, a trace is collected in memory, is
+//! available in `no_std` builds and can be serialized, e.g. to replay a failed transaction
+//! offline. Tracing is enabled per session by adding an [`ExecutionTracer`] to the session's
+//! native context extensions; the interpreter records into it while executing functions and
+//! scripts of that session, and the tracer can be taken back out of the extensions afterwards.
+
+use crate::{
+    loader::{Function, Loader},
+    native_extensions::NativeContextExtensions,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use better_any::{Tid, TidAble};
+use move_binary_format::{errors::VMResult, file_format::Bytecode};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::InternalGas,
+    language_storage::{ModuleId, TypeTag},
+    vm_status::StatusCode,
+};
+use move_vm_types::loaded_data::runtime_types::Type;
+use serde::{Deserialize, Serialize};
+
+/// The recorded steps of an execution, in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    pub events: Vec<TraceEvent>,
+}
+
+/// A single step of an execution. Gas is given in internal gas units.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceEvent {
+    /// A frame for a Move function was pushed onto the call stack. The outermost frame has depth
+    /// 1.
+    CallFrame {
+        depth: u64,
+        module: Option<ModuleId>,
+        function: String,
+        type_args: Vec<TypeTag>,
+    },
+    /// The instruction at `pc` in the current frame was executed, leaving `gas_left`.
+    Instruction {
+        pc: u16,
+        instruction: String,
+        gas_left: u64,
+    },
+    /// A native function was called from the current frame, leaving `gas_left`.
+    NativeCall {
+        module: Option<ModuleId>,
+        function: String,
+        type_args: Vec<TypeTag>,
+        gas_left: u64,
+    },
+    /// A resource in global storage was accessed by the current instruction.
+    ResourceAccess {
+        kind: ResourceAccessKind,
+        address: AccountAddress,
+        resource: Option<TypeTag>,
+    },
+    /// The frame at `depth` returned to its caller.
+    Return { depth: u64 },
+    /// Execution stopped with an error.
+    Error {
+        status_code: StatusCode,
+        sub_status: Option<u64>,
+        gas_left: u64,
+    },
+}
+
+/// The kind of global storage access of a `ResourceAccess` event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResourceAccessKind {
+    BorrowGlobal,
+    BorrowGlobalMut,
+    Exists,
+    MoveFrom,
+    MoveTo,
+}
+
+/// Collects an [`ExecutionTrace`]. Add it to the native context extensions of a session to trace
+/// everything executed in that session.
+#[derive(Default, Tid)]
+pub struct ExecutionTracer {
+    trace: ExecutionTrace,
+    /// Index of the last event whose gas is only known once the next step starts.
+    pending_gas: Option<usize>,
+    /// Depth of the call stack.
+    depth: u64,
+}
+
+impl ExecutionTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The trace recorded so far.
+    pub fn trace(&self) -> &ExecutionTrace {
+        &self.trace
+    }
+
+    pub fn into_trace(self) -> ExecutionTrace {
+        self.trace
+    }
+
+    /// Takes the tracer out of `extensions` if there is one, to be held by the interpreter while
+    /// it executes.
+    pub(crate) fn take_from(extensions: &mut NativeContextExtensions) -> Option<Self> {
+        if extensions.contains::<Self>() {
+            Some(extensions.remove::<Self>())
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn call_frame(
+        &mut self,
+        function: &Function,
+        ty_args: &[Type],
+        loader: &Loader,
+        gas_left: InternalGas,
+    ) {
+        self.settle(gas_left);
+        self.depth += 1;
+        self.push(TraceEvent::CallFrame {
+            depth: self.depth,
+            module: function.module_id().cloned(),
+            function: function.name().to_string(),
+            type_args: type_tags(ty_args, loader),
+        });
+    }
+
+    pub(crate) fn native_call(
+        &mut self,
+        function: &Function,
+        ty_args: &[Type],
+        loader: &Loader,
+        gas_left: InternalGas,
+    ) {
+        self.settle(gas_left);
+        self.push_pending(TraceEvent::NativeCall {
+            module: function.module_id().cloned(),
+            function: function.name().to_string(),
+            type_args: type_tags(ty_args, loader),
+            gas_left: gas_left.into(),
+        });
+    }
+
+    pub(crate) fn instruction(&mut self, pc: u16, instruction: &Bytecode, gas_left: InternalGas) {
+        self.settle(gas_left);
+        self.push_pending(TraceEvent::Instruction {
+            pc,
+            instruction: format!("{:?}", instruction),
+            gas_left: gas_left.into(),
+        });
+    }
+
+    pub(crate) fn resource_access(
+        &mut self,
+        kind: ResourceAccessKind,
+        address: AccountAddress,
+        ty: &Type,
+        loader: &Loader,
+    ) {
+        // Recorded while the instruction is executing, so it must not settle the instruction.
+        self.trace.events.push(TraceEvent::ResourceAccess {
+            kind,
+            address,
+            resource: loader.type_to_type_tag(ty).ok(),
+        });
+    }
+
+    pub(crate) fn ret(&mut self, gas_left: InternalGas) {
+        self.settle(gas_left);
+        self.push(TraceEvent::Return { depth: self.depth });
+        self.depth = self.depth.saturating_sub(1);
+    }
+
+    /// Records the end of an execution started by the interpreter.
+    pub(crate) fn finish<T>(&mut self, result: &VMResult<T>, gas_left: InternalGas) {
+        self.settle(gas_left);
+        if let Err(err) = result {
+            self.push(TraceEvent::Error {
+                status_code: err.major_status(),
+                sub_status: err.sub_status(),
+                gas_left: gas_left.into(),
+            });
+        }
+        self.depth = 0;
+    }
+
+    /// Sets the gas left after the last pending step.
+    fn settle(&mut self, gas: InternalGas) {
+        if let Some(idx) = self.pending_gas.take() {
+            if let TraceEvent::Instruction { gas_left, .. }
+            | TraceEvent::NativeCall { gas_left, .. } = &mut self.trace.events[idx]
+            {
+                *gas_left = gas.into();
+            }
+        }
+    }
+
+    fn push(&mut self, event: TraceEvent) {
+        self.trace.events.push(event);
+    }
+
+    fn push_pending(&mut self, event: TraceEvent) {
+        self.pending_gas = Some(self.trace.events.len());
+        self.trace.events.push(event);
+    }
+}
+
+fn type_tags(ty_args: &[Type], loader: &Loader) -> Vec<TypeTag> {
+    ty_args
+        .iter()
+        .filter_map(|ty| loader.type_to_type_tag(ty).ok())
+        .collect()
+}
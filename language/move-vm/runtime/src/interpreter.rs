@@ -6,6 +6,8 @@
 use crate::trace;
 
 use crate::{
+    debugger::{DebugFrame, DebugState, DebuggerExtension},
+    execution_trace::{ExecutionTracer, ResourceAccessKind},
     loader::{Function, Loader, Resolver},
     native_functions::NativeContext,
 };
@@ -38,7 +40,7 @@ use alloc::string::String;
 use alloc::string::ToString;
 use alloc::vec::Vec;
 use alloc::{collections::VecDeque, fmt::Write, sync::Arc};
-use core::cmp::min;
+use core::{cmp::min, iter};
 use tracing::error;
 
 macro_rules! debug_write {
@@ -77,6 +79,10 @@ pub(crate) struct Interpreter {
     call_stack: CallStack,
     /// Whether to perform a paranoid type safety checks at runtime.
     paranoid_type_checks: bool,
+    /// Collects a structured trace of the execution, if one was requested.
+    tracer: Option<ExecutionTracer>,
+    /// The debugger attached to the session, if any.
+    debugger: Option<DebuggerExtension>,
 }
 
 struct TypeWithLoader<'a, 'b> {
@@ -102,14 +108,25 @@ impl Interpreter {
         extensions: &mut NativeContextExtensions,
         loader: &Loader,
     ) -> VMResult<Vec<Value>> {
-        Interpreter {
+        let mut interpreter = Interpreter {
             operand_stack: Stack::new(),
             call_stack: CallStack::new(),
             paranoid_type_checks: loader.vm_config().paranoid_type_checks,
-        }
-        .execute_main(
+            tracer: ExecutionTracer::take_from(extensions),
+            debugger: DebuggerExtension::take_from(extensions),
+        };
+        let result = interpreter.execute_main(
             loader, data_store, gas_meter, extensions, function, ty_args, args,
-        )
+        );
+        // Hand the tracer back so it outlives this call and collects the rest of the session.
+        if let Some(mut tracer) = interpreter.tracer.take() {
+            tracer.finish(&result, gas_meter.balance_internal());
+            extensions.add(tracer);
+        }
+        if let Some(debugger) = interpreter.debugger.take() {
+            extensions.add(debugger);
+        }
+        result
     }
 
     /// Main loop for the execution of a function.
@@ -119,7 +136,7 @@ impl Interpreter {
     /// on call. When that happens the frame is changes to a new one (call) or to the one
     /// at the top of the stack (return). If the call stack is empty execution is completed.
     fn execute_main(
-        mut self,
+        &mut self,
         loader: &Loader,
         data_store: &mut impl DataStore,
         gas_meter: &mut impl GasMeter,
@@ -138,12 +155,12 @@ impl Interpreter {
         let mut current_frame = self
             .make_new_frame(loader, function, ty_args, locals)
             .map_err(|err| self.set_location(err))?;
+        self.trace_call_frame(&current_frame, loader, gas_meter);
         loop {
             let resolver = current_frame.resolver(loader);
-            let exit_code =
-                current_frame //self
-                    .execute_code(&resolver, &mut self, data_store, gas_meter)
-                    .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
+            let exit_code = current_frame //self
+                .execute_code(&resolver, self, data_store, gas_meter)
+                .map_err(|err| self.maybe_core_dump(err, &current_frame))?;
             match exit_code {
                 ExitCode::Return => {
                     let non_ref_vals = current_frame
@@ -155,14 +172,17 @@ impl Interpreter {
                     gas_meter
                         .charge_drop_frame(non_ref_vals.into_iter())
                         .map_err(|e| self.set_location(e))?;
+                    if let Some(tracer) = &mut self.tracer {
+                        tracer.ret(gas_meter.balance_internal());
+                    }
 
                     if let Some(frame) = self.call_stack.pop() {
                         // Note: the caller will find the callee's return values at the top of the shared operand stack
                         current_frame = frame;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
                     } else {
-                        // end of execution. `self` should no longer be used afterward
-                        return Ok(self.operand_stack.value);
+                        // end of execution
+                        return Ok(core::mem::take(&mut self.operand_stack.value));
                     }
                 }
                 ExitCode::Call(fh_idx) => {
@@ -192,6 +212,9 @@ impl Interpreter {
                         .map_err(|e| set_err_info!(current_frame, e))?;
 
                     if func.is_native() {
+                        if let Some(tracer) = &mut self.tracer {
+                            tracer.native_call(&func, &[], loader, gas_meter.balance_internal());
+                        }
                         self.call_native(
                             &resolver,
                             data_store,
@@ -212,6 +235,7 @@ impl Interpreter {
                         let err = set_err_info!(frame, err);
                         self.maybe_core_dump(err, &frame)
                     })?;
+                    self.trace_call_frame(&frame, loader, gas_meter);
                     // Note: the caller will find the the callee's return values at the top of the shared operand stack
                     current_frame = frame;
                 }
@@ -247,6 +271,14 @@ impl Interpreter {
                         .map_err(|e| set_err_info!(current_frame, e))?;
 
                     if func.is_native() {
+                        if let Some(tracer) = &mut self.tracer {
+                            tracer.native_call(
+                                &func,
+                                &ty_args,
+                                loader,
+                                gas_meter.balance_internal(),
+                            );
+                        }
                         self.call_native(
                             &resolver, data_store, gas_meter, extensions, func, ty_args,
                         )?;
@@ -262,12 +294,36 @@ impl Interpreter {
                         let err = set_err_info!(frame, err);
                         self.maybe_core_dump(err, &frame)
                     })?;
+                    self.trace_call_frame(&frame, loader, gas_meter);
                     current_frame = frame;
                 }
             }
         }
     }
 
+    fn trace_call_frame(&mut self, frame: &Frame, loader: &Loader, gas_meter: &impl GasMeter) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.call_frame(
+                &frame.function,
+                frame.ty_args(),
+                loader,
+                gas_meter.balance_internal(),
+            );
+        }
+    }
+
+    fn trace_resource_access(
+        &mut self,
+        kind: ResourceAccessKind,
+        addr: AccountAddress,
+        ty: &Type,
+        loader: &Loader,
+    ) {
+        if let Some(tracer) = &mut self.tracer {
+            tracer.resource_access(kind, addr, ty, loader);
+        }
+    }
+
     /// Returns a `Frame` if the call is to a Move function. Calls to native functions are
     /// "inlined" and this returns `None`.
     ///
@@ -579,6 +635,12 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
+        let kind = if is_mut {
+            ResourceAccessKind::BorrowGlobalMut
+        } else {
+            ResourceAccessKind::BorrowGlobal
+        };
+        self.trace_resource_access(kind, addr, ty, loader);
         let res = Self::load_resource(gas_meter, data_store, addr, ty)?.borrow_global();
         gas_meter.charge_borrow_global(
             is_mut,
@@ -600,6 +662,7 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
+        self.trace_resource_access(ResourceAccessKind::Exists, addr, ty, loader);
         let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
         let exists = gv.exists()?;
         gas_meter.charge_exists(is_generic, TypeWithLoader { ty, loader }, exists)?;
@@ -617,6 +680,7 @@ impl Interpreter {
         addr: AccountAddress,
         ty: &Type,
     ) -> PartialVMResult<()> {
+        self.trace_resource_access(ResourceAccessKind::MoveFrom, addr, ty, loader);
         let resource = match Self::load_resource(gas_meter, data_store, addr, ty)?.move_from() {
             Ok(resource) => {
                 gas_meter.charge_move_from(
@@ -647,6 +711,7 @@ impl Interpreter {
         ty: &Type,
         resource: Value,
     ) -> PartialVMResult<()> {
+        self.trace_resource_access(ResourceAccessKind::MoveTo, addr, ty, loader);
         let gv = Self::load_resource(gas_meter, data_store, addr, ty)?;
         // NOTE(Gas): To maintain backward compatibility, we need to charge gas after attempting
         //            the move_to operation.
@@ -1741,6 +1806,47 @@ impl Frame {
         Ok(())
     }
 
+    /// Hands the state before executing `instruction` to the debugger attached to the session.
+    fn debug_instruction(
+        &self,
+        instruction: &Bytecode,
+        loader: &Loader,
+        interpreter: &mut Interpreter,
+        data_store: &mut impl DataStore,
+    ) {
+        let Some(mut debugger) = interpreter.debugger.take() else {
+            return;
+        };
+        let frames = iter::once(self)
+            .chain(interpreter.call_stack.0.iter().rev())
+            .map(|frame| DebugFrame {
+                function: &frame.function,
+                pc: frame.pc,
+                locals: &frame.locals,
+                ty_args: frame.ty_args(),
+            })
+            .collect();
+        let mut global = |addr: AccountAddress, tag: &TypeTag| -> PartialVMResult<Option<String>> {
+            let ty = loader
+                .load_type(tag, &*data_store)
+                .map_err(|err| err.to_partial())?;
+            let (gv, _) = data_store.load_resource(addr, &ty)?;
+            if !gv.exists()? {
+                return Ok(None);
+            }
+            let mut buf = String::new();
+            values::debug::print_value(&mut buf, &gv.borrow_global()?)?;
+            Ok(Some(buf))
+        };
+        debugger.on_instruction(&mut DebugState::new(
+            frames,
+            instruction,
+            loader,
+            &mut global,
+        ));
+        interpreter.debugger = Some(debugger);
+    }
+
     fn execute_code_impl(
         &mut self,
         resolver: &Resolver,
@@ -1772,6 +1878,14 @@ impl Frame {
                     interpreter
                 );
 
+                if let Some(tracer) = &mut interpreter.tracer {
+                    tracer.instruction(self.pc, instruction, gas_meter.balance_internal());
+                }
+
+                if interpreter.debugger.is_some() {
+                    self.debug_instruction(instruction, resolver.loader(), interpreter, data_store);
+                }
+
                 #[cfg(feature = "std")]
                 fail::fail_point!("move_vm::interpreter_loop", |_| {
                     Err(
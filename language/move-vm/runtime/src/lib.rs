@@ -16,6 +16,8 @@
 extern crate alloc;
 
 pub mod data_cache;
+pub mod debugger;
+pub mod execution_trace;
 mod interpreter;
 mod loader;
 pub mod logging;
@@ -24,6 +26,7 @@ pub mod native_extensions;
 pub mod native_functions;
 mod runtime;
 pub mod session;
+pub mod verified_module_cache;
 #[macro_use]
 #[cfg(feature = "debugging")]
 mod tracing;
@@ -7,6 +7,7 @@ use crate::{
     logging::expect_no_verification_errors,
     native_functions::{NativeFunction, NativeFunctions, UnboxedNativeFunction},
     session::LoadedFunctionInstantiation,
+    verified_module_cache,
 };
 use alloc::borrow::ToOwned;
 use alloc::boxed::Box;
@@ -34,7 +35,7 @@ use move_binary_format::{
     },
     IndexKind,
 };
-use move_bytecode_verifier::{self, cyclic_dependencies, dependencies};
+use move_bytecode_verifier::{self, cyclic_dependencies, dependencies, meter::BoundMeter};
 use move_core_types::{
     identifier::{IdentStr, Identifier},
     language_storage::{ModuleId, StructTag, TypeTag},
@@ -44,6 +45,7 @@ use move_core_types::{
 };
 use move_vm_types::{
     data_store::DataStore,
+    gas::GasMeter,
     loaded_data::runtime_types::{CachedStructIndex, DepthFormula, StructType, Type},
 };
 use sha3::{Digest, Sha3_256};
@@ -175,7 +177,7 @@ impl ModuleCache {
         &mut self,
         natives: &NativeFunctions,
         id: ModuleId,
-        module: CompiledModule,
+        module: Arc<CompiledModule>,
     ) -> VMResult<Arc<Module>> {
         if let Some(cached) = self.module_at(&id) {
             return Ok(cached);
@@ -876,6 +878,7 @@ impl Loader {
         &self,
         modules: &[CompiledModule],
         data_store: &mut impl DataStore,
+        gas_meter: &mut impl GasMeter,
     ) -> VMResult<()> {
         #[cfg(feature = "std")]
         fail::fail_point!("verifier-failpoint-1", |_| { Ok(()) });
@@ -891,6 +894,7 @@ impl Loader {
                 &bundle_verified,
                 &bundle_unverified,
                 data_store,
+                gas_meter,
             )?;
             bundle_verified.insert(module_id.clone(), module.clone());
         }
@@ -914,12 +918,24 @@ impl Loader {
         bundle_verified: &BTreeMap<ModuleId, CompiledModule>,
         bundle_unverified: &BTreeSet<ModuleId>,
         data_store: &impl DataStore,
+        gas_meter: &mut impl GasMeter,
     ) -> VMResult<()> {
         // Performs all verification steps to load the module without loading it, i.e., the new
         // module will NOT show up in `module_cache`. In the module republishing case, it means
         // that the old module is still in the `module_cache`, unless a new Loader is created,
         // which means that a new MoveVM instance needs to be created.
-        move_bytecode_verifier::verify_module_with_config(&self.vm_config.verifier, module)?;
+        let mut meter = BoundMeter::new(&self.vm_config.verifier);
+        let verified = move_bytecode_verifier::verify_module_with_config_metered(
+            &self.vm_config.verifier,
+            module,
+            &mut meter,
+        );
+        // The publisher pays for the verification work, which the verifier config bounds, even
+        // if the module is rejected.
+        gas_meter
+            .charge_verification(meter.get_total_usage())
+            .map_err(|err| err.finish(Location::Module(module.self_id())))?;
+        verified?;
         self.check_natives(module)?;
 
         let mut visited = BTreeSet::new();
@@ -1131,7 +1147,7 @@ impl Loader {
         id: &ModuleId,
         data_store: &impl DataStore,
         allow_loading_failure: bool,
-    ) -> VMResult<CompiledModule> {
+    ) -> VMResult<Arc<CompiledModule>> {
         // bytes fetching, allow loading to fail if the flag is set
         let bytes = match data_store.load_module(id) {
             Ok(bytes) => bytes,
@@ -1142,6 +1158,21 @@ impl Loader {
             }
         };
 
+        // modules verified before, possibly by another VM sharing the cache, are not verified
+        // again as long as their bytecode is unchanged
+        let cache_entry = self
+            .vm_config
+            .verified_module_cache
+            .as_ref()
+            .map(|cache| (cache, verified_module_cache::module_hash(&bytes)));
+        if let Some((cache, hash)) = &cache_entry {
+            if let Some(module) = cache.get(id, hash) {
+                self.check_natives(&module)
+                    .map_err(expect_no_verification_errors)?;
+                return Ok(module);
+            }
+        }
+
         // for bytes obtained from the data store, they should always deserialize and verify.
         // It is an invariant violation if they don't.
         let module = CompiledModule::deserialize_with_max_version(
@@ -1157,7 +1188,7 @@ impl Loader {
         .map_err(expect_no_verification_errors)?;
 
         #[cfg(feature = "std")]
-        fail::fail_point!("verifier-failpoint-2", |_| { Ok(module.clone()) });
+        fail::fail_point!("verifier-failpoint-2", |_| { Ok(Arc::new(module.clone())) });
 
         if self.vm_config.paranoid_type_checks && &module.self_id() != id {
             return Err(
@@ -1172,6 +1203,11 @@ impl Loader {
             .map_err(expect_no_verification_errors)?;
         self.check_natives(&module)
             .map_err(expect_no_verification_errors)?;
+
+        let module = Arc::new(module);
+        if let Some((cache, hash)) = cache_entry {
+            cache.insert(id.clone(), hash, module.clone());
+        }
         Ok(module)
     }
 
@@ -1881,9 +1917,9 @@ pub(crate) struct Module {
 
 impl Module {
     fn new(
-        module: CompiledModule,
+        module: Arc<CompiledModule>,
         cache: &ModuleCache,
-    ) -> Result<Self, (PartialVMError, CompiledModule)> {
+    ) -> Result<Self, (PartialVMError, Arc<CompiledModule>)> {
         let id = module.self_id();
 
         let mut struct_refs = vec![];
@@ -2056,7 +2092,7 @@ impl Module {
         match create() {
             Ok(_) => Ok(Self {
                 id,
-                module: Arc::new(module),
+                module,
                 struct_refs,
                 structs,
                 struct_instantiations,
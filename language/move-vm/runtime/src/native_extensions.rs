@@ -28,6 +28,10 @@ impl<'a> NativeContextExtensions<'a> {
         )
     }
 
+    pub fn contains<T: TidAble<'a>>(&self) -> bool {
+        self.map.contains_key(&T::id())
+    }
+
     pub fn get<T: TidAble<'a>>(&self) -> &T {
         self.map
             .get(&T::id())
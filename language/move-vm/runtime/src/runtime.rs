@@ -74,7 +74,7 @@ impl VMRuntime {
         modules: Vec<Vec<u8>>,
         sender: AccountAddress,
         data_store: &mut impl DataStore,
-        _gas_meter: &mut impl GasMeter,
+        gas_meter: &mut impl GasMeter,
         compat: Compatibility,
     ) -> VMResult<()> {
         // deserialize the modules. Perform bounds check. After this indexes can be
@@ -137,8 +137,11 @@ impl VMRuntime {
         }
 
         // Perform bytecode and loading verification. Modules must be sorted in topological order.
-        self.loader
-            .verify_module_bundle_for_publication(&compiled_modules, data_store)?;
+        self.loader.verify_module_bundle_for_publication(
+            &compiled_modules,
+            data_store,
+            gas_meter,
+        )?;
 
         // NOTE: we want to (informally) argue that all modules pass the linking check before being
         // published to the data store.
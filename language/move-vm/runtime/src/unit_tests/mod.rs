@@ -3,3 +3,4 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod vm_arguments_tests;
+pub mod verified_module_cache_tests;
@@ -0,0 +1,57 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::verified_module_cache::{module_hash, VerifiedModuleCache};
+use move_binary_format::file_format::empty_module;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use std::sync::Arc;
+
+fn module_id(name: &str) -> ModuleId {
+    ModuleId::new(AccountAddress::ONE, Identifier::new(name).unwrap())
+}
+
+#[test]
+fn cached_modules_are_shared_and_match_their_hash() {
+    let cache = VerifiedModuleCache::new();
+    let module = Arc::new(empty_module());
+    let hash = module_hash(b"a");
+    cache.insert(module_id("a"), hash, module.clone());
+
+    let cached = cache.get(&module_id("a"), &hash).unwrap();
+    assert!(Arc::ptr_eq(&cached, &module));
+    assert_eq!(cache.hits(), 1);
+
+    // Other bytecode for the same module misses.
+    assert!(cache.get(&module_id("a"), &module_hash(b"b")).is_none());
+    assert_eq!(cache.hits(), 1);
+}
+
+#[test]
+fn least_recently_used_modules_are_evicted_once_full() {
+    let cache = VerifiedModuleCache::with_capacity(2);
+    let hash = module_hash(b"");
+    cache.insert(module_id("a"), hash, Arc::new(empty_module()));
+    cache.insert(module_id("b"), hash, Arc::new(empty_module()));
+
+    // Using `a` makes `b` the least recently used module.
+    assert!(cache.get(&module_id("a"), &hash).is_some());
+    cache.insert(module_id("c"), hash, Arc::new(empty_module()));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&module_id("a")));
+    assert!(!cache.contains(&module_id("b")));
+    assert!(cache.contains(&module_id("c")));
+
+    // Replacing a cached module doesn't evict another one.
+    cache.insert(module_id("c"), module_hash(b"c"), Arc::new(empty_module()));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.contains(&module_id("a")));
+}
+
+#[test]
+fn nothing_is_cached_without_capacity() {
+    let cache = VerifiedModuleCache::with_capacity(0);
+    cache.insert(module_id("a"), module_hash(b""), Arc::new(empty_module()));
+    assert!(cache.is_empty());
+}
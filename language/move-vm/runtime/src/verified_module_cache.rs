@@ -0,0 +1,144 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use alloc::{collections::BTreeMap, sync::Arc};
+use move_binary_format::file_format::CompiledModule;
+use move_core_types::language_storage::ModuleId;
+use sha3::{Digest, Sha3_256};
+use spin::Mutex;
+
+/// Hash of a module's serialized bytecode.
+pub type ModuleHash = [u8; 32];
+
+/// Computes the hash a module is cached under.
+pub fn module_hash(bytes: &[u8]) -> ModuleHash {
+    let mut sha3_256 = Sha3_256::new();
+    sha3_256.update(bytes);
+    sha3_256.finalize().into()
+}
+
+/// Number of modules a cache holds by default.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// A cache of deserialized modules which passed the bytecode verifier, keyed by module id and
+/// the hash of their bytecode.
+///
+/// Unlike the loader cache, which lives and dies with a single VM, this cache is meant to be
+/// shared: cloning it returns another handle to the same entries, so any number of VMs can be
+/// created with it (see `VMConfig::verified_module_cache`) and pay for verifying a module only
+/// once. A cached module is only used if the bytes loaded from storage hash to the cached hash,
+/// so a stale entry is never used, but the embedder should still `invalidate` modules it
+/// upgrades or deletes to release them.
+///
+/// The cache holds at most `capacity` modules. Once full, caching another module evicts the
+/// least recently used one.
+///
+/// Only the checks which depend on nothing but the module itself are skipped. Linking against
+/// dependencies is still done by every VM. All VMs sharing a cache must use the same verifier
+/// configuration.
+#[derive(Clone)]
+pub struct VerifiedModuleCache {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    capacity: usize,
+    /// Module id -> (bytecode hash, module, last use)
+    modules: BTreeMap<ModuleId, (ModuleHash, Arc<CompiledModule>, u64)>,
+    /// Incremented on every use, to order the entries by last use
+    clock: u64,
+    hits: u64,
+}
+
+impl Default for VerifiedModuleCache {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl VerifiedModuleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache holding at most `capacity` modules.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                capacity,
+                modules: BTreeMap::new(),
+                clock: 0,
+                hits: 0,
+            })),
+        }
+    }
+
+    /// Returns the verified module `id`, if it is cached for bytecode hashing to `hash`.
+    pub fn get(&self, id: &ModuleId, hash: &ModuleHash) -> Option<Arc<CompiledModule>> {
+        let mut inner = self.inner.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+        let module = match inner.modules.get_mut(id) {
+            Some((cached_hash, module, last_use)) if cached_hash == hash => {
+                *last_use = clock;
+                module.clone()
+            }
+            _ => return None,
+        };
+        inner.hits += 1;
+        Some(module)
+    }
+
+    /// Caches `module`, verified from bytecode hashing to `hash`, replacing any other version.
+    pub fn insert(&self, id: ModuleId, hash: ModuleHash, module: Arc<CompiledModule>) {
+        let mut inner = self.inner.lock();
+        if inner.capacity == 0 {
+            return;
+        }
+        if !inner.modules.contains_key(&id) && inner.modules.len() >= inner.capacity {
+            let least_recently_used = inner
+                .modules
+                .iter()
+                .min_by_key(|(_, (_, _, last_use))| *last_use)
+                .map(|(id, _)| id.clone());
+            if let Some(evicted) = least_recently_used {
+                inner.modules.remove(&evicted);
+            }
+        }
+        inner.clock += 1;
+        let clock = inner.clock;
+        inner.modules.insert(id, (hash, module, clock));
+    }
+
+    /// Removes the module `id` from the cache.
+    pub fn invalidate(&self, id: &ModuleId) {
+        self.inner.lock().modules.remove(id);
+    }
+
+    /// Removes all modules from the cache.
+    pub fn clear(&self) {
+        self.inner.lock().modules.clear();
+    }
+
+    /// Returns true if a module `id` is cached, for any bytecode.
+    pub fn contains(&self, id: &ModuleId) -> bool {
+        self.inner.lock().modules.contains_key(id)
+    }
+
+    /// Number of times a module was served from the cache, i.e. not verified again.
+    pub fn hits(&self) -> u64 {
+        self.inner.lock().hits
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.lock().capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().modules.is_empty()
+    }
+}
@@ -114,6 +114,8 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
+    /// Internal gas charged per unit of work done by the bytecode verifier
+    verification_unit_cost: u64,
 }
 
 impl<'a> GasStatus<'a> {
@@ -126,6 +128,7 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
+            verification_unit_cost: 0,
         }
     }
 
@@ -138,6 +141,7 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
+            verification_unit_cost: 0,
         }
     }
 
@@ -198,6 +202,12 @@ impl<'a> GasStatus<'a> {
     pub fn set_metering(&mut self, enabled: bool) {
         self.charge = enabled
     }
+
+    /// Set the internal gas charged per unit of work done by the bytecode verifier when
+    /// verifying published modules. Verification is free by default.
+    pub fn set_verification_unit_cost(&mut self, cost: u64) {
+        self.verification_unit_cost = cost
+    }
 }
 
 fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
@@ -263,7 +273,12 @@ fn get_simple_instruction_opcode(instr: SimpleInstruction) -> Opcodes {
 
 impl<'b> GasMeter for GasStatus<'b> {
     fn balance_internal(&self) -> InternalGas {
-        self.gas_left
+        // An unmetered execution never runs out of gas.
+        if self.charge {
+            self.gas_left
+        } else {
+            InternalGas::new(u64::MAX)
+        }
     }
 
     /// Charge an instruction and fail if not enough gas units are left.
@@ -554,6 +569,11 @@ impl<'b> GasMeter for GasStatus<'b> {
     ) -> PartialVMResult<()> {
         Ok(())
     }
+
+    fn charge_verification(&mut self, units: u128) -> PartialVMResult<()> {
+        let amount = units.saturating_mul(self.verification_unit_cost as u128);
+        self.deduct_gas(InternalGas::new(amount.try_into().unwrap_or(u64::MAX)))
+    }
 }
 
 pub fn new_from_instructions(mut instrs: Vec<(Bytecode, GasCost)>) -> CostTable {
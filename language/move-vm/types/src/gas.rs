@@ -232,6 +232,11 @@ pub trait GasMeter {
         &mut self,
         locals: impl Iterator<Item = impl ValueView>,
     ) -> PartialVMResult<()>;
+
+    /// Charges for verifying a module being published, where `units` is the work done by the
+    /// bytecode verifier as measured by its meter.
+    /// Should fail if not enough gas units are left.
+    fn charge_verification(&mut self, units: u128) -> PartialVMResult<()>;
 }
 
 /// A dummy gas meter that does not meter anything.
@@ -448,4 +453,8 @@ impl GasMeter for UnmeteredGasMeter {
     ) -> PartialVMResult<()> {
         Ok(())
     }
+
+    fn charge_verification(&mut self, _units: u128) -> PartialVMResult<()> {
+        Ok(())
+    }
 }
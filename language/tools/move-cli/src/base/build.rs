@@ -1,7 +1,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::reroot_path;
+use super::{gas_report, reroot_path};
 use clap::*;
 use move_package::{Architecture, BuildConfig};
 use std::path::PathBuf;
@@ -9,7 +9,12 @@ use std::path::PathBuf;
 /// Build the package at `path`. If no path is provided defaults to current directory.
 #[derive(Parser)]
 #[clap(name = "build")]
-pub struct Build;
+pub struct Build {
+    /// Print a static estimate of the gas cost of the functions in the package, per basic block
+    /// and per function, under the gas schedule of the Substrate MoveVM backend
+    #[clap(long = "gas-report")]
+    pub gas_report: bool,
+}
 
 impl Build {
     pub fn execute(self, path: Option<PathBuf>, config: BuildConfig) -> anyhow::Result<()> {
@@ -26,10 +31,18 @@ impl Build {
 
         match architecture {
             Architecture::Move | Architecture::AsyncMove => {
-                config.compile_package(&rerooted_path, &mut std::io::stdout())?;
+                let package = config.compile_package(&rerooted_path, &mut std::io::stdout())?;
+                if self.gas_report {
+                    let reports = gas_report::package_report(&package)?;
+                    gas_report::write_gas_report(&reports, &mut std::io::stdout())?;
+                }
             }
 
             Architecture::Ethereum => {
+                if self.gas_report {
+                    anyhow::bail!("The gas report is not supported for the Ethereum architecture.");
+                }
+
                 #[cfg(feature = "evm-backend")]
                 config.compile_package_evm(&rerooted_path, &mut std::io::stderr())?;
 
@@ -5,12 +5,17 @@ use super::reroot_path;
 use clap::*;
 use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_coverage::{
-    coverage_map::CoverageMap, format_csv_summary, format_human_summary,
+    cobertura::write_cobertura, coverage_map::CoverageMap, format_csv_summary,
+    format_human_summary, lcov::write_lcov, report::ModuleReport,
     source_coverage::SourceCoverageBuilder, summary::summarize_inst_cov,
 };
 use move_disassembler::disassembler::Disassembler;
-use move_package::BuildConfig;
-use std::path::PathBuf;
+use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig};
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
 #[derive(Parser)]
 pub enum CoverageSummaryOptions {
@@ -36,6 +41,22 @@ pub enum CoverageSummaryOptions {
         #[clap(long = "module")]
         module_name: String,
     },
+    /// Output line, function and branch coverage for all modules in this package as an lcov
+    /// tracefile
+    #[clap(name = "lcov")]
+    Lcov {
+        /// File to write the tracefile to, instead of stdout
+        #[clap(long = "output-file", short = 'o')]
+        output_file: Option<PathBuf>,
+    },
+    /// Output line, function and branch coverage for all modules in this package as a Cobertura
+    /// XML report
+    #[clap(name = "cobertura")]
+    Cobertura {
+        /// File to write the report to, instead of stdout
+        #[clap(long = "output-file", short = 'o')]
+        output_file: Option<PathBuf>,
+    },
 }
 
 /// Inspect test coverage for this package. A previous test run with the `--coverage` flag must
@@ -104,7 +125,58 @@ impl Coverage {
                 disassembler.add_coverage_map(coverage_map.to_unified_exec_map());
                 println!("{}", disassembler.disassemble()?);
             }
+            CoverageSummaryOptions::Lcov { output_file } => {
+                let reports = module_reports(&path, &package, &coverage_map)?;
+                write_lcov(&reports, &mut output_writer(output_file)?)?;
+            }
+            CoverageSummaryOptions::Cobertura { output_file } => {
+                let reports = module_reports(&path, &package, &coverage_map)?;
+                write_cobertura(
+                    package.compiled_package_info.package_name.as_str(),
+                    ".",
+                    &reports,
+                    &mut output_writer(output_file)?,
+                )?;
+            }
         }
         Ok(())
     }
 }
+
+/// Coverage reports for the root modules of `package`, with source paths relative to the package
+/// root at `path`.
+fn module_reports(
+    path: &Path,
+    package: &CompiledPackage,
+    coverage_map: &CoverageMap,
+) -> anyhow::Result<Vec<ModuleReport>> {
+    let root = path.canonicalize()?;
+    package
+        .root_modules()
+        .map(|unit| {
+            let CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) = &unit.unit
+            else {
+                unreachable!("Should all be modules")
+            };
+            let mut report =
+                ModuleReport::new(module, coverage_map, source_map, &unit.source_path)?;
+            if let Ok(relative) = unit
+                .source_path
+                .strip_prefix(path)
+                .or_else(|_| unit.source_path.strip_prefix(&root))
+            {
+                report.source_path = relative.to_path_buf();
+            }
+            Ok(report)
+        })
+        .collect()
+}
+
+fn output_writer(output_file: Option<PathBuf>) -> anyhow::Result<Box<dyn Write>> {
+    Ok(match output_file {
+        Some(output_file) => Box::new(File::create(output_file)?),
+        None => Box::new(io::stdout()),
+    })
+}
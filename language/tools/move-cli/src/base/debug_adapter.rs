@@ -0,0 +1,238 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    base::test::{run_move_unit_tests, UnitTestResult},
+    sandbox::{
+        self,
+        utils::{is_bytecode_file, PackageContext},
+    },
+    NativeFunctionRecord, DEFAULT_STORAGE_DIR,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use clap::*;
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::{errmap::ErrorMapping, parser};
+use move_debugger::{
+    dap::{self, Launcher, Program},
+    source_index::SourceIndex,
+};
+use move_package::BuildConfig;
+use move_unit_test::{extensions::set_extension_hook, UnitTestingConfig};
+use move_vm_runtime::native_extensions::NativeContextExtensions;
+use move_vm_test_utils::gas_schedule::CostTable;
+use serde_json::Value;
+use std::{io::BufReader, net::TcpListener, path::PathBuf};
+
+/// Serve the Debug Adapter Protocol, to debug sandbox runs and unit tests from an editor.
+///
+/// The adapter listens on a local TCP port, so that the output of the debugged program doesn't
+/// interfere with the protocol. A `launch` request accepts the following arguments:
+/// `packagePath`, `stopOnEntry`, and either `test: true` with an optional `filter` to debug the
+/// package's unit tests, or `script`, `name`, `signers`, `args`, `typeArgs`, `gasBudget`,
+/// `storageDir` and `dryRun`, with the meaning of the `move sandbox run` options of the same
+/// names.
+#[derive(Parser)]
+#[clap(name = "debug-adapter")]
+pub struct DebugAdapter {
+    /// Port to listen on for connections of debugging clients.
+    #[clap(long = "port", default_value = "4711")]
+    pub port: u16,
+}
+
+impl DebugAdapter {
+    pub fn execute(
+        self,
+        config: BuildConfig,
+        natives: Vec<NativeFunctionRecord>,
+        cost_table: &CostTable,
+        error_descriptions: &ErrorMapping,
+    ) -> Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", self.port))?;
+        eprintln!(
+            "Listening for debugging clients on {}",
+            listener.local_addr()?
+        );
+        let mut launcher = MoveLauncher {
+            config,
+            natives,
+            cost_table: cost_table.clone(),
+            error_descriptions: error_descriptions.clone(),
+        };
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let reader = BufReader::new(stream.try_clone()?);
+            if let Err(err) = dap::serve(reader, stream, &mut launcher) {
+                eprintln!("Debug session failed: {:#}", err);
+            }
+        }
+        Ok(())
+    }
+}
+
+struct MoveLauncher {
+    config: BuildConfig,
+    natives: Vec<NativeFunctionRecord>,
+    cost_table: CostTable,
+    error_descriptions: ErrorMapping,
+}
+
+impl MoveLauncher {
+    fn launch_tests(
+        &self,
+        mut config: BuildConfig,
+        package_path: PathBuf,
+        arguments: &Value,
+    ) -> Result<Program> {
+        config.test_mode = true;
+        config.dev_mode = true;
+        let package = config
+            .clone()
+            .compile_package(&package_path, &mut Vec::new())?;
+        let index = SourceIndex::from_package(&package)?;
+
+        let unit_test_config = UnitTestingConfig {
+            filter: string_argument(arguments, "filter")?,
+            // The debugger follows a single program at a time.
+            num_threads: 1,
+            ..UnitTestingConfig::default_with_bound(None)
+        };
+        let natives = self.natives.clone();
+        let cost_table = self.cost_table.clone();
+        Ok(Program {
+            index,
+            run: Box::new(move |debugger| {
+                set_extension_hook(Box::new(move |extensions| {
+                    extensions.add(debugger.extension())
+                }));
+                let result = run_move_unit_tests(
+                    &package_path,
+                    config,
+                    unit_test_config,
+                    natives,
+                    Some(cost_table),
+                    false,
+                    &mut std::io::stdout(),
+                );
+                set_extension_hook(Box::new(|_| {}));
+                match result? {
+                    UnitTestResult::Success => Ok(()),
+                    UnitTestResult::Failure => bail!("Some tests failed"),
+                }
+            }),
+        })
+    }
+
+    fn launch_run(
+        &self,
+        config: BuildConfig,
+        package_path: PathBuf,
+        arguments: &Value,
+    ) -> Result<Program> {
+        let script = string_argument(arguments, "script")?
+            .ok_or_else(|| anyhow!("Missing 'script' argument"))?;
+        let script = package_path.join(script);
+        let script_name = string_argument(arguments, "name")?;
+        let storage_dir = package_path.join(
+            string_argument(arguments, "storageDir")?
+                .unwrap_or_else(|| DEFAULT_STORAGE_DIR.to_string()),
+        );
+        let signers = string_list_argument(arguments, "signers")?;
+        let txn_args = string_list_argument(arguments, "args")?
+            .iter()
+            .map(|arg| parser::parse_transaction_argument(arg))
+            .collect::<Result<Vec<_>>>()?;
+        let type_args = string_list_argument(arguments, "typeArgs")?
+            .iter()
+            .map(|arg| parser::parse_type_tag(arg))
+            .collect::<Result<Vec<_>>>()?;
+        let gas_budget = arguments["gasBudget"].as_u64();
+        let dry_run = arguments["dryRun"].as_bool().unwrap_or(false);
+
+        let context = PackageContext::new(&Some(package_path), &config)?;
+        let mut index = SourceIndex::from_package(context.package())?;
+        if !is_bytecode_file(&script) {
+            let file_contents = std::fs::read_to_string(&script)
+                .with_context(|| format!("Unable to read script file {:?}", script))?;
+            let unit = context
+                .package()
+                .scripts()
+                .find(|unit| unit.unit.source_map().check(&file_contents))
+                .ok_or_else(|| anyhow!("Unable to find script in file {:?}", script))?;
+            if let CompiledUnitEnum::Script(compiled) = &unit.unit {
+                index.set_script(&compiled.script, &compiled.source_map, &script)?;
+            }
+        }
+
+        let natives = self.natives.clone();
+        let cost_table = self.cost_table.clone();
+        let error_descriptions = self.error_descriptions.clone();
+        Ok(Program {
+            index,
+            run: Box::new(move |debugger| {
+                let state = context.prepare_state(None, &storage_dir)?;
+                let mut extensions = NativeContextExtensions::default();
+                extensions.add(debugger.extension());
+                sandbox::commands::run(
+                    natives,
+                    &cost_table,
+                    &error_descriptions,
+                    &state,
+                    context.package(),
+                    &script,
+                    &script_name,
+                    &signers,
+                    &txn_args,
+                    type_args,
+                    gas_budget,
+                    None,
+                    dry_run,
+                    false,
+                    extensions,
+                )
+            }),
+        })
+    }
+}
+
+impl Launcher for MoveLauncher {
+    fn launch(&mut self, arguments: &Value) -> Result<Program> {
+        let package_path = PathBuf::from(
+            string_argument(arguments, "packagePath")?.unwrap_or_else(|| ".".to_string()),
+        );
+        let mut config = self.config.clone();
+        if config.install_dir.is_none() {
+            // Build where `move` would when run from the package.
+            config.install_dir = Some(package_path.clone());
+        }
+        if arguments["test"].as_bool().unwrap_or(false) {
+            self.launch_tests(config, package_path, arguments)
+        } else {
+            self.launch_run(config, package_path, arguments)
+        }
+    }
+}
+
+fn string_argument(arguments: &Value, name: &str) -> Result<Option<String>> {
+    match &arguments[name] {
+        Value::Null => Ok(None),
+        Value::String(value) => Ok(Some(value.clone())),
+        _ => bail!("Argument '{}' must be a string", name),
+    }
+}
+
+fn string_list_argument(arguments: &Value, name: &str) -> Result<Vec<String>> {
+    match &arguments[name] {
+        Value::Null => Ok(vec![]),
+        Value::Array(values) => values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("Argument '{}' must be a list of strings", name))
+            })
+            .collect(),
+        _ => bail!("Argument '{}' must be a list of strings", name),
+    }
+}
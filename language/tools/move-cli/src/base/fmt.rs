@@ -0,0 +1,90 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::reroot_path;
+use clap::*;
+use move_command_line_common::files::{find_move_filenames, FileHash};
+use move_compiler::{diagnostics::report_diagnostics_to_color_buffer, formatter::format_source};
+use move_package::source_package::{layout::SourcePackageLayout, manifest_parser};
+use move_symbol_pool::Symbol;
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Format the Move source files of the package at `path`. If no path is provided defaults to
+/// current directory. The layout can be configured in the `[format]` section of `Move.toml`.
+#[derive(Parser)]
+#[clap(name = "fmt")]
+pub struct Fmt {
+    /// Do not write the formatted files, only list the files that are not formatted and fail if
+    /// there are any
+    #[clap(long = "check")]
+    pub check: bool,
+}
+
+impl Fmt {
+    pub fn execute(self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        let rerooted_path = reroot_path(path)?;
+        let manifest = manifest_parser::parse_move_manifest_from_file(&rerooted_path)?;
+        let options = manifest.format.unwrap_or_default();
+
+        let mut unformatted = vec![];
+        let mut failed = false;
+        for file in package_move_files(&rerooted_path)? {
+            let source = std::fs::read_to_string(&file)?.replace("\r\n", "\n");
+            match format_source(&source, &options) {
+                Ok(formatted) if formatted == source => (),
+                Ok(formatted) => {
+                    if !self.check {
+                        std::fs::write(&file, formatted)?;
+                    }
+                    unformatted.push(file);
+                }
+                Err(diags) => {
+                    let files = HashMap::from([(
+                        FileHash::new(&source),
+                        (Symbol::from(file.as_str()), source),
+                    )]);
+                    std::io::stderr()
+                        .write_all(&report_diagnostics_to_color_buffer(&files, diags))?;
+                    failed = true;
+                }
+            }
+        }
+
+        for file in &unformatted {
+            if self.check {
+                println!("Not formatted: {}", file);
+            } else {
+                println!("Formatted: {}", file);
+            }
+        }
+        if failed {
+            anyhow::bail!("Some files could not be formatted");
+        }
+        if self.check && !unformatted.is_empty() {
+            anyhow::bail!("{} file(s) are not formatted", unformatted.len());
+        }
+        Ok(())
+    }
+}
+
+/// The Move files in the source directories of the package at `root`, in a stable order.
+fn package_move_files(root: &Path) -> anyhow::Result<Vec<String>> {
+    let dirs = [
+        SourcePackageLayout::Sources,
+        SourcePackageLayout::Scripts,
+        SourcePackageLayout::Examples,
+        SourcePackageLayout::Tests,
+        SourcePackageLayout::Specifications,
+    ]
+    .iter()
+    .map(|dir| root.join(dir.path()))
+    .filter(|dir| dir.is_dir())
+    .collect::<Vec<_>>();
+    let mut files = find_move_filenames(&dirs, false)?;
+    files.sort();
+    Ok(files)
+}
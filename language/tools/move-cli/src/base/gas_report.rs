@@ -0,0 +1,1209 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Static estimate of the gas cost of compiled modules and scripts under the gas schedule of the
+//! Substrate MoveVM backend (`move_vm_backend_common::gas_schedule`).
+//!
+//! Costs are computed per basic block, following the charges of the VM's gas meter. Charges that
+//! depend on the size of a value are bounded using the type of the value, so they are exact for
+//! primitives and structs of them, but have no upper bound for vectors and type parameters.
+//!
+//! The bounds of a function cover the executions that return normally. The lower bound is the
+//! cheapest path through the function. The upper bound is the most expensive path, and is only
+//! known if the function has no loops and the cost of everything it calls is bounded as well.
+
+use anyhow::{bail, Result};
+use codespan_reporting::files::{Files, SimpleFile};
+use move_binary_format::{
+    access::ModuleAccess,
+    binary_views::{BinaryIndexedView, FunctionView},
+    control_flow_graph::{BlockId, ControlFlowGraph},
+    file_format::{
+        Bytecode, CodeOffset, FunctionDefinitionIndex, SignatureIndex, SignatureToken,
+        StructDefinitionIndex, StructFieldInformation,
+    },
+    file_format_common::{instruction_key, Opcodes},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule, NamedCompiledScript};
+use move_core_types::{
+    account_address::AccountAddress,
+    gas_algebra::{InternalGas, InternalGasPerByte},
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+use move_package::compilation::compiled_package::CompiledPackage;
+use move_vm_backend_common::gas_schedule::{INSTRUCTION_COST_TABLE, NATIVE_COST_PARAMS};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt, fs,
+    io::{self, Write},
+    ops::Add,
+    path::{Path, PathBuf},
+};
+
+/// Abstract memory size of primitive values, references and structs, as used by the gas meter
+const CONST_SIZE: u64 = 16;
+const REFERENCE_SIZE: u64 = 8;
+const STRUCT_SIZE: u64 = 2;
+/// Abstract memory size charged by `exists` for resources that do not exist
+const MIN_EXISTS_DATA_SIZE: u64 = 100;
+
+/// Lower and, if known, upper bound of a gas cost in internal gas units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasBounds {
+    pub min: u64,
+    pub max: Option<u64>,
+}
+
+impl GasBounds {
+    pub const ZERO: Self = Self::exact(0);
+
+    pub const fn exact(cost: u64) -> Self {
+        Self {
+            min: cost,
+            max: Some(cost),
+        }
+    }
+
+    pub const fn at_least(min: u64) -> Self {
+        Self { min, max: None }
+    }
+}
+
+impl Add for GasBounds {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            min: self.min.saturating_add(other.min),
+            max: self
+                .max
+                .zip(other.max)
+                .map(|(lhs, rhs)| lhs.saturating_add(rhs)),
+        }
+    }
+}
+
+impl fmt::Display for GasBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{}", max),
+            Some(max) => write!(f, "{} - {}", self.min, max),
+            None => write!(f, "{} - unbounded", self.min),
+        }
+    }
+}
+
+/// Cost of a native function: a base cost, plus costs that grow with the size of its input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeCost {
+    pub base: u64,
+    pub rates: Vec<NativeRate>,
+}
+
+/// A native cost per unit of input, charged for at least `min_units` units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeRate {
+    pub per_unit: u64,
+    pub min_units: u64,
+    pub unit: &'static str,
+}
+
+impl NativeCost {
+    fn fixed(base: InternalGas) -> Self {
+        Self {
+            base: base.into(),
+            rates: vec![],
+        }
+    }
+
+    fn per_byte(base: InternalGas, per_byte: InternalGasPerByte, unit: &'static str) -> Self {
+        Self::fixed(base).with_rate(per_byte.into(), 0, unit)
+    }
+
+    fn with_rate(mut self, per_unit: u64, min_units: u64, unit: &'static str) -> Self {
+        if per_unit > 0 {
+            self.rates.push(NativeRate {
+                per_unit,
+                min_units,
+                unit,
+            })
+        }
+        self
+    }
+
+    pub fn bounds(&self) -> GasBounds {
+        let min = self.rates.iter().fold(self.base, |acc, rate| {
+            acc.saturating_add(rate.per_unit.saturating_mul(rate.min_units))
+        });
+        if self.rates.is_empty() {
+            GasBounds::exact(min)
+        } else {
+            GasBounds::at_least(min)
+        }
+    }
+}
+
+impl fmt::Display for NativeCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut terms = vec![];
+        if self.base > 0 || self.rates.is_empty() {
+            terms.push(self.base.to_string())
+        }
+        for rate in &self.rates {
+            let mut term = format!("{} per {}", rate.per_unit, rate.unit);
+            if rate.min_units > 0 {
+                term.push_str(&format!(" (at least {})", rate.min_units));
+            }
+            terms.push(term)
+        }
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+/// The costs of the natives of the standard library, by module and function name
+fn native_costs() -> BTreeMap<(&'static str, &'static str), NativeCost> {
+    let params = &*NATIVE_COST_PARAMS;
+    let bcs = &params.bcs.to_bytes;
+    let hash = &params.hash;
+    let string = &params.string;
+    let vector = &params.vector;
+    let balance = &params.balance;
+    let cross_vm = &params.cross_vm;
+    let substrate_hash = &params.substrate_hash;
+    let unit_test = &params.unit_test.create_signers_for_testing;
+    BTreeMap::from([
+        (
+            ("bcs", "to_bytes"),
+            NativeCost::fixed(0.into()).with_rate(
+                bcs.per_byte_serialized.into(),
+                bcs.legacy_min_output_size.into(),
+                "byte of output",
+            ),
+        ),
+        (
+            ("hash", "sha2_256"),
+            NativeCost::fixed(hash.sha2_256.base).with_rate(
+                hash.sha2_256.per_byte.into(),
+                hash.sha2_256.legacy_min_input_len.into(),
+                "byte of input",
+            ),
+        ),
+        (
+            ("hash", "sha3_256"),
+            NativeCost::fixed(hash.sha3_256.base).with_rate(
+                hash.sha3_256.per_byte.into(),
+                hash.sha3_256.legacy_min_input_len.into(),
+                "byte of input",
+            ),
+        ),
+        (
+            ("type_name", "get"),
+            NativeCost::per_byte(
+                params.type_name.get.base,
+                params.type_name.get.per_byte,
+                "byte of the type name",
+            ),
+        ),
+        (
+            ("signer", "borrow_address"),
+            NativeCost::fixed(params.signer.borrow_address.base),
+        ),
+        (
+            ("string", "internal_check_utf8"),
+            NativeCost::per_byte(
+                string.check_utf8.base,
+                string.check_utf8.per_byte,
+                "byte of the string",
+            ),
+        ),
+        (
+            ("string", "internal_is_char_boundary"),
+            NativeCost::fixed(string.is_char_boundary.base),
+        ),
+        (
+            ("string", "internal_sub_string"),
+            NativeCost::per_byte(
+                string.sub_string.base,
+                string.sub_string.per_byte,
+                "byte of the substring",
+            ),
+        ),
+        (
+            ("string", "internal_index_of"),
+            NativeCost::per_byte(
+                string.index_of.base,
+                string.index_of.per_byte_pattern,
+                "byte of the pattern",
+            )
+            .with_rate(string.index_of.per_byte_searched.into(), 0, "byte searched"),
+        ),
+        (("vector", "empty"), NativeCost::fixed(vector.empty.base)),
+        (("vector", "length"), NativeCost::fixed(vector.length.base)),
+        (
+            ("vector", "push_back"),
+            NativeCost::fixed(vector.push_back.base).with_rate(
+                vector.push_back.legacy_per_abstract_memory_unit.into(),
+                1,
+                "unit of element size",
+            ),
+        ),
+        (("vector", "borrow"), NativeCost::fixed(vector.borrow.base)),
+        (
+            ("vector", "borrow_mut"),
+            NativeCost::fixed(vector.borrow.base),
+        ),
+        (
+            ("vector", "pop_back"),
+            NativeCost::fixed(vector.pop_back.base),
+        ),
+        (
+            ("vector", "destroy_empty"),
+            NativeCost::fixed(vector.destroy_empty.base),
+        ),
+        (("vector", "swap"), NativeCost::fixed(vector.swap.base)),
+        (
+            ("balance", "transfer"),
+            NativeCost::fixed(balance.transfer.base),
+        ),
+        (
+            ("balance", "cheque_amount"),
+            NativeCost::fixed(balance.cheque_amount.base),
+        ),
+        (
+            ("balance", "total_amount"),
+            NativeCost::fixed(balance.total_amount.base),
+        ),
+        (
+            ("cross_vm", "call"),
+            NativeCost::per_byte(
+                cross_vm.call.base,
+                cross_vm.call.per_byte,
+                "byte of input and output",
+            )
+            .with_rate(1, 0, "unit of gas used by the contract"),
+        ),
+        (
+            ("substrate_hash", "sip_hash"),
+            NativeCost::per_byte(
+                substrate_hash.sip_hash.base,
+                substrate_hash.sip_hash.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("substrate_hash", "blake2b_256"),
+            NativeCost::per_byte(
+                substrate_hash.blake2b_256.base,
+                substrate_hash.blake2b_256.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("substrate_hash", "ripemd160"),
+            NativeCost::per_byte(
+                substrate_hash.ripemd160.base,
+                substrate_hash.ripemd160.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("substrate_hash", "keccak256"),
+            NativeCost::per_byte(
+                substrate_hash.keccak256.base,
+                substrate_hash.keccak256.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("substrate_hash", "sha2_512"),
+            NativeCost::per_byte(
+                substrate_hash.sha2_512.base,
+                substrate_hash.sha2_512.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("substrate_hash", "sha3_512"),
+            NativeCost::per_byte(
+                substrate_hash.sha3_512.base,
+                substrate_hash.sha3_512.per_byte,
+                "byte of input",
+            ),
+        ),
+        (
+            ("unit_test", "create_signers_for_testing"),
+            NativeCost::fixed(unit_test.base_cost).with_rate(
+                unit_test.unit_cost.into(),
+                0,
+                "signer",
+            ),
+        ),
+    ])
+}
+
+/// Gas costs of a module or script, mapped back to its source file.
+#[derive(Debug, Clone)]
+pub struct ModuleGasReport {
+    pub name: String,
+    pub source_path: PathBuf,
+    pub functions: Vec<FunctionGasReport>,
+}
+
+/// Gas costs of a (non-native) function. Lines are 1-based.
+#[derive(Debug, Clone)]
+pub struct FunctionGasReport {
+    pub name: Identifier,
+    /// Line the function is defined on
+    pub line: u32,
+    pub bounds: GasBounds,
+    /// Lines of the loops of the function, which leave it without an upper bound
+    pub loops: Vec<u32>,
+    pub blocks: Vec<BlockGasReport>,
+    /// Calls of natives whose cost depends on the size of their input, or is not known
+    pub native_calls: Vec<NativeCallReport>,
+}
+
+/// Gas cost of a basic block, including the cost of the functions it calls.
+#[derive(Debug, Clone)]
+pub struct BlockGasReport {
+    pub offset: CodeOffset,
+    /// First and last line of the block's instructions
+    pub lines: (u32, u32),
+    pub bounds: GasBounds,
+}
+
+#[derive(Debug, Clone)]
+pub struct NativeCallReport {
+    pub line: u32,
+    /// Fully qualified name of the native, e.g. `0x1::hash::sha2_256`
+    pub function: String,
+    /// `None` if the native is not part of the gas schedule
+    pub cost: Option<NativeCost>,
+}
+
+/// A type, as far as needed to know the size of its values
+#[derive(Debug, Clone)]
+enum Ty {
+    Primitive,
+    Address,
+    Signer,
+    Vector(Box<Ty>),
+    Struct(ModuleId, Identifier, Vec<Ty>),
+    Reference(Box<Ty>),
+    /// A type parameter, or a value the analysis lost track of
+    Unknown,
+}
+
+/// Bounds of the abstract memory size of a value
+type SizeBounds = (u64, Option<u64>);
+
+struct FunctionAnalysis {
+    bounds: GasBounds,
+    blocks: Vec<(BlockId, GasBounds)>,
+    loop_heads: BTreeSet<BlockId>,
+    /// Calls of natives whose cost is not bounded
+    native_calls: Vec<(CodeOffset, String, Option<NativeCost>)>,
+}
+
+/// Computes gas costs over a set of modules, which must include the modules the analyzed code
+/// calls for the costs of these calls to be bounded.
+pub struct GasAnalyzer<'a> {
+    modules: BTreeMap<ModuleId, &'a CompiledModule>,
+    natives: BTreeMap<(&'static str, &'static str), NativeCost>,
+    // bounds of the functions analyzed so far, `None` while a function is being analyzed
+    functions: BTreeMap<(ModuleId, Identifier), Option<GasBounds>>,
+}
+
+impl<'a> GasAnalyzer<'a> {
+    pub fn new(modules: impl IntoIterator<Item = &'a CompiledModule>) -> Self {
+        Self {
+            modules: modules
+                .into_iter()
+                .map(|module| (module.self_id(), module))
+                .collect(),
+            natives: native_costs(),
+            functions: BTreeMap::new(),
+        }
+    }
+
+    /// Computes the costs of the (non-native) functions of `module`, mapping bytecode back to the
+    /// source at `source_path` using `source_map`.
+    pub fn module_report(
+        &mut self,
+        module: &CompiledModule,
+        source_map: &SourceMap,
+        source_path: &Path,
+    ) -> Result<ModuleGasReport> {
+        let file = source_file(source_map, source_path)?;
+        let view = BinaryIndexedView::Module(module);
+        let mut functions = vec![];
+        for (function_def_idx, function_def) in module.function_defs().iter().enumerate() {
+            let Some(code) = &function_def.code else {
+                continue;
+            };
+            let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+            let handle = module.function_handle_at(function_def.function);
+            let function = FunctionView::function(module, function_def_idx, code, handle);
+            let name = module.identifier_at(handle.name).to_owned();
+            functions.push(self.function_report(
+                view,
+                &function,
+                name,
+                function_def_idx,
+                source_map,
+                &file,
+            )?);
+        }
+        Ok(ModuleGasReport {
+            name: module.self_id().short_str_lossless(),
+            source_path: source_path.to_path_buf(),
+            functions,
+        })
+    }
+
+    /// Computes the costs of the script `script`, see `module_report`
+    pub fn script_report(
+        &mut self,
+        name: &str,
+        script: &NamedCompiledScript,
+        source_path: &Path,
+    ) -> Result<ModuleGasReport> {
+        let file = source_file(&script.source_map, source_path)?;
+        let view = BinaryIndexedView::Script(&script.script);
+        let function = FunctionView::script(&script.script);
+        let report = self.function_report(
+            view,
+            &function,
+            Identifier::new(name)?,
+            FunctionDefinitionIndex(0),
+            &script.source_map,
+            &file,
+        )?;
+        Ok(ModuleGasReport {
+            name: name.to_string(),
+            source_path: source_path.to_path_buf(),
+            functions: vec![report],
+        })
+    }
+
+    fn function_report(
+        &mut self,
+        view: BinaryIndexedView,
+        function: &FunctionView,
+        name: Identifier,
+        function_def_idx: FunctionDefinitionIndex,
+        source_map: &SourceMap,
+        file: &SimpleFile<String, String>,
+    ) -> Result<FunctionGasReport> {
+        let analysis = self.analyze(view, function);
+        let line_of = |offset: CodeOffset| -> Result<u32> {
+            let loc = source_map.get_code_location(function_def_idx, offset)?;
+            Ok(file.line_index((), loc.start() as usize)? as u32 + 1)
+        };
+        let cfg = function.cfg();
+        let mut blocks = vec![];
+        for (block, bounds) in analysis.blocks {
+            let mut lines = (u32::MAX, 0);
+            for offset in cfg.instr_indexes(block) {
+                let line = line_of(offset)?;
+                lines = (lines.0.min(line), lines.1.max(line));
+            }
+            blocks.push(BlockGasReport {
+                offset: block,
+                lines,
+                bounds,
+            })
+        }
+        let loops = analysis
+            .loop_heads
+            .iter()
+            .map(|head| line_of(*head))
+            .collect::<Result<_>>()?;
+        let native_calls = analysis
+            .native_calls
+            .into_iter()
+            .map(|(offset, function, cost)| {
+                Ok(NativeCallReport {
+                    line: line_of(offset)?,
+                    function,
+                    cost,
+                })
+            })
+            .collect::<Result<_>>()?;
+        let definition_location = source_map
+            .get_function_source_map(function_def_idx)?
+            .definition_location;
+        Ok(FunctionGasReport {
+            name,
+            line: file.line_index((), definition_location.start() as usize)? as u32 + 1,
+            bounds: analysis.bounds,
+            loops,
+            blocks,
+            native_calls,
+        })
+    }
+
+    fn analyze(&mut self, view: BinaryIndexedView, function: &FunctionView) -> FunctionAnalysis {
+        let code = &function.code().code;
+        let cfg = function.cfg();
+        let locals: Vec<Ty> = function
+            .parameters()
+            .0
+            .iter()
+            .chain(&function.locals().0)
+            .map(|token| signature_ty(view, token, &[]))
+            .collect();
+
+        // The verifier ensures the operand stack is empty at the start of each block
+        let mut blocks = BTreeMap::new();
+        let mut native_calls = vec![];
+        for block in cfg.blocks() {
+            let mut stack = vec![];
+            let mut bounds = GasBounds::ZERO;
+            for offset in cfg.instr_indexes(block) {
+                let instr = &code[offset as usize];
+                bounds = bounds
+                    + self.instruction(view, &locals, &mut stack, instr, offset, &mut native_calls);
+            }
+            blocks.insert(block, bounds);
+        }
+
+        // Longest and shortest paths over the forward edges, in topological order
+        let entry = cfg.entry_block_id();
+        let reachable = cfg.reachable_from(entry);
+        let mut loop_heads = BTreeSet::new();
+        let mut predecessors: BTreeMap<BlockId, usize> = BTreeMap::new();
+        for block in &reachable {
+            for successor in cfg.successors(*block) {
+                if cfg.is_back_edge(*block, *successor) {
+                    loop_heads.insert(*successor);
+                } else {
+                    *predecessors.entry(*successor).or_default() += 1;
+                }
+            }
+        }
+        let mut paths = BTreeMap::from([(entry, blocks[&entry])]);
+        let mut ready = vec![entry];
+        while let Some(block) = ready.pop() {
+            let path = paths[&block];
+            for successor in cfg.successors(block) {
+                if cfg.is_back_edge(block, *successor) {
+                    continue;
+                }
+                let through = path + blocks[successor];
+                let bounds = paths.entry(*successor).or_insert(through);
+                bounds.min = bounds.min.min(through.min);
+                bounds.max = bounds.max.zip(through.max).map(|(lhs, rhs)| lhs.max(rhs));
+                let count = predecessors.get_mut(successor).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(*successor)
+                }
+            }
+        }
+
+        let exits_with = |is_exit: fn(&Bytecode) -> bool| -> Vec<GasBounds> {
+            reachable
+                .iter()
+                .filter(|block| is_exit(&code[cfg.block_end(**block) as usize]))
+                .filter_map(|block| paths.get(block).copied())
+                .collect()
+        };
+        let mut exits = exits_with(|instr| matches!(instr, Bytecode::Ret));
+        if exits.is_empty() {
+            exits = exits_with(|instr| matches!(instr, Bytecode::Abort));
+        }
+        let bounds = match exits.iter().map(|bounds| bounds.min).min() {
+            Some(min) if loop_heads.is_empty() => GasBounds {
+                min,
+                max: exits
+                    .iter()
+                    .map(|bounds| bounds.max)
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|maxs| maxs.into_iter().max()),
+            },
+            Some(min) => GasBounds::at_least(min),
+            None => GasBounds::at_least(0),
+        };
+
+        FunctionAnalysis {
+            bounds,
+            blocks: blocks.into_iter().collect(),
+            loop_heads,
+            native_calls,
+        }
+    }
+
+    /// The cost of `instr`, applying its effect to the types on the operand `stack`
+    fn instruction(
+        &mut self,
+        view: BinaryIndexedView,
+        locals: &[Ty],
+        stack: &mut Vec<Ty>,
+        instr: &Bytecode,
+        offset: CodeOffset,
+        native_calls: &mut Vec<(CodeOffset, String, Option<NativeCost>)>,
+    ) -> GasBounds {
+        use Bytecode as B;
+
+        fn pop(stack: &mut Vec<Ty>) -> Ty {
+            stack.pop().unwrap_or(Ty::Unknown)
+        }
+        fn popn(stack: &mut Vec<Ty>, n: usize) -> Vec<Ty> {
+            stack.split_off(stack.len().saturating_sub(n))
+        }
+        let local = |idx: u8| locals.get(idx as usize).cloned().unwrap_or(Ty::Unknown);
+        let fixed = GasBounds::exact(unit_cost(instruction_key(instr)));
+        let with_size = |(min, max): SizeBounds| {
+            let unit = unit_cost(instruction_key(instr));
+            GasBounds {
+                min: unit.saturating_mul(min.max(1)),
+                max: max.map(|max| unit.saturating_mul(max.max(1))),
+            }
+        };
+
+        match instr {
+            B::Pop | B::BrTrue(_) | B::BrFalse(_) | B::Abort => {
+                pop(stack);
+                fixed
+            }
+            B::Ret => {
+                stack.clear();
+                fixed
+            }
+            B::Branch(_) | B::Nop => fixed,
+
+            B::LdU8(_)
+            | B::LdU16(_)
+            | B::LdU32(_)
+            | B::LdU64(_)
+            | B::LdU128(_)
+            | B::LdU256(_)
+            | B::LdTrue
+            | B::LdFalse => {
+                stack.push(Ty::Primitive);
+                fixed
+            }
+            B::LdConst(idx) => {
+                let constant = view.constant_at(*idx);
+                stack.push(signature_ty(view, &constant.type_, &[]));
+                let size = constant.data.len() as u64;
+                with_size((size, Some(size)))
+            }
+
+            B::CopyLoc(idx) | B::MoveLoc(idx) => {
+                let ty = local(*idx);
+                let cost = with_size(self.size(&ty));
+                stack.push(ty);
+                cost
+            }
+            B::StLoc(idx) => {
+                pop(stack);
+                with_size(self.size(&local(*idx)))
+            }
+            B::MutBorrowLoc(idx) | B::ImmBorrowLoc(idx) => {
+                stack.push(Ty::Reference(Box::new(local(*idx))));
+                fixed
+            }
+            B::MutBorrowField(idx) | B::ImmBorrowField(idx) => {
+                pop(stack);
+                let ty = view
+                    .field_handle_at(*idx)
+                    .ok()
+                    .and_then(|handle| self.field_ty(view, handle.owner, handle.field, &[]));
+                stack.push(Ty::Reference(Box::new(ty.unwrap_or(Ty::Unknown))));
+                fixed
+            }
+            B::MutBorrowFieldGeneric(idx) | B::ImmBorrowFieldGeneric(idx) => {
+                pop(stack);
+                let ty = view.field_instantiation_at(*idx).ok().and_then(|inst| {
+                    let handle = view.field_handle_at(inst.handle).ok()?;
+                    let type_args = signature_tys(view, &view.signature_at(inst.type_parameters).0);
+                    self.field_ty(view, handle.owner, handle.field, &type_args)
+                });
+                stack.push(Ty::Reference(Box::new(ty.unwrap_or(Ty::Unknown))));
+                fixed
+            }
+            B::FreezeRef => fixed,
+
+            B::Call(idx) => {
+                let handle = view.function_handle_at(*idx);
+                let num_args = view.signature_at(handle.parameters).len();
+                popn(stack, num_args);
+                for ty in signature_tys(view, &view.signature_at(handle.return_).0) {
+                    stack.push(ty)
+                }
+                let args = num_args as u64 + 1;
+                let module = view.module_id_for_handle(view.module_handle_at(handle.module));
+                let name = view.identifier_at(handle.name);
+                with_size((args, Some(args))) + self.call(module, name, offset, native_calls)
+            }
+            B::CallGeneric(idx) => {
+                let inst = view.function_instantiation_at(*idx);
+                let handle = view.function_handle_at(inst.handle);
+                let type_args = signature_tys(view, &view.signature_at(inst.type_parameters).0);
+                let num_args = view.signature_at(handle.parameters).len();
+                popn(stack, num_args);
+                for token in &view.signature_at(handle.return_).0 {
+                    stack.push(signature_ty(view, token, &type_args))
+                }
+                let args = (type_args.len() + num_args) as u64 + 1;
+                let module = view.module_id_for_handle(view.module_handle_at(handle.module));
+                let name = view.identifier_at(handle.name);
+                with_size((args, Some(args))) + self.call(module, name, offset, native_calls)
+            }
+
+            B::Pack(_) | B::PackGeneric(_) | B::Unpack(_) | B::UnpackGeneric(_) => {
+                let (ty, fields) = match instr {
+                    B::Pack(idx) | B::Unpack(idx) => self.struct_def(view, *idx, vec![]),
+                    B::PackGeneric(idx) | B::UnpackGeneric(idx) => {
+                        match view.struct_instantiation_at(*idx) {
+                            Ok(inst) => {
+                                let type_args =
+                                    signature_tys(view, &view.signature_at(inst.type_parameters).0);
+                                self.struct_def(view, inst.def, type_args)
+                            }
+                            Err(_) => (Ty::Unknown, vec![]),
+                        }
+                    }
+                    _ => unreachable!(),
+                };
+                if matches!(instr, B::Pack(_) | B::PackGeneric(_)) {
+                    popn(stack, fields.len());
+                    stack.push(ty);
+                } else {
+                    pop(stack);
+                    stack.extend(fields.iter().cloned());
+                }
+                let count = fields.len() as u64;
+                with_size(fields.iter().fold((count, Some(count)), |acc, field| {
+                    add_sizes(acc, self.size(field))
+                }))
+            }
+
+            B::ReadRef => {
+                let ty = match pop(stack) {
+                    Ty::Reference(ty) => *ty,
+                    _ => Ty::Unknown,
+                };
+                let cost = with_size(self.size(&ty));
+                stack.push(ty);
+                cost
+            }
+            B::WriteRef => {
+                pop(stack);
+                with_size(self.size(&pop(stack)))
+            }
+
+            B::Add
+            | B::Sub
+            | B::Mul
+            | B::Mod
+            | B::Div
+            | B::BitOr
+            | B::BitAnd
+            | B::Xor
+            | B::Shl
+            | B::Shr
+            | B::Or
+            | B::And
+            | B::Lt
+            | B::Gt
+            | B::Le
+            | B::Ge => {
+                popn(stack, 2);
+                stack.push(Ty::Primitive);
+                fixed
+            }
+            B::Not
+            | B::CastU8
+            | B::CastU16
+            | B::CastU32
+            | B::CastU64
+            | B::CastU128
+            | B::CastU256 => {
+                pop(stack);
+                stack.push(Ty::Primitive);
+                fixed
+            }
+            B::Eq | B::Neq => {
+                let size = add_sizes(self.size(&pop(stack)), self.size(&pop(stack)));
+                stack.push(Ty::Primitive);
+                with_size(size)
+            }
+
+            B::Exists(_) | B::ExistsGeneric(_) => {
+                pop(stack);
+                stack.push(Ty::Primitive);
+                with_size((REFERENCE_SIZE, Some(MIN_EXISTS_DATA_SIZE)))
+            }
+            B::MutBorrowGlobal(_)
+            | B::ImmBorrowGlobal(_)
+            | B::MutBorrowGlobalGeneric(_)
+            | B::ImmBorrowGlobalGeneric(_)
+            | B::MoveFrom(_)
+            | B::MoveFromGeneric(_) => {
+                pop(stack);
+                let ty = match instr {
+                    B::MutBorrowGlobal(idx) | B::ImmBorrowGlobal(idx) | B::MoveFrom(idx) => {
+                        self.struct_def(view, *idx, vec![]).0
+                    }
+                    B::MutBorrowGlobalGeneric(idx)
+                    | B::ImmBorrowGlobalGeneric(idx)
+                    | B::MoveFromGeneric(idx) => match view.struct_instantiation_at(*idx) {
+                        Ok(inst) => {
+                            let type_args =
+                                signature_tys(view, &view.signature_at(inst.type_parameters).0);
+                            self.struct_def(view, inst.def, type_args).0
+                        }
+                        Err(_) => Ty::Unknown,
+                    },
+                    _ => unreachable!(),
+                };
+                if matches!(instr, B::MoveFrom(_) | B::MoveFromGeneric(_)) {
+                    let cost = with_size(self.size(&ty));
+                    stack.push(ty);
+                    cost
+                } else {
+                    stack.push(Ty::Reference(Box::new(ty)));
+                    with_size((REFERENCE_SIZE, Some(REFERENCE_SIZE)))
+                }
+            }
+            B::MoveTo(_) | B::MoveToGeneric(_) => {
+                let ty = pop(stack);
+                pop(stack);
+                with_size(self.size(&ty))
+            }
+
+            B::VecPack(idx, num) => {
+                popn(stack, *num as usize);
+                let elem = self.vector_elem(view, *idx);
+                stack.push(Ty::Vector(Box::new(elem)));
+                with_size((*num, Some(*num)))
+            }
+            B::VecLen(_) => {
+                pop(stack);
+                stack.push(Ty::Primitive);
+                fixed
+            }
+            B::VecImmBorrow(idx) | B::VecMutBorrow(idx) => {
+                popn(stack, 2);
+                let elem = self.vector_elem(view, *idx);
+                stack.push(Ty::Reference(Box::new(elem)));
+                fixed
+            }
+            B::VecPushBack(_) => {
+                let elem = pop(stack);
+                pop(stack);
+                with_size(self.size(&elem))
+            }
+            B::VecPopBack(idx) => {
+                pop(stack);
+                stack.push(self.vector_elem(view, *idx));
+                fixed
+            }
+            B::VecUnpack(idx, num) => {
+                pop(stack);
+                let elem = self.vector_elem(view, *idx);
+                for _ in 0..*num {
+                    stack.push(elem.clone())
+                }
+                // charged as a push back per element by the gas meter
+                let unit = unit_cost(Opcodes::VEC_PUSH_BACK as u8);
+                GasBounds::exact(unit.saturating_mul((*num).max(1)))
+            }
+            B::VecSwap(_) => {
+                popn(stack, 3);
+                fixed
+            }
+        }
+    }
+
+    /// The cost of a call of `module::name`, excluding the cost of the call instruction
+    fn call(
+        &mut self,
+        module: ModuleId,
+        name: &IdentStr,
+        offset: CodeOffset,
+        native_calls: &mut Vec<(CodeOffset, String, Option<NativeCost>)>,
+    ) -> GasBounds {
+        let key = (module, name.to_owned());
+        match self.functions.get(&key) {
+            Some(Some(bounds)) => return *bounds,
+            // a recursive call
+            Some(None) => return GasBounds::at_least(0),
+            None => (),
+        }
+        let (module, name) = key;
+        let Some(compiled) = self.modules.get(&module).copied() else {
+            return GasBounds::at_least(0);
+        };
+        let def = compiled
+            .function_defs()
+            .iter()
+            .enumerate()
+            .find(|(_, def)| {
+                compiled.identifier_at(compiled.function_handle_at(def.function).name) == &*name
+            });
+        let Some((def_idx, def)) = def else {
+            return GasBounds::at_least(0);
+        };
+        let Some(code) = &def.code else {
+            let cost = if *module.address() == AccountAddress::ONE {
+                self.natives
+                    .get(&(module.name().as_str(), name.as_str()))
+                    .cloned()
+            } else {
+                None
+            };
+            let bounds = cost
+                .as_ref()
+                .map_or(GasBounds::at_least(0), NativeCost::bounds);
+            if bounds.max.is_none() {
+                let function = format!("{}::{}", module.short_str_lossless(), name);
+                native_calls.push((offset, function, cost))
+            }
+            return bounds;
+        };
+
+        let key = (module, name);
+        self.functions.insert(key.clone(), None);
+        let handle = compiled.function_handle_at(def.function);
+        let function = FunctionView::function(
+            compiled,
+            FunctionDefinitionIndex(def_idx as u16),
+            code,
+            handle,
+        );
+        let bounds = self
+            .analyze(BinaryIndexedView::Module(compiled), &function)
+            .bounds;
+        self.functions.insert(key, Some(bounds));
+        bounds
+    }
+
+    fn vector_elem(&self, view: BinaryIndexedView, idx: SignatureIndex) -> Ty {
+        match view.signature_at(idx).0.first() {
+            Some(token) => signature_ty(view, token, &[]),
+            None => Ty::Unknown,
+        }
+    }
+
+    /// The type of a struct defined in `view` and the types of its fields
+    fn struct_def(
+        &self,
+        view: BinaryIndexedView,
+        idx: StructDefinitionIndex,
+        type_args: Vec<Ty>,
+    ) -> (Ty, Vec<Ty>) {
+        let Ok(def) = view.struct_def_at(idx) else {
+            return (Ty::Unknown, vec![]);
+        };
+        let handle = view.struct_handle_at(def.struct_handle);
+        let fields = match &def.field_information {
+            StructFieldInformation::Native => vec![],
+            StructFieldInformation::Declared(fields) => fields
+                .iter()
+                .map(|field| signature_ty(view, &field.signature.0, &type_args))
+                .collect(),
+        };
+        let module = view.module_id_for_handle(view.module_handle_at(handle.module));
+        let name = view.identifier_at(handle.name).to_owned();
+        (Ty::Struct(module, name, type_args), fields)
+    }
+
+    fn field_ty(
+        &self,
+        view: BinaryIndexedView,
+        owner: StructDefinitionIndex,
+        field: u16,
+        type_args: &[Ty],
+    ) -> Option<Ty> {
+        let (_, fields) = self.struct_def(view, owner, type_args.to_vec());
+        fields.into_iter().nth(field as usize)
+    }
+
+    /// The abstract memory size of values of type `ty`, as charged by the gas meter
+    fn size(&self, ty: &Ty) -> SizeBounds {
+        match ty {
+            Ty::Primitive => (CONST_SIZE, Some(CONST_SIZE)),
+            Ty::Address => {
+                let size = AccountAddress::LENGTH as u64;
+                (size, Some(size))
+            }
+            Ty::Signer => {
+                let size = STRUCT_SIZE + AccountAddress::LENGTH as u64;
+                (size, Some(size))
+            }
+            Ty::Reference(_) => (REFERENCE_SIZE, Some(REFERENCE_SIZE)),
+            // vectors of primitives are stored unboxed, without the size of a container
+            Ty::Vector(elem) => match **elem {
+                Ty::Primitive | Ty::Address => (0, None),
+                _ => (STRUCT_SIZE, None),
+            },
+            Ty::Struct(module, name, type_args) => {
+                let Some(module) = self.modules.get(module).copied() else {
+                    return (STRUCT_SIZE, None);
+                };
+                let view = BinaryIndexedView::Module(module);
+                let def = module.struct_defs().iter().position(|def| {
+                    module.identifier_at(module.struct_handle_at(def.struct_handle).name) == &**name
+                });
+                let Some(def) = def else {
+                    return (STRUCT_SIZE, None);
+                };
+                let idx = StructDefinitionIndex(def as u16);
+                let (_, fields) = self.struct_def(view, idx, type_args.clone());
+                fields
+                    .iter()
+                    .fold((STRUCT_SIZE, Some(STRUCT_SIZE)), |acc, field| {
+                        add_sizes(acc, self.size(field))
+                    })
+            }
+            Ty::Unknown => (0, None),
+        }
+    }
+}
+
+fn signature_ty(view: BinaryIndexedView, token: &SignatureToken, type_args: &[Ty]) -> Ty {
+    use SignatureToken as S;
+    match token {
+        S::Bool | S::U8 | S::U16 | S::U32 | S::U64 | S::U128 | S::U256 => Ty::Primitive,
+        S::Address => Ty::Address,
+        S::Signer => Ty::Signer,
+        S::Vector(elem) => Ty::Vector(Box::new(signature_ty(view, elem, type_args))),
+        S::Struct(idx) | S::StructInstantiation(idx, _) => {
+            let handle = view.struct_handle_at(*idx);
+            let module = view.module_id_for_handle(view.module_handle_at(handle.module));
+            let name = view.identifier_at(handle.name).to_owned();
+            let args = match token {
+                S::StructInstantiation(_, args) => args
+                    .iter()
+                    .map(|arg| signature_ty(view, arg, type_args))
+                    .collect(),
+                _ => vec![],
+            };
+            Ty::Struct(module, name, args)
+        }
+        S::Reference(ty) | S::MutableReference(ty) => {
+            Ty::Reference(Box::new(signature_ty(view, ty, type_args)))
+        }
+        S::TypeParameter(idx) => type_args.get(*idx as usize).cloned().unwrap_or(Ty::Unknown),
+    }
+}
+
+fn signature_tys(view: BinaryIndexedView, tokens: &[SignatureToken]) -> Vec<Ty> {
+    tokens
+        .iter()
+        .map(|token| signature_ty(view, token, &[]))
+        .collect()
+}
+
+fn add_sizes((lhs_min, lhs_max): SizeBounds, (rhs_min, rhs_max): SizeBounds) -> SizeBounds {
+    (
+        lhs_min.saturating_add(rhs_min),
+        lhs_max
+            .zip(rhs_max)
+            .map(|(lhs, rhs)| lhs.saturating_add(rhs)),
+    )
+}
+
+/// The cost of an instruction per unit of the size it is charged for
+fn unit_cost(opcode: u8) -> u64 {
+    INSTRUCTION_COST_TABLE.instruction_cost(opcode).total()
+}
+
+fn source_file(source_map: &SourceMap, source_path: &Path) -> Result<SimpleFile<String, String>> {
+    let file_contents = fs::read_to_string(source_path)?;
+    if !source_map.check(&file_contents) {
+        bail!(
+            "File contents of {:?} out of sync with source map",
+            source_path
+        );
+    }
+    Ok(SimpleFile::new(
+        source_path.to_string_lossy().to_string(),
+        file_contents,
+    ))
+}
+
+/// Computes the costs of the modules and scripts of the root package of `package`. The modules of
+/// its dependencies are used for the costs of calls into them.
+pub fn package_report(package: &CompiledPackage) -> Result<Vec<ModuleGasReport>> {
+    let modules = package.all_modules().filter_map(|unit| match &unit.unit {
+        CompiledUnit::Module(NamedCompiledModule { module, .. }) => Some(module),
+        CompiledUnit::Script(_) => None,
+    });
+    let mut analyzer = GasAnalyzer::new(modules);
+    let mut reports = vec![];
+    for unit in &package.root_compiled_units {
+        reports.push(match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => analyzer.module_report(module, source_map, &unit.source_path)?,
+            CompiledUnit::Script(script) => {
+                analyzer.script_report(script.name.as_str(), script, &unit.source_path)?
+            }
+        })
+    }
+    Ok(reports)
+}
+
+/// Writes `reports` as text, listing the bounds of each function, followed by its loops, its
+/// basic blocks and its native calls with a cost that depends on their input.
+pub fn write_gas_report<W: Write>(reports: &[ModuleGasReport], w: &mut W) -> io::Result<()> {
+    fn lines((first, last): (u32, u32)) -> String {
+        if first == last {
+            format!("line {}", first)
+        } else {
+            format!("lines {}-{}", first, last)
+        }
+    }
+
+    writeln!(w, "GAS REPORT (internal gas units)")?;
+    for report in reports {
+        writeln!(
+            w,
+            "{} ({})",
+            report.name,
+            report.source_path.to_string_lossy()
+        )?;
+        for function in &report.functions {
+            writeln!(
+                w,
+                "  fun {} (line {}): {}",
+                function.name, function.line, function.bounds
+            )?;
+            for line in &function.loops {
+                writeln!(w, "    loop at line {}", line)?;
+            }
+            for block in &function.blocks {
+                writeln!(
+                    w,
+                    "    block {} ({}): {}",
+                    block.offset,
+                    lines(block.lines),
+                    block.bounds
+                )?;
+            }
+            for call in &function.native_calls {
+                match &call.cost {
+                    Some(cost) => writeln!(
+                        w,
+                        "    native call at line {}: {} costs {}",
+                        call.line, call.function, cost
+                    )?,
+                    None => writeln!(
+                        w,
+                        "    native call at line {}: {} has an unknown cost",
+                        call.line, call.function
+                    )?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
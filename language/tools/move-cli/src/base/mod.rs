@@ -3,9 +3,12 @@
 
 pub mod build;
 pub mod coverage;
+pub mod debug_adapter;
 pub mod disassemble;
 pub mod docgen;
 pub mod errmap;
+pub mod fmt;
+pub mod gas_report;
 pub mod info;
 pub mod new;
 pub mod prove;
@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use base::{
-    build::Build, coverage::Coverage, disassemble::Disassemble, docgen::Docgen, errmap::Errmap,
-    info::Info, new::New, prove::Prove, test::Test,
+    build::Build, coverage::Coverage, debug_adapter::DebugAdapter, disassemble::Disassemble,
+    docgen::Docgen, errmap::Errmap, fmt::Fmt, info::Info, new::New, prove::Prove, test::Test,
 };
 use move_package::BuildConfig;
 
@@ -64,9 +64,11 @@ pub struct MoveCLI {
 pub enum Command {
     Build(Build),
     Coverage(Coverage),
+    DebugAdapter(DebugAdapter),
     Disassemble(Disassemble),
     Docgen(Docgen),
     Errmap(Errmap),
+    Fmt(Fmt),
     Info(Info),
     New(New),
     Prove(Prove),
@@ -109,9 +111,13 @@ pub fn run_cli(
     match cmd {
         Command::Build(c) => c.execute(package_path, build_config),
         Command::Coverage(c) => c.execute(package_path, build_config),
+        Command::DebugAdapter(c) => {
+            c.execute(build_config, natives, cost_table, error_descriptions)
+        }
         Command::Disassemble(c) => c.execute(package_path, build_config),
         Command::Docgen(c) => c.execute(package_path, build_config),
         Command::Errmap(c) => c.execute(package_path, build_config),
+        Command::Fmt(c) => c.execute(package_path),
         Command::Info(c) => c.execute(package_path, build_config),
         Command::New(c) => c.execute_with_defaults(package_path),
         Command::Prove(c) => c.execute(package_path, build_config),
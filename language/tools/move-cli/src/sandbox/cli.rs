@@ -16,6 +16,7 @@ use move_core_types::{
     transaction_argument::TransactionArgument,
 };
 use move_package::compilation::package_layout::CompiledPackageLayout;
+use move_vm_runtime::native_extensions::NativeContextExtensions;
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     fs,
@@ -256,6 +257,7 @@ impl SandboxCommand {
                     bytecode_version,
                     *dry_run,
                     move_args.verbose,
+                    NativeContextExtensions::default(),
                 )
             }
             SandboxCommand::Test {
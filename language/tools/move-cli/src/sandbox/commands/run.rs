@@ -21,16 +21,16 @@ use move_core_types::{
     value::MoveValue,
 };
 use move_package::compilation::compiled_package::CompiledPackage;
-use move_vm_runtime::move_vm::MoveVM;
+use move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{fs, path::Path};
 
 #[allow(clippy::too_many_arguments)]
-pub fn run(
+pub fn run<'r>(
     natives: impl IntoIterator<Item = NativeFunctionRecord>,
     cost_table: &CostTable,
     error_descriptions: &ErrorMapping,
-    state: &OnDiskStateView,
+    state: &'r OnDiskStateView,
     package: &CompiledPackage,
     script_path: &Path,
     script_name_opt: &Option<String>,
@@ -41,6 +41,7 @@ pub fn run(
     bytecode_version: Option<u32>,
     dry_run: bool,
     verbose: bool,
+    extensions: NativeContextExtensions<'r>,
 ) -> Result<()> {
     if !script_path.exists() {
         bail!("Script file {:?} does not exist", script_path)
@@ -78,7 +79,7 @@ move run` must be applied to a module inside `storage/`",
 
     let vm = MoveVM::new(natives).unwrap();
     let mut gas_status = get_gas_status(cost_table, gas_budget)?;
-    let mut session = vm.new_session(state);
+    let mut session = vm.new_session_with_extensions(state, extensions);
 
     let script_type_parameters = vec![];
     let script_parameters = vec![];
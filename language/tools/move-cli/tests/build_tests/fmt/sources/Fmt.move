@@ -0,0 +1,13 @@
+module 0x42::fmt {
+    // Sums the numbers below `n`.
+    public fun sum(n: u64): u64 {
+        let i = 0; let total = 0;
+        while (i < n) {
+            total = total+i;
+            i = i + 1;
+        };
+
+
+        total
+    }
+}
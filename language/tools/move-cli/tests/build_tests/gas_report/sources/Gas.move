@@ -0,0 +1,41 @@
+module 0x42::Gas {
+    use std::hash;
+    use std::vector;
+
+    struct Pair has copy, drop {
+        x: u64,
+        y: u64,
+    }
+
+    public fun add(a: u64, b: u64): u64 {
+        a + b
+    }
+
+    public fun max(a: u64, b: u64): u64 {
+        if (a > b) {
+            a
+        } else {
+            b
+        }
+    }
+
+    public fun sum(p: Pair): u64 {
+        add(p.x, p.y)
+    }
+
+    public fun count(n: u64): u64 {
+        let i = 0;
+        while (i < n) {
+            i = i + 1;
+        };
+        i
+    }
+
+    public fun digest(bytes: vector<u8>): vector<u8> {
+        hash::sha2_256(bytes)
+    }
+
+    public fun len(v: &vector<u64>): u64 {
+        vector::length(v)
+    }
+}
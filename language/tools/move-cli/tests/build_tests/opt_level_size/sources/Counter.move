@@ -0,0 +1,65 @@
+module 0x42::counter {
+    use std::signer;
+    use std::vector;
+
+    const ENOT_PUBLISHED: u64 = 1;
+    // not used by the code, but kept for the error maps
+    const EMAX_REACHED: u64 = 2;
+    const UNUSED_NAME: vector<u8> = b"counter";
+    const DEFAULT_VALUES: vector<u64> = vector[1, 2, 3];
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    // run when the module is published, so it is kept although it is private
+    fun init_module(account: &signer) {
+        publish(account)
+    }
+
+    public fun publish(account: &signer) {
+        move_to(account, Counter { value: initial_value() })
+    }
+
+    public fun increment(addr: address) acquires Counter {
+        assert!(exists<Counter>(addr), ENOT_PUBLISHED);
+        let counter = borrow_global_mut<Counter>(addr);
+        counter.value = add(counter.value, 1);
+    }
+
+    public entry fun reset(account: &signer) acquires Counter {
+        let counter = borrow_global_mut<Counter>(signer::address_of(account));
+        counter.value = initial_value();
+    }
+
+    fun initial_value(): u64 {
+        *vector::borrow(&DEFAULT_VALUES, 0)
+    }
+
+    fun add(a: u64, b: u64): u64 {
+        a + b
+    }
+
+    // not called by any public or entry function
+    fun debug_name(): vector<u8> {
+        UNUSED_NAME
+    }
+
+    fun sum(values: &vector<u64>): u64 {
+        let total = 0;
+        let i = 0;
+        while (i < vector::length(values)) {
+            total = add(total, *vector::borrow(values, i));
+            i = i + 1;
+        };
+        total
+    }
+
+    fun unused_sum(): u64 {
+        sum(&vector[debug_name_length(), 2])
+    }
+
+    fun debug_name_length(): u64 {
+        vector::length(&debug_name())
+    }
+}
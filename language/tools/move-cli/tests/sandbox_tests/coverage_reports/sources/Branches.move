@@ -0,0 +1,44 @@
+module 0x42::Branches {
+    public fun clamp(x: u64): u64 {
+        let y = x;
+        if (x > 10) y = 10;
+        y
+    }
+
+    public fun sign(x: u64): u64 {
+        if (x == 0) {
+            0
+        } else {
+            1
+        }
+    }
+
+    public fun count_down(n: u64): u64 {
+        let steps = 0;
+        while (n > 0) {
+            n = n - 1;
+            steps = steps + 1;
+        };
+        steps
+    }
+
+    public fun unused(): u64 {
+        7
+    }
+
+    #[test]
+    fun test_clamp() {
+        clamp(3);
+    }
+
+    #[test]
+    fun test_sign() {
+        sign(0);
+        sign(5);
+    }
+
+    #[test]
+    fun test_count_down() {
+        count_down(2);
+    }
+}
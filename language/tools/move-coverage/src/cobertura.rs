@@ -0,0 +1,138 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Export of coverage as a Cobertura XML report.
+
+use crate::report::{ModuleReport, Tally};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+/// Writes a Cobertura report with a single package named `package_name`, holding one class per
+/// module and one method per function. Source paths are written as they appear in `reports`,
+/// relative to `source_root`.
+pub fn write_cobertura<W: Write>(
+    package_name: &str,
+    source_root: &str,
+    reports: &[ModuleReport],
+    writer: &mut W,
+) -> io::Result<()> {
+    let (lines, branches) = reports.iter().fold(
+        (Tally::default(), Tally::default()),
+        |(lines, branches), report| {
+            (
+                lines + report.line_tally(),
+                branches + report.branch_tally(),
+            )
+        },
+    );
+
+    writeln!(writer, r#"<?xml version="1.0" ?>"#)?;
+    writeln!(
+        writer,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )?;
+    writeln!(
+        writer,
+        r#"<coverage line-rate="{:.4}" branch-rate="{:.4}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="0.1" timestamp="0">"#,
+        lines.rate(),
+        branches.rate(),
+        lines.hit,
+        lines.found,
+        branches.hit,
+        branches.found,
+    )?;
+    writeln!(writer, "  <sources>")?;
+    writeln!(writer, "    <source>{}</source>", escape(source_root))?;
+    writeln!(writer, "  </sources>")?;
+    writeln!(writer, "  <packages>")?;
+    writeln!(
+        writer,
+        r#"    <package name="{}" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+        escape(package_name),
+        lines.rate(),
+        branches.rate(),
+    )?;
+    writeln!(writer, "      <classes>")?;
+
+    for report in reports {
+        writeln!(
+            writer,
+            r#"        <class name="{}" filename="{}" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+            escape(report.name.as_str()),
+            escape(&report.source_path.display().to_string()),
+            report.line_tally().rate(),
+            report.branch_tally().rate(),
+        )?;
+
+        writeln!(writer, "          <methods>")?;
+        for function in &report.functions {
+            writeln!(
+                writer,
+                r#"            <method name="{}" signature="" line-rate="{:.4}" branch-rate="{:.4}" complexity="0">"#,
+                escape(function.name.as_str()),
+                function.line_tally().rate(),
+                function.branch_tally().rate(),
+            )?;
+            write_lines(
+                writer,
+                "              ",
+                &function.lines,
+                &function.branches,
+            )?;
+            writeln!(writer, "            </method>")?;
+        }
+        writeln!(writer, "          </methods>")?;
+
+        write_lines(writer, "          ", &report.lines(), &report.branches())?;
+        writeln!(writer, "        </class>")?;
+    }
+
+    writeln!(writer, "      </classes>")?;
+    writeln!(writer, "    </package>")?;
+    writeln!(writer, "  </packages>")?;
+    writeln!(writer, "</coverage>")?;
+    Ok(())
+}
+
+fn write_lines<W: Write>(
+    writer: &mut W,
+    indent: &str,
+    lines: &BTreeMap<u32, u64>,
+    branches: &BTreeMap<u32, Vec<u64>>,
+) -> io::Result<()> {
+    writeln!(writer, "{}<lines>", indent)?;
+    for (line, hits) in lines {
+        match branches.get(line) {
+            None => writeln!(
+                writer,
+                r#"{}  <line number="{}" hits="{}" branch="false"/>"#,
+                indent, line, hits
+            )?,
+            Some(outcomes) => {
+                let tally = Tally::of(outcomes);
+                writeln!(
+                    writer,
+                    r#"{}  <line number="{}" hits="{}" branch="true" condition-coverage="{}% ({}/{})"/>"#,
+                    indent,
+                    line,
+                    hits,
+                    (tally.rate() * 100.0).round() as u64,
+                    tally.hit,
+                    tally.found,
+                )?
+            }
+        }
+    }
+    writeln!(writer, "{}</lines>", indent)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,93 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Export of coverage as an lcov tracefile (`.info`).
+
+use crate::report::ModuleReport;
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    path::Path,
+};
+
+/// Writes one lcov record per source file, merging the reports of modules defined in the same
+/// file. Functions are named `<module>::<function>`.
+pub fn write_lcov<W: Write>(reports: &[ModuleReport], writer: &mut W) -> io::Result<()> {
+    let mut files: BTreeMap<&Path, Vec<&ModuleReport>> = BTreeMap::new();
+    for report in reports {
+        files
+            .entry(report.source_path.as_path())
+            .or_default()
+            .push(report);
+    }
+
+    for (source_path, reports) in files {
+        writeln!(writer, "TN:")?;
+        writeln!(writer, "SF:{}", source_path.display())?;
+
+        let mut functions_hit = 0;
+        let functions = reports.iter().flat_map(|report| {
+            report
+                .functions
+                .iter()
+                .map(move |function| (report.name.as_str(), function))
+        });
+        for (module, function) in functions.clone() {
+            writeln!(writer, "FN:{},{}::{}", function.line, module, function.name)?;
+        }
+        for (module, function) in functions.clone() {
+            writeln!(
+                writer,
+                "FNDA:{},{}::{}",
+                function.hits, module, function.name
+            )?;
+            functions_hit += u64::from(function.hits > 0);
+        }
+        writeln!(writer, "FNF:{}", functions.count())?;
+        writeln!(writer, "FNH:{}", functions_hit)?;
+
+        let mut branches: BTreeMap<u32, Vec<u64>> = BTreeMap::new();
+        let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+        for report in &reports {
+            for (line, hits) in report.branches() {
+                branches.entry(line).or_default().extend(hits);
+            }
+            for (line, hits) in report.lines() {
+                let line_hits = lines.entry(line).or_insert(0);
+                *line_hits = (*line_hits).max(hits);
+            }
+        }
+
+        let (mut branches_found, mut branches_hit) = (0, 0);
+        for (line, hits) in &branches {
+            let line_hit = lines.get(line).map_or(false, |hits| *hits > 0);
+            for (branch, hits) in hits.iter().enumerate() {
+                // A branch on a line that never ran is reported as `-` rather than `0`.
+                if line_hit {
+                    writeln!(writer, "BRDA:{},0,{},{}", line, branch, hits)?;
+                } else {
+                    writeln!(writer, "BRDA:{},0,{},-", line, branch)?;
+                }
+                branches_found += 1;
+                branches_hit += u64::from(*hits > 0);
+            }
+        }
+        writeln!(writer, "BRF:{}", branches_found)?;
+        writeln!(writer, "BRH:{}", branches_hit)?;
+
+        for (line, hits) in &lines {
+            writeln!(writer, "DA:{},{}", line, hits)?;
+        }
+        writeln!(writer, "LF:{}", lines.len())?;
+        writeln!(
+            writer,
+            "LH:{}",
+            lines.values().filter(|hits| **hits > 0).count()
+        )?;
+        writeln!(writer, "end_of_record")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Line, function and branch coverage of modules against their source files, used to export
+//! coverage in formats understood by other tools (see `lcov` and `cobertura`).
+
+use crate::coverage_map::CoverageMap;
+use anyhow::{bail, Result};
+use codespan::Files;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::identifier::Identifier;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Coverage of a single function. Lines are 1-based.
+#[derive(Debug, Clone)]
+pub struct FunctionReport {
+    pub name: Identifier,
+    /// Line the function is defined on
+    pub line: u32,
+    /// Number of times the function was entered
+    pub hits: u64,
+    /// Line -> number of times it was executed
+    pub lines: BTreeMap<u32, u64>,
+    /// Line -> number of times each outcome of a conditional branch on that line was taken. The
+    /// jump is listed before the fall through. Branches whose outcomes can't be told apart from
+    /// the instruction counts are left out (see `branch_outcomes`).
+    pub branches: BTreeMap<u32, Vec<u64>>,
+}
+
+/// Coverage of a module's (non-native) functions against its source file.
+#[derive(Debug, Clone)]
+pub struct ModuleReport {
+    pub name: Identifier,
+    pub source_path: PathBuf,
+    pub functions: Vec<FunctionReport>,
+}
+
+/// Counts of covered and coverable items, e.g. lines or branches.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tally {
+    pub found: u64,
+    pub hit: u64,
+}
+
+impl ModuleReport {
+    /// Computes the coverage of `module` in `coverage_map`, mapping bytecode back to the source
+    /// at `source_path` using `source_map`. The coverage of a module that was never executed is
+    /// reported as zero.
+    pub fn new(
+        module: &CompiledModule,
+        coverage_map: &CoverageMap,
+        source_map: &SourceMap,
+        source_path: &Path,
+    ) -> Result<Self> {
+        let file_contents = fs::read_to_string(source_path)?;
+        if !source_map.check(&file_contents) {
+            bail!(
+                "File contents of {:?} out of sync with source map",
+                source_path
+            );
+        }
+        let mut files = Files::new();
+        let file_id = files.add(source_path.as_os_str().to_os_string(), file_contents);
+        let line_of = |byte_index: u32| -> Result<u32> {
+            Ok(files.location(file_id, byte_index)?.line.0 + 1)
+        };
+
+        let module_id = module.self_id();
+        let unified_exec_map = coverage_map.to_unified_exec_map();
+        let module_map = unified_exec_map
+            .module_maps
+            .get(&(*module_id.address(), module_id.name().to_owned()));
+
+        let mut functions = vec![];
+        for (function_def_idx, function_def) in module.function_defs().iter().enumerate() {
+            let Some(code_unit) = &function_def.code else {
+                continue;
+            };
+            let fn_handle = module.function_handle_at(function_def.function);
+            let fn_name = module.identifier_at(fn_handle.name).to_owned();
+            let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+            let function_map = module_map.and_then(|m| m.function_maps.get(&fn_name));
+            let count = |offset: CodeOffset| {
+                function_map
+                    .and_then(|f| f.get(&(offset as u64)))
+                    .copied()
+                    .unwrap_or(0)
+            };
+
+            let function_source_map = source_map.get_function_source_map(function_def_idx)?;
+            let predecessors = predecessors(&code_unit.code);
+            let mut lines = BTreeMap::new();
+            let mut branches = BTreeMap::new();
+            for (offset, instr) in code_unit.code.iter().enumerate() {
+                let offset = offset as CodeOffset;
+                let loc = source_map.get_code_location(function_def_idx, offset)?;
+                let line = line_of(loc.start())?;
+                let hits = count(offset);
+
+                let line_hits = lines.entry(line).or_insert(0);
+                *line_hits = (*line_hits).max(hits);
+
+                if let Bytecode::BrTrue(target) | Bytecode::BrFalse(target) = instr {
+                    if let Some(outcomes) =
+                        branch_outcomes(offset, *target, hits, &predecessors, count)
+                    {
+                        branches
+                            .entry(line)
+                            .or_insert_with(Vec::new)
+                            .extend(outcomes);
+                    }
+                }
+            }
+
+            functions.push(FunctionReport {
+                name: fn_name,
+                line: line_of(function_source_map.definition_location.start())?,
+                hits: count(0),
+                lines,
+                branches,
+            });
+        }
+
+        Ok(Self {
+            name: module_id.name().to_owned(),
+            source_path: source_path.to_path_buf(),
+            functions,
+        })
+    }
+
+    /// Line -> number of times it was executed, across all functions.
+    pub fn lines(&self) -> BTreeMap<u32, u64> {
+        let mut lines = BTreeMap::new();
+        for function in &self.functions {
+            for (line, hits) in &function.lines {
+                let line_hits = lines.entry(*line).or_insert(0);
+                *line_hits = (*line_hits).max(*hits);
+            }
+        }
+        lines
+    }
+
+    /// Line -> outcomes of the conditional branches on that line, across all functions.
+    pub fn branches(&self) -> BTreeMap<u32, Vec<u64>> {
+        let mut branches = BTreeMap::new();
+        for function in &self.functions {
+            for (line, hits) in &function.branches {
+                branches
+                    .entry(*line)
+                    .or_insert_with(Vec::new)
+                    .extend(hits.iter().copied());
+            }
+        }
+        branches
+    }
+
+    pub fn line_tally(&self) -> Tally {
+        Tally::of(self.lines().values())
+    }
+
+    pub fn branch_tally(&self) -> Tally {
+        Tally::of(self.branches().values().flatten())
+    }
+
+    pub fn function_tally(&self) -> Tally {
+        Tally::of(self.functions.iter().map(|f| &f.hits))
+    }
+}
+
+/// Offset -> offsets of the instructions which can be executed right before it.
+fn predecessors(code: &[Bytecode]) -> BTreeMap<CodeOffset, Vec<CodeOffset>> {
+    let mut predecessors: BTreeMap<CodeOffset, Vec<CodeOffset>> = BTreeMap::new();
+    for (offset, instr) in code.iter().enumerate() {
+        let offset = offset as CodeOffset;
+        if let Bytecode::BrTrue(target) | Bytecode::BrFalse(target) | Bytecode::Branch(target) =
+            instr
+        {
+            predecessors.entry(*target).or_default().push(offset);
+        }
+        if !matches!(instr, Bytecode::Branch(_) | Bytecode::Ret | Bytecode::Abort) {
+            predecessors.entry(offset + 1).or_default().push(offset);
+        }
+    }
+    predecessors
+}
+
+/// Number of times the jump and the fall through of the conditional branch at `offset` were
+/// taken, out of its `hits`.
+///
+/// Per-instruction counts do not record which way a branch went, but a successor only reached
+/// from the branch runs exactly as often as the branch went its way, and the other outcome is
+/// the rest of the hits. Counting the other successor instead would also count the runs reaching
+/// it from elsewhere, e.g. the join block of an `if` without `else`. If both successors are
+/// reached from elsewhere, the outcomes are unknown and `None` is returned.
+fn branch_outcomes(
+    offset: CodeOffset,
+    target: CodeOffset,
+    hits: u64,
+    predecessors: &BTreeMap<CodeOffset, Vec<CodeOffset>>,
+    count: impl Fn(CodeOffset) -> u64,
+) -> Option<[u64; 2]> {
+    let only_from_branch = |successor: CodeOffset| {
+        predecessors
+            .get(&successor)
+            .map_or(false, |preds| preds.iter().all(|pred| *pred == offset))
+    };
+    let fall_through = offset + 1;
+    if target == fall_through {
+        None
+    } else if only_from_branch(fall_through) {
+        let not_taken = count(fall_through).min(hits);
+        Some([hits - not_taken, not_taken])
+    } else if only_from_branch(target) {
+        let taken = count(target).min(hits);
+        Some([taken, hits - taken])
+    } else {
+        None
+    }
+}
+
+impl FunctionReport {
+    pub fn line_tally(&self) -> Tally {
+        Tally::of(self.lines.values())
+    }
+
+    pub fn branch_tally(&self) -> Tally {
+        Tally::of(self.branches.values().flatten())
+    }
+}
+
+impl Tally {
+    /// Tallies the given hit counts; every count is an item, covered if it is non-zero.
+    pub fn of<'a>(hits: impl IntoIterator<Item = &'a u64>) -> Self {
+        hits.into_iter()
+            .fold(Tally::default(), |tally, hits| Tally {
+                found: tally.found + 1,
+                hit: tally.hit + u64::from(*hits > 0),
+            })
+    }
+
+    /// Fraction of the items that are covered. Nothing to cover counts as fully covered.
+    pub fn rate(&self) -> f64 {
+        if self.found == 0 {
+            1.0
+        } else {
+            self.hit as f64 / self.found as f64
+        }
+    }
+}
+
+impl std::ops::Add for Tally {
+    type Output = Tally;
+
+    fn add(self, other: Tally) -> Tally {
+        Tally {
+            found: self.found + other.found,
+            hit: self.hit + other.hit,
+        }
+    }
+}
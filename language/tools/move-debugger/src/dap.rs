@@ -0,0 +1,391 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A Debug Adapter Protocol (DAP) server for the debugger, so that editors like VS Code can debug
+//! Move programs.
+//!
+//! The server handles a single debug session per connection. What is run is decided by the
+//! embedding through a [`Launcher`], which receives the arguments of the client's `launch`
+//! request. Besides the usual requests, `evaluate` accepts the name of a local of the selected
+//! frame or `borrow_global<T>(address)` to inspect global storage.
+
+use crate::{
+    debugger::{
+        new_debugger, DebugClient, DebugEvent, Debugger, Resume, StopReason, VariableValue,
+    },
+    source_index::SourceIndex,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use move_core_types::{account_address::AccountAddress, parser::parse_type_tag};
+use serde_json::{json, Value};
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+/// The id of the only thread of a Move program.
+const THREAD_ID: i64 = 1;
+
+/// A program prepared for debugging.
+pub struct Program {
+    /// The code of the program.
+    pub index: SourceIndex,
+    /// Runs the program, attaching the debugger to every session it executes code in.
+    pub run: ProgramRun,
+}
+
+pub type ProgramRun = Box<dyn FnOnce(Debugger) -> Result<()> + Send>;
+
+/// Prepares the programs to debug.
+pub trait Launcher {
+    /// Prepares the program described by the `arguments` of a `launch` request.
+    fn launch(&mut self, arguments: &Value) -> Result<Program>;
+}
+
+/// Writes protocol messages, shared between the request loop and the thread forwarding the
+/// debugger's events.
+struct Output<W> {
+    writer: Mutex<W>,
+    seq: AtomicI64,
+}
+
+impl<W: Write> Output<W> {
+    fn send(&self, mut message: Value) -> Result<()> {
+        message["seq"] = json!(self.seq.fetch_add(1, Ordering::SeqCst));
+        let content = serde_json::to_string(&message)?;
+        let mut writer = self.writer.lock().unwrap();
+        write!(
+            writer,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn event(&self, event: &str, body: Value) -> Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+}
+
+/// Serves a debug session, reading requests from `reader` and writing responses and events to
+/// `writer`, until the client disconnects.
+pub fn serve<R: BufRead, W: Write + Send + 'static>(
+    mut reader: R,
+    writer: W,
+    launcher: &mut dyn Launcher,
+) -> Result<()> {
+    let output = Arc::new(Output {
+        writer: Mutex::new(writer),
+        seq: AtomicI64::new(1),
+    });
+    let mut session = Session {
+        output: output.clone(),
+        launcher,
+        client: None,
+        program: None,
+    };
+
+    while let Some(request) = read_message(&mut reader)? {
+        let command = request["command"].as_str().unwrap_or_default().to_string();
+        let result = session.handle(&command, &request["arguments"]);
+        let mut response = json!({
+            "type": "response",
+            "request_seq": request["seq"],
+            "command": command,
+            "success": result.is_ok(),
+        });
+        match result {
+            Ok(body) => response["body"] = body,
+            Err(err) => response["message"] = json!(format!("{:#}", err)),
+        }
+        output.send(response)?;
+        if command == "disconnect" {
+            break;
+        }
+        if command == "launch" && session.client.is_some() {
+            output.event("initialized", json!({}))?;
+        }
+    }
+    Ok(())
+}
+
+struct Session<'a, W> {
+    output: Arc<Output<W>>,
+    launcher: &'a mut dyn Launcher,
+    client: Option<Arc<DebugClient>>,
+    /// Runs the launched program with its debugger, until the client is done configuring it.
+    program: Option<(ProgramRun, Debugger)>,
+}
+
+impl<'a, W: Write + Send + 'static> Session<'a, W> {
+    fn handle(&mut self, command: &str, arguments: &Value) -> Result<Value> {
+        match command {
+            "initialize" => Ok(json!({
+                "supportsConfigurationDoneRequest": true,
+                "supportsEvaluateForHovers": true,
+            })),
+            "launch" => self.launch(arguments),
+            "setBreakpoints" => self.set_breakpoints(arguments),
+            "setExceptionBreakpoints" => Ok(json!({})),
+            "configurationDone" => self.configuration_done(),
+            "threads" => Ok(json!({ "threads": [{ "id": THREAD_ID, "name": "main" }] })),
+            "stackTrace" => self.stack_trace(),
+            "scopes" => {
+                let frame = arguments["frameId"].as_i64().unwrap_or_default();
+                Ok(json!({
+                    "scopes": [{
+                        "name": "Locals",
+                        "variablesReference": frame + 1,
+                        "expensive": false,
+                    }]
+                }))
+            }
+            "variables" => self.variables(arguments),
+            "continue" => {
+                self.client()?.resume(Resume::Continue)?;
+                Ok(json!({ "allThreadsContinued": true }))
+            }
+            "next" => self.resume(Resume::StepOver),
+            "stepIn" => self.resume(Resume::StepInto),
+            "stepOut" => self.resume(Resume::StepOut),
+            "pause" => {
+                self.client()?.pause();
+                Ok(json!({}))
+            }
+            "evaluate" => self.evaluate(arguments),
+            "disconnect" => {
+                if let Some(client) = self.client.take() {
+                    client.detach();
+                }
+                Ok(json!({}))
+            }
+            _ => bail!("Unsupported request '{}'", command),
+        }
+    }
+
+    fn client(&self) -> Result<&DebugClient> {
+        self.client
+            .as_deref()
+            .ok_or_else(|| anyhow!("No program has been launched"))
+    }
+
+    fn launch(&mut self, arguments: &Value) -> Result<Value> {
+        if self.client.is_some() {
+            bail!("A program has already been launched");
+        }
+        let Program { index, run } = self.launcher.launch(arguments)?;
+        let stop_on_entry = arguments["stopOnEntry"].as_bool().unwrap_or(false);
+        let (debugger, client) = new_debugger(index, stop_on_entry);
+        self.client = Some(Arc::new(client));
+        self.program = Some((run, debugger));
+        Ok(json!({}))
+    }
+
+    fn set_breakpoints(&mut self, arguments: &Value) -> Result<Value> {
+        let path = arguments["source"]["path"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Missing source path"))?;
+        let lines = arguments["breakpoints"]
+            .as_array()
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .filter_map(|bp| bp["line"].as_u64())
+                    .map(|line| line as u32)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let verified = self.client()?.set_breakpoints(Path::new(path), &lines);
+        let breakpoints = lines
+            .iter()
+            .zip(verified)
+            .map(|(line, verified)| json!({ "verified": verified, "line": line }))
+            .collect::<Vec<_>>();
+        Ok(json!({ "breakpoints": breakpoints }))
+    }
+
+    /// Starts the program, which runs on its own thread while the session serves requests.
+    fn configuration_done(&mut self) -> Result<Value> {
+        let (run, debugger) = self
+            .program
+            .take()
+            .ok_or_else(|| anyhow!("No program has been launched"))?;
+        let client = self
+            .client
+            .clone()
+            .ok_or_else(|| anyhow!("No program has been launched"))?;
+
+        thread::spawn(move || {
+            let result = run(debugger.clone());
+            debugger.exit(result);
+        });
+
+        let output = self.output.clone();
+        thread::spawn(move || forward_events(&client, &output));
+        Ok(json!({}))
+    }
+
+    fn stack_trace(&self) -> Result<Value> {
+        let client = self.client()?;
+        let frames = client
+            .stack_trace()?
+            .into_iter()
+            .enumerate()
+            .map(|(id, frame)| {
+                let name = match &frame.module {
+                    Some(module) => format!("{}::{}", module.short_str_lossless(), frame.function),
+                    None => frame.function.clone(),
+                };
+                let mut dap_frame = json!({ "id": id, "name": name, "line": 0, "column": 0 });
+                if let Some(line) = client.index().source_line(
+                    frame.module.as_ref(),
+                    frame.function_index,
+                    frame.pc,
+                ) {
+                    dap_frame["source"] = json!({ "path": line.path });
+                    dap_frame["line"] = json!(line.line);
+                    dap_frame["column"] = json!(1);
+                }
+                dap_frame
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "stackFrames": frames, "totalFrames": frames.len() }))
+    }
+
+    fn variables(&self, arguments: &Value) -> Result<Value> {
+        let frame = arguments["variablesReference"]
+            .as_u64()
+            .and_then(|reference| reference.checked_sub(1))
+            .ok_or_else(|| anyhow!("Invalid variables reference"))?;
+        let variables = self
+            .client()?
+            .locals(frame as usize)?
+            .into_iter()
+            .map(|variable| {
+                json!({
+                    "name": variable.name,
+                    "value": render(&variable.value),
+                    "variablesReference": 0,
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "variables": variables }))
+    }
+
+    fn resume(&self, resume: Resume) -> Result<Value> {
+        self.client()?.resume(resume)?;
+        Ok(json!({}))
+    }
+
+    fn evaluate(&self, arguments: &Value) -> Result<Value> {
+        let client = self.client()?;
+        let expression = arguments["expression"].as_str().unwrap_or_default().trim();
+        let result = if let Some((tag, address)) = parse_borrow_global(expression) {
+            let tag = parse_type_tag(tag)?;
+            let address = AccountAddress::from_hex_literal(address)
+                .with_context(|| format!("Invalid address '{}'", address))?;
+            client
+                .global(address, tag)?
+                .unwrap_or_else(|| "<no resource>".to_string())
+        } else {
+            let frame = arguments["frameId"].as_u64().unwrap_or_default() as usize;
+            client
+                .locals(frame)?
+                .into_iter()
+                .find(|variable| variable.name == expression)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "Local '{}' is not available in this frame, it may have been optimized away",
+                        expression
+                    )
+                })
+                .map(|variable| render(&variable.value).to_string())?
+        };
+        Ok(json!({ "result": result, "variablesReference": 0 }))
+    }
+}
+
+/// The text shown for the value of a local.
+fn render(value: &VariableValue) -> &str {
+    match value {
+        VariableValue::Value(value) => value,
+        VariableValue::Moved => "<moved>",
+        VariableValue::Uninitialized => "<uninitialized>",
+    }
+}
+
+/// Splits `borrow_global<T>(address)` into `T` and `address`.
+fn parse_borrow_global(expression: &str) -> Option<(&str, &str)> {
+    let rest = expression.strip_prefix("borrow_global<")?;
+    let (tag, address) = rest.strip_suffix(')')?.rsplit_once(">(")?;
+    Some((tag.trim(), address.trim()))
+}
+
+/// Forwards the debugger's events to the client until the program exits.
+fn forward_events<W: Write>(client: &DebugClient, output: &Output<W>) {
+    while let Some(event) = client.next_event() {
+        let sent = match event {
+            DebugEvent::Stopped { reason, .. } => {
+                let reason = match reason {
+                    StopReason::Entry => "entry",
+                    StopReason::Breakpoint => "breakpoint",
+                    StopReason::Step => "step",
+                    StopReason::Pause => "pause",
+                };
+                output.event(
+                    "stopped",
+                    json!({ "reason": reason, "threadId": THREAD_ID, "allThreadsStopped": true }),
+                )
+            }
+            DebugEvent::Exited { error } => {
+                let exit_code = match error {
+                    Some(error) => {
+                        let _ = output.event(
+                            "output",
+                            json!({ "category": "stderr", "output": format!("{}\n", error) }),
+                        );
+                        1
+                    }
+                    None => 0,
+                };
+                let _ = output.event("exited", json!({ "exitCode": exit_code }));
+                let _ = output.event("terminated", json!({}));
+                return;
+            }
+        };
+        if sent.is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads a message framed by a `Content-Length` header. Returns `None` at the end of the input.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(length) = header.strip_prefix("Content-Length:") {
+            content_length = Some(length.trim().parse::<usize>()?);
+        }
+    }
+    let Some(content_length) = content_length else {
+        bail!("Missing Content-Length header");
+    };
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
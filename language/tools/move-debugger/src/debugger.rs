@@ -0,0 +1,490 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A source-level debugger driving the Move VM through its debug hook.
+//!
+//! The debugger is split into two halves created together by [`new_debugger`]: the [`Debugger`],
+//! attached to the sessions of the program being debugged, and the [`DebugClient`], used from
+//! another thread to set breakpoints, step through the program and inspect it while it is
+//! stopped.
+
+use crate::source_index::{CodeLocation, SourceIndex, SourceLine};
+use anyhow::{anyhow, bail, Result};
+use move_binary_format::file_format::{Bytecode, FunctionDefinitionIndex, LocalIndex};
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{ModuleId, TypeTag},
+};
+use move_vm_runtime::debugger::{DebugHook, DebugState, DebuggerExtension, StackFrame};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+};
+
+/// Why the program stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    /// The program is about to execute its first instruction with source.
+    Entry,
+    Breakpoint,
+    /// A step requested by the client completed.
+    Step,
+    /// The client asked the program to pause.
+    Pause,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DebugEvent {
+    /// The program stopped before executing the instruction at `location`.
+    Stopped {
+        reason: StopReason,
+        location: CodeLocation,
+    },
+    /// The program finished, with an error message if it failed.
+    Exited { error: Option<String> },
+}
+
+/// How to continue the program once it stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resume {
+    Continue,
+    /// Run to the next line of the current function, or to its caller if it returns.
+    StepOver,
+    /// Run to the next line, entering functions that are called.
+    StepInto,
+    /// Run until the current function returns.
+    StepOut,
+}
+
+/// A local variable of a stopped frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub value: VariableValue,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VariableValue {
+    /// The rendered value.
+    Value(String),
+    /// The value has been moved out of the local.
+    Moved,
+    /// The local has not been assigned yet.
+    Uninitialized,
+}
+
+enum Request {
+    Resume(Resume),
+    StackTrace(Sender<Vec<StackFrame>>),
+    Locals(usize, Sender<Option<Vec<VariableValue>>>),
+    Global(
+        AccountAddress,
+        TypeTag,
+        Sender<Result<Option<String>, String>>,
+    ),
+}
+
+/// Where the program should stop next, besides breakpoints and pause requests.
+enum Mode {
+    Run,
+    Entry,
+    StepInto {
+        depth: usize,
+        from: Option<SourceLine>,
+    },
+    StepOver {
+        depth: usize,
+        from: Option<SourceLine>,
+    },
+    StepOut {
+        depth: usize,
+    },
+}
+
+struct Shared {
+    index: SourceIndex,
+    /// Source file -> instructions to stop at for the breakpoints set in it
+    breakpoints: Mutex<BTreeMap<PathBuf, BTreeSet<CodeLocation>>>,
+    pause_requested: AtomicBool,
+    paused: AtomicBool,
+    /// Set once the client is done debugging, to let the program run to completion.
+    detached: AtomicBool,
+}
+
+struct Control {
+    mode: Mode,
+    requests: Receiver<Request>,
+    events: Sender<DebugEvent>,
+    moves: MoveTracker,
+}
+
+/// Tracks the locals moved out of in each frame of the call stack, to tell them apart from locals
+/// which are not assigned yet: the VM leaves both invalid.
+#[derive(Default)]
+struct MoveTracker {
+    /// For each frame, from the bottom of the call stack: its function and its moved locals
+    frames: Vec<(FrameFunction, BTreeSet<LocalIndex>)>,
+    /// The instruction executed last and the depth it was executed at, applied once it is done
+    last: Option<(usize, Bytecode)>,
+}
+
+type FrameFunction = (Option<ModuleId>, FunctionDefinitionIndex);
+
+/// The half of the debugger attached to the program. Clones share their state, so a clone can be
+/// attached to each session the program creates.
+#[derive(Clone)]
+pub struct Debugger {
+    shared: Arc<Shared>,
+    control: Arc<Mutex<Control>>,
+}
+
+/// The half of the debugger controlling the program.
+pub struct DebugClient {
+    shared: Arc<Shared>,
+    requests: Sender<Request>,
+    events: Mutex<Receiver<DebugEvent>>,
+}
+
+/// Creates a debugger for a program whose code is indexed in `index`.
+pub fn new_debugger(index: SourceIndex, stop_on_entry: bool) -> (Debugger, DebugClient) {
+    let shared = Arc::new(Shared {
+        index,
+        breakpoints: Mutex::new(BTreeMap::new()),
+        pause_requested: AtomicBool::new(false),
+        paused: AtomicBool::new(false),
+        detached: AtomicBool::new(false),
+    });
+    let (request_sender, request_receiver) = channel();
+    let (event_sender, event_receiver) = channel();
+
+    let debugger = Debugger {
+        shared: shared.clone(),
+        control: Arc::new(Mutex::new(Control {
+            mode: if stop_on_entry {
+                Mode::Entry
+            } else {
+                Mode::Run
+            },
+            requests: request_receiver,
+            events: event_sender,
+            moves: MoveTracker::default(),
+        })),
+    };
+    let client = DebugClient {
+        shared,
+        requests: request_sender,
+        events: Mutex::new(event_receiver),
+    };
+    (debugger, client)
+}
+
+impl Debugger {
+    /// The extension attaching this debugger to a session.
+    pub fn extension(&self) -> DebuggerExtension {
+        DebuggerExtension::new(self.clone())
+    }
+
+    /// Reports to the client that the program finished.
+    pub fn exit(&self, result: Result<()>) {
+        let error = result.err().map(|err| format!("{:#}", err));
+        // The client may be gone already, in which case nobody is interested.
+        let _ = self
+            .control
+            .lock()
+            .unwrap()
+            .events
+            .send(DebugEvent::Exited { error });
+    }
+
+    fn stop_reason(&self, mode: &Mode, state: &DebugState) -> Option<StopReason> {
+        if self.shared.detached.load(Ordering::SeqCst) {
+            return None;
+        }
+        if self.shared.pause_requested.swap(false, Ordering::SeqCst) {
+            return Some(StopReason::Pause);
+        }
+
+        let index = &self.shared.index;
+        let (module, function, pc) = (state.module(), state.function_index(), state.pc());
+        let on_line = |from: &Option<SourceLine>| {
+            from.as_ref()
+                .map_or(false, |line| index.same_line(module, function, pc, line))
+        };
+        let has_source = || index.has_source(module, function);
+        let depth = state.depth();
+        let reason = match mode {
+            Mode::Run => None,
+            Mode::Entry => has_source().then_some(StopReason::Entry),
+            Mode::StepInto {
+                depth: from_depth,
+                from,
+            } => (has_source() && (depth != *from_depth || !on_line(from)))
+                .then_some(StopReason::Step),
+            Mode::StepOver {
+                depth: from_depth,
+                from,
+            } => (has_source()
+                && (depth < *from_depth || (depth == *from_depth && !on_line(from))))
+            .then_some(StopReason::Step),
+            Mode::StepOut { depth: from_depth } => {
+                (has_source() && depth < *from_depth).then_some(StopReason::Step)
+            }
+        };
+        if reason.is_some() {
+            return reason;
+        }
+
+        let breakpoints = self.shared.breakpoints.lock().unwrap();
+        if breakpoints.values().all(BTreeSet::is_empty) {
+            return None;
+        }
+        let location = CodeLocation {
+            module: module.cloned(),
+            function,
+            pc,
+        };
+        breakpoints
+            .values()
+            .any(|locations| locations.contains(&location))
+            .then_some(StopReason::Breakpoint)
+    }
+
+    /// Serves the client's requests while the program is stopped, until it is resumed. Returns
+    /// where to stop next.
+    fn serve(
+        &self,
+        requests: &Receiver<Request>,
+        moves: &MoveTracker,
+        state: &mut DebugState,
+    ) -> Mode {
+        let depth = state.depth();
+        let from =
+            self.shared
+                .index
+                .source_line(state.module(), state.function_index(), state.pc());
+        while let Ok(request) = requests.recv() {
+            match request {
+                Request::Resume(Resume::Continue) => return Mode::Run,
+                Request::Resume(Resume::StepInto) => return Mode::StepInto { depth, from },
+                Request::Resume(Resume::StepOver) => return Mode::StepOver { depth, from },
+                Request::Resume(Resume::StepOut) => return Mode::StepOut { depth },
+                Request::StackTrace(reply) => {
+                    let _ = reply.send(state.stack_trace());
+                }
+                Request::Locals(frame, reply) => {
+                    let _ = reply.send(moves.locals(state, frame));
+                }
+                Request::Global(address, tag, reply) => {
+                    let _ = reply.send(state.global(address, &tag).map_err(|e| e.to_string()));
+                }
+            }
+        }
+        // The client is gone, let the program run to completion.
+        Mode::Run
+    }
+}
+
+impl MoveTracker {
+    /// Accounts for the instruction executed last, and for the frames pushed or popped since.
+    fn on_instruction(&mut self, state: &DebugState) {
+        if let Some((depth, instruction)) = self.last.take() {
+            match instruction {
+                Bytecode::MoveLoc(idx) => {
+                    if let Some((_, moved)) = self.frames.get_mut(depth - 1) {
+                        moved.insert(idx);
+                    }
+                }
+                Bytecode::StLoc(idx) => {
+                    if let Some((_, moved)) = self.frames.get_mut(depth - 1) {
+                        moved.remove(&idx);
+                    }
+                }
+                Bytecode::Ret => self.frames.truncate(depth - 1),
+                Bytecode::Abort => self.frames.clear(),
+                _ => (),
+            }
+        }
+
+        let depth = state.depth();
+        let function = (state.module().cloned(), state.function_index());
+        self.frames.truncate(depth);
+        // A frame running another function was pushed in place of the one tracked, e.g. by
+        // another session after an execution error.
+        if self.frames.len() == depth && self.frames[depth - 1].0 != function {
+            self.frames.truncate(depth - 1);
+        }
+        while self.frames.len() < depth {
+            let frame_function = if self.frames.len() == depth - 1 {
+                function.clone()
+            } else {
+                (None, FunctionDefinitionIndex(0))
+            };
+            self.frames.push((frame_function, BTreeSet::new()));
+        }
+        self.last = Some((depth, state.instruction().clone()));
+    }
+
+    /// The values of the locals of the given frame, numbered from the top of the call stack.
+    fn locals(&self, state: &DebugState, frame: usize) -> Option<Vec<VariableValue>> {
+        let moved = state
+            .depth()
+            .checked_sub(frame + 1)
+            .and_then(|idx| self.frames.get(idx))
+            .map(|(_, moved)| moved);
+        let values = state.locals(frame)?;
+        Some(
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(idx, value)| match value {
+                    Some(value) => VariableValue::Value(value),
+                    None if moved.map_or(false, |moved| moved.contains(&(idx as LocalIndex))) => {
+                        VariableValue::Moved
+                    }
+                    None => VariableValue::Uninitialized,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl DebugHook for Debugger {
+    fn on_instruction(&mut self, state: &mut DebugState) {
+        let mut control = self.control.lock().unwrap();
+        control.moves.on_instruction(state);
+        let Some(reason) = self.stop_reason(&control.mode, state) else {
+            return;
+        };
+
+        let location = CodeLocation {
+            module: state.module().cloned(),
+            function: state.function_index(),
+            pc: state.pc(),
+        };
+        self.shared.paused.store(true, Ordering::SeqCst);
+        let stopped = control
+            .events
+            .send(DebugEvent::Stopped { reason, location })
+            .is_ok();
+        let mode = if stopped {
+            self.serve(&control.requests, &control.moves, state)
+        } else {
+            Mode::Run
+        };
+        control.mode = mode;
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+}
+
+impl DebugClient {
+    pub fn index(&self) -> &SourceIndex {
+        &self.shared.index
+    }
+
+    /// Replaces the breakpoints in the source file at `path` with breakpoints on `lines`. Returns
+    /// for each line whether there is code to stop at on it.
+    pub fn set_breakpoints(&self, path: &Path, lines: &[u32]) -> Vec<bool> {
+        let mut locations = BTreeSet::new();
+        let verified = lines
+            .iter()
+            .map(|line| {
+                let line_locations = self.shared.index.breakpoint_locations(path, *line);
+                let verified = !line_locations.is_empty();
+                locations.extend(line_locations);
+                verified
+            })
+            .collect();
+        self.shared
+            .breakpoints
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), locations);
+        verified
+    }
+
+    /// Asks the program to stop before the next instruction it executes.
+    pub fn pause(&self) {
+        self.shared.pause_requested.store(true, Ordering::SeqCst)
+    }
+
+    /// Stops debugging. The program runs to completion without stopping again.
+    pub fn detach(&self) {
+        self.shared.detached.store(true, Ordering::SeqCst);
+        // The program may have exited already.
+        let _ = self.resume(Resume::Continue);
+    }
+
+    pub fn resume(&self, resume: Resume) -> Result<()> {
+        self.ensure_paused()?;
+        self.requests
+            .send(Request::Resume(resume))
+            .map_err(|_| anyhow!("The program has exited"))
+    }
+
+    /// Waits for the next event of the program. Returns `None` once there are no more events.
+    pub fn next_event(&self) -> Option<DebugEvent> {
+        self.events.lock().unwrap().recv().ok()
+    }
+
+    /// The call stack of the stopped program, innermost frame first.
+    pub fn stack_trace(&self) -> Result<Vec<StackFrame>> {
+        self.request(Request::StackTrace)
+    }
+
+    /// The locals of the given frame of the stopped program. Locals the compiler optimized away
+    /// have no value at runtime and are not listed.
+    pub fn locals(&self, frame: usize) -> Result<Vec<Variable>> {
+        let stack = self.stack_trace()?;
+        let Some(stack_frame) = stack.get(frame) else {
+            bail!("No frame {} on the call stack", frame);
+        };
+        let values = self
+            .request(|reply| Request::Locals(frame, reply))?
+            .unwrap_or_default();
+        let names = self
+            .shared
+            .index
+            .local_names(stack_frame.module.as_ref(), stack_frame.function_index)
+            .unwrap_or_default();
+        Ok(values
+            .into_iter()
+            .enumerate()
+            .map(|(idx, value)| Variable {
+                name: names
+                    .get(idx)
+                    .cloned()
+                    .unwrap_or_else(|| format!("local#{}", idx)),
+                value,
+            })
+            .collect())
+    }
+
+    /// The resource of type `tag` stored under `address` in the stopped program, if there is one.
+    pub fn global(&self, address: AccountAddress, tag: TypeTag) -> Result<Option<String>> {
+        self.request(|reply| Request::Global(address, tag, reply))?
+            .map_err(|err| anyhow!("Unable to load resource: {}", err))
+    }
+
+    fn request<T>(&self, request: impl FnOnce(Sender<T>) -> Request) -> Result<T> {
+        self.ensure_paused()?;
+        let (reply, response) = channel();
+        self.requests
+            .send(request(reply))
+            .map_err(|_| anyhow!("The program has exited"))?;
+        response
+            .recv()
+            .map_err(|_| anyhow!("The program has exited"))
+    }
+
+    fn ensure_paused(&self) -> Result<()> {
+        if !self.shared.paused.load(Ordering::SeqCst) {
+            bail!("The program is not stopped");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! A source-level debugger for Move programs executed by the Move VM, exposed through the Debug
+//! Adapter Protocol.
+
+pub mod dap;
+pub mod debugger;
+pub mod source_index;
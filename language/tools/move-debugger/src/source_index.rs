@@ -0,0 +1,246 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mapping between bytecode offsets and source lines of the code being debugged.
+
+use anyhow::{bail, Result};
+use codespan::Files;
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, CodeUnit, CompiledScript, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::language_storage::ModuleId;
+use move_package::compilation::compiled_package::CompiledPackage;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The location of an instruction in loaded code.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CodeLocation {
+    /// The module of the function, or `None` for the script being debugged.
+    pub module: Option<ModuleId>,
+    pub function: FunctionDefinitionIndex,
+    pub pc: CodeOffset,
+}
+
+/// A line in a source file. Lines are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLine {
+    pub path: PathBuf,
+    pub line: u32,
+}
+
+struct FunctionSource {
+    /// Line of each instruction
+    lines: Vec<u32>,
+    /// Names of the parameters and locals, by local index
+    local_names: Vec<String>,
+}
+
+struct UnitSource {
+    path: PathBuf,
+    functions: BTreeMap<FunctionDefinitionIndex, FunctionSource>,
+}
+
+/// Maps the instructions of the modules and the script being debugged to source lines.
+#[derive(Default)]
+pub struct SourceIndex {
+    modules: BTreeMap<ModuleId, UnitSource>,
+    script: Option<UnitSource>,
+}
+
+impl SourceIndex {
+    /// Indexes all modules of `package` and its dependencies.
+    pub fn from_package(package: &CompiledPackage) -> Result<Self> {
+        let mut index = Self::default();
+        for unit in package.all_compiled_units_with_source() {
+            if let CompiledUnitEnum::Module(module) = &unit.unit {
+                index.add_module(&module.module, &module.source_map, &unit.source_path)?;
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn add_module(
+        &mut self,
+        module: &CompiledModule,
+        source_map: &SourceMap,
+        source_path: &Path,
+    ) -> Result<()> {
+        let code_units = module
+            .function_defs()
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, def)| {
+                def.code
+                    .as_ref()
+                    .map(|code| (FunctionDefinitionIndex(idx as u16), code))
+            });
+        let unit = UnitSource::new(code_units, source_map, source_path)?;
+        self.modules.insert(module.self_id(), unit);
+        Ok(())
+    }
+
+    /// Sets the script being debugged.
+    pub fn set_script(
+        &mut self,
+        script: &CompiledScript,
+        source_map: &SourceMap,
+        source_path: &Path,
+    ) -> Result<()> {
+        let code_units = [(CompiledScript::MAIN_INDEX, &script.code)];
+        self.script = Some(UnitSource::new(code_units, source_map, source_path)?);
+        Ok(())
+    }
+
+    /// The source line of the instruction at `pc`, if the function is indexed.
+    pub fn source_line(
+        &self,
+        module: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+        pc: CodeOffset,
+    ) -> Option<SourceLine> {
+        let unit = self.unit(module)?;
+        let line = *unit.functions.get(&function)?.lines.get(pc as usize)?;
+        Some(SourceLine {
+            path: unit.path.clone(),
+            line,
+        })
+    }
+
+    /// Whether the instruction at `pc` is on `line`. Instructions of functions that aren't indexed
+    /// are on no line.
+    pub(crate) fn same_line(
+        &self,
+        module: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+        pc: CodeOffset,
+        line: &SourceLine,
+    ) -> bool {
+        self.unit(module).map_or(false, |unit| {
+            unit.path == line.path
+                && unit
+                    .functions
+                    .get(&function)
+                    .and_then(|f| f.lines.get(pc as usize))
+                    == Some(&line.line)
+        })
+    }
+
+    /// Whether the function is indexed, i.e. it can be stepped through at the source level.
+    pub(crate) fn has_source(
+        &self,
+        module: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+    ) -> bool {
+        self.unit(module)
+            .map_or(false, |unit| unit.functions.contains_key(&function))
+    }
+
+    /// The instructions at which execution stops for a breakpoint on `line` of the file at
+    /// `path`: the first instruction on that line of each function that has code on it.
+    pub fn breakpoint_locations(&self, path: &Path, line: u32) -> Vec<CodeLocation> {
+        let units = self
+            .modules
+            .iter()
+            .map(|(id, unit)| (Some(id), unit))
+            .chain(self.script.iter().map(|unit| (None, unit)))
+            .filter(|(_, unit)| same_file(&unit.path, path));
+
+        let mut locations = vec![];
+        for (module, unit) in units {
+            for (function, source) in &unit.functions {
+                if let Some(pc) = source.lines.iter().position(|l| *l == line) {
+                    locations.push(CodeLocation {
+                        module: module.cloned(),
+                        function: *function,
+                        pc: pc as CodeOffset,
+                    });
+                }
+            }
+        }
+        locations
+    }
+
+    /// Names of the parameters and locals of a function, by local index, as they appear in the
+    /// source. Temporaries introduced by the compiler are named as such.
+    pub fn local_names(
+        &self,
+        module: Option<&ModuleId>,
+        function: FunctionDefinitionIndex,
+    ) -> Option<&[String]> {
+        Some(&self.unit(module)?.functions.get(&function)?.local_names)
+    }
+
+    fn unit(&self, module: Option<&ModuleId>) -> Option<&UnitSource> {
+        match module {
+            Some(id) => self.modules.get(id),
+            None => self.script.as_ref(),
+        }
+    }
+}
+
+impl UnitSource {
+    fn new<'a>(
+        code_units: impl IntoIterator<Item = (FunctionDefinitionIndex, &'a CodeUnit)>,
+        source_map: &SourceMap,
+        source_path: &Path,
+    ) -> Result<Self> {
+        let file_contents = fs::read_to_string(source_path)?;
+        if !source_map.check(&file_contents) {
+            bail!(
+                "File contents of {:?} out of sync with source map",
+                source_path
+            );
+        }
+        let mut files = Files::new();
+        let file_id = files.add(source_path.as_os_str().to_os_string(), file_contents);
+
+        let mut functions = BTreeMap::new();
+        for (function, code_unit) in code_units {
+            let mut lines = vec![];
+            for offset in 0..code_unit.code.len() {
+                let loc = source_map.get_code_location(function, offset as CodeOffset)?;
+                lines.push(files.location(file_id, loc.start())?.line.0 + 1);
+            }
+
+            let function_source_map = source_map.get_function_source_map(function)?;
+            let local_names = function_source_map
+                .parameters
+                .iter()
+                .chain(&function_source_map.locals)
+                .map(|(name, _)| source_name(name))
+                .collect();
+
+            functions.insert(function, FunctionSource { lines, local_names });
+        }
+
+        Ok(Self {
+            path: source_path.to_path_buf(),
+            functions,
+        })
+    }
+}
+
+/// Strips the suffixes the compiler adds to make local names unique, e.g. `x#1#0` is `x`.
+/// Temporaries (e.g. `%#1`) keep their full name.
+fn source_name(name: &str) -> String {
+    match name.split_once('#') {
+        Some((name, _)) if !name.is_empty() && !name.starts_with('%') => name.to_string(),
+        _ => name.to_string(),
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (fs::canonicalize(a), fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
@@ -0,0 +1,142 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_compiler::compiled_unit::CompiledUnitEnum;
+use move_core_types::account_address::AccountAddress;
+use move_debugger::{
+    debugger::{new_debugger, DebugEvent, Resume, StopReason, Variable, VariableValue},
+    source_index::SourceIndex,
+};
+use move_package::BuildConfig;
+use move_vm_runtime::{move_vm::MoveVM, native_extensions::NativeContextExtensions};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::{fs, path::Path, thread};
+use tempfile::tempdir;
+
+const PACKAGE: &str = "tests/packages/Counter";
+
+/// The 1-based line of the first line in the file at `path` containing `text`.
+fn line_of(path: &Path, text: &str) -> u32 {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .position(|line| line.contains(text))
+        .unwrap() as u32
+        + 1
+}
+
+#[test]
+fn stop_at_breakpoint_inspect_and_step() {
+    let install_dir = tempdir().unwrap();
+    let package = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package(Path::new(PACKAGE), &mut Vec::new())
+    .unwrap();
+
+    let mut index = SourceIndex::from_package(&package).unwrap();
+    let script_unit = package.scripts().next().unwrap();
+    let CompiledUnitEnum::Script(script) = &script_unit.unit else {
+        unreachable!()
+    };
+    index
+        .set_script(&script.script, &script.source_map, &script_unit.source_path)
+        .unwrap();
+    let script_bytes = script_unit.unit.serialize(None);
+
+    let mut storage = InMemoryStorage::new();
+    {
+        let vm = MoveVM::new(vec![]).unwrap();
+        let mut session = vm.new_session(&storage);
+        for module in package.root_modules() {
+            session
+                .publish_module(
+                    module.unit.serialize(None),
+                    AccountAddress::TWO,
+                    &mut UnmeteredGasMeter,
+                )
+                .unwrap();
+        }
+        let (changeset, _) = session.finish().unwrap();
+        storage.apply(changeset).unwrap();
+    }
+
+    let module_path = Path::new(PACKAGE).join("sources").join("counter.move");
+    let breakpoint_line = line_of(&module_path, "let y = x * 2;");
+    let (debugger, client) = new_debugger(index, false);
+    assert_eq!(
+        client.set_breakpoints(&module_path, &[breakpoint_line, 1]),
+        vec![true, false]
+    );
+
+    let program = thread::spawn(move || {
+        let vm = MoveVM::new(vec![]).unwrap();
+        let mut extensions = NativeContextExtensions::default();
+        extensions.add(debugger.extension());
+        let mut session = vm.new_session_with_extensions(&storage, extensions);
+        let result = session.execute_script(
+            script_bytes,
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            &mut UnmeteredGasMeter,
+        );
+        debugger.exit(result.map(|_| ()).map_err(|err| err.into()));
+    });
+
+    let Some(DebugEvent::Stopped { reason, location }) = client.next_event() else {
+        panic!("program didn't stop at the breakpoint")
+    };
+    assert_eq!(reason, StopReason::Breakpoint);
+    assert_eq!(
+        client
+            .index()
+            .source_line(location.module.as_ref(), location.function, location.pc)
+            .unwrap()
+            .line,
+        breakpoint_line
+    );
+
+    let stack = client.stack_trace().unwrap();
+    assert_eq!(stack.len(), 2);
+    assert_eq!(stack[0].function, "double");
+    assert_eq!(stack[1].module, None);
+    assert!(client.locals(0).unwrap().contains(&Variable {
+        name: "x".to_string(),
+        value: VariableValue::Value("21".to_string()),
+    }));
+    // The compiler folds the constant `a` into the call, so it has no value to show.
+    assert!(client
+        .locals(1)
+        .unwrap()
+        .iter()
+        .all(|variable| variable.name != "a"));
+
+    // Stepping over moves to the next line of `double`.
+    client.resume(Resume::StepOver).unwrap();
+    let Some(DebugEvent::Stopped { reason, location }) = client.next_event() else {
+        panic!("program didn't stop after stepping")
+    };
+    assert_eq!(reason, StopReason::Step);
+    assert_eq!(
+        client
+            .index()
+            .source_line(location.module.as_ref(), location.function, location.pc)
+            .unwrap()
+            .line,
+        breakpoint_line + 1
+    );
+    // `x` was moved out of to compute `y`.
+    assert!(client.locals(0).unwrap().contains(&Variable {
+        name: "x".to_string(),
+        value: VariableValue::Moved,
+    }));
+
+    client.resume(Resume::Continue).unwrap();
+    assert_eq!(
+        client.next_event(),
+        Some(DebugEvent::Exited { error: None })
+    );
+    program.join().unwrap();
+}
@@ -0,0 +1,6 @@
+module counter::counter {
+    public fun double(x: u64): u64 {
+        let y = x * 2;
+        y
+    }
+}
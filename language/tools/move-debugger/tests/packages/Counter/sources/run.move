@@ -0,0 +1,9 @@
+script {
+    use counter::counter;
+
+    fun run() {
+        let a = 21;
+        let b = counter::double(a);
+        assert!(b == 42, 0);
+    }
+}
@@ -15,14 +15,17 @@ use anyhow::{ensure, Result};
 use colored::Colorize;
 use itertools::{Either, Itertools};
 use move_abigen::{Abigen, AbigenOptions};
-use move_binary_format::file_format::{CompiledModule, CompiledScript};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CompiledModule, CompiledScript},
+};
 use move_bytecode_source_map::utils::source_map_from_file;
 use move_bytecode_utils::Modules;
 use move_command_line_common::{
     env::get_bytecode_version_from_env,
     files::{
-        extension_equals, find_filenames, MOVE_COMPILED_EXTENSION, MOVE_EXTENSION,
-        SOURCE_MAP_EXTENSION,
+        extension_equals, find_filenames, MOVE_BUNDLE_EXTENSION, MOVE_COMPILED_EXTENSION,
+        MOVE_EXTENSION, SOURCE_MAP_EXTENSION,
     },
 };
 use move_compiler::{
@@ -30,9 +33,12 @@ use move_compiler::{
         self, AnnotatedCompiledUnit, CompiledUnit, NamedCompiledModule, NamedCompiledScript,
     },
     diagnostics::FilesSourceText,
+    linters::LintLevel,
     shared::{Flags, NamedAddressMap, NumericalAddress, PackagePaths},
+    to_bytecode::size_optimize::{self, OptLevel},
     Compiler,
 };
+use move_core_types::language_storage::ModuleId;
 use move_docgen::{Docgen, DocgenOptions};
 use move_model::{model::GlobalEnv, options::ModelBuilderOptions, run_model_builder_with_options};
 use move_symbol_pool::Symbol;
@@ -90,6 +96,9 @@ pub struct CompiledPackage {
     /// filename -> json bytes for ScriptABI. Can then be used to generate transaction builders in
     /// various languages.
     pub compiled_abis: Option<Vec<(String, Vec<u8>)>>,
+    /// BCS encoded bundle of the root package modules, in dependency order. The encoding matches
+    /// `ModuleBundle` as consumed by `Mvm::publish_module_bundle`.
+    pub compiled_bundle: Option<Vec<u8>>,
 }
 
 /// Represents a compiled package that has been saved to disk. This holds only the minimal metadata
@@ -195,12 +204,24 @@ impl OnDiskCompiledPackage {
             None
         };
 
+        let bundle_path = self
+            .root_path
+            .join(CompiledPackageLayout::CompiledBundles.path())
+            .join(root_name.as_str())
+            .with_extension(MOVE_BUNDLE_EXTENSION);
+        let compiled_bundle = if bundle_path.is_file() {
+            Some(std::fs::read(&bundle_path)?)
+        } else {
+            None
+        };
+
         Ok(CompiledPackage {
             compiled_package_info: self.package.compiled_package_info.clone(),
             root_compiled_units,
             deps_compiled_units,
             compiled_docs,
             compiled_abis,
+            compiled_bundle,
         })
     }
 
@@ -579,7 +600,13 @@ impl CompiledPackage {
             Flags::testing()
         } else {
             Flags::empty()
-        };
+        }
+        .set_lint_level(
+            resolution_graph
+                .build_options
+                .lint
+                .unwrap_or(LintLevel::None),
+        );
         // Partition deps_package according whether src is available
         let (src_deps, bytecode_deps): (Vec<_>, Vec<_>) = deps_package_paths
             .clone()
@@ -620,6 +647,14 @@ impl CompiledPackage {
                 deps_compiled_units.push((package_name, unit))
             }
         }
+        // unit tests call private test functions which must not be optimized away
+        if !resolution_graph.build_options.test_mode {
+            let opt_level = resolution_graph
+                .build_options
+                .opt_level
+                .unwrap_or(OptLevel::Default);
+            Self::optimize_units(w, opt_level, &mut root_compiled_units)?;
+        }
         let bytecode_version = get_bytecode_version_from_env(bytecode_version);
 
         let mut compiled_docs = None;
@@ -652,6 +687,12 @@ impl CompiledPackage {
             }
         };
 
+        let compiled_bundle = if resolution_graph.build_options.generate_bundle {
+            Some(Self::build_bundle(bytecode_version, &root_compiled_units)?)
+        } else {
+            None
+        };
+
         let compiled_package = CompiledPackage {
             compiled_package_info: CompiledPackageInfo {
                 package_name: resolved_package.source_package.package.name,
@@ -663,6 +704,7 @@ impl CompiledPackage {
             deps_compiled_units,
             compiled_docs,
             compiled_abis,
+            compiled_bundle,
         };
 
         compiled_package.save_to_disk(
@@ -789,6 +831,16 @@ impl CompiledPackage {
             }
         }
 
+        if let Some(bundle) = &self.compiled_bundle {
+            on_disk_package.save_under(
+                CompiledPackageLayout::CompiledBundles
+                    .path()
+                    .join(root_package.as_str())
+                    .with_extension(MOVE_BUNDLE_EXTENSION),
+                bundle,
+            )?;
+        }
+
         on_disk_package.save_under(
             CompiledPackageLayout::BuildInfo.path(),
             serde_yaml::to_string(&on_disk_package.package)?.as_bytes(),
@@ -797,6 +849,90 @@ impl CompiledPackage {
         Ok(on_disk_package)
     }
 
+    /// Runs the module-level optimizations of `opt_level` on the modules of `compiled_units`,
+    /// reporting the bytes saved for each module.
+    fn optimize_units<W: Write>(
+        w: &mut W,
+        opt_level: OptLevel,
+        compiled_units: &mut [CompiledUnitWithSource],
+    ) -> Result<()> {
+        if opt_level == OptLevel::Default {
+            return Ok(());
+        }
+        for compiled_unit in compiled_units {
+            if let CompiledUnit::Module(module) = &mut compiled_unit.unit {
+                let report = size_optimize::module(module, opt_level)?;
+                writeln!(
+                    w,
+                    "{} {}: {} -> {} bytes (saved {})",
+                    "OPTIMIZED".bold().green(),
+                    module.name,
+                    report.original_size,
+                    report.optimized_size,
+                    report.savings()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Serializes the modules of the root package into a single bundle. Modules are ordered so
+    /// that every module comes after the root modules it depends on, which allows the bundle to be
+    /// published in one go.
+    fn build_bundle(
+        bytecode_version: Option<u32>,
+        compiled_units: &[CompiledUnitWithSource],
+    ) -> Result<Vec<u8>> {
+        let modules: BTreeMap<_, _> = compiled_units
+            .iter()
+            .filter_map(|unit| match &unit.unit {
+                CompiledUnit::Module(named) => Some((named.module.self_id(), &unit.unit)),
+                CompiledUnit::Script(_) => None,
+            })
+            .collect();
+
+        // Depth-first topological sort restricted to the root package modules; dependencies
+        // outside of the root package are expected to be already published.
+        fn visit<'a>(
+            id: &'a ModuleId,
+            modules: &'a BTreeMap<ModuleId, &CompiledUnit>,
+            visiting: &mut BTreeSet<&'a ModuleId>,
+            ordered: &mut Vec<&'a ModuleId>,
+        ) -> Result<()> {
+            if ordered.contains(&id) {
+                return Ok(());
+            }
+            ensure!(
+                visiting.insert(id),
+                "Circular dependency detected for module {}",
+                id
+            );
+            if let Some(CompiledUnit::Module(named)) = modules.get(id) {
+                for dep in named.module.immediate_dependencies() {
+                    if let Some((dep_id, _)) = modules.get_key_value(&dep) {
+                        visit(dep_id, modules, visiting, ordered)?;
+                    }
+                }
+            }
+            visiting.remove(id);
+            ordered.push(id);
+            Ok(())
+        }
+
+        let mut ordered = vec![];
+        let mut visiting = BTreeSet::new();
+        for id in modules.keys() {
+            visit(id, &modules, &mut visiting, &mut ordered)?;
+        }
+
+        // `ModuleBundle { modules: Vec<Vec<u8>> }` has the same BCS encoding as its only field.
+        let bundle = ordered
+            .into_iter()
+            .map(|id| modules[id].serialize(bytecode_version))
+            .collect::<Vec<_>>();
+        Ok(bcs::to_bytes(&bundle)?)
+    }
+
     fn build_abis(
         bytecode_version: Option<u32>,
         model: &GlobalEnv,
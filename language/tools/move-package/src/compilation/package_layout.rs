@@ -16,6 +16,7 @@ pub enum CompiledPackageLayout {
     CompiledScripts,
     CompiledDocs,
     CompiledABIs,
+    CompiledBundles,
 }
 
 impl CompiledPackageLayout {
@@ -31,6 +32,7 @@ impl CompiledPackageLayout {
             Self::CompiledScripts => "bytecode_scripts",
             Self::CompiledDocs => "docs",
             Self::CompiledABIs => "abis",
+            Self::CompiledBundles => "bundles",
         };
         Path::new(path)
     }
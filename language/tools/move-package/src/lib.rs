@@ -9,8 +9,9 @@ pub mod package_hooks;
 pub mod resolution;
 pub mod source_package;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use clap::*;
+use move_compiler::{linters::LintLevel, to_bytecode::size_optimize::OptLevel};
 use move_core_types::account_address::AccountAddress;
 use move_model::model::GlobalEnv;
 use resolution::{dependency_graph::DependencyGraph, lock_file::LockFile};
@@ -113,6 +114,10 @@ pub struct BuildConfig {
     #[clap(name = "generate-abis", long = "abi", global = true)]
     pub generate_abis: bool,
 
+    /// Generate a module bundle for the root package
+    #[clap(name = "generate-bundle", long = "bundle", global = true)]
+    pub generate_bundle: bool,
+
     /// Installation directory for compiled artifacts. Defaults to current directory.
     #[clap(long = "install-dir", parse(from_os_str), global = true)]
     pub install_dir: Option<PathBuf>,
@@ -143,6 +148,43 @@ pub struct BuildConfig {
     /// Bytecode version to compile move code
     #[clap(long = "bytecode-version", global = true)]
     pub bytecode_version: Option<u32>,
+
+    /// Lints to run: 'none', 'default' or 'all'. Defaults to the 'lint' of the '[build]' section
+    /// of the manifest, or to 'none'
+    #[clap(long = "lint", global = true)]
+    #[serde(default, with = "level")]
+    pub lint: Option<LintLevel>,
+
+    /// Optimization level: 'default' or 'size', which also runs module-level optimizations
+    /// reducing the size of the root package modules. Defaults to the 'opt_level' of the
+    /// '[build]' section of the manifest, or to 'default'
+    #[clap(long = "opt-level", global = true)]
+    #[serde(default, with = "level")]
+    pub opt_level: Option<OptLevel>,
+}
+
+/// (De)serializes the lint and optimization levels by their names
+mod level {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        level: &Option<T>,
+        s: S,
+    ) -> Result<S::Ok, S::Error> {
+        level.as_ref().map(|level| level.to_string()).serialize(s)
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<Option<T>, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(d)?
+            .map(|level| level.parse().map_err(D::Error::custom))
+            .transpose()
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
@@ -249,6 +291,14 @@ impl BuildConfig {
             writer,
         )?;
 
+        if let Some(lock_path) = &self.lock_file {
+            if lock_path.is_file() {
+                dependency_graph
+                    .check_on_chain_pins(&mut std::fs::File::open(lock_path)?)
+                    .context("Checking on-chain dependencies against lock file")?;
+            }
+        }
+
         dependency_graph.write_to_lock(&mut lock)?;
         if let Some(lock_path) = &self.lock_file {
             lock.commit(lock_path)?;
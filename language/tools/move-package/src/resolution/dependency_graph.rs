@@ -16,8 +16,8 @@ use crate::{
     source_package::{
         manifest_parser::parse_dependency,
         parsed_manifest::{
-            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, PackageName,
-            SourceManifest, SubstOrRename, Substitution,
+            CustomDepInfo, Dependency, DependencyKind, GitInfo, NamedAddress, OnChainInfo,
+            PackageName, SourceManifest, SubstOrRename, Substitution,
         },
     },
 };
@@ -25,7 +25,7 @@ use crate::{
 use super::{
     download_and_update_if_remote,
     lock_file::{schema, LockFile},
-    parse_package_manifest,
+    on_chain, parse_package_manifest,
 };
 
 /// A representation of the transitive dependency graph of a Move package.  If successfully created,
@@ -289,7 +289,7 @@ impl DependencyGraph {
             Entry::Vacant(entry) => entry.insert(dep),
 
             // Seeing the same package again, pointing to the same dependency: OK, return early.
-            Entry::Occupied(entry) if entry.get().kind == dep.kind => {
+            Entry::Occupied(entry) if entry.get().kind.is_same_source(&dep.kind) => {
                 return Ok(());
             }
 
@@ -307,8 +307,16 @@ impl DependencyGraph {
         download_and_update_if_remote(dep_name, dep, skip_fetch_latest_git_deps, progress_output)
             .with_context(|| format!("Fetching '{}'", dep_name))?;
 
-        let (manifest, _) = parse_package_manifest(dep, &dep_name, self.root_path.clone())
-            .with_context(|| format!("Parsing manifest for '{}'", dep_name))?;
+        let (manifest, package_path) =
+            parse_package_manifest(dep, &dep_name, self.root_path.clone())
+                .with_context(|| format!("Parsing manifest for '{}'", dep_name))?;
+
+        // Pin the hashes of the fetched modules, so they end up in the lock file.
+        if let DependencyKind::OnChain(info) = &mut dep.kind {
+            if info.module_hashes.is_empty() {
+                info.module_hashes = on_chain::materialized_module_hashes(dep_name, &package_path)?;
+            }
+        }
 
         if dep_name != manifest.package.name {
             bail!(
@@ -324,6 +332,47 @@ impl DependencyGraph {
             .with_context(|| format!("Resolving dependencies for package '{}'", dep_name))
     }
 
+    /// Check that the modules fetched for on-chain dependencies match the hashes pinned for them
+    /// in a previously generated `lock` file. Fails if the modules changed on chain since they
+    /// were last locked.
+    pub fn check_on_chain_pins(&self, lock: &mut impl Read) -> Result<()> {
+        for schema::Dependency { name, source, .. } in schema::Dependencies::read(lock)? {
+            let package = PackageName::from(name.as_str());
+            let Some(Dependency {
+                kind: DependencyKind::OnChain(current),
+                ..
+            }) = self.package_table.get(&package)
+            else {
+                continue;
+            };
+
+            if source.get("on_chain").is_none() {
+                continue;
+            }
+
+            let locked = parse_dependency(package.as_str(), source)
+                .with_context(|| format!("Deserializing dependency {}", package))?;
+            let DependencyKind::OnChain(locked) = locked.kind else {
+                continue;
+            };
+
+            // The dependency was moved, so the previous pins do not apply.
+            if (locked.location, locked.address) != (current.location, current.address) {
+                continue;
+            }
+
+            if !locked.module_hashes.is_empty() && locked.module_hashes != current.module_hashes {
+                bail!(
+                    "Modules of on-chain dependency '{}' changed since they were locked. Remove \
+                     its entry from the lock file to accept the new modules.",
+                    package,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check that every dependency in the graph, excluding the root package, is present in the
     /// package table.
     fn check_consistency(&self) -> Result<()> {
@@ -442,6 +491,32 @@ impl<'a> fmt::Display for DependencyTOML<'a> {
                 write!(f, ", subdir = ")?;
                 f.write_str(&path_escape(subdir)?)?;
             }
+
+            DependencyKind::OnChain(OnChainInfo {
+                location,
+                address,
+                module_hashes,
+                package_name: _,
+            }) => {
+                write!(f, "on_chain = ")?;
+                f.write_str(&str_escape(location.as_str())?)?;
+
+                write!(f, ", address = ")?;
+                f.write_str(&str_escape(&address.to_hex_literal())?)?;
+
+                if !module_hashes.is_empty() {
+                    write!(f, ", module_hashes = {{ ")?;
+                    for (i, (module, hash)) in module_hashes.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        f.write_str(&str_escape(module.as_str())?)?;
+                        write!(f, " = ")?;
+                        f.write_str(&str_escape(hash.as_str())?)?;
+                    }
+                    write!(f, " }}")?;
+                }
+            }
         }
 
         if let Some((major, minor, bugfix)) = version {
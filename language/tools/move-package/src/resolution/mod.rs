@@ -19,8 +19,8 @@ use crate::{
         layout::SourcePackageLayout,
         manifest_parser::{parse_move_manifest_string, parse_source_manifest},
         parsed_manifest::{
-            CustomDepInfo, Dependencies, Dependency, DependencyKind, GitInfo, PackageName,
-            SourceManifest,
+            CustomDepInfo, Dependencies, Dependency, DependencyKind, GitInfo, OnChainInfo,
+            PackageName, SourceManifest,
         },
     },
     BuildConfig,
@@ -29,6 +29,7 @@ use crate::{
 pub mod dependency_graph;
 mod digest;
 pub mod lock_file;
+pub mod on_chain;
 pub mod resolution_graph;
 
 pub fn download_dependency_repos<Progress: Write>(
@@ -96,6 +97,24 @@ fn download_and_update_if_remote<Progress: Write>(
             package_hooks::resolve_custom_dependency(dep_name, node_info)
         }
 
+        kind @ DependencyKind::OnChain(info) => {
+            let package_path = repository_path(kind);
+            if package_path.exists() && skip_fetch_latest_git_deps {
+                return Ok(());
+            }
+
+            writeln!(
+                progress_output,
+                "{} {} at {}",
+                "FETCHING ON-CHAIN DEPENDENCY".bold().green(),
+                info.address.to_hex_literal(),
+                info.location,
+            )?;
+
+            on_chain::materialize(dep_name, info, &package_path)
+                .with_context(|| format!("While fetching on-chain package '{}'", dep_name))
+        }
+
         kind @ DependencyKind::Git(GitInfo {
             git_url,
             git_rev,
@@ -293,6 +312,24 @@ fn repository_path(kind: &DependencyKind) -> PathBuf {
         ]
         .iter()
         .collect(),
+
+        // Fetched packages are of the form <sanitized_location>_<address>_<package>
+        DependencyKind::OnChain(OnChainInfo {
+            location,
+            address,
+            package_name,
+            module_hashes: _,
+        }) => [
+            &*MOVE_HOME,
+            &format!(
+                "{}_{}_{}",
+                url_to_file_name(location.as_str()),
+                address.short_str_lossless(),
+                package_name.as_str(),
+            ),
+        ]
+        .iter()
+        .collect(),
     }
 }
 
@@ -0,0 +1,273 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for dependencies that are only available as bytecode published on chain.
+//!
+//! An on-chain dependency is fetched through an [`OnChainResolver`] and materialized as a package
+//! without sources whose modules live under its `build` directory, so the build plan picks them
+//! up as bytecode dependencies.
+
+use anyhow::{bail, Context, Result};
+use move_binary_format::CompiledModule;
+use move_command_line_common::files::MOVE_COMPILED_EXTENSION;
+use move_compiler::command_line::DEFAULT_OUTPUT_DIR;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::StructTag,
+};
+use move_symbol_pool::Symbol;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::source_package::{
+    layout::SourcePackageLayout,
+    parsed_manifest::{OnChainInfo, PackageName},
+};
+
+/// The JSON-RPC method used by the default resolver to fetch the modules of an account.
+pub const GET_MODULES_RPC_METHOD: &str = "mvm_getModules";
+
+/// Directory below `build/<package>` holding the fetched modules.
+const BYTECODE_MODULES_DIR: &str = "bytecode_modules";
+
+/// A source of published modules for on-chain dependencies. An instance can be registered
+/// globally to replace the default resolver.
+pub trait OnChainResolver {
+    /// Returns the serialized modules published under `address` at `location`.
+    fn fetch_modules(&self, location: &str, address: AccountAddress) -> Result<Vec<Vec<u8>>>;
+}
+
+static RESOLVER: Lazy<Mutex<Box<dyn OnChainResolver + Send + Sync>>> =
+    Lazy::new(|| Mutex::new(Box::new(DefaultResolver)));
+
+/// Registers the resolver used to fetch on-chain dependencies for the process in which the
+/// package system is used.
+pub fn register_on_chain_resolver(resolver: Box<dyn OnChainResolver + Send + Sync>) {
+    *RESOLVER.lock().unwrap() = resolver
+}
+
+/// The default resolver. `http://` and `https://` locations are queried over JSON-RPC, anything
+/// else (optionally prefixed with `file://`) is read as a [`StorageSnapshot`].
+pub struct DefaultResolver;
+
+impl OnChainResolver for DefaultResolver {
+    fn fetch_modules(&self, location: &str, address: AccountAddress) -> Result<Vec<Vec<u8>>> {
+        if location.starts_with("http://") || location.starts_with("https://") {
+            fetch_modules_over_rpc(location, address)
+        } else {
+            let path = location.strip_prefix("file://").unwrap_or(location);
+            StorageSnapshot::read_from_file(Path::new(path))?.modules(address)
+        }
+    }
+}
+
+/// Queries the `mvm_getModules` method of a JSON-RPC node, which returns the hex encoded modules
+/// published under an address.
+fn fetch_modules_over_rpc(url: &str, address: AccountAddress) -> Result<Vec<Vec<u8>>> {
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": GET_MODULES_RPC_METHOD,
+        "params": [address.to_hex_literal()],
+    });
+
+    let response: serde_json::Value = reqwest::blocking::Client::new()
+        .post(url)
+        .json(&request)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .with_context(|| format!("Failed to query on-chain modules from '{}'", url))?
+        .json()
+        .with_context(|| format!("Invalid JSON-RPC response from '{}'", url))?;
+
+    if let Some(error) = response.get("error") {
+        bail!("JSON-RPC node '{}' returned an error: {}", url, error);
+    }
+
+    let Some(modules) = response.get("result").and_then(|result| result.as_array()) else {
+        bail!("JSON-RPC node '{}' returned no module list", url);
+    };
+
+    modules
+        .iter()
+        .map(|module| {
+            let Some(module) = module.as_str() else {
+                bail!(
+                    "JSON-RPC node '{}' returned a module that is not a string",
+                    url
+                );
+            };
+            hex::decode(module.trim_start_matches("0x"))
+                .with_context(|| format!("JSON-RPC node '{}' returned invalid hex", url))
+        })
+        .collect()
+}
+
+/// The data stored under an account key in a storage snapshot. Mirrors the layout used by the
+/// MoveVM backend's warehouse.
+#[derive(Default, Serialize, Deserialize)]
+struct AccountData {
+    modules: BTreeMap<Identifier, Vec<u8>>,
+    resources: BTreeMap<StructTag, Vec<u8>>,
+}
+
+/// A dump of the raw key-value storage of a MoveVM backend, BCS encoded. Keys are account
+/// addresses, values the account's modules and resources.
+#[derive(Default, Serialize, Deserialize)]
+pub struct StorageSnapshot(BTreeMap<Vec<u8>, Vec<u8>>);
+
+impl StorageSnapshot {
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Unable to read storage snapshot at {:?}", path))?;
+        bcs::from_bytes(&bytes).with_context(|| format!("Invalid storage snapshot at {:?}", path))
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        fs::write(path, bcs::to_bytes(self)?)
+            .with_context(|| format!("Unable to write storage snapshot to {:?}", path))
+    }
+
+    /// Adds a published module to the snapshot.
+    pub fn publish_module(
+        &mut self,
+        address: AccountAddress,
+        name: Identifier,
+        bytes: Vec<u8>,
+    ) -> Result<()> {
+        let mut account = self.account(address)?.unwrap_or_default();
+        account.modules.insert(name, bytes);
+        self.0.insert(address.to_vec(), bcs::to_bytes(&account)?);
+        Ok(())
+    }
+
+    /// Returns all modules published under `address`.
+    pub fn modules(&self, address: AccountAddress) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .account(address)?
+            .map(|account| account.modules.into_values().collect())
+            .unwrap_or_default())
+    }
+
+    fn account(&self, address: AccountAddress) -> Result<Option<AccountData>> {
+        self.0
+            .get(address.as_ref())
+            .map(|bytes| bcs::from_bytes(bytes).context("Invalid account data in snapshot"))
+            .transpose()
+    }
+}
+
+/// The hash pinned for a module's bytecode.
+pub fn module_hash(bytes: &[u8]) -> Symbol {
+    Symbol::from(format!("{:X}", Sha256::digest(bytes)))
+}
+
+/// Fetches the modules of the on-chain dependency `dep_name` and writes them out as a bytecode
+/// only package at `package_path`. If `info` pins module hashes, the fetched modules must match
+/// them exactly.
+pub(crate) fn materialize(
+    dep_name: PackageName,
+    info: &OnChainInfo,
+    package_path: &Path,
+) -> Result<()> {
+    let modules = RESOLVER
+        .lock()
+        .unwrap()
+        .fetch_modules(info.location.as_str(), info.address)?;
+
+    let mut named_modules = BTreeMap::new();
+    for bytes in modules {
+        let module = CompiledModule::deserialize(&bytes).with_context(|| {
+            format!(
+                "Invalid module fetched for on-chain dependency '{}'",
+                dep_name
+            )
+        })?;
+        let name = Symbol::from(module.self_id().name().as_str());
+        named_modules.insert(name, bytes);
+    }
+
+    if named_modules.is_empty() {
+        bail!(
+            "No modules published under {} at '{}' for on-chain dependency '{}'",
+            info.address.to_hex_literal(),
+            info.location,
+            dep_name
+        );
+    }
+
+    if !info.module_hashes.is_empty() {
+        let fetched = named_modules
+            .iter()
+            .map(|(name, bytes)| (*name, module_hash(bytes)))
+            .collect::<BTreeMap<_, _>>();
+        if fetched != info.module_hashes {
+            bail!(
+                "Modules fetched for on-chain dependency '{}' do not match the pinned module \
+                 hashes",
+                dep_name
+            );
+        }
+    }
+
+    if package_path.exists() {
+        fs::remove_dir_all(package_path)?;
+    }
+    fs::create_dir_all(package_path.join(SourcePackageLayout::Sources.path()))?;
+    fs::write(
+        package_path.join(SourcePackageLayout::Manifest.path()),
+        format!("[package]\nname = \"{}\"\nversion = \"0.0.0\"\n", dep_name),
+    )?;
+
+    let modules_path = bytecode_modules_path(package_path, dep_name);
+    fs::create_dir_all(&modules_path)?;
+    for (name, bytes) in named_modules {
+        fs::write(
+            modules_path
+                .join(name.as_str())
+                .with_extension(MOVE_COMPILED_EXTENSION),
+            bytes,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Hashes of the modules of an on-chain dependency materialized at `package_path`.
+pub(crate) fn materialized_module_hashes(
+    dep_name: PackageName,
+    package_path: &Path,
+) -> Result<BTreeMap<Symbol, Symbol>> {
+    let modules_path = bytecode_modules_path(package_path, dep_name);
+    let mut hashes = BTreeMap::new();
+    for entry in fs::read_dir(&modules_path)
+        .with_context(|| format!("Unable to read on-chain modules at {:?}", modules_path))?
+    {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |ext| ext == MOVE_COMPILED_EXTENSION)
+        {
+            if let Some(name) = path.file_stem() {
+                hashes.insert(
+                    Symbol::from(name.to_string_lossy().as_ref()),
+                    module_hash(&fs::read(&path)?),
+                );
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn bytecode_modules_path(package_path: &Path, dep_name: PackageName) -> PathBuf {
+    package_path
+        .join(DEFAULT_OUTPUT_DIR)
+        .join(dep_name.as_str())
+        .join(BYTECODE_MODULES_DIR)
+}
@@ -99,10 +99,16 @@ impl ResolvingGraph {
         mut build_options: BuildConfig,
         progress_output: &mut Progress,
     ) -> Result<ResolvingGraph> {
-        if build_options.architecture.is_none() {
-            if let Some(info) = &root_package.build {
+        if let Some(info) = &root_package.build {
+            if build_options.architecture.is_none() {
                 build_options.architecture = info.architecture;
             }
+            if build_options.lint.is_none() {
+                build_options.lint = info.lint;
+            }
+            if build_options.opt_level.is_none() {
+                build_options.opt_level = info.opt_level;
+            }
         }
         let mut resolution_graph = Self {
             root_package_path: root_package_path.clone(),
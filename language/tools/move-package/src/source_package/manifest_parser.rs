@@ -4,6 +4,9 @@
 
 use crate::{package_hooks, source_package::parsed_manifest as PM, Architecture};
 use anyhow::{anyhow, bail, format_err, Context, Result};
+use move_compiler::{
+    formatter::FormatOptions, linters::LintLevel, to_bytecode::size_optimize::OptLevel,
+};
 use move_core_types::account_address::{AccountAddress, AccountAddressParseError};
 use move_symbol_pool::symbol::Symbol;
 use std::{
@@ -22,6 +25,7 @@ const ADDRESSES_NAME: &str = "addresses";
 const DEV_ADDRESSES_NAME: &str = "dev-addresses";
 const DEPENDENCY_NAME: &str = "dependencies";
 const DEV_DEPENDENCY_NAME: &str = "dev-dependencies";
+const FORMAT_NAME: &str = "format";
 
 const KNOWN_NAMES: &[&str] = &[
     PACKAGE_NAME,
@@ -30,6 +34,7 @@ const KNOWN_NAMES: &[&str] = &[
     DEV_ADDRESSES_NAME,
     DEPENDENCY_NAME,
     DEV_DEPENDENCY_NAME,
+    FORMAT_NAME,
 ];
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
@@ -86,6 +91,11 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 .transpose()
                 .context("Error parsing '[dev-dependencies]' section of manifest")?
                 .unwrap_or_default();
+            let format = table
+                .remove(FORMAT_NAME)
+                .map(parse_format_options)
+                .transpose()
+                .context("Error parsing '[format]' section of manifest")?;
             Ok(PM::SourceManifest {
                 package,
                 addresses,
@@ -93,6 +103,7 @@ pub fn parse_source_manifest(tval: TV) -> Result<PM::SourceManifest> {
                 build,
                 dependencies,
                 dev_dependencies,
+                format,
             })
         }
         x => {
@@ -197,13 +208,15 @@ pub fn parse_dependencies(tval: TV) -> Result<PM::Dependencies> {
 pub fn parse_build_info(tval: TV) -> Result<PM::BuildInfo> {
     match tval {
         TV::Table(mut table) => {
-            warn_if_unknown_field_names(&table, &["language_version", "arch"]);
+            warn_if_unknown_field_names(&table, &["language_version", "arch", "lint", "opt_level"]);
             Ok(PM::BuildInfo {
                 language_version: table
                     .remove("language_version")
                     .map(parse_version)
                     .transpose()?,
                 architecture: table.remove("arch").map(parse_architecture).transpose()?,
+                lint: table.remove("lint").map(parse_lint_level).transpose()?,
+                opt_level: table.remove("opt_level").map(parse_opt_level).transpose()?,
             })
         }
         x => bail!(
@@ -214,6 +227,27 @@ pub fn parse_build_info(tval: TV) -> Result<PM::BuildInfo> {
     }
 }
 
+pub fn parse_format_options(tval: TV) -> Result<FormatOptions> {
+    match tval {
+        TV::Table(mut table) => {
+            warn_if_unknown_field_names(&table, &["indent_width", "max_blank_lines"]);
+            let mut options = FormatOptions::default();
+            if let Some(width) = table.remove("indent_width") {
+                options.indent_width = parse_format_number("indent_width", width)?;
+            }
+            if let Some(lines) = table.remove("max_blank_lines") {
+                options.max_blank_lines = parse_format_number("max_blank_lines", lines)?;
+            }
+            Ok(options)
+        }
+        x => bail!(
+            "Malformed section in manifest {}. Expected a table, but encountered a {}",
+            x,
+            x.type_str()
+        ),
+    }
+}
+
 pub fn parse_addresses(tval: TV) -> Result<PM::AddressDeclarations> {
     match tval {
         TV::Table(table) => {
@@ -326,6 +360,8 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         "rev",
         "subdir",
         "address",
+        "on_chain",
+        "module_hashes",
     ];
 
     let custom_key_opt = &package_hooks::custom_dependency_key();
@@ -347,8 +383,9 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
         table.remove("subdir"),
         table.remove("git"),
         custom_key_opt.as_ref().and_then(|k| table.remove(k)),
+        table.remove("on_chain"),
     ) {
-        (Some(local), subdir, None, None) => {
+        (Some(local), subdir, None, None, None) => {
             if subdir.is_some() {
                 bail!("'subdir' not supported for local dependencies");
             }
@@ -360,7 +397,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             PM::DependencyKind::Local(local)
         }
 
-        (None, subdir, Some(git_url), None) => {
+        (None, subdir, Some(git_url), None, None) => {
             let Some(git_rev) = table.remove("rev") else {
                 bail!("Git revision not supplied for dependency")
             };
@@ -388,7 +425,7 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
-        (None, subdir, None, Some(custom_key)) => {
+        (None, subdir, None, Some(custom_key), None) => {
             let Some(package_address) = table.remove("address") else {
                 bail!("Address not supplied for 'node' dependency");
             };
@@ -419,8 +456,43 @@ pub fn parse_dependency(dep_name: &str, mut tval: TV) -> Result<PM::Dependency>
             })
         }
 
+        (None, None, None, None, Some(location)) => {
+            let Some(location) = location.as_str().map(Symbol::from) else {
+                bail!("On-chain location not a string")
+            };
+
+            let Some(address) = table.remove("address") else {
+                bail!("Address not supplied for 'on_chain' dependency");
+            };
+
+            let address = address
+                .as_str()
+                .ok_or_else(|| anyhow!("On-chain address not a string"))
+                .and_then(|address| {
+                    parse_address_literal(address)
+                        .map_err(|_| anyhow!("Invalid on-chain address '{}'", address))
+                })?;
+
+            let module_hashes = table
+                .remove("module_hashes")
+                .map(parse_module_hashes)
+                .transpose()?
+                .unwrap_or_default();
+
+            PM::DependencyKind::OnChain(PM::OnChainInfo {
+                location,
+                address,
+                package_name: Symbol::from(dep_name),
+                module_hashes,
+            })
+        }
+
+        (None, Some(_), None, None, Some(_)) => {
+            bail!("'subdir' not supported for on-chain dependencies");
+        }
+
         _ => {
-            let mut keys = vec!["'local'", "'git'"];
+            let mut keys = vec!["'local'", "'git'", "'on_chain'"];
             let quoted_custom_key = custom_key_opt.as_ref().map(|k| format!("'{}'", k));
             if let Some(k) = &quoted_custom_key {
                 keys.push(k.as_str())
@@ -499,6 +571,26 @@ fn parse_architecture(tval: TV) -> Result<Architecture> {
     Architecture::try_parse_from_str(tval.as_str().unwrap())
 }
 
+fn parse_lint_level(tval: TV) -> Result<LintLevel> {
+    tval.as_str()
+        .ok_or_else(|| format_err!("Invalid lint level. Expected a string"))?
+        .parse()
+        .map_err(|e: String| format_err!(e))
+}
+
+fn parse_format_number(field: &str, tval: TV) -> Result<usize> {
+    tval.as_integer()
+        .and_then(|n| usize::try_from(n).ok())
+        .ok_or_else(|| format_err!("Invalid '{}'. Expected a non-negative integer", field))
+}
+
+fn parse_opt_level(tval: TV) -> Result<OptLevel> {
+    tval.as_str()
+        .ok_or_else(|| format_err!("Invalid optimization level. Expected a string"))?
+        .parse()
+        .map_err(|e: String| format_err!(e))
+}
+
 fn parse_digest(tval: TV) -> Result<PM::PackageDigest> {
     let digest_str = tval
         .as_str()
@@ -506,6 +598,22 @@ fn parse_digest(tval: TV) -> Result<PM::PackageDigest> {
     Ok(PM::PackageDigest::from(digest_str))
 }
 
+fn parse_module_hashes(tval: TV) -> Result<BTreeMap<Symbol, Symbol>> {
+    let Some(table) = tval.as_table() else {
+        bail!("Module hashes must be a table of module names to hashes");
+    };
+
+    table
+        .iter()
+        .map(|(module, hash)| {
+            let hash = hash
+                .as_str()
+                .ok_or_else(|| format_err!("Invalid hash for module '{}'", module))?;
+            Ok((Symbol::from(module.as_str()), Symbol::from(hash)))
+        })
+        .collect()
+}
+
 // check that only recognized names are provided at the top-level
 fn warn_if_unknown_field_names(table: &toml::map::Map<String, TV>, known_names: &[&str]) {
     let mut unknown_names = BTreeSet::new();
@@ -5,6 +5,9 @@
 use anyhow::{bail, Result};
 
 use crate::Architecture;
+use move_compiler::{
+    formatter::FormatOptions, linters::LintLevel, to_bytecode::size_optimize::OptLevel,
+};
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::symbol::Symbol;
 use std::{
@@ -31,6 +34,7 @@ pub struct SourceManifest {
     pub build: Option<BuildInfo>,
     pub dependencies: Dependencies,
     pub dev_dependencies: Dependencies,
+    pub format: Option<FormatOptions>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -55,6 +59,7 @@ pub enum DependencyKind {
     Local(PathBuf),
     Git(GitInfo),
     Custom(CustomDepInfo),
+    OnChain(OnChainInfo),
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -81,10 +86,26 @@ pub struct CustomDepInfo {
     pub subdir: PathBuf,
 }
 
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OnChainInfo {
+    /// Where the modules are fetched from: a path to a storage snapshot or the url of a JSON-RPC
+    /// node. Interpreted by the registered on-chain resolver.
+    pub location: Symbol,
+    /// The address the modules are published under
+    pub address: AccountAddress,
+    /// The package's name (i.e. the dependency name).
+    pub package_name: Symbol,
+    /// Module name -> hash of its bytecode. If not empty, the fetched modules must match these
+    /// exactly.
+    pub module_hashes: BTreeMap<Symbol, Symbol>,
+}
+
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
     pub architecture: Option<Architecture>,
+    pub lint: Option<LintLevel>,
+    pub opt_level: Option<OptLevel>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -105,10 +126,13 @@ impl DependencyKind {
         let mut parent = parent.clone();
 
         match (&mut parent, &self) {
-            // If `self` is a git or custom dependency kind, it does not need to be re-rooted
-            // because its URI is already absolute. (i.e. the location of an absolute URI does not
-            // change if referenced relative to some other URI).
-            (_, DependencyKind::Git(_) | DependencyKind::Custom(_)) => return Ok(()),
+            // If `self` is a git, custom or on-chain dependency kind, it does not need to be
+            // re-rooted because its URI is already absolute. (i.e. the location of an absolute URI
+            // does not change if referenced relative to some other URI).
+            (
+                _,
+                DependencyKind::Git(_) | DependencyKind::Custom(_) | DependencyKind::OnChain(_),
+            ) => return Ok(()),
 
             (DependencyKind::Local(parent), DependencyKind::Local(subdir)) => {
                 parent.push(subdir);
@@ -124,6 +148,14 @@ impl DependencyKind {
                 custom.subdir.push(subdir);
                 custom.subdir = normalize_path(&custom.subdir, /* allow_cwd_parent */ false)?;
             }
+
+            // On-chain packages only consist of bytecode and never have dependencies of their own.
+            (DependencyKind::OnChain(on_chain), DependencyKind::Local(_)) => {
+                bail!(
+                    "Local dependency found in on-chain package '{}'",
+                    on_chain.package_name
+                )
+            }
         };
 
         *self = parent;
@@ -131,6 +163,24 @@ impl DependencyKind {
     }
 }
 
+impl DependencyKind {
+    /// Returns true if `self` and `other` fetch the package from the same place. Unlike equality,
+    /// this ignores module hashes of on-chain dependencies unless both sides pin them.
+    pub fn is_same_source(&self, other: &DependencyKind) -> bool {
+        match (self, other) {
+            (DependencyKind::OnChain(this), DependencyKind::OnChain(other)) => {
+                this.location == other.location
+                    && this.address == other.address
+                    && this.package_name == other.package_name
+                    && (this.module_hashes.is_empty()
+                        || other.module_hashes.is_empty()
+                        || this.module_hashes == other.module_hashes)
+            }
+            _ => self == other,
+        }
+    }
+}
+
 /// Default `DependencyKind` is the one that acts as the left and right identity to
 /// `DependencyKind::rerooted` (modulo path normalization).
 impl Default for DependencyKind {
@@ -0,0 +1,7 @@
+module Bundle::Alpha {
+    use Bundle::Beta;
+
+    public fun value(): u64 {
+        Beta::value() + Bundle::Gamma::value()
+    }
+}
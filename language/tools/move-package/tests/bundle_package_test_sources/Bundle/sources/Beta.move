@@ -0,0 +1,5 @@
+module Bundle::Beta {
+    public fun value(): u64 {
+        Bundle::Gamma::value() * 2
+    }
+}
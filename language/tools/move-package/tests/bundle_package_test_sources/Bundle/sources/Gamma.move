@@ -0,0 +1,11 @@
+module Bundle::Gamma {
+    public fun value(): u64 {
+        21
+    }
+}
+
+script {
+    fun main() {
+        Bundle::Alpha::value();
+    }
+}
@@ -0,0 +1,23 @@
+module A::SizeOptimized {
+    /// The configuration is not published under the address.
+    const ENOT_INITIALIZED: u64 = 1;
+
+    /// Configuration stored under the publisher's address.
+    struct Config has key {
+        version: u64
+    }
+
+    /// Run once when the module is published for the first time.
+    fun init_module(publisher: &signer) {
+        move_to(publisher, Config { version: initial_version() });
+    }
+
+    fun initial_version(): u64 {
+        2
+    }
+
+    /// Not called by any public, entry or initializer function.
+    fun unused(): u64 {
+        42
+    }
+}
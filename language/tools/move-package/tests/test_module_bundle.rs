@@ -0,0 +1,66 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::file_format::CompiledModule;
+use move_command_line_common::files::MOVE_BUNDLE_EXTENSION;
+use move_package::{compilation::package_layout::CompiledPackageLayout, BuildConfig};
+use std::path::Path;
+use tempfile::tempdir;
+
+#[test]
+fn bundle_modules_in_dependency_order() {
+    let install_dir = tempdir().unwrap();
+    let package = BuildConfig {
+        generate_bundle: true,
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package(
+        Path::new("tests/bundle_package_test_sources/Bundle"),
+        &mut Vec::new(),
+    )
+    .unwrap();
+
+    let bundle = package.compiled_bundle.as_ref().unwrap();
+    let modules = bcs::from_bytes::<Vec<Vec<u8>>>(bundle)
+        .unwrap()
+        .iter()
+        .map(|bytes| CompiledModule::deserialize(bytes).unwrap())
+        .collect::<Vec<_>>();
+
+    // Scripts are left out and every module comes after its dependencies.
+    let names = modules
+        .iter()
+        .map(|module| module.self_id().name().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Gamma", "Beta", "Alpha"]);
+    assert!(modules
+        .iter()
+        .all(|module| module.self_id().address().to_hex()
+            == "8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48"));
+
+    let on_disk = install_dir
+        .path()
+        .join(CompiledPackageLayout::Root.path())
+        .join("Bundle")
+        .join(CompiledPackageLayout::CompiledBundles.path())
+        .join("Bundle")
+        .with_extension(MOVE_BUNDLE_EXTENSION);
+    assert_eq!(&std::fs::read(on_disk).unwrap(), bundle);
+}
+
+#[test]
+fn no_bundle_by_default() {
+    let install_dir = tempdir().unwrap();
+    let package = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package(
+        Path::new("tests/bundle_package_test_sources/Bundle"),
+        &mut Vec::new(),
+    )
+    .unwrap();
+
+    assert!(package.compiled_bundle.is_none());
+}
@@ -0,0 +1,151 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+use move_package::{
+    resolution::on_chain::{module_hash, StorageSnapshot},
+    BuildConfig,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::{tempdir, TempDir};
+
+const BUNDLE_ADDRESS: &str = "0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+
+/// Compiles the `Bundle` test package and publishes the modules accepted by `filter` into a
+/// storage snapshot in `dir`. Returns the path to the snapshot and the published modules.
+fn publish_bundle(dir: &Path, filter: impl Fn(&str) -> bool) -> (PathBuf, Vec<(String, Vec<u8>)>) {
+    let install_dir = tempdir().unwrap();
+    let package = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    }
+    .compile_package(
+        Path::new("tests/bundle_package_test_sources/Bundle"),
+        &mut Vec::new(),
+    )
+    .unwrap();
+
+    let mut snapshot = StorageSnapshot::default();
+    let mut modules = vec![];
+    for module in package.root_modules() {
+        let name = module.unit.name().to_string();
+        if !filter(&name) {
+            continue;
+        }
+
+        let bytes = module.unit.serialize(None);
+        snapshot
+            .publish_module(
+                AccountAddress::from_hex_literal(BUNDLE_ADDRESS).unwrap(),
+                Identifier::new(name.as_str()).unwrap(),
+                bytes.clone(),
+            )
+            .unwrap();
+        modules.push((name, bytes));
+    }
+
+    let path = dir.join("storage.snapshot");
+    snapshot.write_to_file(&path).unwrap();
+    (path, modules)
+}
+
+/// Creates a package depending on the on-chain `Bundle` package published in `snapshot`.
+fn create_consumer(snapshot: &Path, pins: &str) -> TempDir {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("Move.toml"),
+        format!(
+            "[package]\n\
+             name = \"Consumer\"\n\
+             version = \"0.0.0\"\n\
+             \n\
+             [dependencies]\n\
+             Bundle = {{ on_chain = \"{}\", address = \"{}\"{} }}\n",
+            snapshot.display(),
+            BUNDLE_ADDRESS,
+            pins,
+        ),
+    )
+    .unwrap();
+
+    fs::create_dir(dir.path().join("sources")).unwrap();
+    fs::write(
+        dir.path().join("sources").join("Consumer.move"),
+        format!(
+            "module 0x2::Consumer {{\n    \
+                 public fun value(): u64 {{\n        \
+                     {}::Alpha::value()\n    \
+                 }}\n\
+             }}\n",
+            BUNDLE_ADDRESS,
+        ),
+    )
+    .unwrap();
+
+    dir
+}
+
+fn build(package: &Path) -> anyhow::Result<()> {
+    BuildConfig {
+        install_dir: Some(package.to_path_buf()),
+        lock_file: Some(package.join("Move.lock")),
+        ..Default::default()
+    }
+    .compile_package(package, &mut Vec::new())
+    .map(|_| ())
+}
+
+#[test]
+fn compile_against_on_chain_modules() {
+    let chain = tempdir().unwrap();
+    let (snapshot, modules) = publish_bundle(chain.path(), |_| true);
+    let consumer = create_consumer(&snapshot, "");
+
+    build(consumer.path()).unwrap();
+
+    let lock = fs::read_to_string(consumer.path().join("Move.lock")).unwrap();
+    assert!(lock.contains("on_chain = "), "{}", lock);
+    for (name, bytes) in &modules {
+        let pin = format!("\"{}\" = \"{}\"", name, module_hash(bytes));
+        assert!(
+            lock.contains(&pin),
+            "Missing '{}' in lock file:\n{}",
+            pin,
+            lock
+        );
+    }
+}
+
+#[test]
+fn manifest_pins_must_match() {
+    let chain = tempdir().unwrap();
+    let (snapshot, _) = publish_bundle(chain.path(), |_| true);
+    let consumer = create_consumer(
+        &snapshot,
+        ", module_hashes = { Alpha = \"00\", Beta = \"00\", Gamma = \"00\" }",
+    );
+
+    let err = format!("{:#}", build(consumer.path()).unwrap_err());
+    assert!(
+        err.contains("do not match the pinned module hashes"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn lock_file_pins_must_match() {
+    let chain = tempdir().unwrap();
+    let (snapshot, _) = publish_bundle(chain.path(), |_| true);
+    let consumer = create_consumer(&snapshot, "");
+    build(consumer.path()).unwrap();
+
+    // Modules change on chain after the dependency was locked.
+    publish_bundle(chain.path(), |name| name != "Beta");
+
+    let err = format!("{:#}", build(consumer.path()).unwrap_err());
+    assert!(err.contains("changed since they were locked"), "{}", err);
+}
@@ -117,6 +117,7 @@ impl Test<'_> {
             test_mode: false,
             generate_docs: false,
             generate_abis: false,
+            generate_bundle: false,
             install_dir: Some(out_path),
             force_recompilation: false,
             lock_file: ["locked", "notlocked"]
@@ -0,0 +1,61 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
+use move_compiler::{compiled_unit::CompiledUnit, to_bytecode::size_optimize::OptLevel};
+use move_package::BuildConfig;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// Function names and constants of the module compiled at the given level.
+fn compile(opt_level: Option<OptLevel>) -> (Vec<String>, Vec<Vec<u8>>) {
+    let install_dir = tempdir().unwrap();
+    let package = BuildConfig {
+        install_dir: Some(install_dir.path().to_path_buf()),
+        opt_level,
+        ..Default::default()
+    }
+    .compile_package(
+        Path::new("tests/size_optimization_test_sources/SizeOptimized"),
+        &mut Vec::new(),
+    )
+    .unwrap();
+
+    let unit = package
+        .get_module_by_name_from_root("SizeOptimized")
+        .unwrap();
+    let CompiledUnit::Module(named) = &unit.unit else {
+        panic!("SizeOptimized is not a module");
+    };
+    let module: &CompiledModule = &named.module;
+    let functions = module
+        .function_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.function_handle_at(def.function);
+            module.identifier_at(handle.name).to_string()
+        })
+        .collect();
+    let constants = module
+        .constant_pool()
+        .iter()
+        .map(|constant| constant.data.clone())
+        .collect();
+    (functions, constants)
+}
+
+#[test]
+fn size_optimization_keeps_initializer_and_error_constants() {
+    // The opt level of the manifest applies.
+    let (functions, constants) = compile(None);
+    assert_eq!(functions, ["init_module", "initial_version"]);
+    // The unused error constant is kept for the error maps.
+    assert_eq!(constants, [bcs::to_bytes(&1u64).unwrap()]);
+}
+
+#[test]
+fn default_optimization_keeps_unused_functions() {
+    let (functions, constants) = compile(Some(OptLevel::Default));
+    assert_eq!(functions, ["init_module", "initial_version", "unused"]);
+    assert_eq!(constants, [bcs::to_bytes(&1u64).unwrap()]);
+}
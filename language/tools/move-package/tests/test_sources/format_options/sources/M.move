@@ -0,0 +1,3 @@
+module 0x1::M {
+  public fun foo() {}
+}
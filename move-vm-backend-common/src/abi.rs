@@ -10,7 +10,8 @@ use move_binary_format::file_format::{
 use move_binary_format::CompiledModule;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use move_core_types::value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout};
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
@@ -123,10 +124,109 @@ pub struct StructDef {
     pub id: ModuleId,
     /// Name.
     pub name: Identifier,
-    /// Struct fields.
+    /// Type arguments of the instantiation.
     pub fields: Vec<Type>,
 }
 
+impl From<&TypeTag> for Type {
+    fn from(tag: &TypeTag) -> Self {
+        match tag {
+            TypeTag::Bool => Type::Bool,
+            TypeTag::U8 => Type::U8,
+            TypeTag::U16 => Type::U16,
+            TypeTag::U32 => Type::U32,
+            TypeTag::U64 => Type::U64,
+            TypeTag::U128 => Type::U128,
+            TypeTag::U256 => Type::U256,
+            TypeTag::Address => Type::Address,
+            TypeTag::Signer => Type::Signer,
+            TypeTag::Vector(tag) => Type::Vector(Box::new(Type::from(tag.as_ref()))),
+            TypeTag::Struct(tag) => Type::Struct(StructDef::from(tag.as_ref())),
+        }
+    }
+}
+
+impl From<&StructTag> for StructDef {
+    fn from(tag: &StructTag) -> Self {
+        StructDef {
+            id: tag.module_id(),
+            name: tag.name.clone(),
+            fields: tag.type_params.iter().map(Type::from).collect(),
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
+)]
+/// Fully resolved layout of the values of a type.
+pub enum TypeLayout {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Signer,
+    Vector(Box<TypeLayout>),
+    Struct(StructLayout),
+}
+
+#[derive(
+    Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
+)]
+/// Layout of a struct instantiation.
+pub struct StructLayout {
+    /// Instantiated struct, with its type arguments.
+    pub def: StructDef,
+    /// Struct fields, with the type arguments substituted.
+    pub fields: Vec<FieldLayout>,
+}
+
+#[derive(
+    Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
+)]
+/// Layout of a struct field.
+pub struct FieldLayout {
+    /// Name.
+    pub name: Identifier,
+    /// Layout.
+    pub layout: TypeLayout,
+}
+
+impl From<&TypeLayout> for MoveTypeLayout {
+    fn from(layout: &TypeLayout) -> Self {
+        match layout {
+            TypeLayout::Bool => MoveTypeLayout::Bool,
+            TypeLayout::U8 => MoveTypeLayout::U8,
+            TypeLayout::U16 => MoveTypeLayout::U16,
+            TypeLayout::U32 => MoveTypeLayout::U32,
+            TypeLayout::U64 => MoveTypeLayout::U64,
+            TypeLayout::U128 => MoveTypeLayout::U128,
+            TypeLayout::U256 => MoveTypeLayout::U256,
+            TypeLayout::Address => MoveTypeLayout::Address,
+            TypeLayout::Signer => MoveTypeLayout::Signer,
+            TypeLayout::Vector(elem) => {
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::from(elem.as_ref())))
+            }
+            TypeLayout::Struct(layout) => MoveTypeLayout::Struct(MoveStructLayout::with_fields(
+                layout
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        MoveFieldLayout::new(
+                            field.name.clone(),
+                            MoveTypeLayout::from(&field.layout),
+                        )
+                    })
+                    .collect(),
+            )),
+        }
+    }
+}
+
 #[derive(
     Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
 )]
@@ -142,25 +242,25 @@ pub struct Function {
     pub parameters: Vec<Type>,
     /// Return types.
     pub returns: Vec<Type>,
+    /// Whether the function can be called directly by transactions.
+    pub is_entry: bool,
 }
 
 #[derive(
     Clone, Debug, Serialize, Deserialize, Ord, PartialOrd, Eq, PartialEq, TypeInfo, Decode, Encode,
 )]
 /// Function visibility.
-// Private not needed since it's not accessible to outer modules.
+// Private only for entry functions, the other ones aren't accessible to outer modules.
 pub enum FunctionVisibility {
     Public,
     Friend,
+    Private,
 }
 
 impl From<&Visibility> for FunctionVisibility {
     fn from(val: &Visibility) -> Self {
         match val {
-            Visibility::Private => {
-                // not possible
-                FunctionVisibility::Public
-            }
+            Visibility::Private => FunctionVisibility::Private,
             Visibility::Public => FunctionVisibility::Public,
             Visibility::Friend => FunctionVisibility::Friend,
         }
@@ -268,7 +368,7 @@ fn make_func_abi(module: &CompiledModule) -> Vec<Function> {
         .iter()
         .filter(|def| match def.visibility {
             Visibility::Public | Visibility::Friend => true,
-            Visibility::Private => false,
+            Visibility::Private => def.is_entry,
         })
         .map(|def| {
             let handle = module.function_handle_at(def.function);
@@ -284,6 +384,7 @@ fn make_func_abi(module: &CompiledModule) -> Vec<Function> {
                     .collect(),
                 parameters: parameters.iter().map(|st| make_type(st, module)).collect(),
                 returns: return_.iter().map(|st| make_type(st, module)).collect(),
+                is_entry: def.is_entry,
             }
         })
         .collect()
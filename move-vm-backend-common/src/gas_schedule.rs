@@ -19,9 +19,18 @@ use move_core_types::u256;
 use move_stdlib::natives::GasParameters;
 use move_vm_test_utils::gas_schedule::{new_from_instructions, CostTable, GasCost};
 
+/// Internal gas units per gas unit.
+///
+/// The MoveVM meters in internal gas, while the gas amounts given to and reported by the backend
+/// and the gas used by the calls to other VMs are in gas units.
+pub const INTERNAL_GAS_MULTIPLIER: u64 = 1000;
+
 /// A predefined gas cost to published byte ratio.
 pub const MILLIGAS_COST_PER_PUBLISHED_BYTE: u64 = 1000;
 
+/// A predefined gas cost per unit of work done by the bytecode verifier when publishing modules.
+pub const MILLIGAS_COST_PER_VERIFIER_METER_UNIT: u64 = 10;
+
 /// Generic gas cost table scale factor, that will be applied linearly.
 pub const TABLE_GAS_COST_SCALE_FACTOR: f64 = 1.0;
 
@@ -231,6 +240,14 @@ lazy_static! {
                 cheque_amount: move_stdlib::natives::balance::ChequeAmountGasParameters { base: 3892.into() },
                 total_amount: move_stdlib::natives::balance::TotalAmountGasParameters { base: 3769.into() },
             },
+            // the gas consumed by the called contract is charged on top of these.
+            cross_vm: move_stdlib::natives::cross_vm::GasParameters {
+                call: move_stdlib::natives::cross_vm::CallGasParameters {
+                    base: 22054.into(),
+                    per_byte: 55.into(),
+                    per_gas_unit: INTERNAL_GAS_MULTIPLIER.into()
+                },
+            },
             substrate_hash: move_stdlib::natives::substrate_hash::GasParameters {
                 sip_hash: move_stdlib::natives::substrate_hash::SipHashGasParameters {
                     base: 3676.into(),
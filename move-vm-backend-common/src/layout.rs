@@ -0,0 +1,132 @@
+//! Resolution of the full layouts of types from the ABIs of the modules declaring them.
+//!
+//! Values and resources are encoded in BCS, which can only be decoded knowing the layouts of
+//! their types. A [`TypeLayout`] is a tree of the fields of a type, with the structs of all the
+//! modules involved expanded and their type parameters substituted.
+
+use crate::abi::{FieldLayout, ModuleAbi, StructDef, StructLayout, Type, TypeLayout};
+use crate::scale::{substitute, TypeBudget};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Result};
+use move_core_types::language_storage::{ModuleId, TypeTag};
+
+/// Source of the module ABIs, e.g. the storage of the MoveVM.
+pub trait AbiResolver {
+    /// Get the ABI of the module, or `None` if it doesn't exist.
+    fn get_module_abi(&self, id: &ModuleId) -> Result<Option<ModuleAbi>>;
+}
+
+impl AbiResolver for [ModuleAbi] {
+    fn get_module_abi(&self, id: &ModuleId) -> Result<Option<ModuleAbi>> {
+        Ok(self.iter().find(|abi| &abi.id == id).cloned())
+    }
+}
+
+/// Resolves the layout of the type, loading the ABIs of the modules declaring its structs.
+///
+/// Fails if a module or a struct is missing, if a struct contains itself, or if the type is
+/// nested more than [`MAX_TYPE_DEPTH`] levels deep or has more than [`MAX_TYPE_NODES`] nodes.
+///
+/// [`MAX_TYPE_DEPTH`]: crate::scale::MAX_TYPE_DEPTH
+/// [`MAX_TYPE_NODES`]: crate::scale::MAX_TYPE_NODES
+pub fn resolve_type_layout<R: AbiResolver + ?Sized>(
+    resolver: &R,
+    tag: &TypeTag,
+) -> Result<TypeLayout> {
+    let mut resolution = Resolution {
+        resolver,
+        abis: BTreeMap::new(),
+        parents: Vec::new(),
+        budget: TypeBudget::default(),
+    };
+    resolution.layout(&Type::from(tag), 0)
+}
+
+struct Resolution<'a, R: ?Sized> {
+    resolver: &'a R,
+    // ABIs loaded so far.
+    abis: BTreeMap<ModuleId, ModuleAbi>,
+    // Struct instantiations being expanded, to detect recursion.
+    parents: Vec<StructDef>,
+    // Size of the layout so far.
+    budget: TypeBudget,
+}
+
+impl<'a, R: AbiResolver + ?Sized> Resolution<'a, R> {
+    fn layout(&mut self, ty: &Type, depth: usize) -> Result<TypeLayout> {
+        self.budget.visit(depth)?;
+        Ok(match ty {
+            Type::Bool => TypeLayout::Bool,
+            Type::U8 => TypeLayout::U8,
+            Type::U16 => TypeLayout::U16,
+            Type::U32 => TypeLayout::U32,
+            Type::U64 => TypeLayout::U64,
+            Type::U128 => TypeLayout::U128,
+            Type::U256 => TypeLayout::U256,
+            Type::Address => TypeLayout::Address,
+            Type::Signer => TypeLayout::Signer,
+            Type::Vector(elem) => TypeLayout::Vector(Box::new(self.layout(elem, depth + 1)?)),
+            Type::Struct(def) => TypeLayout::Struct(self.struct_layout(def, depth)?),
+            // Only reachable from ill-formed ABIs: type tags are concrete, and the type parameters
+            // of the fields are substituted.
+            Type::Reference(_) | Type::MutableReference(_) | Type::TypeParameter(_) => {
+                bail!("Type {:?} has no value layout", ty)
+            }
+        })
+    }
+
+    fn struct_layout(&mut self, def: &StructDef, depth: usize) -> Result<StructLayout> {
+        if self.parents.contains(def) {
+            bail!("Struct {}::{} contains itself", def.id, def.name);
+        }
+
+        let struct_ = self
+            .module_abi(&def.id)?
+            .structs
+            .iter()
+            .find(|s| s.name == def.name)
+            .ok_or_else(|| anyhow!("Struct {}::{} not found", def.id, def.name))?;
+        if struct_.type_parameters.len() != def.fields.len() {
+            bail!(
+                "Struct {}::{} expects {} type arguments, got {}",
+                def.id,
+                def.name,
+                struct_.type_parameters.len(),
+                def.fields.len()
+            );
+        }
+        let fields = struct_
+            .fields
+            .iter()
+            .map(|field| Ok((field.name.clone(), substitute(&field.tp, &def.fields)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        self.parents.push(def.clone());
+        let fields = fields
+            .into_iter()
+            .map(|(name, ty)| {
+                let layout = self.layout(&ty, depth + 1)?;
+                Ok(FieldLayout { name, layout })
+            })
+            .collect::<Result<_>>();
+        self.parents.pop();
+
+        Ok(StructLayout {
+            def: def.clone(),
+            fields: fields?,
+        })
+    }
+
+    fn module_abi(&mut self, id: &ModuleId) -> Result<&ModuleAbi> {
+        if !self.abis.contains_key(id) {
+            let abi = self
+                .resolver
+                .get_module_abi(id)?
+                .ok_or_else(|| anyhow!("Module {} not found", id))?;
+            self.abis.insert(id.clone(), abi);
+        }
+        Ok(&self.abis[id])
+    }
+}
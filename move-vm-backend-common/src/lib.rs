@@ -4,6 +4,9 @@ extern crate alloc;
 
 pub mod abi;
 pub mod bytecode;
+pub mod layout;
+pub mod proof;
+pub mod scale;
 pub mod types;
 
 #[cfg(feature = "gas_schedule")]
@@ -0,0 +1,163 @@
+//! Merkle commitment over the modules and resources of a Move account.
+//!
+//! The leaves of the tree are the entries of the account: its modules ordered by name, followed
+//! by its resources ordered by struct tag. Each level pairs adjacent nodes from the left; the last
+//! node of a level with an odd number of nodes is promoted to the next level unchanged. The root of
+//! an account without entries is [`EMPTY_ROOT`].
+//!
+//! Leaves and inner nodes are hashed with SHA3-256 using distinct prefixes, so that an inner node
+//! can't be presented as a leaf.
+
+use alloc::vec::Vec;
+use move_core_types::{identifier::Identifier, language_storage::StructTag};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+
+/// Hash of a node of the tree.
+pub type Hash = [u8; 32];
+
+/// Root of an account without modules and resources.
+pub const EMPTY_ROOT: Hash = [0; 32];
+
+const LEAF_PREFIX: u8 = 0;
+const NODE_PREFIX: u8 = 1;
+
+/// Key of an account entry, hashed together with its value into a leaf.
+#[derive(Serialize)]
+pub enum EntryKey<'a> {
+    /// Module with the given name.
+    Module(&'a Identifier),
+    /// Resource with the given type.
+    Resource(&'a StructTag),
+}
+
+/// Proof that an entry is included in the tree of an account.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, TypeInfo, Decode, Encode)]
+pub struct MerkleProof {
+    /// Position of the entry's leaf.
+    pub leaf_index: u64,
+    /// Number of leaves of the tree.
+    pub leaf_count: u64,
+    /// Hashes of the siblings on the path from the leaf to the root, from the bottom up.
+    pub siblings: Vec<Hash>,
+}
+
+/// Resource of an account together with the proof of its inclusion in the account.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, TypeInfo, Decode, Encode)]
+pub struct ResourceProof {
+    /// BCS encoded resource.
+    pub resource: Vec<u8>,
+    /// Root of the account holding the resource.
+    pub root: Hash,
+    /// Proof of inclusion of the resource.
+    pub proof: MerkleProof,
+}
+
+/// Hash of the leaf for the entry `key` holding `value`.
+pub fn leaf_hash(key: &EntryKey, value: &[u8]) -> Hash {
+    let mut hasher = Sha3_256::new();
+    hasher.update([LEAF_PREFIX]);
+    // Serializing references to identifiers and struct tags can't fail.
+    hasher.update(bcs::to_bytes(key).expect("entry key serialization failed"));
+    hasher.update(value);
+    hasher.finalize().into()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha3_256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [left, right] => node_hash(left, right),
+            [single] => *single,
+            _ => unreachable!("chunks have one or two elements"),
+        })
+        .collect()
+}
+
+/// Root of the tree with the given leaves.
+pub fn merkle_root(leaves: &[Hash]) -> Hash {
+    if leaves.is_empty() {
+        return EMPTY_ROOT;
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+/// Proof of inclusion of the leaf at `index`, or `None` if there is no such leaf.
+pub fn merkle_proof(leaves: &[Hash], index: usize) -> Option<MerkleProof> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut siblings = Vec::new();
+    let mut level = leaves.to_vec();
+    let mut position = index;
+    while level.len() > 1 {
+        let sibling = position ^ 1;
+        if sibling < level.len() {
+            siblings.push(level[sibling]);
+        }
+        level = next_level(&level);
+        position /= 2;
+    }
+
+    Some(MerkleProof {
+        leaf_index: index as u64,
+        leaf_count: leaves.len() as u64,
+        siblings,
+    })
+}
+
+/// Check that the leaf with hash `leaf` is included in the tree with root `root`.
+pub fn verify_merkle_proof(root: &Hash, leaf: Hash, proof: &MerkleProof) -> bool {
+    if proof.leaf_index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut siblings = proof.siblings.iter();
+    let mut hash = leaf;
+    let mut position = proof.leaf_index;
+    let mut level_len = proof.leaf_count;
+    while level_len > 1 {
+        let sibling = position ^ 1;
+        if sibling < level_len {
+            let Some(sibling_hash) = siblings.next() else {
+                return false;
+            };
+            hash = if position % 2 == 0 {
+                node_hash(&hash, sibling_hash)
+            } else {
+                node_hash(sibling_hash, &hash)
+            };
+        }
+        position /= 2;
+        level_len = level_len.div_ceil(2);
+    }
+
+    siblings.next().is_none() && hash == *root
+}
+
+/// Check that the resource of type `tag` with the BCS encoded `value` is included in the account
+/// with root `root`.
+pub fn verify_resource_proof(
+    root: &Hash,
+    tag: &StructTag,
+    value: &[u8],
+    proof: &MerkleProof,
+) -> bool {
+    verify_merkle_proof(root, leaf_hash(&EntryKey::Resource(tag), value), proof)
+}
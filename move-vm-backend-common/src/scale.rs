@@ -0,0 +1,359 @@
+//! Conversion of Move values between BCS and SCALE, driven by the types of the module ABIs.
+//!
+//! The MoveVM and the pallet exchange values in BCS, while the Substrate front-ends (polkadot.js,
+//! subxt) work in SCALE and describe types with `scale-info`. The [`ScaleConverter`] resolves the
+//! [`Type`]s of a set of [`ModuleAbi`]s into value layouts, converts values between both
+//! encodings and registers the types in a `scale-info` registry.
+//!
+//! See [`move_core_types::scale`] for how Move values are SCALE encoded.
+
+use crate::abi::{Field, ModuleAbi, Struct, StructDef, Type};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Result};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::scale;
+use move_core_types::value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout, MoveValue};
+use scale_info::form::PortableForm;
+use scale_info::{
+    Field as ScaleField, Path, PortableRegistry, PortableRegistryBuilder, Type as ScaleType,
+    TypeDefArray, TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeParameter,
+};
+
+/// Maximum nesting of the resolved types.
+///
+/// Move modules can't declare recursive structs, but the ABIs given to the converter aren't
+/// verified.
+pub const MAX_TYPE_DEPTH: usize = 128;
+
+/// Maximum number of nodes of the resolved types.
+///
+/// The type arguments of a generic struct are expanded in each of its fields, so the size of a
+/// type can grow exponentially with its depth, e.g. `Pair<Pair<Pair<u8>>>`.
+pub const MAX_TYPE_NODES: usize = 4096;
+
+/// Converts the values of the types declared in a set of module ABIs.
+///
+/// The set must contain the ABI of every module declaring a struct used by the converted types.
+pub struct ScaleConverter<'a> {
+    abis: &'a [ModuleAbi],
+}
+
+impl<'a> ScaleConverter<'a> {
+    /// Creates a converter for the types of the given modules.
+    pub fn new(abis: &'a [ModuleAbi]) -> Self {
+        Self { abis }
+    }
+
+    /// Returns the layout of the values of `ty`, with the field names of the structs.
+    ///
+    /// References have the layout of the referenced type: the only references accepted as entry
+    /// function arguments are `&signer`, passed as the address of the signer.
+    pub fn type_layout(&self, ty: &Type) -> Result<MoveTypeLayout> {
+        self.layout(ty, &[], 0, &mut TypeBudget::default())
+    }
+
+    /// Converts a BCS encoded value of type `ty` to SCALE.
+    pub fn bcs_to_scale(&self, ty: &Type, bcs: &[u8]) -> Result<Vec<u8>> {
+        let value = MoveValue::simple_deserialize(bcs, &self.type_layout(ty)?)?;
+        Ok(scale::encode_value(&value))
+    }
+
+    /// Converts a SCALE encoded value of type `ty` to BCS.
+    pub fn scale_to_bcs(&self, ty: &Type, bytes: &[u8]) -> Result<Vec<u8>> {
+        // Decorated values are serialized with their field names.
+        scale::decode_value(&self.type_layout(ty)?, bytes)?
+            .undecorate()
+            .simple_serialize()
+            .ok_or_else(|| anyhow!("Failed to serialize the value in BCS"))
+    }
+
+    /// Registers `ty` and the types it's made of, returning the ID of `ty` in the registry.
+    ///
+    /// Structs are registered as composites named `address::module::Struct`, with their type
+    /// arguments as type parameters. Addresses and signers are registered as
+    /// `move_core_types::account_address::AccountAddress`, a composite of the address bytes.
+    pub fn register_type(&self, ty: &Type, registry: &mut PortableRegistryBuilder) -> Result<u32> {
+        self.register(ty, &[], registry, 0, &mut TypeBudget::default())
+    }
+
+    /// Builds the registry of the types of the module: its structs and the parameters of its
+    /// entry functions.
+    ///
+    /// Generic structs and the parameters of generic functions are skipped: `scale-info` only
+    /// describes the instantiations of generic types.
+    pub fn module_registry(&self, module: &ModuleAbi) -> Result<PortableRegistry> {
+        let mut registry = PortableRegistryBuilder::new();
+        for struct_ in module.structs.iter() {
+            if struct_.type_parameters.is_empty() {
+                let def = StructDef {
+                    id: module.id.clone(),
+                    name: struct_.name.clone(),
+                    fields: vec![],
+                };
+                self.register_type(&Type::Struct(def), &mut registry)?;
+            }
+        }
+        for func in module.funcs.iter() {
+            if func.type_parameters.is_empty() && func.is_entry {
+                for ty in func.parameters.iter() {
+                    self.register_type(ty, &mut registry)?;
+                }
+            }
+        }
+        Ok(registry.finish())
+    }
+
+    fn layout(
+        &self,
+        ty: &Type,
+        ty_args: &[Type],
+        depth: usize,
+        budget: &mut TypeBudget,
+    ) -> Result<MoveTypeLayout> {
+        budget.visit(depth)?;
+        Ok(match ty {
+            Type::Bool => MoveTypeLayout::Bool,
+            Type::U8 => MoveTypeLayout::U8,
+            Type::U16 => MoveTypeLayout::U16,
+            Type::U32 => MoveTypeLayout::U32,
+            Type::U64 => MoveTypeLayout::U64,
+            Type::U128 => MoveTypeLayout::U128,
+            Type::U256 => MoveTypeLayout::U256,
+            Type::Address => MoveTypeLayout::Address,
+            Type::Signer => MoveTypeLayout::Signer,
+            Type::Vector(elem) => {
+                MoveTypeLayout::Vector(Box::new(self.layout(elem, ty_args, depth + 1, budget)?))
+            }
+            Type::Reference(ty) | Type::MutableReference(ty) => {
+                self.layout(ty, ty_args, depth + 1, budget)?
+            }
+            Type::TypeParameter(idx) => {
+                self.layout(type_arg(ty_args, *idx)?, &[], depth + 1, budget)?
+            }
+            Type::Struct(def) => {
+                let (struct_, struct_ty_args) = self.resolve_struct(def, ty_args)?;
+                let fields = struct_
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let layout = self.layout(&field.tp, &struct_ty_args, depth + 1, budget)?;
+                        Ok(MoveFieldLayout::new(field.name.clone(), layout))
+                    })
+                    .collect::<Result<_>>()?;
+                MoveTypeLayout::Struct(MoveStructLayout::with_fields(fields))
+            }
+        })
+    }
+
+    fn register(
+        &self,
+        ty: &Type,
+        ty_args: &[Type],
+        registry: &mut PortableRegistryBuilder,
+        depth: usize,
+        budget: &mut TypeBudget,
+    ) -> Result<u32> {
+        budget.visit(depth)?;
+        let ty = match ty {
+            Type::Bool => primitive(TypeDefPrimitive::Bool),
+            Type::U8 => primitive(TypeDefPrimitive::U8),
+            Type::U16 => primitive(TypeDefPrimitive::U16),
+            Type::U32 => primitive(TypeDefPrimitive::U32),
+            Type::U64 => primitive(TypeDefPrimitive::U64),
+            Type::U128 => primitive(TypeDefPrimitive::U128),
+            Type::U256 => primitive(TypeDefPrimitive::U256),
+            Type::Address | Type::Signer => {
+                let u8_id = registry.register_type(primitive(TypeDefPrimitive::U8));
+                let bytes = ScaleType::new(
+                    Path::default(),
+                    vec![],
+                    TypeDefArray::new(AccountAddress::LENGTH as u32, u8_id.into()),
+                    vec![],
+                );
+                let bytes_id = registry.register_type(bytes);
+                ScaleType::new(
+                    Path::from_segments_unchecked(
+                        ["move_core_types", "account_address", "AccountAddress"].map(String::from),
+                    ),
+                    vec![],
+                    TypeDefComposite::new([ScaleField::new(
+                        None,
+                        bytes_id.into(),
+                        Some(format!("[u8; {}]", AccountAddress::LENGTH)),
+                        vec![],
+                    )]),
+                    vec![],
+                )
+            }
+            Type::Vector(elem) => {
+                let elem_id = self.register(elem, ty_args, registry, depth + 1, budget)?;
+                ScaleType::new(
+                    Path::default(),
+                    vec![],
+                    TypeDefSequence::new(elem_id.into()),
+                    vec![],
+                )
+            }
+            Type::Reference(ty) | Type::MutableReference(ty) => {
+                return self.register(ty, ty_args, registry, depth + 1, budget);
+            }
+            Type::TypeParameter(idx) => {
+                return self.register(type_arg(ty_args, *idx)?, &[], registry, depth + 1, budget);
+            }
+            Type::Struct(def) => {
+                let (struct_, struct_ty_args) = self.resolve_struct(def, ty_args)?;
+                let type_params = struct_ty_args
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, ty_arg)| {
+                        let id = self.register(ty_arg, &[], registry, depth + 1, budget)?;
+                        Ok(TypeParameter::new_portable(
+                            format!("T{idx}"),
+                            Some(id.into()),
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let fields = struct_
+                    .fields
+                    .iter()
+                    .map(|Field { name, tp }| {
+                        let id = self.register(tp, &struct_ty_args, registry, depth + 1, budget)?;
+                        let type_name = type_name(&substitute(tp, &struct_ty_args)?);
+                        Ok(ScaleField::new(
+                            Some(name.to_string()),
+                            id.into(),
+                            Some(type_name),
+                            vec![],
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                ScaleType::new(
+                    Path::from_segments_unchecked([
+                        def.id.address().to_hex_literal(),
+                        def.id.name().to_string(),
+                        def.name.to_string(),
+                    ]),
+                    type_params,
+                    TypeDefComposite::new(fields),
+                    vec![],
+                )
+            }
+        };
+        Ok(registry.register_type(ty))
+    }
+
+    /// Finds the declaration of the struct, and its type arguments with the type parameters of
+    /// the enclosing type substituted.
+    fn resolve_struct(&self, def: &StructDef, ty_args: &[Type]) -> Result<(&'a Struct, Vec<Type>)> {
+        let module = self
+            .abis
+            .iter()
+            .find(|abi| abi.id == def.id)
+            .ok_or_else(|| anyhow!("Missing the ABI of module {}", def.id))?;
+        let struct_ = module
+            .structs
+            .iter()
+            .find(|s| s.name == def.name)
+            .ok_or_else(|| anyhow!("Struct {}::{} not found", def.id, def.name))?;
+        if struct_.type_parameters.len() != def.fields.len() {
+            bail!(
+                "Struct {}::{} expects {} type arguments, got {}",
+                def.id,
+                def.name,
+                struct_.type_parameters.len(),
+                def.fields.len()
+            );
+        }
+        let struct_ty_args = def
+            .fields
+            .iter()
+            .map(|ty| substitute(ty, ty_args))
+            .collect::<Result<_>>()?;
+        Ok((struct_, struct_ty_args))
+    }
+}
+
+/// Bounds the traversal of a type by [`MAX_TYPE_DEPTH`] and [`MAX_TYPE_NODES`].
+#[derive(Default)]
+pub(crate) struct TypeBudget {
+    nodes: usize,
+}
+
+impl TypeBudget {
+    /// Counts a node of the type at `depth`, failing when a limit is exceeded.
+    pub(crate) fn visit(&mut self, depth: usize) -> Result<()> {
+        if depth > MAX_TYPE_DEPTH {
+            bail!(
+                "Type nesting exceeds the maximum depth of {}",
+                MAX_TYPE_DEPTH
+            );
+        }
+        self.nodes += 1;
+        if self.nodes > MAX_TYPE_NODES {
+            bail!("Type exceeds the maximum size of {} nodes", MAX_TYPE_NODES);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn type_arg(ty_args: &[Type], idx: u16) -> Result<&Type> {
+    ty_args
+        .get(idx as usize)
+        .ok_or_else(|| anyhow!("Unresolved type parameter T{}", idx))
+}
+
+/// Replaces the type parameters of `ty` with the given type arguments.
+pub(crate) fn substitute(ty: &Type, ty_args: &[Type]) -> Result<Type> {
+    Ok(match ty {
+        Type::TypeParameter(idx) => type_arg(ty_args, *idx)?.clone(),
+        Type::Vector(elem) => Type::Vector(Box::new(substitute(elem, ty_args)?)),
+        Type::Reference(ty) => Type::Reference(Box::new(substitute(ty, ty_args)?)),
+        Type::MutableReference(ty) => Type::MutableReference(Box::new(substitute(ty, ty_args)?)),
+        Type::Struct(def) => Type::Struct(StructDef {
+            id: def.id.clone(),
+            name: def.name.clone(),
+            fields: def
+                .fields
+                .iter()
+                .map(|ty| substitute(ty, ty_args))
+                .collect::<Result<_>>()?,
+        }),
+        ty => ty.clone(),
+    })
+}
+
+/// Move source name of the type, e.g. `vector<0x1::string::String>`.
+fn type_name(ty: &Type) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::U128 => "u128".to_string(),
+        Type::U256 => "u256".to_string(),
+        Type::Address => "address".to_string(),
+        Type::Signer => "signer".to_string(),
+        Type::Vector(elem) => format!("vector<{}>", type_name(elem)),
+        Type::Reference(ty) => format!("&{}", type_name(ty)),
+        Type::MutableReference(ty) => format!("&mut {}", type_name(ty)),
+        Type::TypeParameter(idx) => format!("T{idx}"),
+        Type::Struct(def) => {
+            let name = format!("{}::{}", def.id.short_str_lossless(), def.name);
+            if def.fields.is_empty() {
+                name
+            } else {
+                let ty_args: Vec<_> = def.fields.iter().map(type_name).collect();
+                format!("{}<{}>", name, ty_args.join(", "))
+            }
+        }
+    }
+}
+
+fn primitive(primitive: TypeDefPrimitive) -> ScaleType<PortableForm> {
+    ScaleType::new(Path::default(), vec![], primitive, vec![])
+}
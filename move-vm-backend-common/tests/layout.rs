@@ -0,0 +1,236 @@
+//! Integration tests for the resolution of type layouts.
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use move_core_types::value::{MoveStruct, MoveTypeLayout, MoveValue};
+use move_vm_backend_common::abi::{
+    Field, FieldLayout, ModuleAbi, Struct, StructDef, StructLayout, Type, TypeAbilities, TypeLayout,
+};
+use move_vm_backend_common::layout::resolve_type_layout;
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn module_id(name: &str) -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        ident(name),
+    )
+}
+
+fn struct_def(module: &str, name: &str, ty_args: Vec<Type>) -> StructDef {
+    StructDef {
+        id: module_id(module),
+        name: ident(name),
+        fields: ty_args,
+    }
+}
+
+fn struct_abi(name: &str, type_parameters: usize, fields: Vec<(&str, Type)>) -> Struct {
+    Struct {
+        name: ident(name),
+        type_parameters: vec![TypeAbilities { abilities: vec![] }; type_parameters],
+        abilities: TypeAbilities { abilities: vec![] },
+        fields: fields
+            .into_iter()
+            .map(|(name, tp)| Field {
+                name: ident(name),
+                tp,
+            })
+            .collect(),
+    }
+}
+
+fn module_abi(name: &str, structs: Vec<Struct>) -> ModuleAbi {
+    ModuleAbi {
+        id: module_id(name),
+        friends: vec![],
+        structs,
+        funcs: vec![],
+    }
+}
+
+fn struct_tag(module: &str, name: &str, type_params: Vec<TypeTag>) -> TypeTag {
+    TypeTag::Struct(Box::new(StructTag {
+        address: *module_id(module).address(),
+        module: ident(module),
+        name: ident(name),
+        type_params,
+    }))
+}
+
+/// ABIs of the modules:
+/// ```move
+/// module 0xCAFE::token { struct Token { id: u64 } }
+/// module 0xCAFE::vault {
+///     struct Entry<K, V> { key: K, value: V }
+///     struct Vault<T> { entries: vector<Entry<address, T>> }
+/// }
+/// ```
+fn abis() -> Vec<ModuleAbi> {
+    vec![
+        module_abi(
+            "token",
+            vec![struct_abi("Token", 0, vec![("id", Type::U64)])],
+        ),
+        module_abi(
+            "vault",
+            vec![
+                struct_abi(
+                    "Entry",
+                    2,
+                    vec![
+                        ("key", Type::TypeParameter(0)),
+                        ("value", Type::TypeParameter(1)),
+                    ],
+                ),
+                struct_abi(
+                    "Vault",
+                    1,
+                    vec![(
+                        "entries",
+                        Type::Vector(Box::new(Type::Struct(struct_def(
+                            "vault",
+                            "Entry",
+                            vec![Type::Address, Type::TypeParameter(0)],
+                        )))),
+                    )],
+                ),
+            ],
+        ),
+    ]
+}
+
+#[test]
+fn structs_are_expanded_with_their_type_arguments() {
+    let abis = abis();
+    let tag = struct_tag("vault", "Vault", vec![struct_tag("token", "Token", vec![])]);
+
+    let token = Type::Struct(struct_def("token", "Token", vec![]));
+    let token_layout = TypeLayout::Struct(StructLayout {
+        def: struct_def("token", "Token", vec![]),
+        fields: vec![FieldLayout {
+            name: ident("id"),
+            layout: TypeLayout::U64,
+        }],
+    });
+    let entry_layout = TypeLayout::Struct(StructLayout {
+        def: struct_def("vault", "Entry", vec![Type::Address, token.clone()]),
+        fields: vec![
+            FieldLayout {
+                name: ident("key"),
+                layout: TypeLayout::Address,
+            },
+            FieldLayout {
+                name: ident("value"),
+                layout: token_layout,
+            },
+        ],
+    });
+    let expected = TypeLayout::Struct(StructLayout {
+        def: struct_def("vault", "Vault", vec![token]),
+        fields: vec![FieldLayout {
+            name: ident("entries"),
+            layout: TypeLayout::Vector(Box::new(entry_layout)),
+        }],
+    });
+    let layout = resolve_type_layout(abis.as_slice(), &tag).unwrap();
+    assert_eq!(layout, expected);
+
+    // The layout decodes the BCS encoded values.
+    let owner = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+    let bcs = bcs::to_bytes(&vec![(owner, 7u64)]).unwrap();
+    let value = MoveValue::simple_deserialize(&bcs, &MoveTypeLayout::from(&layout)).unwrap();
+    let expected = MoveValue::Struct(MoveStruct::with_fields(vec![(
+        ident("entries"),
+        MoveValue::Vector(vec![MoveValue::Struct(MoveStruct::with_fields(vec![
+            (ident("key"), MoveValue::Address(owner)),
+            (
+                ident("value"),
+                MoveValue::Struct(MoveStruct::with_fields(vec![(
+                    ident("id"),
+                    MoveValue::U64(7),
+                )])),
+            ),
+        ]))]),
+    )]));
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn unresolvable_types_are_rejected() {
+    let abis = abis();
+    let token = || struct_tag("token", "Token", vec![]);
+
+    // Missing module.
+    assert!(resolve_type_layout(&abis[1..], &token()).is_err());
+    // Missing struct.
+    assert!(resolve_type_layout(abis.as_slice(), &struct_tag("token", "Coin", vec![])).is_err());
+    // Wrong number of type arguments.
+    let tag = struct_tag("vault", "Vault", vec![token(), token()]);
+    assert!(resolve_type_layout(abis.as_slice(), &tag).is_err());
+}
+
+#[test]
+fn recursive_structs_are_rejected() {
+    // Structs containing each other through a generic struct.
+    let mut abis = abis();
+    abis.push(module_abi(
+        "cycle",
+        vec![
+            struct_abi(
+                "A",
+                0,
+                vec![(
+                    "b",
+                    Type::Struct(struct_def(
+                        "vault",
+                        "Vault",
+                        vec![Type::Struct(struct_def("cycle", "B", vec![]))],
+                    )),
+                )],
+            ),
+            struct_abi(
+                "B",
+                0,
+                vec![("a", Type::Struct(struct_def("cycle", "A", vec![])))],
+            ),
+        ],
+    ));
+
+    let err = resolve_type_layout(abis.as_slice(), &struct_tag("cycle", "A", vec![])).unwrap_err();
+    assert!(err.to_string().contains("contains itself"), "{err}");
+}
+
+#[test]
+fn deeply_nested_types_are_rejected() {
+    let nested = |depth| (0..depth).fold(TypeTag::U8, |tag, _| TypeTag::Vector(Box::new(tag)));
+
+    assert!(resolve_type_layout(abis().as_slice(), &nested(100)).is_ok());
+    assert!(resolve_type_layout(abis().as_slice(), &nested(1000)).is_err());
+}
+
+#[test]
+fn exponentially_large_types_are_rejected() {
+    // Each level doubles the size of the layout.
+    let mut abis = abis();
+    abis.push(module_abi(
+        "pair",
+        vec![struct_abi(
+            "Pair",
+            1,
+            vec![
+                ("first", Type::TypeParameter(0)),
+                ("second", Type::TypeParameter(0)),
+            ],
+        )],
+    ));
+    let nested =
+        |depth| (0..depth).fold(TypeTag::U8, |tag, _| struct_tag("pair", "Pair", vec![tag]));
+
+    assert!(resolve_type_layout(abis.as_slice(), &nested(5)).is_ok());
+    let err = resolve_type_layout(abis.as_slice(), &nested(64)).unwrap_err();
+    assert!(err.to_string().contains("maximum size"), "{err}");
+}
@@ -0,0 +1,48 @@
+//! Tests for the Merkle commitment over Move accounts.
+
+use move_vm_backend_common::proof::{
+    merkle_proof, merkle_root, verify_merkle_proof, Hash, MerkleProof, EMPTY_ROOT,
+};
+
+fn leaves(count: u8) -> Vec<Hash> {
+    (0..count).map(|i| [i; 32]).collect()
+}
+
+#[test]
+fn proofs_verify_for_every_leaf() {
+    for count in 1..=9 {
+        let leaves = leaves(count);
+        let root = merkle_root(&leaves);
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle_proof(&leaves, index).unwrap();
+            assert!(verify_merkle_proof(&root, *leaf, &proof));
+        }
+        assert!(merkle_proof(&leaves, leaves.len()).is_none());
+    }
+}
+
+#[test]
+fn proofs_reject_wrong_leaves_and_positions() {
+    let leaves = leaves(5);
+    let root = merkle_root(&leaves);
+    let proof = merkle_proof(&leaves, 2).unwrap();
+
+    assert!(!verify_merkle_proof(&root, leaves[3], &proof));
+
+    let moved = MerkleProof {
+        leaf_index: 3,
+        ..proof.clone()
+    };
+    assert!(!verify_merkle_proof(&root, leaves[2], &moved));
+
+    let truncated = MerkleProof {
+        siblings: proof.siblings[1..].to_vec(),
+        ..proof
+    };
+    assert!(!verify_merkle_proof(&root, leaves[2], &truncated));
+}
+
+#[test]
+fn empty_account_has_empty_root() {
+    assert_eq!(merkle_root(&[]), EMPTY_ROOT);
+}
@@ -0,0 +1,244 @@
+//! Integration tests for the SCALE conversion of Move values.
+
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use move_vm_backend_common::abi::{
+    Field, Function, FunctionVisibility, ModuleAbi, Struct, StructDef, Type, TypeAbilities,
+};
+use move_vm_backend_common::scale::ScaleConverter;
+use parity_scale_codec::Encode;
+use scale_info::{TypeDef, TypeDefPrimitive};
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn module_id() -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        ident("bank"),
+    )
+}
+
+fn struct_type(name: &str, ty_args: Vec<Type>) -> Type {
+    Type::Struct(StructDef {
+        id: module_id(),
+        name: ident(name),
+        fields: ty_args,
+    })
+}
+
+fn field(name: &str, tp: Type) -> Field {
+    Field {
+        name: ident(name),
+        tp,
+    }
+}
+
+fn abilities() -> TypeAbilities {
+    TypeAbilities { abilities: vec![] }
+}
+
+/// ABI of a module declaring:
+/// ```move
+/// struct Token { id: u128, name: vector<u8> }
+/// struct Vault<T> { items: vector<T>, owner: address }
+/// public entry fun deposit(account: &signer, vault: Vault<Token>, amount: u64)
+/// ```
+fn bank_abi() -> ModuleAbi {
+    ModuleAbi {
+        id: module_id(),
+        friends: vec![],
+        structs: vec![
+            Struct {
+                name: ident("Token"),
+                type_parameters: vec![],
+                abilities: abilities(),
+                fields: vec![
+                    field("id", Type::U128),
+                    field("name", Type::Vector(Box::new(Type::U8))),
+                ],
+            },
+            Struct {
+                name: ident("Vault"),
+                type_parameters: vec![abilities()],
+                abilities: abilities(),
+                fields: vec![
+                    field("items", Type::Vector(Box::new(Type::TypeParameter(0)))),
+                    field("owner", Type::Address),
+                ],
+            },
+        ],
+        funcs: vec![Function {
+            name: ident("deposit"),
+            visibility: FunctionVisibility::Public,
+            type_parameters: vec![],
+            parameters: vec![
+                Type::Reference(Box::new(Type::Signer)),
+                vault_of_tokens(),
+                Type::U64,
+            ],
+            returns: vec![],
+            is_entry: true,
+        }],
+    }
+}
+
+fn vault_of_tokens() -> Type {
+    struct_type("Vault", vec![struct_type("Token", vec![])])
+}
+
+#[test]
+fn values_are_converted_between_bcs_and_scale() {
+    let abis = [bank_abi()];
+    let converter = ScaleConverter::new(&abis);
+    let owner = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+    let items = vec![(7u128, b"abc".to_vec()), (8u128, b"de".to_vec())];
+
+    let bcs = bcs::to_bytes(&(items.clone(), owner)).unwrap();
+    let scale = (items, owner.into_bytes()).encode();
+
+    let ty = vault_of_tokens();
+    assert_eq!(converter.bcs_to_scale(&ty, &bcs).unwrap(), scale);
+    assert_eq!(converter.scale_to_bcs(&ty, &scale).unwrap(), bcs);
+
+    // Signers are passed as their address.
+    let signer = Type::Reference(Box::new(Type::Signer));
+    let bcs = bcs::to_bytes(&owner).unwrap();
+    assert_eq!(
+        converter.bcs_to_scale(&signer, &bcs).unwrap(),
+        owner.encode()
+    );
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    let abis = [bank_abi()];
+    let converter = ScaleConverter::new(&abis);
+
+    let scale = (
+        vec![(7u128, b"abc".to_vec())],
+        [0u8; AccountAddress::LENGTH],
+    )
+        .encode();
+    assert!(converter
+        .scale_to_bcs(&vault_of_tokens(), &scale[..scale.len() - 1])
+        .is_err());
+    assert!(converter.bcs_to_scale(&Type::U64, &[1, 2]).is_err());
+}
+
+#[test]
+fn unresolvable_types_are_rejected() {
+    let abis = [bank_abi()];
+    let converter = ScaleConverter::new(&abis);
+
+    // The module of the struct isn't part of the converter.
+    assert!(ScaleConverter::new(&[])
+        .type_layout(&vault_of_tokens())
+        .is_err());
+    // Unknown struct.
+    assert!(converter
+        .type_layout(&struct_type("Missing", vec![]))
+        .is_err());
+    // Wrong number of type arguments.
+    assert!(converter
+        .type_layout(&struct_type("Vault", vec![]))
+        .is_err());
+    // Type parameters without type arguments.
+    assert!(converter.type_layout(&Type::TypeParameter(0)).is_err());
+}
+
+#[test]
+fn recursive_structs_are_rejected() {
+    let mut abi = bank_abi();
+    abi.structs.push(Struct {
+        name: ident("Loop"),
+        type_parameters: vec![],
+        abilities: abilities(),
+        fields: vec![field("next", struct_type("Loop", vec![]))],
+    });
+    let abis = [abi];
+    let converter = ScaleConverter::new(&abis);
+
+    let ty = struct_type("Loop", vec![]);
+    assert!(converter.type_layout(&ty).is_err());
+    assert!(converter
+        .register_type(&ty, &mut Default::default())
+        .is_err());
+}
+
+#[test]
+fn exponentially_large_types_are_rejected() {
+    // Each level doubles the size of the layout.
+    let mut abi = bank_abi();
+    abi.structs.push(Struct {
+        name: ident("Pair"),
+        type_parameters: vec![abilities()],
+        abilities: abilities(),
+        fields: vec![
+            field("first", Type::TypeParameter(0)),
+            field("second", Type::TypeParameter(0)),
+        ],
+    });
+    let abis = [abi];
+    let converter = ScaleConverter::new(&abis);
+    let nested = |depth| (0..depth).fold(Type::U8, |ty, _| struct_type("Pair", vec![ty]));
+
+    assert!(converter.type_layout(&nested(5)).is_ok());
+    assert!(converter
+        .register_type(&nested(5), &mut Default::default())
+        .is_ok());
+    let err = converter.type_layout(&nested(64)).unwrap_err();
+    assert!(err.to_string().contains("maximum size"), "{err}");
+    assert!(converter
+        .register_type(&nested(64), &mut Default::default())
+        .is_err());
+}
+
+#[test]
+fn module_types_are_registered() {
+    let abis = [bank_abi()];
+    let registry = ScaleConverter::new(&abis)
+        .module_registry(&abis[0])
+        .unwrap();
+
+    let find = |name: &str| {
+        registry
+            .types
+            .iter()
+            .find(|ty| ty.ty.path.segments.last().map(String::as_str) == Some(name))
+            .unwrap_or_else(|| panic!("{name} isn't registered"))
+    };
+    let primitive = |id: u32| match &registry.resolve(id).unwrap().type_def {
+        TypeDef::Primitive(primitive) => primitive.clone(),
+        def => panic!("{def:?} isn't a primitive"),
+    };
+
+    let token = find("Token");
+    assert_eq!(token.ty.path.segments, ["0xcafe", "bank", "Token"]);
+    let TypeDef::Composite(composite) = &token.ty.type_def else {
+        panic!("Token isn't a composite");
+    };
+    let fields: Vec<_> = composite
+        .fields
+        .iter()
+        .map(|f| (f.name.as_deref().unwrap(), f.type_name.as_deref().unwrap()))
+        .collect();
+    assert_eq!(fields, [("id", "u128"), ("name", "vector<u8>")]);
+    assert_eq!(primitive(composite.fields[0].ty.id), TypeDefPrimitive::U128);
+
+    // The instantiation used by the function is registered with its type argument.
+    let vault = find("Vault");
+    assert_eq!(vault.ty.type_params.len(), 1);
+    assert_eq!(vault.ty.type_params[0].ty.unwrap().id, token.id);
+    let TypeDef::Composite(composite) = &vault.ty.type_def else {
+        panic!("Vault isn't a composite");
+    };
+    assert_eq!(
+        composite.fields[0].type_name.as_deref(),
+        Some("vector<0xcafe::bank::Token>")
+    );
+
+    find("AccountAddress");
+}
@@ -19,7 +19,8 @@ pub trait BalanceHandler {
     fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error>;
 }
 
-/// An unused [`BalanceHandler`] implementation that is needed for special cases (genesis configuration).
+/// A [`BalanceHandler`] without any balances, for special cases (genesis configuration without a
+/// balance handler). Accessing a balance fails.
 pub(crate) struct DummyBalanceHandler;
 
 impl BalanceHandler for DummyBalanceHandler {
@@ -31,14 +32,14 @@ impl BalanceHandler for DummyBalanceHandler {
         _dst: AccountAddress,
         _cheque_amount: u128,
     ) -> Result<bool, Self::Error> {
-        unreachable!()
+        Err(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
     }
 
     fn cheque_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
-        unreachable!()
+        Err(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
     }
 
     fn total_amount(&self, _account: AccountAddress) -> Result<u128, Self::Error> {
-        unreachable!()
+        Err(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
     }
 }
@@ -0,0 +1,43 @@
+use move_core_types::vm_status::StatusCode;
+use move_stdlib::natives::cross_vm::CrossVmResolver;
+
+pub use move_stdlib::natives::cross_vm::{CrossVmCall, CrossVmOutput};
+
+/// Trait for a cross-VM call handler.
+///
+/// This is used to dispatch the calls made with the `cross_vm` natives from within the MoveVM to
+/// the contracts of other VMs running on the chain, e.g. the ink! contracts of pallet-contracts.
+///
+/// The handler is responsible for reverting the effects of the calls if the Move script or
+/// function call fails afterwards.
+pub trait CrossVmHandler {
+    type Error: Into<StatusCode>;
+
+    /// Execute the call. The input of the contract is the message selector followed by the SCALE
+    /// encoded arguments.
+    ///
+    /// The gas limit and the gas used are in Move gas units, which the handler converts into the
+    /// weight of the other VM.
+    fn call(&self, call: CrossVmCall) -> Result<CrossVmOutput, Self::Error>;
+}
+
+/// The [`CrossVmHandler`] of VMs created without one: it can't be instantiated, so calls to other
+/// VMs abort.
+pub enum NoCrossVmHandler {}
+
+impl CrossVmHandler for NoCrossVmHandler {
+    type Error = StatusCode;
+
+    fn call(&self, _call: CrossVmCall) -> Result<CrossVmOutput, Self::Error> {
+        match *self {}
+    }
+}
+
+/// Exposes a [`CrossVmHandler`] to the `cross_vm` natives.
+pub(crate) struct CrossVmDispatcher<'a, C: CrossVmHandler>(pub(crate) &'a C);
+
+impl<'a, C: CrossVmHandler> CrossVmResolver for CrossVmDispatcher<'a, C> {
+    fn call(&self, call: CrossVmCall) -> Result<CrossVmOutput, StatusCode> {
+        self.0.call(call).map_err(Into::into)
+    }
+}
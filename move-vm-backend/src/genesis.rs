@@ -1,17 +1,26 @@
 //! Provides a configuration to prepare the initial MoveVM storage state.
 
-use crate::balance::DummyBalanceHandler;
+use crate::balance::{BalanceHandler, DummyBalanceHandler};
 use crate::Mvm;
 use crate::VmResult;
 use crate::{storage::Storage, types::GasStrategy};
 use alloc::borrow::Cow;
 use alloc::borrow::ToOwned;
 use alloc::vec::Vec;
+use anyhow::{anyhow, bail, Error};
 use core::cell::RefCell;
 use core::fmt;
 use hashbrown::HashMap;
-use move_core_types::language_storage::CORE_CODE_ADDRESS;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::effects::{ChangeSet, Op};
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS};
+use move_core_types::value::MoveValue;
+use move_core_types::vm_status::StatusCode;
 use move_stdlib::{move_stdlib_bundle, substrate_stdlib_bundle};
+use move_vm_backend_common::abi::TypeAbility;
+use move_vm_backend_common::layout::AbiResolver;
+use serde::{Deserialize, Serialize};
 
 /// Error codes for [`GenesisConfig`].
 #[derive(Debug)]
@@ -20,6 +29,12 @@ pub enum GenesisConfigError {
     MoveVmInitFailure,
     /// Publish bundle failure.
     PublishBundle(VmResult),
+    /// Initial resources couldn't be stored.
+    InitialResources(Error),
+    /// Initialization function failure.
+    InitFunction(VmResult),
+    /// The balance handler refused a transfer made by the initialization functions.
+    BalanceTransfer(StatusCode),
 }
 
 impl fmt::Display for GenesisConfigError {
@@ -27,6 +42,11 @@ impl fmt::Display for GenesisConfigError {
         match self {
             Self::MoveVmInitFailure => write!(f, "MoveVM failed to initalize"),
             Self::PublishBundle(vm_result) => write!(f, "Publish bundle failed: {:?}", vm_result),
+            Self::InitialResources(err) => write!(f, "Storing initial resources failed: {}", err),
+            Self::InitFunction(vm_result) => {
+                write!(f, "Initialization function failed: {:?}", vm_result)
+            }
+            Self::BalanceTransfer(status) => write!(f, "Balance transfer failed: {:?}", status),
         }
     }
 }
@@ -34,13 +54,73 @@ impl fmt::Display for GenesisConfigError {
 /// Configuration to prepare the initial MoveVM storage state.
 ///
 /// By default - a precompiled standard library is used from the `substrate-move` repository.
+///
+/// The configuration is applied in the following order: the standard libraries are published,
+/// then the additional bundles, then the initial resources are stored and finally the
+/// initialization functions are executed. Nothing is written to the storage and no balance is
+/// transferred unless all steps succeed.
+///
+/// The configuration can be (de)serialized, e.g. as part of a chain specification. Balances are
+/// not part of the MoveVM storage, so they must be configured in the genesis of the balance
+/// handler.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct VmGenesisConfig {
     /// Move standard library.
     stdlib_bundle: Vec<u8>,
     /// An extended standard library for Substrate framework.
     substrate_stdlib_bundle: Vec<u8>,
-    // - additional_bundles: Vec<Vec<u8>>,
-    // - initial_script
+    /// Bundles published after the standard libraries, in order.
+    additional_bundles: Vec<GenesisBundle>,
+    /// Accounts with resources stored after all bundles are published.
+    initial_accounts: Vec<GenesisAccount>,
+    /// Entry functions executed once the storage is populated, in order.
+    init_functions: Vec<GenesisFunction>,
+}
+
+/// Bundle of modules published at genesis.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisBundle {
+    /// Address the modules are published under.
+    pub address: AccountAddress,
+    /// Bundle of modules, as accepted by [`Mvm::publish_module_bundle`].
+    pub bundle: Vec<u8>,
+}
+
+/// Account with resources stored at genesis.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisAccount {
+    /// Address of the account.
+    pub address: AccountAddress,
+    /// Resources stored under the account.
+    pub resources: Vec<GenesisResource>,
+}
+
+/// Resource stored at genesis.
+///
+/// The resource must be of a struct with the `key` ability, and its value must match the layout
+/// of the struct.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisResource {
+    /// Type of the resource, which must be declared by a module published at genesis.
+    pub tag: StructTag,
+    /// BCS encoded value of the resource.
+    pub value: Vec<u8>,
+}
+
+/// Entry function executed at genesis.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenesisFunction {
+    /// Address of the module declaring the function.
+    pub module_address: AccountAddress,
+    /// Name of the module declaring the function.
+    pub module_name: Identifier,
+    /// Name of the function.
+    pub function_name: Identifier,
+    /// Type arguments.
+    pub type_args: Vec<TypeTag>,
+    /// BCS encoded arguments, with signers represented by their address.
+    pub args: Vec<Vec<u8>>,
 }
 
 impl Default for VmGenesisConfig {
@@ -48,6 +128,9 @@ impl Default for VmGenesisConfig {
         Self {
             stdlib_bundle: move_stdlib_bundle().to_vec(),
             substrate_stdlib_bundle: substrate_stdlib_bundle().to_vec(),
+            additional_bundles: Vec::new(),
+            initial_accounts: Vec::new(),
+            init_functions: Vec::new(),
         }
     }
 }
@@ -63,15 +146,45 @@ impl VmGenesisConfig {
         self.substrate_stdlib_bundle = bundle;
     }
 
+    /// Add a bundle to publish under `address` after the standard libraries and the bundles
+    /// added before.
+    pub fn add_bundle(&mut self, address: AccountAddress, bundle: Vec<u8>) {
+        self.additional_bundles
+            .push(GenesisBundle { address, bundle });
+    }
+
+    /// Add an account with resources to store once all bundles are published.
+    pub fn add_account(&mut self, account: GenesisAccount) {
+        self.initial_accounts.push(account);
+    }
+
+    /// Add an entry function to execute once the storage is populated, after the functions added
+    /// before.
+    pub fn add_init_function(&mut self, function: GenesisFunction) {
+        self.init_functions.push(function);
+    }
+
     /// Apply the configuration to the storage.
+    ///
+    /// There are no balances: initialization functions accessing them fail.
     pub fn apply<S: Storage>(self, storage: S) -> Result<(), GenesisConfigError> {
+        self.apply_with_balance_handler(storage, DummyBalanceHandler {})
+    }
+
+    /// Apply the configuration to the storage, with the balance handler used by the
+    /// initialization functions.
+    pub fn apply_with_balance_handler<S: Storage, B: BalanceHandler>(
+        self,
+        storage: S,
+        balance_handler: B,
+    ) -> Result<(), GenesisConfigError> {
         let storage_safe = StorageSafe::new(storage);
-        let vm = Mvm::new(&storage_safe, DummyBalanceHandler {})
+        let balance_safe = BalanceSafe::new(balance_handler);
+        let vm = Mvm::new(&storage_safe, &balance_safe)
             .map_err(|_| GenesisConfigError::MoveVmInitFailure)?;
 
-        let publish_under_stdaddr = |bundle: &[u8]| {
-            let result =
-                vm.publish_module_bundle(bundle, CORE_CODE_ADDRESS, GasStrategy::Unmetered);
+        let publish_bundle = |bundle: &[u8], address| {
+            let result = vm.publish_module_bundle(bundle, address, GasStrategy::Unmetered);
 
             if !result.is_ok() {
                 return Err(GenesisConfigError::PublishBundle(result));
@@ -79,16 +192,160 @@ impl VmGenesisConfig {
             Ok(())
         };
 
-        publish_under_stdaddr(&self.stdlib_bundle)?;
-        publish_under_stdaddr(&self.substrate_stdlib_bundle)?;
+        publish_bundle(&self.stdlib_bundle, CORE_CODE_ADDRESS)?;
+        publish_bundle(&self.substrate_stdlib_bundle, CORE_CODE_ADDRESS)?;
+        for GenesisBundle { address, bundle } in &self.additional_bundles {
+            publish_bundle(bundle, *address)?;
+        }
+
+        let mut changeset = ChangeSet::new();
+        for account in self.initial_accounts {
+            for GenesisResource { tag, value } in account.resources {
+                check_resource(&vm, &tag, &value).map_err(GenesisConfigError::InitialResources)?;
+                changeset
+                    .add_resource_op(account.address, tag, Op::New(value))
+                    .map_err(GenesisConfigError::InitialResources)?;
+            }
+        }
+        vm.warehouse
+            .apply_changes(changeset)
+            .map_err(GenesisConfigError::InitialResources)?;
+
+        for function in self.init_functions {
+            let result = vm.execute_function(
+                function.module_address,
+                function.module_name,
+                function.function_name,
+                function.type_args,
+                function.args.iter().map(Vec::as_slice).collect(),
+                GasStrategy::Unmetered,
+            );
 
-        // In case of the successful initialization, apply changes to the storage.
+            if !result.is_ok() {
+                return Err(GenesisConfigError::InitFunction(result));
+            }
+        }
+
+        // In case of the successful initialization, apply changes to the balances and the storage.
+        balance_safe
+            .apply_transfers()
+            .map_err(GenesisConfigError::BalanceTransfer)?;
         storage_safe.apply_changes();
 
         Ok(())
     }
 }
 
+/// Checks that the resource is of a struct declared with the `key` ability and that its value
+/// matches the layout of the struct.
+fn check_resource<S: Storage, B: BalanceHandler>(
+    vm: &Mvm<S, B>,
+    tag: &StructTag,
+    value: &[u8],
+) -> Result<(), Error> {
+    let module_id = tag.module_id();
+    let abi = vm
+        .warehouse
+        .get_module_abi(&module_id)?
+        .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+    let def = abi
+        .structs
+        .iter()
+        .find(|def| def.name == tag.name)
+        .ok_or_else(|| anyhow!("Struct {} not found", tag))?;
+    if !def.abilities.abilities.contains(&TypeAbility::Key) {
+        bail!("Struct {} doesn't have the key ability", tag);
+    }
+
+    let layout = vm.resource_layout(tag.clone())?;
+    MoveValue::simple_deserialize(value, &layout)
+        .map_err(|err| anyhow!("Value of {} doesn't match its layout: {}", tag, err))?;
+    Ok(())
+}
+
+/// Balance safe stages the transfers made by the initialization functions until they are
+/// specifically applied, so a failing genesis leaves the balances untouched.
+struct BalanceSafe<B: BalanceHandler> {
+    /// The balance handler receiving the transfers.
+    inner: B,
+    /// Transfers made so far, in order.
+    transfers: RefCell<Vec<(AccountAddress, AccountAddress, u128)>>,
+}
+
+impl<B: BalanceHandler> BalanceSafe<B> {
+    /// Creates a [`BalanceSafe`].
+    fn new(balance_handler: B) -> BalanceSafe<B> {
+        BalanceSafe {
+            inner: balance_handler,
+            transfers: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Amount held by the account once the staged transfers are applied.
+    fn staged_amount(&self, account: AccountAddress, amount: u128) -> Result<u128, StatusCode> {
+        let transfers = self.transfers.borrow();
+        let received = transfers
+            .iter()
+            .filter(|(_, dst, _)| *dst == account)
+            .try_fold(0u128, |sum, (_, _, amount)| sum.checked_add(*amount));
+        let sent = transfers
+            .iter()
+            .filter(|(src, _, _)| *src == account)
+            .try_fold(0u128, |sum, (_, _, amount)| sum.checked_add(*amount));
+        received
+            .zip(sent)
+            .and_then(|(received, sent)| amount.checked_add(received)?.checked_sub(sent))
+            .ok_or(StatusCode::ARITHMETIC_ERROR)
+    }
+
+    /// Finally applies the staged transfers to the balance handler.
+    ///
+    /// The transfers were checked against the staged balances, so they only fail if the balance
+    /// handler disagrees. In that case, the transfers already applied are reverted.
+    fn apply_transfers(self) -> Result<(), StatusCode> {
+        let transfers = self.transfers.take();
+        for (applied, &(src, dst, amount)) in transfers.iter().enumerate() {
+            let status = match self.inner.transfer(src, dst, amount) {
+                Ok(true) => continue,
+                Ok(false) => StatusCode::INSUFFICIENT_BALANCE,
+                Err(err) => err.into(),
+            };
+            for &(src, dst, amount) in transfers[..applied].iter().rev() {
+                let _ = self.inner.transfer(dst, src, amount);
+            }
+            return Err(status);
+        }
+        Ok(())
+    }
+}
+
+impl<B: BalanceHandler> BalanceHandler for &BalanceSafe<B> {
+    type Error = StatusCode;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        if self.cheque_amount(src)? < cheque_amount {
+            return Err(StatusCode::INSUFFICIENT_BALANCE);
+        }
+        self.transfers.borrow_mut().push((src, dst, cheque_amount));
+        Ok(true)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        let amount = self.inner.cheque_amount(account).map_err(Into::into)?;
+        self.staged_amount(account, amount)
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        let amount = self.inner.total_amount(account).map_err(Into::into)?;
+        self.staged_amount(account, amount)
+    }
+}
+
 /// Storage safe keeps internal storage immutable until the changes are specificially applied.
 struct StorageSafe<S: Storage> {
     /// A safe place for our storage.
@@ -0,0 +1,85 @@
+//! Module initializers, run once when a module is published for the first time.
+//!
+//! A module can declare a private `init_module` function which takes the publisher as its only
+//! parameter, a `signer` or `&signer`, and returns nothing. It is executed in the session
+//! publishing the module, so the module is published only if its initializer succeeds, and
+//! nobody can call it before the publisher does.
+
+use crate::{balance::BalanceHandler, storage::Storage, warehouse::Warehouse};
+use alloc::vec::Vec;
+use move_binary_format::{
+    access::ModuleAccess,
+    errors::VMResult,
+    file_format::{SignatureToken, Visibility},
+    CompiledModule,
+};
+use move_core_types::{
+    account_address::AccountAddress, ident_str, identifier::IdentStr, language_storage::ModuleId,
+    resolver::ModuleResolver,
+};
+use move_vm_runtime::session::Session;
+use move_vm_types::gas::GasMeter;
+
+/// Name of the module initializer function.
+const INIT_MODULE_FUNCTION: &IdentStr = ident_str!("init_module");
+
+/// Modules among `modules` which are not published yet and declare an initializer.
+pub(crate) fn modules_to_initialize<S, B>(
+    warehouse: &Warehouse<S, B>,
+    modules: &[CompiledModule],
+) -> Vec<ModuleId>
+where
+    S: Storage,
+    B: BalanceHandler,
+{
+    modules
+        .iter()
+        .filter(|module| has_initializer(module))
+        .map(|module| module.self_id())
+        .filter(|id| matches!(warehouse.get_module(id), Ok(None)))
+        .collect()
+}
+
+/// Whether the module declares a valid initializer.
+fn has_initializer(module: &CompiledModule) -> bool {
+    module.function_defs().iter().any(|def| {
+        let handle = module.function_handle_at(def.function);
+        if module.identifier_at(handle.name) != INIT_MODULE_FUNCTION {
+            return false;
+        }
+
+        let is_signer = |ty: &SignatureToken| match ty {
+            SignatureToken::Signer => true,
+            SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+            _ => false,
+        };
+        let parameters = &module.signature_at(handle.parameters).0;
+        def.visibility == Visibility::Private
+            && handle.type_parameters.is_empty()
+            && matches!(parameters.as_slice(), [ty] if is_signer(ty))
+            && module.signature_at(handle.return_).is_empty()
+    })
+}
+
+/// Run the initializers of the `modules` published in the session, with `publisher` as signer.
+pub(crate) fn initialize_modules<S, B>(
+    sess: &mut Session<'_, '_, Warehouse<S, B>>,
+    modules: &[ModuleId],
+    publisher: AccountAddress,
+    gas_meter: &mut impl GasMeter,
+) -> VMResult<()>
+where
+    S: Storage,
+    B: BalanceHandler,
+{
+    for module_id in modules {
+        sess.execute_function_bypass_visibility(
+            module_id,
+            INIT_MODULE_FUNCTION,
+            Vec::new(),
+            alloc::vec![publisher.to_vec()],
+            gas_meter,
+        )?;
+    }
+    Ok(())
+}
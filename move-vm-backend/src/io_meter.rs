@@ -0,0 +1,139 @@
+use crate::types::{IoLimits, IoUsage};
+use alloc::{collections::BTreeSet, string::String, vec::Vec};
+use anyhow::{anyhow, Error};
+use core::cell::{Cell, RefCell};
+use move_core_types::{
+    account_address::AccountAddress,
+    effects::{ChangeSet, Event, Op},
+    language_storage::{ModuleId, StructTag},
+    resolver::{BalanceResolver, ModuleResolver, ResourceResolver},
+    vm_status::StatusCode,
+};
+
+/// Resolver measuring the resources a session reads from the wrapped resolver, and failing the
+/// read which crosses one of the read or account limits.
+///
+/// The session caches the resources it loaded, so each resource is read (and counted) once.
+pub(crate) struct IoMeter<'r, R> {
+    resolver: &'r R,
+    limits: &'r IoLimits,
+    accounts: RefCell<BTreeSet<AccountAddress>>,
+    reads: Cell<u64>,
+    read_bytes: Cell<u64>,
+    /// The limit crossed by a read, which the session then fails with
+    exceeded: RefCell<Option<(StatusCode, String)>>,
+}
+
+impl<'r, R> IoMeter<'r, R> {
+    pub(crate) fn new(resolver: &'r R, limits: &'r IoLimits) -> Self {
+        Self {
+            resolver,
+            limits,
+            accounts: RefCell::new(BTreeSet::new()),
+            reads: Cell::new(0),
+            read_bytes: Cell::new(0),
+            exceeded: RefCell::new(None),
+        }
+    }
+
+    /// The status code and the description of the read or account limit crossed, if any.
+    pub(crate) fn exceeded(&self) -> Option<(StatusCode, String)> {
+        self.exceeded.borrow().clone()
+    }
+
+    /// Usage of the session, including the changes and events it produced.
+    pub(crate) fn usage(&self, changeset: Option<&ChangeSet>, events: &[Event]) -> IoUsage {
+        let mut write_bytes = 0u64;
+        if let Some(changeset) = changeset {
+            for account in changeset.accounts().values() {
+                for op in account.resources().values() {
+                    if let Op::New(blob) | Op::Modify(blob) = op {
+                        write_bytes = write_bytes.saturating_add(blob.len() as u64);
+                    }
+                }
+            }
+        }
+
+        IoUsage {
+            write_bytes,
+            events: events.len() as u64,
+            event_bytes: events
+                .iter()
+                .map(|(_, _, _, data)| data.len() as u64)
+                .fold(0, u64::saturating_add),
+            ..self.read_usage()
+        }
+    }
+
+    fn read_usage(&self) -> IoUsage {
+        IoUsage {
+            accounts: self.accounts.borrow().len() as u64,
+            reads: self.reads.get(),
+            read_bytes: self.read_bytes.get(),
+            ..Default::default()
+        }
+    }
+
+    /// Fails if the reads so far crossed a limit, remembering it for the session.
+    fn check_reads(&self) -> Result<(), Error> {
+        self.limits
+            .check_reads(&self.read_usage())
+            .map_err(|(status_code, msg)| {
+                let err = anyhow!("{}", msg);
+                *self.exceeded.borrow_mut() = Some((status_code, msg));
+                err
+            })
+    }
+}
+
+impl<'r, R: ModuleResolver> ModuleResolver for IoMeter<'r, R> {
+    type Error = R::Error;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.resolver.get_module(module_id)
+    }
+}
+
+impl<'r, R: ResourceResolver<Error = Error>> ResourceResolver for IoMeter<'r, R> {
+    type Error = Error;
+
+    fn get_resource(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Vec<u8>>, Self::Error> {
+        self.accounts.borrow_mut().insert(*address);
+        self.reads.set(self.reads.get().saturating_add(1));
+        self.check_reads()?;
+
+        let resource = self.resolver.get_resource(address, tag)?;
+        if let Some(blob) = &resource {
+            self.read_bytes
+                .set(self.read_bytes.get().saturating_add(blob.len() as u64));
+        }
+        self.check_reads()?;
+
+        Ok(resource)
+    }
+}
+
+impl<'r, R: BalanceResolver> BalanceResolver for IoMeter<'r, R> {
+    type Error = R::Error;
+
+    fn transfer(
+        &self,
+        src: AccountAddress,
+        dst: AccountAddress,
+        cheque_amount: u128,
+    ) -> Result<bool, Self::Error> {
+        self.resolver.transfer(src, dst, cheque_amount)
+    }
+
+    fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.resolver.cheque_amount(account)
+    }
+
+    fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
+        self.resolver.total_amount(account)
+    }
+}
@@ -3,43 +3,83 @@
 extern crate alloc;
 
 pub mod balance;
+pub mod cross_vm;
 pub mod genesis;
+mod initializer;
+mod io_meter;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod storage;
 pub mod types;
 mod warehouse;
 
+use crate::io_meter::IoMeter;
 use crate::storage::Storage;
 use crate::types::{Call, Transaction, VmResult};
-use crate::warehouse::Warehouse;
-use alloc::{format, string::ToString, vec::Vec};
+use crate::warehouse::{ModuleDependencies, Warehouse};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use anyhow::{anyhow, Error};
 use balance::BalanceHandler;
-use move_binary_format::{errors::VMResult, file_format::CompiledModule};
+use cross_vm::{CrossVmDispatcher, CrossVmHandler, NoCrossVmHandler};
+use move_binary_format::{
+    errors::{Location, PartialVMError, VMResult},
+    file_format::CompiledModule,
+};
 use move_core_types::{
     account_address::AccountAddress,
-    effects::{ChangeSet, Event},
+    effects::{ChangeSet, Event, Op},
     identifier::Identifier,
-    language_storage::{ModuleId, TypeTag, CORE_CODE_ADDRESS},
+    language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
     resolver::{ModuleResolver, ResourceResolver},
+    scale,
+    value::{MoveTypeLayout, MoveValue},
     vm_status::StatusCode,
 };
-use move_stdlib::natives::all_natives;
+use move_stdlib::natives::{all_natives, cross_vm::NativeCrossVmContext};
 use move_vm_backend_common::{
-    abi::ModuleAbi, gas_schedule::NATIVE_COST_PARAMS, types::ModuleBundle,
+    abi::{ModuleAbi, TypeLayout},
+    gas_schedule::NATIVE_COST_PARAMS,
+    layout::resolve_type_layout,
+    types::ModuleBundle,
+};
+use move_vm_runtime::{
+    config::VMConfig, move_vm::MoveVM, native_extensions::NativeContextExtensions,
 };
-use move_vm_runtime::move_vm::MoveVM;
-use types::{GasHandler, GasStrategy};
+use types::{GasHandler, GasStrategy, IoLimits, IoUsage, ModuleRemoval, VerificationConfig};
+
+pub use move_vm_backend_common::proof::{
+    verify_resource_proof, Hash, MerkleProof, ResourceProof, EMPTY_ROOT,
+};
+pub use move_vm_runtime::execution_trace::{
+    ExecutionTrace, ExecutionTracer, ResourceAccessKind, TraceEvent,
+};
+pub use move_vm_runtime::verified_module_cache::VerifiedModuleCache;
 
 /// Main MoveVM structure, which is used to represent the virutal machine itself.
-pub struct Mvm<S, B>
+pub struct Mvm<S, B, C = NoCrossVmHandler>
 where
     S: Storage,
     B: BalanceHandler,
+    C: CrossVmHandler,
 {
     // MoveVM instance - from move_vm_runtime crate
     vm: MoveVM,
     // Storage instance
     warehouse: Warehouse<S, B>,
+    // Cache of verified modules shared with other instances, if any
+    #[cfg_attr(not(feature = "parallel"), allow(dead_code))]
+    module_cache: Option<VerifiedModuleCache>,
+    // Limits and cost of the bytecode verifier when publishing
+    verification: VerificationConfig,
+    // Limits on the global storage accessed by a single call
+    io_limits: IoLimits,
+    // Handler of the calls to the contracts of other VMs, if any
+    cross_vm: Option<C>,
 }
 
 impl<S, B> Mvm<S, B>
@@ -49,27 +89,86 @@ where
 {
     /// Create a new Move VM with the given storage.
     pub fn new(storage: S, balance_handler: B) -> Result<Mvm<S, B>, Error> {
-        Self::new_with_config(storage, balance_handler)
+        Self::new_with_config(
+            storage,
+            balance_handler,
+            None,
+            VerificationConfig::default(),
+        )
+    }
+
+    /// Create a new Move VM with the given storage, verifying published modules with the given
+    /// limits and cost.
+    pub fn new_with_verification_config(
+        storage: S,
+        balance_handler: B,
+        verification: VerificationConfig,
+    ) -> Result<Mvm<S, B>, Error> {
+        Self::new_with_config(storage, balance_handler, None, verification)
+    }
+
+    /// Create a new Move VM with the given storage, sharing the cache of verified modules with
+    /// other instances created with the same cache.
+    ///
+    /// Modules found in the cache are not deserialized and verified again, so the cache should
+    /// outlive the instances (e.g. be kept for the lifetime of the node). Modules upgraded
+    /// through this instance are invalidated in the cache once the changes are committed.
+    pub fn new_with_module_cache(
+        storage: S,
+        balance_handler: B,
+        module_cache: VerifiedModuleCache,
+    ) -> Result<Mvm<S, B>, Error> {
+        Self::new_with_config(
+            storage,
+            balance_handler,
+            Some(module_cache),
+            VerificationConfig::default(),
+        )
     }
 
     /// Create a new Move VM with the given storage and configuration.
     pub(crate) fn new_with_config(
         storage: S,
         balance_handler: B,
-        // config: VMConfig,
+        module_cache: Option<VerifiedModuleCache>,
+        verification: VerificationConfig,
     ) -> Result<Mvm<S, B>, Error> {
         Ok(Mvm {
-            // TODO(rqnsom): see if we can avoid GAS_PARAMS cloning
-            vm: MoveVM::new(all_natives(CORE_CODE_ADDRESS, NATIVE_COST_PARAMS.clone())).map_err(
-                |err| {
-                    let (code, _, msg, _, _, _, _) = err.all_data();
-                    anyhow!("Error code:{:?}: msg: '{}'", code, msg.unwrap_or_default())
-                },
-            )?,
-            warehouse: Warehouse::new(storage, balance_handler),
+            vm: new_move_vm(module_cache.clone(), &verification)?,
+            warehouse: Warehouse::new(storage, balance_handler, module_cache.clone()),
+            module_cache,
+            verification,
+            io_limits: IoLimits::default(),
+            cross_vm: None,
         })
     }
 
+    /// Dispatch the calls made from Move to the contracts of other VMs to the given handler.
+    ///
+    /// Without a handler, such calls abort.
+    pub fn with_cross_vm_handler<C: CrossVmHandler>(self, handler: C) -> Mvm<S, B, C> {
+        Mvm {
+            vm: self.vm,
+            warehouse: self.warehouse,
+            module_cache: self.module_cache,
+            verification: self.verification,
+            io_limits: self.io_limits,
+            cross_vm: Some(handler),
+        }
+    }
+}
+
+impl<S, B, C> Mvm<S, B, C>
+where
+    S: Storage,
+    B: BalanceHandler,
+    C: CrossVmHandler,
+{
+    /// Limit the global storage accessed by each executed script or function.
+    pub fn set_io_limits(&mut self, io_limits: IoLimits) {
+        self.io_limits = io_limits;
+    }
+
     /// Get module binary using the address and the name.
     pub fn get_module(
         &self,
@@ -96,6 +195,16 @@ where
         }
     }
 
+    /// Get the full layout of a type using a BCS encoded `TypeTag`, e.g. to decode the resources
+    /// returned by [`Mvm::get_resource`].
+    ///
+    /// The structs are expanded with the ABIs of the modules declaring them, loaded from the
+    /// storage.
+    pub fn get_type_layout(&self, tag: &[u8]) -> Result<TypeLayout, Error> {
+        let tag: TypeTag = bcs::from_bytes(tag).map_err(Error::msg)?;
+        resolve_type_layout(&self.warehouse, &tag)
+    }
+
     /// Get resource using an address and a tag.
     // TODO: could we use Identifier and AccountAddress here instead as arguments?
     pub fn get_resource(
@@ -107,7 +216,114 @@ where
         self.warehouse.get_resource(address, &tag)
     }
 
+    /// Get resource using an address and a tag, SCALE encoded as in the arguments of the calls to
+    /// other VMs, e.g. for the contracts of other VMs reading Move resources.
+    pub fn get_resource_scale(
+        &self,
+        address: &AccountAddress,
+        tag: &[u8],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let tag: StructTag = bcs::from_bytes(tag).map_err(Error::msg)?;
+        let Some(resource) = self.warehouse.get_resource(address, &tag)? else {
+            return Ok(None);
+        };
+
+        let layout = self.resource_layout(tag)?;
+        let value = MoveValue::simple_deserialize(&resource, &layout)?;
+        Ok(Some(scale::encode_value(&value)))
+    }
+
+    /// Layout of the resource type, as loaded by the VM.
+    pub(crate) fn resource_layout(&self, tag: StructTag) -> Result<MoveTypeLayout, Error> {
+        self.vm
+            .new_session(&self.warehouse)
+            .get_type_layout(&TypeTag::Struct(Box::new(tag)))
+            .map_err(|err| anyhow!("Failed to get the resource layout: {:?}", err))
+    }
+
+    /// Get the root of the Merkle tree over the modules and resources of the account.
+    ///
+    /// Accounts without modules and resources have the root [`EMPTY_ROOT`].
+    pub fn get_account_root(&self, address: &AccountAddress) -> Result<Hash, Error> {
+        self.warehouse.get_account_root(address)
+    }
+
+    /// Get resource using an address and a tag, with the proof of its inclusion in the account.
+    ///
+    /// The proof can be checked against the account root with [`verify_resource_proof`].
+    pub fn get_resource_with_proof(
+        &self,
+        address: &AccountAddress,
+        tag: &[u8],
+    ) -> Result<Option<ResourceProof>, Error> {
+        let tag = bcs::from_bytes(tag).map_err(Error::msg)?;
+        self.warehouse.get_resource_with_proof(address, &tag)
+    }
+
+    /// Remove modules from the storage, e.g. by governance.
+    ///
+    /// Removal is refused while any published module depends on the removed modules, directly or
+    /// transitively. Such dependents are looked up in the reverse-dependency index kept in the
+    /// storage and reported, so they can be removed together. Modules published before the index
+    /// existed must be indexed first, see [`Mvm::index_module_dependencies`]. Resources of the types declared by
+    /// removed modules are kept, see [`Mvm::remove_resources`].
+    pub fn remove_modules(&self, modules: &[ModuleId]) -> Result<ModuleRemoval, Error> {
+        let mut changeset = ChangeSet::new();
+        for module_id in modules {
+            if self.warehouse.get_module(module_id)?.is_none() {
+                return Err(anyhow!("Module {} not found", module_id));
+            }
+            changeset.add_module_op(module_id.clone(), Op::Delete)?;
+        }
+
+        let removed = modules.iter().cloned().collect();
+        let orphans = self.warehouse.find_orphans(&removed)?;
+        if !orphans.is_empty() {
+            return Ok(ModuleRemoval::WouldOrphan(orphans));
+        }
+
+        self.warehouse.apply_changes(changeset)?;
+        // The loader must not serve the removed modules anymore.
+        self.vm.mark_loader_cache_as_invalid();
+        self.vm.flush_loader_cache_if_invalidated();
+
+        Ok(ModuleRemoval::Removed)
+    }
+
+    /// Add published modules to the reverse-dependency index used by [`Mvm::remove_modules`].
+    ///
+    /// Modules are indexed when they are published, so this is only needed once, in a storage
+    /// migration, for the modules published before the index existed. Indexing a module again
+    /// has no effect. The work isn't metered.
+    pub fn index_module_dependencies(&self, modules: &[ModuleId]) -> Result<(), Error> {
+        for module_id in modules {
+            self.warehouse.index_dependencies(module_id)?;
+        }
+        Ok(())
+    }
+
+    /// Remove resources from the account, e.g. to reap it. The account is removed from the storage
+    /// once it holds no modules and resources.
+    pub fn remove_resources(
+        &self,
+        address: &AccountAddress,
+        tags: &[StructTag],
+    ) -> Result<(), Error> {
+        let mut changeset = ChangeSet::new();
+        for tag in tags {
+            if self.warehouse.get_resource(address, tag)?.is_none() {
+                return Err(anyhow!("Resource {} not found under {}", tag, address));
+            }
+            changeset.add_resource_op(*address, tag.clone(), Op::Delete)?;
+        }
+
+        self.warehouse.apply_changes(changeset)
+    }
+
     /// Publish module into the storage. Module is published under the given address.
+    ///
+    /// A module published for the first time is initialized by its private
+    /// `init_module(&signer)` function, if it declares one, with the publisher as signer.
     pub fn publish_module(
         &self,
         module: &[u8],
@@ -115,19 +331,41 @@ where
         gas: GasStrategy,
     ) -> VmResult {
         let mut gas_handler = GasHandler::new(gas);
+        gas_handler.set_verification_cost(self.verification.milligas_per_meter_unit);
 
         // MoveVM by default doesn't charge gas for publishing, so we need to do it manually here.
         if let Err(result) = gas_handler.charge_publishing_to_storage(module.len()) {
             return result;
         }
 
+        let module = module.to_vec();
+        let compiled_modules = deserialize_modules(core::slice::from_ref(&module));
+        let to_initialize = initializer::modules_to_initialize(&self.warehouse, &compiled_modules);
+
         let mut sess = self.vm.new_session(&self.warehouse);
-        let result = sess.publish_module(module.to_vec(), address, &mut gas_handler.status);
+        let result = sess
+            .publish_module(module, address, &mut gas_handler.status)
+            .and_then(|_| {
+                initializer::initialize_modules(
+                    &mut sess,
+                    &to_initialize,
+                    address,
+                    &mut gas_handler.status,
+                )
+            });
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+        let dependencies = warehouse::module_dependencies(&compiled_modules);
+        self.handle_publication(
+            result.and_then(|_| sess.finish()),
+            gas_handler,
+            &dependencies,
+        )
     }
 
     /// Publish a bundle of modules into the storage under the given address.
+    ///
+    /// Modules of the bundle published for the first time are initialized as in
+    /// [`Mvm::publish_module`], in the order of the bundle.
     pub fn publish_module_bundle(
         &self,
         bundle: &[u8],
@@ -135,6 +373,7 @@ where
         gas: GasStrategy,
     ) -> VmResult {
         let mut gas_handler = GasHandler::new(gas);
+        gas_handler.set_verification_cost(self.verification.milligas_per_meter_unit);
 
         let modules = ModuleBundle::try_from(bundle)
             .map_err(|e| VmResult::new(StatusCode::UNKNOWN_MODULE, Some(e.to_string()), 0));
@@ -149,10 +388,27 @@ where
             return result;
         }
 
+        let compiled_modules = deserialize_modules(&modules);
+        let to_initialize = initializer::modules_to_initialize(&self.warehouse, &compiled_modules);
+
         let mut sess = self.vm.new_session(&self.warehouse);
-        let result = sess.publish_module_bundle(modules, address, &mut gas_handler.status);
+        let result = sess
+            .publish_module_bundle(modules, address, &mut gas_handler.status)
+            .and_then(|_| {
+                initializer::initialize_modules(
+                    &mut sess,
+                    &to_initialize,
+                    address,
+                    &mut gas_handler.status,
+                )
+            });
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+        let dependencies = warehouse::module_dependencies(&compiled_modules);
+        self.handle_publication(
+            result.and_then(|_| sess.finish()),
+            gas_handler,
+            &dependencies,
+        )
     }
 
     /// Execute script using the given arguments (args).
@@ -172,6 +428,29 @@ where
                 args: args.iter().map(|x| x.to_vec()).collect(),
             },
             gas,
+            false,
+        )
+    }
+
+    /// Execute script as in [`Mvm::execute_script`] and return the trace of its execution in
+    /// the result. The trace is returned even if the execution fails.
+    pub fn execute_script_with_trace(
+        &self,
+        script: &[u8],
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.execute_script_worker(
+            Transaction {
+                call: Call::Script {
+                    code: script.to_vec(),
+                },
+                type_args,
+                args: args.iter().map(|x| x.to_vec()).collect(),
+            },
+            gas,
+            true,
         )
     }
 
@@ -196,41 +475,78 @@ where
                 args: args.iter().map(|x| x.to_vec()).collect(),
             },
             gas,
+            false,
+        )
+    }
+
+    /// Execute function as in [`Mvm::execute_function`] and return the trace of its execution in
+    /// the result. The trace is returned even if the execution fails.
+    pub fn execute_function_with_trace(
+        &self,
+        mod_address: AccountAddress,
+        mod_name: Identifier,
+        func_name: Identifier,
+        type_args: Vec<TypeTag>,
+        args: Vec<&[u8]>,
+        gas: GasStrategy,
+    ) -> VmResult {
+        self.execute_script_worker(
+            Transaction {
+                call: Call::ScriptFunction {
+                    mod_address,
+                    mod_name,
+                    func_name,
+                },
+                type_args,
+                args: args.iter().map(|x| x.to_vec()).collect(),
+            },
+            gas,
+            true,
         )
     }
 
     /// Execute script using the given arguments (args).
-    fn execute_script_worker(&self, transaction: Transaction, gas: GasStrategy) -> VmResult {
-        let mut gas_handler = GasHandler::new(gas);
-        let mut sess = self.vm.new_session(&self.warehouse);
+    fn execute_script_worker(
+        &self,
+        transaction: Transaction,
+        gas: GasStrategy,
+        trace: bool,
+    ) -> VmResult {
+        let execution = execute_transaction(
+            &self.vm,
+            &self.warehouse,
+            &self.io_limits,
+            self.cross_vm.as_ref(),
+            transaction,
+            gas,
+            trace,
+        );
+        self.handle_execution(execution)
+    }
 
-        let result = match transaction.call {
-            Call::Script { code } => sess.execute_script(
-                code,
-                transaction.type_args,
-                transaction.args,
-                &mut gas_handler.status,
-            ),
-            Call::ScriptFunction {
-                mod_address,
-                mod_name,
-                func_name,
-            } => sess.execute_entry_function(
-                &ModuleId::new(mod_address, mod_name),
-                &func_name,
-                transaction.type_args,
-                transaction.args,
-                &mut gas_handler.status,
-            ),
-        };
+    /// Handle the result of an execution, reporting its trace and storage usage.
+    pub(crate) fn handle_execution(&self, execution: Execution) -> VmResult {
+        let mut result = self.handle_result(execution.result, execution.gas_handler);
+        result.trace = execution.trace;
+        result.io_usage = execution.io_usage;
+        result
+    }
 
-        self.handle_result(result.and_then(|_| sess.finish()), gas_handler)
+    pub(crate) fn handle_result(
+        &self,
+        result: VMResult<(ChangeSet, Vec<Event>)>,
+        gas_handler: GasHandler,
+    ) -> VmResult {
+        self.handle_publication(result, gas_handler, &ModuleDependencies::new())
     }
 
-    fn handle_result(
+    /// Handle the result of a publication, indexing the given `dependencies` of the published
+    /// modules.
+    fn handle_publication(
         &self,
         result: VMResult<(ChangeSet, Vec<Event>)>,
         gas_handler: GasHandler,
+        dependencies: &ModuleDependencies,
     ) -> VmResult {
         match result {
             Ok((changeset, _)) => {
@@ -241,7 +557,10 @@ where
                     return result;
                 }
 
-                if let Err(e) = self.warehouse.apply_changes(changeset) {
+                if let Err(e) = self
+                    .warehouse
+                    .apply_changes_with_dependencies(changeset, dependencies)
+                {
                     result.status_code = StatusCode::STORAGE_ERROR;
                     result.error_message = Some(format!("Storage error: {}", e));
                 }
@@ -250,8 +569,134 @@ where
             }
             Err(err) => {
                 let (status_code, _, msg, _, _, _, _) = err.all_data();
-                VmResult::new(status_code, msg.clone(), 0)
+                VmResult::new(status_code, msg.clone(), gas_handler.gas_used())
             }
         }
     }
 }
+
+/// Outcome of a transaction executed in a session whose changes aren't applied yet.
+pub(crate) struct Execution {
+    result: VMResult<(ChangeSet, Vec<Event>)>,
+    gas_handler: GasHandler<'static>,
+    trace: Option<ExecutionTrace>,
+    io_usage: IoUsage,
+}
+
+/// Execute the transaction in a new session, without applying its changes to the storage.
+///
+/// The transaction fails if the global storage it accessed exceeds `io_limits`. Calls to other
+/// VMs are dispatched to `cross_vm`, and abort if it's `None`.
+pub(crate) fn execute_transaction<S, B, C>(
+    vm: &MoveVM,
+    warehouse: &Warehouse<S, B>,
+    io_limits: &IoLimits,
+    cross_vm: Option<&C>,
+    transaction: Transaction,
+    gas: GasStrategy,
+    trace: bool,
+) -> Execution
+where
+    S: Storage,
+    B: BalanceHandler,
+    C: CrossVmHandler,
+{
+    let mut gas_handler = GasHandler::new(gas);
+    let cross_vm = cross_vm.map(CrossVmDispatcher);
+    let mut extensions = NativeContextExtensions::default();
+    if trace {
+        extensions.add(ExecutionTracer::new());
+    }
+    if let Some(dispatcher) = &cross_vm {
+        extensions.add(NativeCrossVmContext::new(dispatcher));
+    }
+    let io_meter = IoMeter::new(warehouse, io_limits);
+    let mut sess = vm.new_session_with_extensions(&io_meter, extensions);
+
+    let result = match transaction.call {
+        Call::Script { code } => sess.execute_script(
+            code,
+            transaction.type_args,
+            transaction.args,
+            &mut gas_handler.status,
+        ),
+        Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            func_name,
+        } => sess.execute_entry_function(
+            &ModuleId::new(mod_address, mod_name),
+            &func_name,
+            transaction.type_args,
+            transaction.args,
+            &mut gas_handler.status,
+        ),
+    };
+
+    let trace = trace.then(|| {
+        sess.get_native_extensions()
+            .remove::<ExecutionTracer>()
+            .into_trace()
+    });
+
+    let result = result.and_then(|_| sess.finish());
+    let io_usage = match &result {
+        Ok((changeset, events)) => io_meter.usage(Some(changeset), events),
+        Err(_) => io_meter.usage(None, &[]),
+    };
+    let limit_error = |(status_code, msg): (StatusCode, String)| {
+        PartialVMError::new(status_code)
+            .with_message(msg)
+            .finish(Location::Undefined)
+    };
+    // A read crossing a limit failed the session with a storage error, reported as the limit.
+    let result = match io_meter.exceeded() {
+        Some(exceeded) => Err(limit_error(exceeded)),
+        None => result.and_then(|output| {
+            io_limits
+                .check_writes(&io_usage)
+                .map(|()| output)
+                .map_err(limit_error)
+        }),
+    };
+
+    Execution {
+        result,
+        gas_handler,
+        trace,
+        io_usage,
+    }
+}
+
+/// Create a MoveVM instance with the natives and configuration used by [`Mvm`].
+pub(crate) fn new_move_vm(
+    module_cache: Option<VerifiedModuleCache>,
+    verification: &VerificationConfig,
+) -> Result<MoveVM, Error> {
+    let mut config = VMConfig {
+        verified_module_cache: module_cache,
+        ..Default::default()
+    };
+    config.verifier.max_per_fun_meter_units = verification.max_per_fun_meter_units;
+    config.verifier.max_per_mod_meter_units = verification.max_per_mod_meter_units;
+
+    // TODO(rqnsom): see if we can avoid GAS_PARAMS cloning
+    MoveVM::new_with_config(
+        all_natives(CORE_CODE_ADDRESS, NATIVE_COST_PARAMS.clone()),
+        config,
+    )
+    .map_err(|err| {
+        let (code, _, msg, _, _, _, _) = err.all_data();
+        anyhow!("Error code:{:?}: msg: '{}'", code, msg.unwrap_or_default())
+    })
+}
+
+/// Deserialize the modules to publish once, for the checks done besides the publication itself.
+///
+/// Modules which can't be deserialized are skipped, publishing them reports the error.
+fn deserialize_modules(modules: &[Vec<u8>]) -> Vec<CompiledModule> {
+    modules
+        .iter()
+        .filter_map(|bytes| CompiledModule::deserialize(bytes).ok())
+        .collect()
+}
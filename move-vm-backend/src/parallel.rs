@@ -0,0 +1,315 @@
+//! Parallel execution of batches of calls, for off-chain simulation and block building.
+//!
+//! Calls to entry functions are scheduled using the read/write sets inferred by the static
+//! analysis in `read-write-set`, concretized with the arguments of each call. Consecutive calls
+//! whose accesses to global storage don't conflict form a group, whose calls are executed on
+//! separate threads against the storage as it is when the group starts. The changes of a group are
+//! applied in the order of the batch once all its calls finished, before the next group starts,
+//! so the final state is the same as with sequential execution.
+//!
+//! The loader of a MoveVM instance can't be shared between threads, so each thread executing a
+//! group creates its own instance. Verified modules are shared through the module cache of the
+//! [`Mvm`], if it has one.
+//!
+//! Calls whose accesses can't be determined up front run on their own:
+//! - scripts, which the analysis doesn't cover,
+//! - functions that read addresses from global storage to access resources stored under them,
+//!   since an earlier call in the batch could change those addresses,
+//! - functions that may use the `balance` or `cross_vm` natives, since balances and the contracts
+//!   of other VMs are not part of global storage. Calls to other VMs are only dispatched to the
+//!   [`CrossVmHandler`] of the [`Mvm`] by such calls.
+
+use crate::{
+    balance::BalanceHandler,
+    cross_vm::{CrossVmHandler, NoCrossVmHandler},
+    execute_transaction, new_move_vm,
+    storage::Storage,
+    types::{Call, GasStrategy, Transaction, VmResult},
+    Mvm,
+};
+use alloc::collections::BTreeSet;
+use anyhow::{anyhow, Result};
+use move_binary_format::{access::ModuleAccess, file_format::SignatureToken, CompiledModule};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::{ModuleId, ResourceKey, CORE_CODE_ADDRESS},
+    resolver::ModuleResolver,
+};
+use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
+use std::thread;
+
+/// Default number of threads used to execute a group of calls.
+pub const DEFAULT_NUM_THREADS: usize = 4;
+
+/// Global storage accessed by a call.
+#[derive(Debug, Default)]
+struct Accesses {
+    reads: BTreeSet<ResourceKey>,
+    writes: BTreeSet<ResourceKey>,
+}
+
+impl Accesses {
+    /// Whether executing calls with accesses `self` and `other` in any order may give different
+    /// results.
+    fn conflicts_with(&self, other: &Accesses) -> bool {
+        !self.writes.is_disjoint(&other.writes)
+            || !self.writes.is_disjoint(&other.reads)
+            || !self.reads.is_disjoint(&other.writes)
+    }
+
+    fn extend(&mut self, other: Accesses) {
+        self.reads.extend(other.reads);
+        self.writes.extend(other.writes);
+    }
+}
+
+/// Executes batches of calls with [`Mvm`], running calls which don't conflict in parallel.
+pub struct ParallelExecutor<'a, S, B, C = NoCrossVmHandler>
+where
+    S: Storage + Sync,
+    B: BalanceHandler + Sync,
+    C: CrossVmHandler,
+{
+    mvm: &'a Mvm<S, B, C>,
+    analysis: NormalizedReadWriteSetAnalysis,
+    num_threads: usize,
+}
+
+impl<'a, S, B, C> ParallelExecutor<'a, S, B, C>
+where
+    S: Storage + Sync,
+    B: BalanceHandler + Sync,
+    C: CrossVmHandler,
+{
+    /// Create an executor for calls to the functions covered by `analysis`, which should be the
+    /// normalized read/write set analysis of the modules published in the storage of `mvm`.
+    pub fn new(mvm: &'a Mvm<S, B, C>, analysis: NormalizedReadWriteSetAnalysis) -> Self {
+        Self {
+            mvm,
+            analysis,
+            num_threads: DEFAULT_NUM_THREADS,
+        }
+    }
+
+    /// Set the number of threads used to execute a group of calls.
+    pub fn with_num_threads(mut self, num_threads: usize) -> Self {
+        self.num_threads = num_threads.max(1);
+        self
+    }
+
+    /// Execute the batch and apply the changes of each successful call, with the same result as
+    /// executing the calls one after another. Results are returned in the order of the batch.
+    ///
+    /// Fails if a thread can't execute its calls, e.g. because it can't create a MoveVM instance.
+    /// The changes of the group of calls being executed are then discarded, and the rest of the
+    /// batch isn't executed. The changes of the calls before the group stay applied.
+    pub fn execute_batch(&self, batch: Vec<(Transaction, GasStrategy)>) -> Result<Vec<VmResult>> {
+        let mut results = Vec::with_capacity(batch.len());
+        let mut group = Vec::new();
+        let mut group_accesses = Accesses::default();
+
+        for (transaction, gas) in batch {
+            match self.accesses(&transaction) {
+                Some(accesses) if !accesses.conflicts_with(&group_accesses) => {
+                    group_accesses.extend(accesses);
+                    group.push((transaction, gas));
+                }
+                Some(accesses) => {
+                    results.extend(self.execute_group(core::mem::take(&mut group))?);
+                    group_accesses = accesses;
+                    group.push((transaction, gas));
+                }
+                None => {
+                    results.extend(self.execute_group(core::mem::take(&mut group))?);
+                    group_accesses = Accesses::default();
+                    results.extend(self.execute_group(vec![(transaction, gas)])?);
+                }
+            }
+        }
+        results.extend(self.execute_group(group)?);
+
+        Ok(results)
+    }
+
+    /// Execute calls which don't conflict in parallel against the current state of the storage,
+    /// then apply their changes in order.
+    fn execute_group(&self, group: Vec<(Transaction, GasStrategy)>) -> Result<Vec<VmResult>> {
+        if group.len() <= 1 || self.num_threads == 1 {
+            return Ok(group
+                .into_iter()
+                .map(|(transaction, gas)| {
+                    let execution = execute_transaction(
+                        &self.mvm.vm,
+                        &self.mvm.warehouse,
+                        &self.mvm.io_limits,
+                        self.mvm.cross_vm.as_ref(),
+                        transaction,
+                        gas,
+                        false,
+                    );
+                    self.mvm.handle_execution(execution)
+                })
+                .collect());
+        }
+
+        let group_len = group.len();
+        let mut chunks: Vec<Vec<_>> = (0..self.num_threads.min(group_len))
+            .map(|_| Vec::new())
+            .collect();
+        let num_chunks = chunks.len();
+        for (index, call) in group.into_iter().enumerate() {
+            chunks[index % num_chunks].push((index, call));
+        }
+
+        let mut outcomes = thread::scope(|scope| {
+            let workers = chunks
+                .into_iter()
+                .map(|chunk| {
+                    let warehouse = &self.mvm.warehouse;
+                    let module_cache = self.mvm.module_cache.clone();
+                    let verification = self.mvm.verification;
+                    let io_limits = &self.mvm.io_limits;
+                    scope.spawn(move || -> Result<Vec<_>> {
+                        let vm = new_move_vm(module_cache, &verification)?;
+                        Ok(chunk
+                            .into_iter()
+                            .map(|(index, (transaction, gas))| {
+                                // Calls which may use the handler never run in parallel.
+                                let outcome = execute_transaction(
+                                    &vm,
+                                    warehouse,
+                                    io_limits,
+                                    None::<&C>,
+                                    transaction,
+                                    gas,
+                                    false,
+                                );
+                                (index, outcome)
+                            })
+                            .collect())
+                    })
+                })
+                .collect::<Vec<_>>();
+            // All threads are joined before giving up, since the scope panics if one of the
+            // threads it joins itself panicked.
+            let joined: Vec<_> = workers.into_iter().map(|worker| worker.join()).collect();
+            let mut outcomes = Vec::with_capacity(group_len);
+            for chunk_outcomes in joined {
+                outcomes.extend(chunk_outcomes.map_err(|_| anyhow!("Execution thread panicked"))??);
+            }
+            Result::<_>::Ok(outcomes)
+        })?;
+        outcomes.sort_by_key(|(index, _)| *index);
+
+        Ok(outcomes
+            .into_iter()
+            .map(|(_, execution)| self.mvm.handle_execution(execution))
+            .collect())
+    }
+
+    /// The global storage the call accesses, or `None` if it can't be determined before executing
+    /// it.
+    fn accesses(&self, transaction: &Transaction) -> Option<Accesses> {
+        let Call::ScriptFunction {
+            mod_address,
+            mod_name,
+            func_name,
+        } = &transaction.call
+        else {
+            return None;
+        };
+        let module_id = ModuleId::new(*mod_address, mod_name.clone());
+        self.function_accesses(&module_id, func_name, transaction)
+            .ok()
+            .flatten()
+    }
+
+    fn function_accesses(
+        &self,
+        module_id: &ModuleId,
+        func_name: &IdentStr,
+        transaction: &Transaction,
+    ) -> Result<Option<Accesses>> {
+        if self
+            .analysis
+            .may_have_secondary_indexes(module_id, func_name)?
+            || self.may_leave_global_storage(module_id)?
+        {
+            return Ok(None);
+        }
+
+        let module = self.load_module(module_id)?;
+        let parameters = module
+            .function_defs()
+            .iter()
+            .map(|def| module.function_handle_at(def.function))
+            .find(|handle| module.identifier_at(handle.name) == func_name)
+            .map(|handle| &module.signature_at(handle.parameters).0)
+            .ok_or_else(|| anyhow!("Function {}::{} not found", module_id, func_name))?;
+        // Let the VM report calls with the wrong number of arguments.
+        if parameters.len() != transaction.args.len() {
+            return Ok(None);
+        }
+
+        let num_signers = parameters
+            .iter()
+            .take_while(|ty| match ty {
+                SignatureToken::Signer => true,
+                SignatureToken::Reference(inner) => **inner == SignatureToken::Signer,
+                _ => false,
+            })
+            .count();
+        let (signers, actuals) = transaction.args.split_at(num_signers);
+        let signers = signers
+            .iter()
+            .map(AccountAddress::from_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let summary = self.analysis.get_concretized_summary(
+            module_id,
+            func_name,
+            &signers,
+            actuals,
+            &transaction.type_args,
+            &self.mvm.warehouse,
+        )?;
+        let (Some(reads), Some(writes)) = (summary.get_keys_read(), summary.get_keys_written())
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Accesses {
+            reads: reads.into_iter().collect(),
+            writes: writes.into_iter().collect(),
+        }))
+    }
+
+    /// Whether the module or any of its transitive dependencies is the module with the `balance`
+    /// or the `cross_vm` natives.
+    fn may_leave_global_storage(&self, module_id: &ModuleId) -> Result<bool> {
+        let outside = [
+            ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("balance")?),
+            ModuleId::new(CORE_CODE_ADDRESS, Identifier::new("cross_vm")?),
+        ];
+        let mut visited = BTreeSet::new();
+        let mut pending = vec![module_id.clone()];
+        while let Some(id) = pending.pop() {
+            if outside.contains(&id) {
+                return Ok(true);
+            }
+            if visited.insert(id.clone()) {
+                pending.extend(self.load_module(&id)?.immediate_dependencies());
+            }
+        }
+        Ok(false)
+    }
+
+    fn load_module(&self, module_id: &ModuleId) -> Result<CompiledModule> {
+        let bytes = self
+            .mvm
+            .warehouse
+            .get_module(module_id)?
+            .ok_or_else(|| anyhow!("Module {} not found", module_id))?;
+        CompiledModule::deserialize(&bytes).map_err(|err| anyhow!("{:?}", err))
+    }
+}
@@ -1,13 +1,16 @@
+use alloc::format;
 use alloc::string::String;
 use alloc::vec::Vec;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::gas_algebra::GasQuantity;
 use move_core_types::identifier::Identifier;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::language_storage::{ModuleId, TypeTag};
 use move_core_types::vm_status::StatusCode;
 use move_vm_backend_common::gas_schedule::{
-    INSTRUCTION_COST_TABLE, MILLIGAS_COST_PER_PUBLISHED_BYTE,
+    INSTRUCTION_COST_TABLE, INTERNAL_GAS_MULTIPLIER, MILLIGAS_COST_PER_PUBLISHED_BYTE,
+    MILLIGAS_COST_PER_VERIFIER_METER_UNIT,
 };
+use move_vm_runtime::execution_trace::ExecutionTrace;
 use move_vm_test_utils::gas_schedule::GasStatus;
 use move_vm_types::gas::GasMeter;
 
@@ -50,6 +53,10 @@ pub struct VmResult {
     pub error_message: Option<String>,
     /// Gas used.
     pub gas_used: u64,
+    /// Trace of the execution, if it was requested.
+    pub trace: Option<ExecutionTrace>,
+    /// Global storage accessed by the execution.
+    pub io_usage: IoUsage,
 }
 
 impl VmResult {
@@ -59,6 +66,8 @@ impl VmResult {
             status_code,
             error_message,
             gas_used,
+            trace: None,
+            io_usage: IoUsage::default(),
         }
     }
 
@@ -75,11 +84,149 @@ impl VmResult {
     }
 }
 
-/// Inner MoveVM gas handling multiplier.
+/// Limits and cost of the bytecode verification of published modules.
 ///
-/// Internally, MoveVM converts the input gas to gas units which are multiplied by this multiplier,
-/// so in order to get used gas at the same input scale, we need to scale it back with this constant.
-const INTERNAL_GAS_MULTIPLIER: u64 = 1000;
+/// The bytecode verifier meters the work it does in abstract units. Modules exceeding the limits
+/// are rejected. The units used are charged as gas to the publisher, whether the modules are
+/// accepted or not.
+#[derive(Debug, Clone, Copy)]
+pub struct VerificationConfig {
+    /// Maximum units the verification of a single function may use. `None` means unlimited.
+    pub max_per_fun_meter_units: Option<u128>,
+    /// Maximum units the verification of a single module may use. `None` means unlimited.
+    pub max_per_mod_meter_units: Option<u128>,
+    /// Gas charged per unit, in milligas.
+    pub milligas_per_meter_unit: u64,
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            // The bytecode verifier's own defaults.
+            max_per_fun_meter_units: Some(1000 * 8000),
+            max_per_mod_meter_units: Some(1000 * 8000),
+            milligas_per_meter_unit: MILLIGAS_COST_PER_VERIFIER_METER_UNIT,
+        }
+    }
+}
+
+/// Outcome of [`Mvm::remove_modules`](crate::Mvm::remove_modules).
+#[derive(Debug, PartialEq, Eq)]
+pub enum ModuleRemoval {
+    /// The modules were removed.
+    Removed,
+    /// Nothing was removed, because the listed modules depend on the modules to remove and
+    /// couldn't be loaded anymore.
+    WouldOrphan(Vec<ModuleId>),
+}
+
+/// Limits on the global storage a single call may access.
+///
+/// Calls exceeding a limit fail with [`StatusCode::IO_LIMIT_REACHED`] for reads and accounts, or
+/// [`StatusCode::STORAGE_WRITE_LIMIT_REACHED`] for writes and events, and none of their changes
+/// are applied. `None` means unlimited.
+///
+/// The read and account limits are enforced as resources are loaded, so a call fails as soon as
+/// it crosses one. The write and event limits are checked once the call finished.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoLimits {
+    /// Maximum number of distinct accounts whose resources are read or written.
+    pub max_accounts: Option<u64>,
+    /// Maximum number of resources loaded from the storage, including missing ones.
+    pub max_reads: Option<u64>,
+    /// Maximum total size of the resources read, in bytes.
+    pub max_read_bytes: Option<u64>,
+    /// Maximum total size of the resources written, in bytes.
+    pub max_write_bytes: Option<u64>,
+    /// Maximum number of events emitted.
+    pub max_events: Option<u64>,
+    /// Maximum total size of the emitted events data, in bytes.
+    pub max_event_bytes: Option<u64>,
+}
+
+impl IoLimits {
+    /// Check the usage of the reads against the limits, returning the status code and the
+    /// description of the first limit exceeded.
+    pub(crate) fn check_reads(&self, usage: &IoUsage) -> Result<(), (StatusCode, String)> {
+        Self::check_all([
+            (
+                usage.accounts,
+                self.max_accounts,
+                StatusCode::IO_LIMIT_REACHED,
+                "accounts accessed",
+            ),
+            (
+                usage.reads,
+                self.max_reads,
+                StatusCode::IO_LIMIT_REACHED,
+                "resources read",
+            ),
+            (
+                usage.read_bytes,
+                self.max_read_bytes,
+                StatusCode::IO_LIMIT_REACHED,
+                "resource bytes read",
+            ),
+        ])
+    }
+
+    /// Check the usage of the writes and events against the limits, returning the status code and
+    /// the description of the first limit exceeded.
+    pub(crate) fn check_writes(&self, usage: &IoUsage) -> Result<(), (StatusCode, String)> {
+        Self::check_all([
+            (
+                usage.write_bytes,
+                self.max_write_bytes,
+                StatusCode::STORAGE_WRITE_LIMIT_REACHED,
+                "resource bytes written",
+            ),
+            (
+                usage.events,
+                self.max_events,
+                StatusCode::STORAGE_WRITE_LIMIT_REACHED,
+                "events emitted",
+            ),
+            (
+                usage.event_bytes,
+                self.max_event_bytes,
+                StatusCode::STORAGE_WRITE_LIMIT_REACHED,
+                "event bytes emitted",
+            ),
+        ])
+    }
+
+    fn check_all<const N: usize>(
+        checks: [(u64, Option<u64>, StatusCode, &str); N],
+    ) -> Result<(), (StatusCode, String)> {
+        for (used, limit, status_code, what) in checks {
+            if let Some(limit) = limit.filter(|limit| used > *limit) {
+                return Err((
+                    status_code,
+                    format!("Limit of {what} exceeded: {used} > {limit}"),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Global storage accessed by a call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoUsage {
+    /// Number of distinct accounts whose resources were read or written. Resources are loaded
+    /// before they are written, so these are the accounts whose resources were loaded.
+    pub accounts: u64,
+    /// Number of resources loaded from the storage, including missing ones.
+    pub reads: u64,
+    /// Total size of the resources read, in bytes.
+    pub read_bytes: u64,
+    /// Total size of the resources written, in bytes.
+    pub write_bytes: u64,
+    /// Number of events emitted.
+    pub events: u64,
+    /// Total size of the emitted events data, in bytes.
+    pub event_bytes: u64,
+}
 
 /// The maximum possible raw gas amount value.
 pub const MAX_GAS_AMOUNT: u64 = u64::MAX / INTERNAL_GAS_MULTIPLIER;
@@ -182,11 +329,15 @@ impl GasHandler<'_> {
         let remaining_gas = self.status.remaining_gas();
         let amount = GasQuantity::new(num_bytes as u64 * MILLIGAS_COST_PER_PUBLISHED_BYTE);
 
-        self.status.deduct_gas(amount).map_err(|e| VmResult {
-            status_code: e.major_status(),
-            error_message: None,
-            gas_used: remaining_gas.into(),
-        })
+        self.status
+            .deduct_gas(amount)
+            .map_err(|e| VmResult::new(e.major_status(), None, remaining_gas.into()))
+    }
+
+    /// Sets the gas charged per unit of work of the bytecode verifier, in milligas.
+    pub(crate) fn set_verification_cost(&mut self, milligas_per_meter_unit: u64) {
+        self.status
+            .set_verification_unit_cost(milligas_per_meter_unit);
     }
 
     /// Calculates the used gas.
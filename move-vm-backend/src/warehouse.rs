@@ -2,12 +2,13 @@ use crate::{balance::BalanceHandler, storage::Storage};
 use alloc::{
     collections::{
         btree_map::Entry::{Occupied, Vacant},
-        BTreeMap,
+        BTreeMap, BTreeSet,
     },
     vec::Vec,
 };
 use anyhow::{bail, Error, Result};
 use core::ops::Deref;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use move_core_types::account_address::AccountAddress;
 use move_core_types::effects::{
     ChangeSet,
@@ -17,6 +18,12 @@ use move_core_types::identifier::Identifier;
 use move_core_types::language_storage::{ModuleId, StructTag};
 use move_core_types::resolver::{BalanceResolver, ModuleResolver, ResourceResolver};
 use move_core_types::vm_status::StatusCode;
+use move_vm_backend_common::abi::ModuleAbi;
+use move_vm_backend_common::layout::AbiResolver;
+use move_vm_backend_common::proof::{
+    leaf_hash, merkle_proof, merkle_root, EntryKey, Hash, ResourceProof, EMPTY_ROOT,
+};
+use move_vm_runtime::verified_module_cache::VerifiedModuleCache;
 use serde::{Deserialize, Serialize};
 
 /// Structure holding account data which is held under one Move address
@@ -30,6 +37,23 @@ struct AccountData {
 }
 
 impl AccountData {
+    fn is_empty(&self) -> bool {
+        self.modules.is_empty() && self.resources.is_empty()
+    }
+
+    /// Leaves of the Merkle tree over the account entries: modules first, then resources.
+    fn leaves(&self) -> Vec<Hash> {
+        let modules = self
+            .modules
+            .iter()
+            .map(|(name, module)| leaf_hash(&EntryKey::Module(name), module));
+        let resources = self
+            .resources
+            .iter()
+            .map(|(tag, resource)| leaf_hash(&EntryKey::Resource(tag), resource));
+        modules.chain(resources).collect()
+    }
+
     fn apply_changes<K, V>(
         map: &mut BTreeMap<K, V>,
         changes: impl IntoIterator<Item = (K, Op<V>)>,
@@ -64,40 +88,239 @@ impl AccountData {
     }
 }
 
+/// Prefix of the storage keys of the reverse-dependency index. Its keys can't collide with
+/// account keys, which are bare addresses.
+const DEPENDENTS_KEY_PREFIX: &[u8] = b"dependents::";
+
+/// Prefix of the storage keys under which the immediate dependencies of each indexed module are
+/// kept, so updating the index doesn't need the replaced bytecode.
+const DEPENDENCIES_KEY_PREFIX: &[u8] = b"dependencies::";
+
+fn module_key(prefix: &[u8], module_id: &ModuleId) -> Vec<u8> {
+    let mut key = prefix.to_vec();
+    key.extend_from_slice(module_id.address().as_slice());
+    key.extend_from_slice(module_id.name().as_bytes());
+    key
+}
+
+/// Immediate dependencies of published modules, by module.
+pub(crate) type ModuleDependencies = BTreeMap<ModuleId, BTreeSet<ModuleId>>;
+
+/// Immediate dependencies of the modules, derived from the same deserialized modules as the rest
+/// of the publishing checks.
+pub(crate) fn module_dependencies(modules: &[CompiledModule]) -> ModuleDependencies {
+    modules
+        .iter()
+        .map(|module| {
+            let dependencies = module.immediate_dependencies().into_iter().collect();
+            (module.self_id(), dependencies)
+        })
+        .collect()
+}
+
 /// Move VM storage implementation for Substrate storage.
 pub(crate) struct Warehouse<S: Storage, B: BalanceHandler> {
     /// Substrate storage implementing the Storage trait.
     storage: S,
     /// Balance handler which provides access to the external balance handling mechanism.
     balance_handler: B,
+    /// Cache of verified modules shared between VM instances, kept in sync with published modules.
+    module_cache: Option<VerifiedModuleCache>,
 }
 
 impl<S: Storage, B: BalanceHandler> Warehouse<S, B> {
-    pub(crate) fn new(storage: S, balance_handler: B) -> Warehouse<S, B> {
+    pub(crate) fn new(
+        storage: S,
+        balance_handler: B,
+        module_cache: Option<VerifiedModuleCache>,
+    ) -> Warehouse<S, B> {
         Self {
             storage,
             balance_handler,
+            module_cache,
+        }
+    }
+
+    fn get_account(&self, address: &AccountAddress) -> Result<Option<AccountData>> {
+        self.storage
+            .get(address.as_slice())
+            .map(|raw_account| bcs::from_bytes(&raw_account).map_err(Error::msg))
+            .transpose()
+    }
+
+    /// Root of the Merkle tree over the modules and resources of the account.
+    pub(crate) fn get_account_root(&self, address: &AccountAddress) -> Result<Hash> {
+        Ok(self
+            .get_account(address)?
+            .map_or(EMPTY_ROOT, |account| merkle_root(&account.leaves())))
+    }
+
+    /// Resource of the account with the proof of its inclusion in the account's Merkle tree.
+    pub(crate) fn get_resource_with_proof(
+        &self,
+        address: &AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<ResourceProof>> {
+        let Some(account) = self.get_account(address)? else {
+            return Ok(None);
+        };
+        let Some(index) = account.resources.keys().position(|key| key == tag) else {
+            return Ok(None);
+        };
+
+        let leaves = account.leaves();
+        let proof = merkle_proof(&leaves, account.modules.len() + index)
+            .ok_or_else(|| Error::msg("Resource leaf not found"))?;
+        Ok(Some(ResourceProof {
+            resource: account.resources[tag].clone(),
+            root: merkle_root(&leaves),
+            proof,
+        }))
+    }
+
+    fn get_module_set(&self, key: &[u8]) -> Result<BTreeSet<ModuleId>> {
+        self.storage
+            .get(key)
+            .map(|raw_modules| bcs::from_bytes(&raw_modules).map_err(Error::msg))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn set_module_set(&self, key: &[u8], modules: &BTreeSet<ModuleId>) -> Result<()> {
+        if modules.is_empty() {
+            self.storage.remove(key);
+        } else {
+            let modules_bytes = bcs::to_bytes(modules).map_err(Error::msg)?;
+            self.storage.set(key, &modules_bytes);
         }
+        Ok(())
+    }
+
+    /// Modules depending directly on the module, according to the reverse-dependency index.
+    fn get_dependents(&self, module_id: &ModuleId) -> Result<BTreeSet<ModuleId>> {
+        self.get_module_set(&module_key(DEPENDENTS_KEY_PREFIX, module_id))
     }
 
+    /// Immediate dependencies of the module, as recorded in the index. Empty for modules which
+    /// aren't indexed.
+    fn get_dependencies(&self, module_id: &ModuleId) -> Result<BTreeSet<ModuleId>> {
+        self.get_module_set(&module_key(DEPENDENCIES_KEY_PREFIX, module_id))
+    }
+
+    /// Moves `module_id` from the dependents of its indexed dependencies to the ones of its new
+    /// dependencies.
+    fn update_dependents(
+        &self,
+        module_id: &ModuleId,
+        new_dependencies: &BTreeSet<ModuleId>,
+    ) -> Result<()> {
+        let old_dependencies = self.get_dependencies(module_id)?;
+        for dependency in old_dependencies.difference(new_dependencies) {
+            let key = module_key(DEPENDENTS_KEY_PREFIX, dependency);
+            let mut dependents = self.get_module_set(&key)?;
+            dependents.remove(module_id);
+            self.set_module_set(&key, &dependents)?;
+        }
+        for dependency in new_dependencies.difference(&old_dependencies) {
+            let key = module_key(DEPENDENTS_KEY_PREFIX, dependency);
+            let mut dependents = self.get_module_set(&key)?;
+            dependents.insert(module_id.clone());
+            self.set_module_set(&key, &dependents)?;
+        }
+        self.set_module_set(
+            &module_key(DEPENDENCIES_KEY_PREFIX, module_id),
+            new_dependencies,
+        )
+    }
+
+    /// Indexes the dependencies of a published module from its bytecode. Indexing a module again
+    /// has no effect.
+    pub(crate) fn index_dependencies(&self, module_id: &ModuleId) -> Result<()> {
+        let Some(bytes) = self.get_module(module_id)? else {
+            bail!("Module {} not found", module_id);
+        };
+        let module = CompiledModule::deserialize(&bytes).map_err(Error::msg)?;
+        let dependencies = module.immediate_dependencies().into_iter().collect();
+        self.update_dependents(module_id, &dependencies)
+    }
+
+    /// Applies changes which don't publish modules, see [`Warehouse::apply_changes_with_dependencies`].
     pub(crate) fn apply_changes(&self, changeset: ChangeSet) -> Result<()> {
-        for (account, changeset) in changeset.into_inner() {
-            let key = account.as_slice();
+        self.apply_changes_with_dependencies(changeset, &ModuleDependencies::new())
+    }
+
+    /// Applies the changes to the storage. The immediate dependencies of the published modules
+    /// must be in `dependencies`, to keep the dependency index up to date.
+    pub(crate) fn apply_changes_with_dependencies(
+        &self,
+        changeset: ChangeSet,
+        dependencies: &ModuleDependencies,
+    ) -> Result<()> {
+        for (address, changeset) in changeset.into_inner() {
+            let key = address.as_slice();
             let mut account = match self.storage.get(key) {
                 Some(value) => bcs::from_bytes(&value).map_err(Error::msg)?,
                 _ => AccountData::default(),
             };
 
             let (modules, resources) = changeset.into_inner();
+
+            // Dependencies of the changed modules after the changes, needed to keep the dependency
+            // index up to date.
+            let mut dependency_changes = Vec::new();
+            for (name, op) in &modules {
+                let module_id = ModuleId::new(address, name.clone());
+                let new_dependencies = match op {
+                    New(_) | Modify(_) => match dependencies.get(&module_id) {
+                        Some(module_dependencies) => module_dependencies.clone(),
+                        None => bail!("Dependencies of module {} are missing", module_id),
+                    },
+                    Delete => BTreeSet::new(),
+                };
+                dependency_changes.push((module_id, new_dependencies));
+            }
+
             AccountData::apply_changes(&mut account.modules, modules)?;
             AccountData::apply_changes(&mut account.resources, resources)?;
 
-            let account_bytes = bcs::to_bytes(&account).map_err(Error::msg)?;
-            self.storage.set(key, &account_bytes);
+            if account.is_empty() {
+                self.storage.remove(key);
+            } else {
+                let account_bytes = bcs::to_bytes(&account).map_err(Error::msg)?;
+                self.storage.set(key, &account_bytes);
+            }
+
+            for (module_id, new_dependencies) in &dependency_changes {
+                self.update_dependents(module_id, new_dependencies)?;
+            }
+
+            // Upgraded or removed modules must not be served from the cache anymore. This is done
+            // once the storage is updated, so a VM loading a module in between can't cache the
+            // old version again.
+            if let Some(cache) = &self.module_cache {
+                for (module_id, _) in &dependency_changes {
+                    cache.invalidate(module_id);
+                }
+            }
         }
 
         Ok(())
     }
+
+    /// Published modules which can't be loaded anymore once the `removed` modules are gone,
+    /// because they depend on them directly or transitively.
+    pub(crate) fn find_orphans(&self, removed: &BTreeSet<ModuleId>) -> Result<Vec<ModuleId>> {
+        let mut orphans = BTreeSet::new();
+        let mut pending: Vec<ModuleId> = removed.iter().cloned().collect();
+        while let Some(module_id) = pending.pop() {
+            for dependent in self.get_dependents(&module_id)? {
+                if !removed.contains(&dependent) && orphans.insert(dependent.clone()) {
+                    pending.push(dependent);
+                }
+            }
+        }
+        Ok(orphans.into_iter().collect())
+    }
 }
 
 impl<S: Storage, B: BalanceHandler> Deref for Warehouse<S, B> {
@@ -148,6 +371,17 @@ impl<S: Storage, B: BalanceHandler> ResourceResolver for Warehouse<S, B> {
     }
 }
 
+impl<S: Storage, B: BalanceHandler> AbiResolver for Warehouse<S, B> {
+    fn get_module_abi(&self, module_id: &ModuleId) -> Result<Option<ModuleAbi>, Error> {
+        self.get_module(module_id)?
+            .map(|bytecode| {
+                let module = CompiledModule::deserialize(&bytecode).map_err(Error::msg)?;
+                Ok(ModuleAbi::from(module))
+            })
+            .transpose()
+    }
+}
+
 impl<S: Storage, B: BalanceHandler> BalanceResolver for Warehouse<S, B> {
     type Error = StatusCode;
 
@@ -0,0 +1,40 @@
+/// Transfers tokens of an ink! contract from Move, through the `cross_vm` natives.
+module CafeAccount::TokenBridge {
+    use substrate::cross_vm;
+
+    /// Selector of the `transfer(to: AccountId, amount: Balance)` message of the contract.
+    const TRANSFER_SELECTOR: vector<u8> = x"84a15da1";
+
+    /// The contract failed to execute the transfer.
+    const ETRANSFER_FAILED: u64 = 1;
+
+    /// Arguments of the `transfer` message, encoded as the message arguments.
+    struct TransferArgs has drop {
+        to: address,
+        amount: u128,
+    }
+
+    /// Output of the last transfer made by the account.
+    struct LastTransfer has key {
+        output: vector<u8>,
+    }
+
+    public entry fun transfer(
+        account: &signer,
+        contract: address,
+        to: address,
+        amount: u128,
+    ) acquires LastTransfer {
+        let args = TransferArgs { to, amount };
+        let (success, output) =
+            cross_vm::call(account, contract, TRANSFER_SELECTOR, &args, 0, 1000000);
+        assert!(success, ETRANSFER_FAILED);
+
+        let addr = std::signer::address_of(account);
+        if (exists<LastTransfer>(addr)) {
+            borrow_global_mut<LastTransfer>(addr).output = output;
+        } else {
+            move_to(account, LastTransfer { output });
+        }
+    }
+}
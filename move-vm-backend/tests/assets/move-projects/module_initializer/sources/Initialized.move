@@ -0,0 +1,12 @@
+/// A module whose initial resources are created by its initializer when it's published.
+module CafeAccount::Initialized {
+    /// Configuration stored under the publisher's address.
+    struct Config has key {
+        version: u64
+    }
+
+    /// Run once when the module is published for the first time.
+    fun init_module(publisher: &signer) {
+        move_to(publisher, Config { version: 1 });
+    }
+}
@@ -7,9 +7,11 @@ build_dir=(
     "address_checks"
     "base58_smove_build"
     "basic_coin"
+    "cross_vm_call"
     "depends_on__using_stdlib_full"
     "depends_on__using_stdlib_natives"
     "empty"
+    "module_initializer"
     "simple_scripts"
     "using_stdlib_full"
     "substrate_balance"
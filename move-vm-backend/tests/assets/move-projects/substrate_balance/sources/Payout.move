@@ -0,0 +1,7 @@
+module CafeAccount::Payout {
+    use substrate::balance;
+
+    public entry fun pay(src: signer, dst: address, amount: u128) {
+        assert!(balance::transfer(&src, dst, amount), 0);
+    }
+}
@@ -1,21 +1,21 @@
 use move_core_types::account_address::AccountAddress;
 use move_core_types::vm_status::StatusCode;
 use move_vm_backend::balance::BalanceHandler;
+use move_vm_backend::cross_vm::{CrossVmCall, CrossVmHandler, CrossVmOutput};
 use move_vm_backend::storage::Storage;
-use std::cell::RefCell;
 use std::collections::HashMap;
-use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
 // Mock storage implementation for testing.
 #[derive(Clone, Debug)]
 pub struct StorageMock {
-    pub data: Rc<RefCell<HashMap<Vec<u8>, Vec<u8>>>>,
+    pub data: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>>,
 }
 
 impl StorageMock {
     pub fn new() -> StorageMock {
         StorageMock {
-            data: Rc::new(RefCell::new(Default::default())),
+            data: Arc::new(Mutex::new(Default::default())),
         }
     }
 }
@@ -28,17 +28,17 @@ impl Default for StorageMock {
 
 impl Storage for StorageMock {
     fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
-        let data = self.data.borrow();
+        let data = self.data.lock().unwrap();
         data.get(key).map(|blob| blob.to_owned())
     }
 
     fn set(&self, key: &[u8], value: &[u8]) {
-        let mut data = self.data.borrow_mut();
+        let mut data = self.data.lock().unwrap();
         data.insert(key.to_owned(), value.to_owned());
     }
 
     fn remove(&self, key: &[u8]) {
-        let mut data = self.data.borrow_mut();
+        let mut data = self.data.lock().unwrap();
         data.remove(key);
     }
 }
@@ -46,18 +46,18 @@ impl Storage for StorageMock {
 // Mock balance handler implementation for testing.
 #[derive(Clone, Debug)]
 pub struct BalanceMock {
-    cheques: Rc<RefCell<HashMap<AccountAddress, u128>>>,
+    cheques: Arc<Mutex<HashMap<AccountAddress, u128>>>,
 }
 
 impl BalanceMock {
     pub fn new() -> Self {
         Self {
-            cheques: Rc::new(RefCell::new(HashMap::new())),
+            cheques: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
     pub fn write_cheque(&mut self, account: AccountAddress, amount: u128) {
-        let mut cheques = self.cheques.borrow_mut();
+        let mut cheques = self.cheques.lock().unwrap();
 
         if let Some(current_amount) = cheques.get_mut(&account) {
             *current_amount += amount;
@@ -76,7 +76,7 @@ impl BalanceHandler for BalanceMock {
         dst: AccountAddress,
         cheque_amount: u128,
     ) -> Result<bool, Self::Error> {
-        let mut cheques = self.cheques.borrow_mut();
+        let mut cheques = self.cheques.lock().unwrap();
 
         let src_balance = cheques.entry(src).or_insert(0);
         if *src_balance < cheque_amount {
@@ -94,7 +94,7 @@ impl BalanceHandler for BalanceMock {
     }
 
     fn cheque_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
-        Ok(*self.cheques.borrow().get(&account).unwrap_or(&0))
+        Ok(*self.cheques.lock().unwrap().get(&account).unwrap_or(&0))
     }
 
     fn total_amount(&self, account: AccountAddress) -> Result<u128, Self::Error> {
@@ -102,3 +102,39 @@ impl BalanceHandler for BalanceMock {
         self.cheque_amount(account)
     }
 }
+
+// Mock cross-VM call handler implementation for testing.
+#[derive(Clone, Debug, Default)]
+pub struct CrossVmMock {
+    calls: Arc<Mutex<Vec<CrossVmCall>>>,
+    result: Arc<Mutex<Option<Result<CrossVmOutput, StatusCode>>>>,
+}
+
+impl CrossVmMock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the result of the calls dispatched from now on.
+    pub fn set_result(&self, result: Result<CrossVmOutput, StatusCode>) {
+        *self.result.lock().unwrap() = Some(result);
+    }
+
+    /// Calls dispatched so far.
+    pub fn calls(&self) -> Vec<CrossVmCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl CrossVmHandler for CrossVmMock {
+    type Error = StatusCode;
+
+    fn call(&self, call: CrossVmCall) -> Result<CrossVmOutput, Self::Error> {
+        self.calls.lock().unwrap().push(call);
+        self.result
+            .lock()
+            .unwrap()
+            .clone()
+            .unwrap_or_else(|| Ok(CrossVmOutput::default()))
+    }
+}
@@ -6,18 +6,33 @@
 //! otherwise executing scripts or publishing won't work as expected.
 //!
 use crate::mock::BalanceMock;
+use crate::mock::CrossVmMock;
 use crate::mock::StorageMock;
 use move_core_types::account_address::AccountAddress;
 use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
 use move_core_types::language_storage::StructTag;
 use move_core_types::language_storage::CORE_CODE_ADDRESS as ADDR_STD;
 use move_vm_backend::balance::BalanceHandler;
-use move_vm_backend::genesis::VmGenesisConfig;
+use move_vm_backend::cross_vm::{CrossVmCall, CrossVmHandler, CrossVmOutput};
+use move_vm_backend::genesis::{
+    GenesisAccount, GenesisConfigError, GenesisFunction, GenesisResource, VmGenesisConfig,
+};
 use move_vm_backend::types::GasAmount;
+use move_vm_backend::types::ModuleRemoval;
+use move_vm_backend::types::VerificationConfig;
+use move_vm_backend::types::VmResult;
+use move_vm_backend::types::{IoLimits, IoUsage};
 use move_vm_backend::Mvm;
+use move_vm_backend::VerifiedModuleCache;
+use move_vm_backend::{verify_resource_proof, EMPTY_ROOT};
+use move_vm_backend::{ExecutionTrace, ResourceAccessKind, TraceEvent};
+use move_vm_backend_common::abi::{FieldLayout, StructDef, StructLayout, TypeLayout};
 use move_vm_backend_common::types::ModuleBundle;
+use parity_scale_codec::Encode;
 
 use move_core_types::language_storage::TypeTag;
+use move_core_types::vm_status::StatusCode;
 use move_vm_backend::types::GasStrategy;
 
 pub mod mock;
@@ -66,6 +81,16 @@ fn estimate_gas_for_published_bytecode(bytecode: &[u8]) -> u64 {
     num_integer::div_ceil(raw_gas_cost, 1000)
 }
 
+/// Creates a MoveVM which charges only the published bytes for publishing, so that
+/// [`estimate_gas_for_published_bytecode`] is exact.
+fn mvm_without_verification_cost(store: StorageMock) -> Mvm<StorageMock, BalanceMock> {
+    let verification = VerificationConfig {
+        milligas_per_meter_unit: 0,
+        ..Default::default()
+    };
+    Mvm::new_with_verification_config(store, BalanceMock::new(), verification).unwrap()
+}
+
 fn store_preloaded_with_genesis_cfg() -> StorageMock {
     let genesis_cfg = VmGenesisConfig::default();
     let store = StorageMock::new();
@@ -153,7 +178,7 @@ fn publish_module_bundle_from_multiple_module_files() {
 #[test]
 fn publish_module_bundle_from_bundle_file() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = mvm_without_verification_cost(store);
     let provided_gas_amount = GasAmount::max();
     let gas = GasStrategy::Metered(provided_gas_amount);
 
@@ -196,6 +221,181 @@ fn publish_module_dependent_on_stdlib_natives() {
     assert!(result.is_ok(), "the second module cannot be published");
 }
 
+#[test]
+fn remove_modules_reports_orphaned_dependents() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store.clone(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let vector = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let addr_std_natives_user = AccountAddress::from_hex_literal("0x2").unwrap();
+    let result = vm.publish_module(&vector, addr_std_natives_user, gas);
+    assert!(result.is_ok(), "the first module cannot be published");
+
+    let vector_user =
+        read_module_bytes_from_project("depends_on__using_stdlib_natives", "VectorUser");
+    let addr_testing_natives = AccountAddress::from_hex_literal("0x4").unwrap();
+    let result = vm.publish_module(&vector_user, addr_testing_natives, gas);
+    assert!(result.is_ok(), "the second module cannot be published");
+
+    let vector_id = ModuleId::new(addr_std_natives_user, Identifier::new("Vector").unwrap());
+    let vector_user_id =
+        ModuleId::new(addr_testing_natives, Identifier::new("VectorUser").unwrap());
+
+    // The dependent module published under another address would be orphaned, so nothing is
+    // removed.
+    let removal = vm.remove_modules(&[vector_id.clone()]).unwrap();
+    assert_eq!(
+        removal,
+        ModuleRemoval::WouldOrphan(vec![vector_user_id.clone()])
+    );
+    assert!(vm
+        .get_module(addr_std_natives_user, "Vector")
+        .unwrap()
+        .is_some());
+
+    let removal = vm.remove_modules(&[vector_id, vector_user_id]).unwrap();
+    assert_eq!(removal, ModuleRemoval::Removed);
+    assert!(vm
+        .get_module(addr_std_natives_user, "Vector")
+        .unwrap()
+        .is_none());
+    assert!(vm
+        .get_module(addr_testing_natives, "VectorUser")
+        .unwrap()
+        .is_none());
+
+    // Accounts without modules and resources are removed from the storage, and so are the
+    // reverse-dependency index entries of the removed modules.
+    assert!(store.data.lock().unwrap().is_empty());
+}
+
+#[test]
+fn remove_modules_reports_dependents_published_before_the_index() {
+    let store = StorageMock::new();
+    let vm = Mvm::new(store.clone(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let vector = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let addr_std_natives_user = AccountAddress::from_hex_literal("0x2").unwrap();
+    let result = vm.publish_module(&vector, addr_std_natives_user, gas);
+    assert!(result.is_ok(), "the first module cannot be published");
+
+    let vector_user =
+        read_module_bytes_from_project("depends_on__using_stdlib_natives", "VectorUser");
+    let addr_testing_natives = AccountAddress::from_hex_literal("0x4").unwrap();
+    let result = vm.publish_module(&vector_user, addr_testing_natives, gas);
+    assert!(result.is_ok(), "the second module cannot be published");
+
+    // Drop the index, as if the modules were published before it existed.
+    store
+        .data
+        .lock()
+        .unwrap()
+        .retain(|key, _| !key.starts_with(b"dependents::") && !key.starts_with(b"dependencies::"));
+
+    let vector_id = ModuleId::new(addr_std_natives_user, Identifier::new("Vector").unwrap());
+    let vector_user_id =
+        ModuleId::new(addr_testing_natives, Identifier::new("VectorUser").unwrap());
+
+    // Indexing modules twice has no effect.
+    let modules = [vector_id.clone(), vector_user_id.clone()];
+    vm.index_module_dependencies(&modules).unwrap();
+    vm.index_module_dependencies(&modules).unwrap();
+
+    let removal = vm.remove_modules(&[vector_id.clone()]).unwrap();
+    assert_eq!(
+        removal,
+        ModuleRemoval::WouldOrphan(vec![vector_user_id.clone()])
+    );
+
+    let removal = vm.remove_modules(&modules).unwrap();
+    assert_eq!(removal, ModuleRemoval::Removed);
+    assert!(store.data.lock().unwrap().is_empty());
+
+    let missing = vm.index_module_dependencies(&[vector_id]);
+    assert!(missing.is_err(), "removed modules can't be indexed");
+}
+
+#[test]
+fn remove_resources_reaps_empty_accounts() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store.clone(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let addr_param = bcs::to_bytes(&bob).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let result = vm.execute_function(cafe, mod_name, func_name, vec![], vec![&addr_param], gas);
+    assert!(result.is_ok(), "script execution failed");
+    assert!(store.data.lock().unwrap().contains_key(bob.as_slice()));
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    vm.remove_resources(&bob, &[tag.clone()]).unwrap();
+    assert!(!store.data.lock().unwrap().contains_key(bob.as_slice()));
+
+    // Removing a missing resource fails.
+    assert!(vm.remove_resources(&bob, &[tag]).is_err());
+}
+
+/// Reads the `Config` resource created by the initializer of the module_initializer project.
+fn initialized_config(
+    vm: &Mvm<StorageMock, BalanceMock>,
+    address: AccountAddress,
+) -> Option<Vec<u8>> {
+    let tag = StructTag {
+        address,
+        module: Identifier::new("Initialized").unwrap(),
+        name: Identifier::new("Config").unwrap(),
+        type_params: vec![],
+    };
+    vm.get_resource(&address, &bcs::to_bytes(&tag).unwrap())
+        .unwrap()
+}
+
+#[test]
+fn publishing_runs_the_module_initializer_once() {
+    let vm = Mvm::new(StorageMock::new(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("module_initializer", "Initialized");
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert_eq!(
+        initialized_config(&vm, address),
+        Some(bcs::to_bytes(&1u64).unwrap())
+    );
+
+    // The initializer would abort if it ran again, since the resource already exists.
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to republish the module");
+}
+
+#[test]
+fn publishing_a_bundle_runs_the_module_initializers() {
+    let vm = Mvm::new(StorageMock::new(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("module_initializer", "Initialized");
+    let bundle = ModuleBundle::new(vec![module]).encode().unwrap();
+    let result = vm.publish_module_bundle(&bundle, address, gas);
+    assert!(result.is_ok(), "failed to publish the bundle");
+    assert!(initialized_config(&vm, address).is_some());
+}
+
 #[test]
 fn publish_module_using_stdlib_full_fails() {
     let store = StorageMock::new();
@@ -226,6 +426,195 @@ fn genesis_config_inits_stdlib_so_stdlib_full_can_be_published() {
     assert!(result.is_ok(), "failed to publish the module");
 }
 
+/// Genesis configuration publishing BasicCoin under 0xCAFE, storing a balance for `bob` and
+/// publishing a balance for 0xCAFE with an initialization function.
+fn basic_coin_genesis_cfg(bob: AccountAddress) -> VmGenesisConfig {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+
+    let mut genesis_cfg = VmGenesisConfig::default();
+    genesis_cfg.add_bundle(cafe, ModuleBundle::new(vec![module]).encode().unwrap());
+    genesis_cfg.add_account(GenesisAccount {
+        address: bob,
+        resources: vec![GenesisResource {
+            tag: StructTag {
+                address: cafe,
+                module: Identifier::new("BasicCoin").unwrap(),
+                name: Identifier::new("Balance").unwrap(),
+                type_params: vec![],
+            },
+            value: bcs::to_bytes(&5u64).unwrap(),
+        }],
+    });
+    genesis_cfg.add_init_function(GenesisFunction {
+        module_address: cafe,
+        module_name: Identifier::new("BasicCoin").unwrap(),
+        function_name: Identifier::new("publish_balance").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&cafe).unwrap()],
+    });
+    genesis_cfg
+}
+
+#[test]
+fn genesis_config_publishes_bundles_stores_resources_and_runs_init_functions() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+
+    // The configuration survives a round trip through a chain specification.
+    let json = serde_json::to_string(&basic_coin_genesis_cfg(bob)).unwrap();
+    let genesis_cfg: VmGenesisConfig = serde_json::from_str(&json).unwrap();
+
+    let store = StorageMock::new();
+    assert!(
+        genesis_cfg.apply(store.clone()).is_ok(),
+        "genesis configuration failure"
+    );
+
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    assert!(vm.get_module(cafe, "BasicCoin").unwrap().is_some());
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let tag = bcs::to_bytes(&tag).unwrap();
+    let bob_balance = vm.get_resource(&bob, &tag).unwrap();
+    assert_eq!(bob_balance, Some(bcs::to_bytes(&5u64).unwrap()));
+    let cafe_balance = vm.get_resource(&cafe, &tag).unwrap();
+    assert_eq!(cafe_balance, Some(bcs::to_bytes(&0u64).unwrap()));
+}
+
+#[test]
+fn genesis_config_failure_leaves_storage_untouched() {
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let mut genesis_cfg = basic_coin_genesis_cfg(bob);
+    // Publishing a second balance for bob aborts.
+    genesis_cfg.add_init_function(GenesisFunction {
+        module_address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module_name: Identifier::new("BasicCoin").unwrap(),
+        function_name: Identifier::new("publish_balance").unwrap(),
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&bob).unwrap()],
+    });
+
+    let store = StorageMock::new();
+    let result = genesis_cfg.apply(store.clone());
+    assert!(matches!(
+        result,
+        Err(GenesisConfigError::InitFunction(VmResult {
+            status_code: StatusCode::ABORTED,
+            ..
+        }))
+    ));
+    assert!(store.data.lock().unwrap().is_empty());
+}
+
+#[test]
+fn genesis_config_rejects_invalid_initial_resources() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let basic_coin_tag = |name| StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    };
+
+    let invalid_resources = [
+        // The struct doesn't exist.
+        (basic_coin_tag("Missing"), bcs::to_bytes(&5u64).unwrap()),
+        // The struct doesn't have the key ability.
+        (basic_coin_tag("Coin"), bcs::to_bytes(&5u64).unwrap()),
+        // The value doesn't match the layout of the struct.
+        (basic_coin_tag("Balance"), bcs::to_bytes(&5u32).unwrap()),
+    ];
+    for (tag, value) in invalid_resources {
+        let mut genesis_cfg = basic_coin_genesis_cfg(bob);
+        genesis_cfg.add_account(GenesisAccount {
+            address: bob,
+            resources: vec![GenesisResource { tag, value }],
+        });
+
+        let store = StorageMock::new();
+        let result = genesis_cfg.apply(store.clone());
+        assert!(matches!(
+            result,
+            Err(GenesisConfigError::InitialResources(_))
+        ));
+        assert!(store.data.lock().unwrap().is_empty());
+    }
+}
+
+/// Genesis configuration publishing Payout under 0xCAFE and paying the given amounts with it.
+fn payout_genesis_cfg(payments: &[(AccountAddress, AccountAddress, u128)]) -> VmGenesisConfig {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("substrate_balance", "Payout");
+
+    let mut genesis_cfg = VmGenesisConfig::default();
+    genesis_cfg.add_bundle(cafe, ModuleBundle::new(vec![module]).encode().unwrap());
+    for (src, dst, amount) in payments {
+        genesis_cfg.add_init_function(GenesisFunction {
+            module_address: cafe,
+            module_name: Identifier::new("Payout").unwrap(),
+            function_name: Identifier::new("pay").unwrap(),
+            type_args: vec![],
+            args: vec![
+                bcs::to_bytes(src).unwrap(),
+                bcs::to_bytes(dst).unwrap(),
+                bcs::to_bytes(amount).unwrap(),
+            ],
+        });
+    }
+    genesis_cfg
+}
+
+#[test]
+fn genesis_config_transfers_balances_only_on_success() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let alice = AccountAddress::from_hex_literal("0xA11CE").unwrap();
+    let mut balance = BalanceMock::new();
+    balance.write_cheque(cafe, 10);
+
+    // The second payment exceeds what is left after the first one, so nothing is paid.
+    let genesis_cfg = payout_genesis_cfg(&[(cafe, bob, 4), (cafe, bob, 7)]);
+    let store = StorageMock::new();
+    let result = genesis_cfg.apply_with_balance_handler(store.clone(), balance.clone());
+    assert!(matches!(result, Err(GenesisConfigError::InitFunction(_))));
+    assert!(store.data.lock().unwrap().is_empty());
+    assert_eq!(balance.cheque_amount(cafe).unwrap(), 10);
+    assert_eq!(balance.cheque_amount(bob).unwrap(), 0);
+
+    // Later payments can spend what earlier ones paid.
+    let genesis_cfg = payout_genesis_cfg(&[(cafe, bob, 4), (bob, alice, 3)]);
+    let store = StorageMock::new();
+    let result = genesis_cfg.apply_with_balance_handler(store.clone(), balance.clone());
+    assert!(result.is_ok(), "genesis configuration failure");
+    assert_eq!(balance.cheque_amount(cafe).unwrap(), 6);
+    assert_eq!(balance.cheque_amount(bob).unwrap(), 1);
+    assert_eq!(balance.cheque_amount(alice).unwrap(), 3);
+}
+
+#[test]
+fn genesis_config_without_balances_fails_on_balance_access() {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+
+    let genesis_cfg = payout_genesis_cfg(&[(cafe, bob, 4)]);
+    let store = StorageMock::new();
+    match genesis_cfg.apply(store.clone()) {
+        Err(GenesisConfigError::InitFunction(result)) => assert_eq!(
+            result.status_code,
+            StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+        ),
+        _ => panic!("the balance access didn't fail"),
+    }
+    assert!(store.data.lock().unwrap().is_empty());
+}
+
 #[test]
 fn get_module_and_module_abi() {
     let store = StorageMock::new();
@@ -249,6 +638,69 @@ fn get_module_and_module_abi() {
     assert!(result.unwrap().is_some(), "failed to get the module abi");
 }
 
+#[test]
+fn get_type_layout() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let basic_coin_tag = |name: &str| StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new(name).unwrap(),
+        type_params: vec![],
+    };
+    // 0x1::option::Option<0xCAFE::BasicCoin::Balance>
+    let option_tag = StructTag {
+        address: ADDR_STD,
+        module: Identifier::new("option").unwrap(),
+        name: Identifier::new("Option").unwrap(),
+        type_params: vec![TypeTag::Struct(Box::new(basic_coin_tag("Balance")))],
+    };
+    let tag = TypeTag::Struct(Box::new(option_tag.clone()));
+
+    let layout = vm
+        .get_type_layout(&bcs::to_bytes(&tag).unwrap())
+        .expect("failed to get the type layout");
+
+    // Modules of both the option and its elements are loaded, with the elements substituted.
+    let TypeLayout::Struct(option) = &layout else {
+        panic!("unexpected layout {layout:?}");
+    };
+    assert_eq!(option.def, StructDef::from(&option_tag));
+    assert_eq!(option.fields[0].name.as_str(), "vec");
+    let TypeLayout::Vector(balance) = &option.fields[0].layout else {
+        panic!("unexpected layout {:?}", option.fields[0].layout);
+    };
+    let expected_balance = TypeLayout::Struct(StructLayout {
+        def: StructDef::from(&basic_coin_tag("Balance")),
+        fields: vec![FieldLayout {
+            name: Identifier::new("coin").unwrap(),
+            layout: TypeLayout::Struct(StructLayout {
+                def: StructDef::from(&basic_coin_tag("Coin")),
+                fields: vec![FieldLayout {
+                    name: Identifier::new("value").unwrap(),
+                    layout: TypeLayout::U64,
+                }],
+            }),
+        }],
+    });
+    assert_eq!(balance.as_ref(), &expected_balance);
+
+    // Types of missing modules can't be resolved.
+    let tag = TypeTag::Struct(Box::new(StructTag {
+        address: cafe,
+        module: Identifier::new("Missing").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    }));
+    assert!(vm.get_type_layout(&bcs::to_bytes(&tag).unwrap()).is_err());
+}
+
 #[test]
 fn get_resource() {
     let store = StorageMock::new();
@@ -341,6 +793,61 @@ fn get_resource() {
     assert!(result.unwrap().is_none(), "resource found in the module");
 }
 
+#[test]
+fn get_resource_with_proof() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let addr_param = bcs::to_bytes(&cafe).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let result = vm.execute_function(cafe, mod_name, func_name, vec![], vec![&addr_param], gas);
+    assert!(result.is_ok(), "script execution failed");
+
+    let tag = StructTag {
+        address: cafe,
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    let tag_bytes = bcs::to_bytes(&tag).unwrap();
+    let proof = vm
+        .get_resource_with_proof(&cafe, &tag_bytes)
+        .unwrap()
+        .expect("resource not found");
+
+    let root = vm.get_account_root(&cafe).unwrap();
+    assert_eq!(proof.root, root);
+    assert_eq!(
+        Some(proof.resource.clone()),
+        vm.get_resource(&cafe, &tag_bytes).unwrap()
+    );
+    assert!(verify_resource_proof(
+        &root,
+        &tag,
+        &proof.resource,
+        &proof.proof
+    ));
+
+    // The proof doesn't hold for another value.
+    let mut forged = proof.resource.clone();
+    forged[0] ^= 1;
+    assert!(!verify_resource_proof(&root, &tag, &forged, &proof.proof));
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    assert_eq!(vm.get_account_root(&bob).unwrap(), EMPTY_ROOT);
+    assert!(vm
+        .get_resource_with_proof(&bob, &tag_bytes)
+        .unwrap()
+        .is_none());
+}
+
 #[test]
 fn execute_script_with_no_params_test() {
     let store = StorageMock::new();
@@ -462,6 +969,171 @@ fn execute_function_test() {
     assert!(result.is_ok(), "script execution failed");
 }
 
+#[test]
+fn verified_module_cache_is_shared_and_invalidated_on_publish() {
+    let store = store_preloaded_with_genesis_cfg();
+    let cache = VerifiedModuleCache::new();
+    let gas = GasStrategy::Unmetered;
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let module_id = ModuleId::new(address, mod_name.clone());
+
+    let vm = Mvm::new_with_module_cache(store.clone(), BalanceMock::new(), cache.clone()).unwrap();
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    // Loading the module for execution verifies it and its dependencies once.
+    let addr_param = bcs::to_bytes(&address).unwrap();
+    let result = vm.execute_function(
+        address,
+        mod_name.clone(),
+        func_name.clone(),
+        vec![],
+        vec![&addr_param],
+        gas,
+    );
+    assert!(result.is_ok(), "script execution failed");
+    assert!(cache.contains(&module_id), "module wasn't cached");
+    assert!(
+        cache.contains(&ModuleId::new(ADDR_STD, Identifier::new("signer").unwrap())),
+        "dependency wasn't cached"
+    );
+
+    // Another instance uses the cached modules instead of verifying them again.
+    let hits = cache.hits();
+    let vm = Mvm::new_with_module_cache(store.clone(), BalanceMock::new(), cache.clone()).unwrap();
+    let other_address = AccountAddress::from_hex_literal("0xBEEF").unwrap();
+    let addr_param = bcs::to_bytes(&other_address).unwrap();
+    let result = vm.execute_function(address, mod_name, func_name, vec![], vec![&addr_param], gas);
+    assert!(
+        result.is_ok(),
+        "script execution with cached modules failed"
+    );
+    assert!(
+        cache.hits() >= hits + 2,
+        "the module and its dependency weren't served from the cache"
+    );
+
+    // Committing an upgrade of the module removes it from the cache.
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to republish the module");
+    assert!(
+        !cache.contains(&module_id),
+        "upgraded module wasn't invalidated"
+    );
+}
+
+#[test]
+fn execute_function_with_trace_test() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert!(result.trace.is_none(), "publishing shouldn't be traced");
+
+    let addr_param = bcs::to_bytes(&address).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(100_000).unwrap());
+    let result = vm.execute_function_with_trace(
+        address,
+        mod_name.clone(),
+        func_name.clone(),
+        vec![],
+        vec![&addr_param],
+        gas,
+    );
+    assert!(result.is_ok(), "script execution failed");
+    let trace = result.trace.expect("trace wasn't collected");
+
+    let module_id = ModuleId::new(address, mod_name.clone());
+    assert_eq!(
+        trace.events.first(),
+        Some(&TraceEvent::CallFrame {
+            depth: 1,
+            module: Some(module_id),
+            function: "publish_balance".to_owned(),
+            type_args: vec![],
+        })
+    );
+    assert_eq!(trace.events.last(), Some(&TraceEvent::Return { depth: 1 }));
+    assert!(trace.events.iter().any(|event| matches!(
+        event,
+        TraceEvent::NativeCall { function, .. } if function == "borrow_address"
+    )));
+
+    let balance_tag = TypeTag::Struct(Box::new(StructTag {
+        address,
+        module: mod_name.clone(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    }));
+    let accesses = trace
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::ResourceAccess {
+                kind,
+                address,
+                resource,
+            } => Some((*kind, *address, resource.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        accesses,
+        vec![
+            (
+                ResourceAccessKind::Exists,
+                address,
+                Some(balance_tag.clone())
+            ),
+            (ResourceAccessKind::MoveTo, address, Some(balance_tag)),
+        ]
+    );
+
+    // Gas is only ever spent while stepping through the instructions.
+    let gas_left = trace
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            TraceEvent::Instruction { gas_left, .. } => Some(*gas_left),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(!gas_left.is_empty(), "no instructions were traced");
+    assert!(gas_left.windows(2).all(|w| w[0] >= w[1]));
+
+    let encoded = bcs::to_bytes(&trace).unwrap();
+    assert_eq!(bcs::from_bytes::<ExecutionTrace>(&encoded).unwrap(), trace);
+
+    // A failing call still returns its trace, ending with the error.
+    let result = vm.execute_function_with_trace(
+        address,
+        mod_name,
+        func_name,
+        vec![],
+        vec![&addr_param],
+        gas,
+    );
+    assert!(result.is_err(), "publishing the balance twice succeeded");
+    let trace = result.trace.expect("trace wasn't collected");
+    assert!(matches!(
+        trace.events.last(),
+        Some(TraceEvent::Error {
+            status_code: StatusCode::ABORTED,
+            sub_status: Some(2),
+            ..
+        })
+    ));
+}
+
 #[test]
 fn publishing_fails_with_insufficient_gas() {
     let store = StorageMock::new();
@@ -495,6 +1167,78 @@ fn publishing_fails_with_insufficient_gas() {
     }
 }
 
+#[test]
+fn publishing_charges_verification_work() {
+    let address = AccountAddress::from_hex_literal("0x2").unwrap();
+    let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    let vm = mvm_without_verification_cost(StorageMock::new());
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    let gas_without_verification = result.gas_used;
+
+    let vm = Mvm::new(StorageMock::new(), BalanceMock::new()).unwrap();
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+    assert!(
+        result.gas_used > gas_without_verification,
+        "verification wasn't charged"
+    );
+
+    // Publishing fails if the gas only covers the published bytes.
+    let vm = Mvm::new(StorageMock::new(), BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Metered(GasAmount::new(gas_without_verification).unwrap());
+    let result = vm.publish_module(&module, address, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+}
+
+#[test]
+fn publishing_fails_when_verification_exceeds_limits() {
+    let address = AccountAddress::from_hex_literal("0x2").unwrap();
+    let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let verification = VerificationConfig {
+        max_per_mod_meter_units: Some(1),
+        ..Default::default()
+    };
+    let vm =
+        Mvm::new_with_verification_config(StorageMock::new(), BalanceMock::new(), verification)
+            .unwrap();
+
+    let result = vm.publish_module(&module, address, GasStrategy::Unmetered);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+}
+
+#[test]
+fn rejected_publishing_is_charged() {
+    let address = AccountAddress::from_hex_literal("0x2").unwrap();
+    let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
+    let gas = GasStrategy::Metered(GasAmount::max());
+
+    // The published bytes and the verification work done until the module is rejected.
+    let verification = VerificationConfig {
+        max_per_mod_meter_units: Some(1),
+        ..Default::default()
+    };
+    let vm =
+        Mvm::new_with_verification_config(StorageMock::new(), BalanceMock::new(), verification)
+            .unwrap();
+    let result = vm.publish_module(&module, address, gas);
+    assert_eq!(result.status_code, StatusCode::CONSTRAINT_NOT_SATISFIED);
+    assert!(
+        result.gas_used > module.len() as u64,
+        "the rejected module wasn't charged"
+    );
+
+    // All the gas is used when the verification runs out of it.
+    let vm = Mvm::new(StorageMock::new(), BalanceMock::new()).unwrap();
+    let amount = module.len() as u64 + 1;
+    let gas = GasStrategy::Metered(GasAmount::new(amount).unwrap());
+    let result = vm.publish_module(&module, address, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    assert_eq!(result.gas_used, amount);
+}
+
 #[test]
 fn script_execution_fails_with_insufficient_gas() {
     let store = StorageMock::new();
@@ -527,10 +1271,106 @@ fn script_execution_fails_with_insufficient_gas() {
     );
 }
 
+#[test]
+fn execution_reports_io_usage() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let addr_param = bcs::to_bytes(&address).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let result = vm.execute_function(address, mod_name, func_name, vec![], vec![&addr_param], gas);
+    assert!(result.is_ok(), "script execution failed");
+
+    // The new `Balance` holds a single u64.
+    let expected = IoUsage {
+        accounts: 1,
+        reads: 1,
+        read_bytes: 0,
+        write_bytes: 8,
+        events: 0,
+        event_bytes: 0,
+    };
+    assert_eq!(result.io_usage, expected);
+}
+
+#[test]
+fn execution_fails_when_io_limits_are_exceeded() {
+    let store = store_preloaded_with_genesis_cfg();
+    let mut vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let gas = GasStrategy::Unmetered;
+
+    let address = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, address, gas);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let addr_param = bcs::to_bytes(&address).unwrap();
+    let mod_name = Identifier::new("BasicCoin").unwrap();
+    let func_name = Identifier::new("publish_balance").unwrap();
+    let mut publish_balance = |limits| {
+        vm.set_io_limits(limits);
+        vm.execute_function(
+            address,
+            mod_name.clone(),
+            func_name.clone(),
+            vec![],
+            vec![&addr_param],
+            gas,
+        )
+    };
+
+    let result = publish_balance(IoLimits {
+        max_write_bytes: Some(7),
+        ..Default::default()
+    });
+    assert_eq!(result.status_code, StatusCode::STORAGE_WRITE_LIMIT_REACHED);
+    assert_eq!(result.io_usage.write_bytes, 8);
+
+    let result = publish_balance(IoLimits {
+        max_accounts: Some(0),
+        ..Default::default()
+    });
+    assert_eq!(result.status_code, StatusCode::IO_LIMIT_REACHED);
+
+    let result = publish_balance(IoLimits {
+        max_reads: Some(0),
+        ..Default::default()
+    });
+    assert_eq!(result.status_code, StatusCode::IO_LIMIT_REACHED);
+    assert_eq!(result.io_usage.reads, 1);
+
+    // Nothing was published by the failed calls.
+    let result = publish_balance(IoLimits {
+        max_accounts: Some(1),
+        max_reads: Some(1),
+        max_write_bytes: Some(8),
+        ..Default::default()
+    });
+    assert!(result.is_ok(), "script execution failed");
+
+    // Publishing the balance again aborts after reading the existing one, unless the read fails
+    // first.
+    let result = publish_balance(IoLimits::default());
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+    let result = publish_balance(IoLimits {
+        max_read_bytes: Some(7),
+        ..Default::default()
+    });
+    assert_eq!(result.status_code, StatusCode::IO_LIMIT_REACHED);
+    assert_eq!(result.io_usage.read_bytes, 8);
+}
+
 #[test]
 fn dry_run_gas_strategy_doesnt_update_storage() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = mvm_without_verification_cost(store);
 
     let module = read_module_bytes_from_project("using_stdlib_natives", "Vector");
     let address = AccountAddress::from_hex_literal("0x2").unwrap();
@@ -621,10 +1461,195 @@ fn execute_transfer_script_and_check_balance_updates() {
     assert!(!result.is_ok(), "managed to execute the script");
 }
 
+/// Creates a MoveVM dispatching the calls to other VMs to `cross_vm`, with the TokenBridge module
+/// of the cross_vm_call project published under 0xCAFE.
+fn mvm_with_token_bridge(cross_vm: CrossVmMock) -> Mvm<StorageMock, BalanceMock, CrossVmMock> {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new())
+        .unwrap()
+        .with_cross_vm_handler(cross_vm);
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("cross_vm_call", "TokenBridge");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+    vm
+}
+
+/// Calls `TokenBridge::transfer` from `account`.
+fn transfer_through_token_bridge<C: CrossVmHandler>(
+    vm: &Mvm<StorageMock, BalanceMock, C>,
+    account: AccountAddress,
+    contract: AccountAddress,
+    to: AccountAddress,
+    amount: u128,
+    gas: GasStrategy,
+) -> VmResult {
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let account = bcs::to_bytes(&account).unwrap();
+    let contract = bcs::to_bytes(&contract).unwrap();
+    let to = bcs::to_bytes(&to).unwrap();
+    let amount = bcs::to_bytes(&amount).unwrap();
+    vm.execute_function(
+        cafe,
+        Identifier::new("TokenBridge").unwrap(),
+        Identifier::new("transfer").unwrap(),
+        vec![],
+        vec![&account, &contract, &to, &amount],
+        gas,
+    )
+}
+
+#[test]
+fn cross_vm_call_dispatches_scale_encoded_arguments() {
+    let cross_vm = CrossVmMock::new();
+    cross_vm.set_result(Ok(CrossVmOutput {
+        success: true,
+        data: vec![1, 2, 3],
+        gas_used: 1000,
+    }));
+    let vm = mvm_with_token_bridge(cross_vm.clone());
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let contract = AccountAddress::from_hex_literal("0xC0DE").unwrap();
+    let to = AccountAddress::from_hex_literal("0x3EEE").unwrap();
+    let result = transfer_through_token_bridge(&vm, bob, contract, to, 42, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to call the contract");
+
+    // The input is the selector followed by the arguments, as encoded for the ink! message.
+    let mut input = vec![0x84, 0xa1, 0x5d, 0xa1];
+    input.extend((to.into_bytes(), 42u128).encode());
+    assert_eq!(
+        cross_vm.calls(),
+        vec![CrossVmCall {
+            caller: bob,
+            contract,
+            input,
+            value: 0,
+            gas_limit: 1000000,
+        }]
+    );
+
+    // The output of the contract is stored by the module, and can be read back in BCS and SCALE.
+    let tag = StructTag {
+        address: AccountAddress::from_hex_literal("0xCAFE").unwrap(),
+        module: Identifier::new("TokenBridge").unwrap(),
+        name: Identifier::new("LastTransfer").unwrap(),
+        type_params: vec![],
+    };
+    let tag = bcs::to_bytes(&tag).unwrap();
+    let output = vec![1u8, 2, 3];
+    assert_eq!(
+        vm.get_resource(&bob, &tag).unwrap(),
+        Some(bcs::to_bytes(&output).unwrap())
+    );
+    assert_eq!(
+        vm.get_resource_scale(&bob, &tag).unwrap(),
+        Some(output.encode())
+    );
+    assert_eq!(vm.get_resource_scale(&to, &tag).unwrap(), None);
+}
+
+#[test]
+fn cross_vm_call_charges_the_gas_used_by_the_contract() {
+    let cross_vm = CrossVmMock::new();
+    let vm = mvm_with_token_bridge(cross_vm.clone());
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let contract = AccountAddress::from_hex_literal("0xC0DE").unwrap();
+    let to = AccountAddress::from_hex_literal("0x3EEE").unwrap();
+    let call_with_gas_used = |gas_used, gas| {
+        cross_vm.set_result(Ok(CrossVmOutput {
+            success: true,
+            data: vec![],
+            gas_used,
+        }));
+        transfer_through_token_bridge(&vm, bob, contract, to, 42, gas)
+    };
+
+    // The gas used by the contract is charged in Move gas units, on top of the cost of the call.
+    // The first call stores the output of the contract, which costs more than overwriting it.
+    let gas = GasStrategy::Metered(GasAmount::new(1_000_000).unwrap());
+    assert!(
+        call_with_gas_used(0, gas).is_ok(),
+        "failed to call the contract"
+    );
+    let result = call_with_gas_used(0, gas);
+    assert!(result.is_ok(), "failed to call the contract");
+    let gas_without_contract = result.gas_used;
+    let result = call_with_gas_used(500, gas);
+    assert!(result.is_ok(), "failed to call the contract");
+    assert_eq!(result.gas_used, gas_without_contract + 500);
+
+    // The gas limit of the call is capped by the gas left, which the contract can use up.
+    let budget = gas_without_contract + 100;
+    let gas = GasStrategy::Metered(GasAmount::new(budget).unwrap());
+    let result = call_with_gas_used(budget, gas);
+    assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    let gas_limit = cross_vm.calls().last().unwrap().gas_limit;
+    assert!(
+        (100..budget).contains(&gas_limit),
+        "the gas limit {gas_limit} isn't capped by the gas left"
+    );
+
+    // Without enough gas for the call itself, the contract isn't called: the smallest amount of
+    // gas it is called with leaves less than a Move gas unit to the contract.
+    let calls = cross_vm.calls().len();
+    for amount in 1..=gas_without_contract {
+        let gas = GasStrategy::Metered(GasAmount::new(amount).unwrap());
+        let result = call_with_gas_used(0, gas);
+        if cross_vm.calls().len() > calls {
+            break;
+        }
+        assert_eq!(result.status_code, StatusCode::OUT_OF_GAS);
+    }
+    assert_eq!(cross_vm.calls().last().unwrap().gas_limit, 0);
+}
+
+#[test]
+fn cross_vm_call_failures_abort_the_execution() {
+    let cross_vm = CrossVmMock::new();
+    let vm = mvm_with_token_bridge(cross_vm.clone());
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let contract = AccountAddress::from_hex_literal("0xC0DE").unwrap();
+    let to = AccountAddress::from_hex_literal("0x3EEE").unwrap();
+
+    // The contract reverted: the module aborts.
+    cross_vm.set_result(Ok(CrossVmOutput {
+        success: false,
+        data: vec![],
+        gas_used: 0,
+    }));
+    let result = transfer_through_token_bridge(&vm, bob, contract, to, 42, GasStrategy::Unmetered);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+
+    // The handler failed: its status is reported.
+    cross_vm.set_result(Err(StatusCode::INSUFFICIENT_BALANCE));
+    let result = transfer_through_token_bridge(&vm, bob, contract, to, 42, GasStrategy::Unmetered);
+    assert_eq!(result.status_code, StatusCode::INSUFFICIENT_BALANCE);
+    assert_eq!(cross_vm.calls().len(), 2);
+}
+
+#[test]
+fn cross_vm_call_aborts_without_a_handler() {
+    let store = store_preloaded_with_genesis_cfg();
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+
+    let cafe = AccountAddress::from_hex_literal("0xCAFE").unwrap();
+    let module = read_module_bytes_from_project("cross_vm_call", "TokenBridge");
+    let result = vm.publish_module(&module, cafe, GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+
+    let bob = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let result = transfer_through_token_bridge(&vm, bob, bob, bob, 42, GasStrategy::Unmetered);
+    assert_eq!(result.status_code, StatusCode::ABORTED);
+}
+
 #[test]
 fn publish_module_with_base58_address() {
     let store = StorageMock::new();
-    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let vm = mvm_without_verification_cost(store);
 
     let address = AccountAddress::from_hex_literal(
         "0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48",
@@ -0,0 +1,193 @@
+//! Integration tests for the parallel execution of batches of calls.
+//!
+//! Note:
+//! These tests depend on the basic_coin project within tests/assets/move-projects.
+#![cfg(feature = "parallel")]
+
+use crate::mock::BalanceMock;
+use crate::mock::StorageMock;
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use move_core_types::language_storage::StructTag;
+use move_core_types::vm_status::StatusCode;
+use move_stdlib::{move_stdlib_bundle, substrate_stdlib_bundle};
+use move_vm_backend::genesis::VmGenesisConfig;
+use move_vm_backend::parallel::ParallelExecutor;
+use move_vm_backend::types::{Call, GasAmount, GasStrategy, Transaction};
+use move_vm_backend::Mvm;
+use move_vm_backend_common::types::ModuleBundle;
+use read_write_set_dynamic::NormalizedReadWriteSetAnalysis;
+
+pub mod mock;
+
+/// Reads a precompiled Move module from our assets directory.
+fn read_module_bytes_from_project(project: &str, module_name: &str) -> Vec<u8> {
+    const MOVE_PROJECTS: &str = "tests/assets/move-projects";
+
+    let path =
+        format!("{MOVE_PROJECTS}/{project}/build/{project}/bytecode_modules/{module_name}.mv");
+
+    std::fs::read(&path)
+        .unwrap_or_else(|e| panic!("Can't read {path}: {e} - make sure you run move-vm-backend/tests/assets/move-projects/smove-build-all.sh"))
+}
+
+fn cafe() -> AccountAddress {
+    AccountAddress::from_hex_literal("0xCAFE").unwrap()
+}
+
+/// Creates a MoveVM with the stdlib and the BasicCoin module published under 0xCAFE.
+fn mvm_with_basic_coin() -> Mvm<StorageMock, BalanceMock> {
+    let store = StorageMock::new();
+    assert!(
+        VmGenesisConfig::default().apply(store.clone()).is_ok(),
+        "genesis configuration failure"
+    );
+
+    let vm = Mvm::new(store, BalanceMock::new()).unwrap();
+    let module = read_module_bytes_from_project("basic_coin", "BasicCoin");
+    let result = vm.publish_module(&module, cafe(), GasStrategy::Unmetered);
+    assert!(result.is_ok(), "failed to publish the module");
+    vm
+}
+
+/// Analyzes the stdlib and the BasicCoin module, with `BasicCoin::publish_balance` as the only
+/// entry point.
+fn basic_coin_analysis() -> NormalizedReadWriteSetAnalysis {
+    let mut modules = Vec::new();
+    for bundle in [move_stdlib_bundle(), substrate_stdlib_bundle()] {
+        modules.extend(ModuleBundle::try_from(bundle).unwrap().into_inner());
+    }
+    modules.push(read_module_bytes_from_project("basic_coin", "BasicCoin"));
+    let modules = modules
+        .iter()
+        .map(|bytes| CompiledModule::deserialize(bytes).unwrap())
+        .collect::<Vec<_>>();
+
+    let basic_coin = ModuleId::new(cafe(), Identifier::new("BasicCoin").unwrap());
+    read_write_set::analyze(&modules)
+        .expect("analysis failed")
+        .normalize_all_scripts(vec![(
+            basic_coin,
+            Identifier::new("publish_balance").unwrap(),
+        )])
+}
+
+fn publish_balance(who: AccountAddress) -> (Transaction, GasStrategy) {
+    let transaction = Transaction {
+        call: Call::ScriptFunction {
+            mod_address: cafe(),
+            mod_name: Identifier::new("BasicCoin").unwrap(),
+            func_name: Identifier::new("publish_balance").unwrap(),
+        },
+        type_args: vec![],
+        args: vec![bcs::to_bytes(&who).unwrap()],
+    };
+    (
+        transaction,
+        GasStrategy::Metered(GasAmount::new(1_000_000).unwrap()),
+    )
+}
+
+fn balance_of(vm: &Mvm<StorageMock, BalanceMock>, who: &AccountAddress) -> Option<Vec<u8>> {
+    let tag = StructTag {
+        address: cafe(),
+        module: Identifier::new("BasicCoin").unwrap(),
+        name: Identifier::new("Balance").unwrap(),
+        type_params: vec![],
+    };
+    vm.get_resource(who, &bcs::to_bytes(&tag).unwrap()).unwrap()
+}
+
+#[test]
+fn parallel_batch_matches_sequential_execution() {
+    let accounts = (1..=8u64)
+        .map(|i| AccountAddress::from_hex_literal(&format!("{:#x}", 0x1000 + i)).unwrap())
+        .collect::<Vec<_>>();
+    // The second call for the same account conflicts with the first one and must fail.
+    let batch = || publish_balance_batch(&accounts, 2, 5);
+
+    let sequential = mvm_with_basic_coin();
+    let expected = batch()
+        .into_iter()
+        .map(|(transaction, gas)| {
+            let Call::ScriptFunction {
+                mod_address,
+                mod_name,
+                func_name,
+            } = transaction.call
+            else {
+                unreachable!()
+            };
+            let args = transaction.args.iter().map(Vec::as_slice).collect();
+            sequential.execute_function(mod_address, mod_name, func_name, vec![], args, gas)
+        })
+        .collect::<Vec<_>>();
+
+    let parallel = mvm_with_basic_coin();
+    let executor = ParallelExecutor::new(&parallel, basic_coin_analysis()).with_num_threads(3);
+    let results = executor.execute_batch(batch()).unwrap();
+
+    assert_eq!(results.len(), expected.len());
+    for (result, expected) in results.iter().zip(&expected) {
+        assert_eq!(result.status_code, expected.status_code);
+        assert_eq!(result.gas_used, expected.gas_used);
+    }
+    assert_eq!(results[5].status_code, StatusCode::ABORTED);
+    for who in &accounts {
+        assert!(balance_of(&parallel, who).is_some(), "resource not found");
+        assert_eq!(balance_of(&parallel, who), balance_of(&sequential, who));
+    }
+}
+
+/// Calls to `publish_balance` for `accounts`, with a second call for `accounts[duplicate]`
+/// inserted at `position`.
+fn publish_balance_batch(
+    accounts: &[AccountAddress],
+    duplicate: usize,
+    position: usize,
+) -> Vec<(Transaction, GasStrategy)> {
+    let mut batch = accounts
+        .iter()
+        .map(|who| publish_balance(*who))
+        .collect::<Vec<_>>();
+    batch.insert(position, publish_balance(accounts[duplicate]));
+    batch
+}
+
+#[test]
+fn calls_outside_of_the_analysis_run_sequentially() {
+    let vm = mvm_with_basic_coin();
+    let executor = ParallelExecutor::new(&vm, basic_coin_analysis());
+
+    // `mint` is not an entry point of the analysis, so it's executed on its own and rejected by
+    // the VM as it is not an entry function.
+    let who = AccountAddress::from_hex_literal("0xB0B").unwrap();
+    let mint = Transaction {
+        call: Call::ScriptFunction {
+            mod_address: cafe(),
+            mod_name: Identifier::new("BasicCoin").unwrap(),
+            func_name: Identifier::new("mint").unwrap(),
+        },
+        type_args: vec![],
+        args: vec![
+            bcs::to_bytes(&cafe()).unwrap(),
+            bcs::to_bytes(&who).unwrap(),
+            bcs::to_bytes(&10u64).unwrap(),
+        ],
+    };
+    let results = executor
+        .execute_batch(vec![
+            publish_balance(who),
+            (mint, GasStrategy::Unmetered),
+            publish_balance(cafe()),
+        ])
+        .unwrap();
+
+    assert!(results[0].is_ok());
+    assert!(!results[1].is_ok());
+    assert!(results[2].is_ok());
+    assert!(balance_of(&vm, &who).is_some());
+    assert!(balance_of(&vm, &cafe()).is_some());
+}
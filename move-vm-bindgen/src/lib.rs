@@ -0,0 +1,214 @@
+//! Generation of typed client bindings from the ABIs of Move modules.
+//!
+//! The bindings declare the structs of the modules, with their BCS (de)serializers, and builders
+//! of the calls to their entry functions. The builders encode the arguments as expected by the
+//! MoveVM: each argument is BCS encoded, signers as their address.
+//!
+//! Modules are nested in a module, or a namespace in TypeScript, per address, named `addr_`
+//! followed by the address in short hex, e.g. `addr_1::string::String`. Function parameters
+//! aren't named in the ABIs, so the builders take `arg0`, `arg1`, etc.
+
+use anyhow::{bail, Context, Result};
+use move_binary_format::CompiledModule;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::ModuleId;
+use move_vm_backend_common::abi::{Function, ModuleAbi, Struct, Type};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+pub mod rust;
+pub mod typescript;
+
+/// Reads the ABIs of the modules of a package built in `build_dir`, e.g. `build/<package>`, and
+/// of its dependencies if `with_dependencies` is set.
+///
+/// The bindings of a package need the ABIs of the dependencies declaring the structs it uses.
+pub fn package_abis(build_dir: &Path, with_dependencies: bool) -> Result<Vec<ModuleAbi>> {
+    let modules_dir = build_dir.join("bytecode_modules");
+    let mut dirs = vec![modules_dir.clone()];
+    let dependencies_dir = modules_dir.join("dependencies");
+    if with_dependencies && dependencies_dir.is_dir() {
+        for entry in fs::read_dir(&dependencies_dir)? {
+            dirs.push(entry?.path());
+        }
+    }
+
+    let mut abis = vec![];
+    for dir in dirs {
+        let entries = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read the modules in {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().map_or(false, |ext| ext == "mv") {
+                let bytecode = fs::read(&path)?;
+                let module = CompiledModule::deserialize(&bytecode)
+                    .with_context(|| format!("Invalid module {}", path.display()))?;
+                abis.push(ModuleAbi::from(module));
+            }
+        }
+    }
+    abis.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(abis)
+}
+
+type Structs<'a> = BTreeMap<(ModuleId, Identifier), &'a Struct>;
+
+/// The modules to generate the bindings for, grouped by address.
+pub(crate) struct Modules<'a> {
+    pub(crate) by_address: BTreeMap<AccountAddress, Vec<&'a ModuleAbi>>,
+}
+
+impl<'a> Modules<'a> {
+    /// Checks that the types used by the structs and the entry functions of the modules can be
+    /// bound: their structs must be declared by the modules, and only signers can be passed by
+    /// reference.
+    pub(crate) fn new(abis: &'a [ModuleAbi]) -> Result<Self> {
+        let mut structs = Structs::new();
+        for abi in abis {
+            for struct_ in abi.structs.iter() {
+                structs.insert((abi.id.clone(), struct_.name.clone()), struct_);
+            }
+        }
+
+        let mut by_address: BTreeMap<_, Vec<&ModuleAbi>> = BTreeMap::new();
+        let mut ids = BTreeSet::new();
+        for abi in abis {
+            if !ids.insert(&abi.id) {
+                bail!("Duplicate ABI of module {}", abi.id);
+            }
+            for struct_ in abi.structs.iter().filter(|s| !is_native(s)) {
+                for field in struct_.fields.iter() {
+                    check_type(&field.tp, &structs)
+                        .with_context(|| format!("In struct {}::{}", abi.id, struct_.name))?;
+                }
+            }
+            for func in entry_functions(abi) {
+                for ty in func.parameters.iter() {
+                    if !is_signer(ty) {
+                        check_type(ty, &structs)
+                            .with_context(|| format!("In function {}::{}", abi.id, func.name))?;
+                    }
+                }
+            }
+            by_address.entry(*abi.id.address()).or_default().push(abi);
+        }
+        for modules in by_address.values_mut() {
+            modules.sort_by(|a, b| a.id.name().cmp(b.id.name()));
+        }
+        Ok(Self { by_address })
+    }
+}
+
+fn check_type(ty: &Type, structs: &Structs) -> Result<()> {
+    match ty {
+        Type::Vector(elem) => check_type(elem, structs),
+        Type::Struct(def) => {
+            let Some(struct_) = structs.get(&(def.id.clone(), def.name.clone())) else {
+                bail!("Missing the ABI of struct {}::{}", def.id, def.name);
+            };
+            if is_native(struct_) {
+                bail!("Native struct {}::{} has no layout", def.id, def.name);
+            }
+            if struct_.type_parameters.len() != def.fields.len() {
+                bail!(
+                    "Struct {}::{} expects {} type arguments, got {}",
+                    def.id,
+                    def.name,
+                    struct_.type_parameters.len(),
+                    def.fields.len()
+                );
+            }
+            def.fields.iter().try_for_each(|ty| check_type(ty, structs))
+        }
+        Type::Reference(_) | Type::MutableReference(_) => {
+            bail!("References can't be passed to entry functions, except signers")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Name of the module, or namespace, of the modules of the address.
+pub(crate) fn address_module(address: &AccountAddress) -> String {
+    format!("addr_{}", address.short_str_lossless())
+}
+
+/// Native structs have no fields in the ABIs, and no layout.
+pub(crate) fn is_native(struct_: &Struct) -> bool {
+    struct_.fields.is_empty()
+}
+
+/// Whether the value of the entry function parameter is a signer, passed as its address.
+pub(crate) fn is_signer(ty: &Type) -> bool {
+    match ty {
+        Type::Signer => true,
+        Type::Reference(ty) | Type::MutableReference(ty) => **ty == Type::Signer,
+        _ => false,
+    }
+}
+
+pub(crate) fn entry_functions(abi: &ModuleAbi) -> impl Iterator<Item = &Function> {
+    abi.funcs.iter().filter(|func| func.is_entry)
+}
+
+/// Type parameters of the struct which aren't used by its fields, e.g. phantom ones.
+pub(crate) fn unused_type_parameters(struct_: &Struct) -> Vec<u16> {
+    fn collect(ty: &Type, used: &mut BTreeSet<u16>) {
+        match ty {
+            Type::TypeParameter(idx) => {
+                used.insert(*idx);
+            }
+            Type::Vector(ty) | Type::Reference(ty) | Type::MutableReference(ty) => {
+                collect(ty, used)
+            }
+            Type::Struct(def) => def.fields.iter().for_each(|ty| collect(ty, used)),
+            _ => {}
+        }
+    }
+
+    let mut used = BTreeSet::new();
+    struct_
+        .fields
+        .iter()
+        .for_each(|f| collect(&f.tp, &mut used));
+    (0..struct_.type_parameters.len() as u16)
+        .filter(|idx| !used.contains(idx))
+        .collect()
+}
+
+/// Indented source code.
+#[derive(Default)]
+pub(crate) struct Code {
+    source: String,
+    indent: usize,
+}
+
+impl Code {
+    const INDENT: &'static str = "    ";
+
+    pub(crate) fn line(&mut self, line: impl AsRef<str>) {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            self.source.push_str(&Self::INDENT.repeat(self.indent));
+            self.source.push_str(line);
+        }
+        self.source.push('\n');
+    }
+
+    /// Writes the line and indents the following ones.
+    pub(crate) fn open(&mut self, line: impl AsRef<str>) {
+        self.line(line);
+        self.indent += 1;
+    }
+
+    /// Unindents and writes the line.
+    pub(crate) fn close(&mut self, line: impl AsRef<str>) {
+        self.indent -= 1;
+        self.line(line);
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.source
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use clap::{ArgEnum, Parser};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, ArgEnum)]
+enum Language {
+    Rust,
+    Typescript,
+}
+
+/// Generates the client bindings of a built Move package.
+#[derive(Parser)]
+#[clap(author, version, about)]
+struct Args {
+    /// The build directory of the package, e.g. `build/<package>`
+    build_dir: PathBuf,
+    /// The language of the bindings
+    #[clap(long, short, arg_enum, default_value = "rust")]
+    language: Language,
+    /// Also generate the bindings of the dependencies
+    #[clap(long)]
+    with_dependencies: bool,
+    /// The file to write the bindings to, instead of the standard output
+    #[clap(long, short)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let abis = move_vm_bindgen::package_abis(&args.build_dir, args.with_dependencies)?;
+    let bindings = match args.language {
+        Language::Rust => move_vm_bindgen::rust::generate(&abis)?,
+        Language::Typescript => move_vm_bindgen::typescript::generate(&abis)?,
+    };
+    match args.output {
+        Some(path) => fs::write(path, bindings)?,
+        None => print!("{bindings}"),
+    }
+    Ok(())
+}
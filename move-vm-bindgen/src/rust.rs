@@ -0,0 +1,328 @@
+//! Rust client bindings.
+//!
+//! The bindings are the source of a Rust module file, depending on the `move-core-types`, `serde`
+//! and `bcs` crates. The Move values are represented by:
+//! - `bool` and the unsigned integers as the equivalent Rust types, `u256` as
+//!   `move_core_types::u256::U256`
+//! - `address` and `signer` as `move_core_types::account_address::AccountAddress`
+//! - `vector<T>` as `Vec<T>`
+//! - structs as Rust structs, deriving `serde::Serialize` and `serde::Deserialize`, with a
+//!   `_phantom` field if some of their type parameters aren't used by their fields
+//!
+//! The `MoveType` trait gives the type tags of the values, for the type arguments of the generic
+//! entry functions.
+
+use crate::{
+    address_module, entry_functions, is_native, is_signer, unused_type_parameters, Code, Modules,
+};
+use anyhow::Result;
+use move_core_types::language_storage::ModuleId;
+use move_vm_backend_common::abi::{Function, ModuleAbi, Struct, Type};
+
+const ADDRESS: &str = "::move_core_types::account_address::AccountAddress";
+const VEC: &str = "::std::vec::Vec";
+const TYPE_TAG: &str = "::move_core_types::language_storage::TypeTag";
+const RESULT: &str = "::core::result::Result";
+
+// Items at the root of the bindings, seen from the modules.
+const ROOT: &str = "super::super";
+
+const PRELUDE: &str = r#"//! Client bindings of Move modules, generated by `move-vm-bindgen`. Do not edit.
+
+#![allow(dead_code, non_camel_case_types, non_snake_case, clippy::all)]
+
+/// Call of an entry function, with the arguments encoded as expected by the MoveVM: BCS encoded,
+/// signers as their address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryFunctionCall {
+    pub module: ::move_core_types::language_storage::ModuleId,
+    pub function: ::move_core_types::identifier::Identifier,
+    pub type_args: ::std::vec::Vec<::move_core_types::language_storage::TypeTag>,
+    pub args: ::std::vec::Vec<::std::vec::Vec<u8>>,
+}
+
+/// Move type of the values.
+pub trait MoveType {
+    fn type_tag() -> ::move_core_types::language_storage::TypeTag;
+}
+
+macro_rules! move_type {
+    ($($ty:ty => $tag:ident),*) => {
+        $(impl MoveType for $ty {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                ::move_core_types::language_storage::TypeTag::$tag
+            }
+        })*
+    };
+}
+
+move_type!(
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    ::move_core_types::u256::U256 => U256,
+    ::move_core_types::account_address::AccountAddress => Address
+);
+
+impl<T: MoveType> MoveType for ::std::vec::Vec<T> {
+    fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+        ::move_core_types::language_storage::TypeTag::Vector(::std::boxed::Box::new(T::type_tag()))
+    }
+}
+
+fn identifier(name: &str) -> ::move_core_types::identifier::Identifier {
+    ::move_core_types::identifier::Identifier::new(name).expect("Move identifier")
+}
+
+fn struct_tag(
+    address: ::move_core_types::account_address::AccountAddress,
+    module: &str,
+    name: &str,
+    type_params: ::std::vec::Vec<::move_core_types::language_storage::TypeTag>,
+) -> ::move_core_types::language_storage::TypeTag {
+    ::move_core_types::language_storage::TypeTag::Struct(::std::boxed::Box::new(
+        ::move_core_types::language_storage::StructTag {
+            address,
+            module: identifier(module),
+            name: identifier(name),
+            type_params,
+        },
+    ))
+}
+"#;
+
+/// Generates the Rust bindings of the modules.
+pub fn generate(abis: &[ModuleAbi]) -> Result<String> {
+    let modules = Modules::new(abis)?;
+    let mut code = Code::default();
+    for line in PRELUDE.lines() {
+        code.line(line);
+    }
+
+    for (address, abis) in modules.by_address.iter() {
+        code.line("");
+        code.open(format!("pub mod {} {{", address_module(address)));
+        code.line(format!("/// Address `{}`.", address.to_hex_literal()));
+        code.line(format!(
+            "pub const ADDRESS: {ADDRESS} = {ADDRESS}::new({:?});",
+            address.into_bytes()
+        ));
+        for abi in abis {
+            code.line("");
+            generate_module(&mut code, abi);
+        }
+        code.close("}");
+    }
+    Ok(code.finish())
+}
+
+fn generate_module(code: &mut Code, abi: &ModuleAbi) {
+    code.open(format!("pub mod {} {{", ident(abi.id.name().as_str())));
+    code.line(format!(
+        "/// Name of the module `{}`.",
+        abi.id.short_str_lossless()
+    ));
+    code.line(format!(
+        "pub const NAME: &str = {:?};",
+        abi.id.name().as_str()
+    ));
+    for struct_ in abi.structs.iter().filter(|s| !is_native(s)) {
+        code.line("");
+        generate_struct(code, &abi.id, struct_);
+    }
+    for func in entry_functions(abi) {
+        code.line("");
+        generate_entry_function(code, &abi.id, func);
+    }
+    code.close("}");
+}
+
+fn generate_struct(code: &mut Code, module: &ModuleId, struct_: &Struct) {
+    let name = struct_.name.as_str();
+    let ty_params: Vec<_> = (0..struct_.type_parameters.len())
+        .map(|idx| format!("T{idx}"))
+        .collect();
+    let generics = generics(&ty_params);
+
+    code.line(format!("/// `{}::{}`.", module.short_str_lossless(), name));
+    code.line("#[derive(Clone, Debug, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]");
+    code.open(format!("pub struct {name}{generics} {{"));
+    for field in struct_.fields.iter() {
+        code.line(format!(
+            "pub {}: {},",
+            ident(field.name.as_str()),
+            rust_type(&field.tp, module)
+        ));
+    }
+    let unused = unused_type_parameters(struct_);
+    if !unused.is_empty() {
+        let unused: Vec<_> = unused.iter().map(|idx| format!("T{idx}")).collect();
+        code.line("#[serde(skip)]");
+        code.line(format!(
+            "pub _phantom: ::core::marker::PhantomData<({},)>,",
+            unused.join(", ")
+        ));
+    }
+    code.close("}");
+
+    // Generic structs are only (de)serializable if their type arguments are.
+    let (serialize, deserialize) = if ty_params.is_empty() {
+        (String::new(), String::new())
+    } else {
+        (
+            " where Self: ::serde::Serialize".to_string(),
+            " where Self: ::serde::de::DeserializeOwned".to_string(),
+        )
+    };
+    code.line("");
+    code.open(format!("impl{generics} {name}{generics} {{"));
+    code.line("/// Serializes the value in BCS.");
+    code.open(format!(
+        "pub fn to_bcs(&self) -> {RESULT}<{VEC}<u8>, ::bcs::Error>{serialize} {{"
+    ));
+    code.line("::bcs::to_bytes(self)");
+    code.close("}");
+    code.line("");
+    code.line("/// Deserializes a BCS encoded value.");
+    code.open(format!(
+        "pub fn from_bcs(bytes: &[u8]) -> {RESULT}<Self, ::bcs::Error>{deserialize} {{"
+    ));
+    code.line("::bcs::from_bytes(bytes)");
+    code.close("}");
+    code.close("}");
+
+    let bounded: Vec<_> = ty_params
+        .iter()
+        .map(|param| format!("{param}: {ROOT}::MoveType"))
+        .collect();
+    let type_params: Vec<_> = ty_params
+        .iter()
+        .map(|param| format!("{param}::type_tag()"))
+        .collect();
+    code.line("");
+    code.open(format!(
+        "impl{} {ROOT}::MoveType for {name}{generics} {{",
+        self::generics(&bounded)
+    ));
+    code.open(format!("fn type_tag() -> {TYPE_TAG} {{"));
+    code.line(format!(
+        "{ROOT}::struct_tag(super::ADDRESS, NAME, {:?}, ::std::vec![{}])",
+        name,
+        type_params.join(", ")
+    ));
+    code.close("}");
+    code.close("}");
+}
+
+fn generate_entry_function(code: &mut Code, module: &ModuleId, func: &Function) {
+    let ty_params: Vec<_> = (0..func.type_parameters.len())
+        .map(|idx| format!("T{idx}: {ROOT}::MoveType + ::serde::Serialize"))
+        .collect();
+    let params: Vec<_> = func
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| format!("arg{idx}: {}", param_type(ty, module)))
+        .collect();
+    let type_args: Vec<_> = (0..func.type_parameters.len())
+        .map(|idx| format!("T{idx}::type_tag()"))
+        .collect();
+    let args: Vec<_> = (0..func.parameters.len())
+        .map(|idx| format!("::bcs::to_bytes(&arg{idx})?"))
+        .collect();
+
+    code.line(format!(
+        "/// Builds a call of the entry function `{}::{}`.",
+        module.short_str_lossless(),
+        func.name
+    ));
+    code.open(format!(
+        "pub fn {}{}({}) -> {RESULT}<{ROOT}::EntryFunctionCall, ::bcs::Error> {{",
+        ident(func.name.as_str()),
+        generics(&ty_params),
+        params.join(", ")
+    ));
+    code.open(format!("{RESULT}::Ok({ROOT}::EntryFunctionCall {{"));
+    code.line(format!(
+        "module: ::move_core_types::language_storage::ModuleId::new(super::ADDRESS, {ROOT}::identifier(NAME)),"
+    ));
+    code.line(format!(
+        "function: {ROOT}::identifier({:?}),",
+        func.name.as_str()
+    ));
+    code.line(format!("type_args: ::std::vec![{}],", type_args.join(", ")));
+    code.line(format!("args: ::std::vec![{}],", args.join(", ")));
+    code.close("})");
+    code.close("}");
+}
+
+fn generics(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn param_type(ty: &Type, module: &ModuleId) -> String {
+    if is_signer(ty) {
+        ADDRESS.to_string()
+    } else {
+        rust_type(ty, module)
+    }
+}
+
+/// Rust type of the values of `ty`, used in `module`.
+fn rust_type(ty: &Type, module: &ModuleId) -> String {
+    match ty {
+        Type::Bool => "bool".to_string(),
+        Type::U8 => "u8".to_string(),
+        Type::U16 => "u16".to_string(),
+        Type::U32 => "u32".to_string(),
+        Type::U64 => "u64".to_string(),
+        Type::U128 => "u128".to_string(),
+        Type::U256 => "::move_core_types::u256::U256".to_string(),
+        Type::Address | Type::Signer => ADDRESS.to_string(),
+        Type::Vector(elem) => format!("{VEC}<{}>", rust_type(elem, module)),
+        Type::TypeParameter(idx) => format!("T{idx}"),
+        Type::Reference(ty) | Type::MutableReference(ty) => rust_type(ty, module),
+        Type::Struct(def) => {
+            let path = if &def.id == module {
+                def.name.to_string()
+            } else {
+                format!(
+                    "{ROOT}::{}::{}::{}",
+                    address_module(def.id.address()),
+                    ident(def.id.name().as_str()),
+                    def.name
+                )
+            };
+            let ty_args: Vec<_> = def.fields.iter().map(|ty| rust_type(ty, module)).collect();
+            format!("{path}{}", generics(&ty_args))
+        }
+    }
+}
+
+/// Escapes the Move identifiers which are Rust keywords.
+fn ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+        "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let",
+        "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+        "virtual", "where", "while", "yield",
+    ];
+    // Keywords which can't be raw identifiers.
+    const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
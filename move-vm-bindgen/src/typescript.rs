@@ -0,0 +1,496 @@
+//! TypeScript client bindings.
+//!
+//! The bindings are the source of a self-contained TypeScript module. The Move values are
+//! represented by:
+//! - `bool` as `boolean`, `u8`, `u16` and `u32` as `number`, the wider integers as `bigint`
+//! - `address` and `signer` as hex strings, e.g. `"0xcafe"`
+//! - `vector<T>` as arrays
+//! - structs as interfaces, with the fields of the Move structs
+//!
+//! Each type has a `$move.Codec`, serializing its values in BCS, with the type tag in the Move
+//! syntax, e.g. `0xcafe::coin::Coin<u64>`, as parsed by `move_core_types::parser`. Codecs of
+//! generic structs, and builders of calls to generic entry functions, take the codecs of their
+//! type arguments.
+
+use crate::{address_module, entry_functions, is_native, is_signer, Code, Modules};
+use anyhow::Result;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::language_storage::ModuleId;
+use move_vm_backend_common::abi::{Function, ModuleAbi, Struct, Type};
+
+const PRELUDE: &str = r#"// Client bindings of Move modules, generated by `move-vm-bindgen`. Do not edit.
+
+/** Runtime of the bindings: BCS codecs of the Move types. */
+export namespace $move {
+    /** Call of an entry function, with the arguments encoded as expected by the MoveVM. */
+    export interface EntryFunctionCall {
+        module: string;
+        function: string;
+        typeArgs: string[];
+        args: Uint8Array[];
+    }
+
+    export const ADDRESS_LENGTH = {ADDRESS_LENGTH};
+
+    export class Writer {
+        private bytes: number[] = [];
+
+        push(...bytes: number[]): void {
+            this.bytes.push(...bytes);
+        }
+
+        uleb128(value: number): void {
+            do {
+                let byte = value & 0x7f;
+                value = Math.floor(value / 0x80);
+                if (value > 0) {
+                    byte |= 0x80;
+                }
+                this.bytes.push(byte);
+            } while (value > 0);
+        }
+
+        finish(): Uint8Array {
+            return Uint8Array.from(this.bytes);
+        }
+    }
+
+    export class Reader {
+        private offset = 0;
+
+        constructor(private readonly bytes: Uint8Array) {}
+
+        read(length: number): Uint8Array {
+            if (this.offset + length > this.bytes.length) {
+                throw new Error("Unexpected end of the BCS input");
+            }
+            const bytes = this.bytes.subarray(this.offset, this.offset + length);
+            this.offset += length;
+            return bytes;
+        }
+
+        uleb128(): number {
+            let value = 0;
+            for (let shift = 0; shift < 32; shift += 7) {
+                const byte = this.read(1)[0];
+                value += (byte & 0x7f) * 2 ** shift;
+                if ((byte & 0x80) === 0) {
+                    return value;
+                }
+            }
+            throw new Error("Invalid ULEB128 length");
+        }
+
+        finish(): void {
+            if (this.offset !== this.bytes.length) {
+                throw new Error("Trailing bytes in the BCS input");
+            }
+        }
+    }
+
+    /** BCS codec of the values of a Move type. */
+    export interface Codec<T> {
+        tag: string;
+        write(writer: Writer, value: T): void;
+        read(reader: Reader): T;
+    }
+
+    function integer(tag: string, size: number): Codec<bigint> {
+        const max = 1n << BigInt(8 * size);
+        return {
+            tag,
+            write(writer, value) {
+                if (value < 0n || value >= max) {
+                    throw new Error(`${value} is out of the range of ${tag}`);
+                }
+                for (let i = 0; i < size; i++) {
+                    writer.push(Number(value & 0xffn));
+                    value >>= 8n;
+                }
+            },
+            read(reader) {
+                const bytes = reader.read(size);
+                let value = 0n;
+                for (let i = size - 1; i >= 0; i--) {
+                    value = (value << 8n) | BigInt(bytes[i]);
+                }
+                return value;
+            },
+        };
+    }
+
+    function small(codec: Codec<bigint>): Codec<number> {
+        return {
+            tag: codec.tag,
+            write: (writer, value) => codec.write(writer, BigInt(value)),
+            read: (reader) => Number(codec.read(reader)),
+        };
+    }
+
+    export const bool: Codec<boolean> = {
+        tag: "bool",
+        write: (writer, value) => writer.push(value ? 1 : 0),
+        read(reader) {
+            const byte = reader.read(1)[0];
+            if (byte > 1) {
+                throw new Error(`Invalid bool ${byte}`);
+            }
+            return byte === 1;
+        },
+    };
+    export const u8 = small(integer("u8", 1));
+    export const u16 = small(integer("u16", 2));
+    export const u32 = small(integer("u32", 4));
+    export const u64 = integer("u64", 8);
+    export const u128 = integer("u128", 16);
+    export const u256 = integer("u256", 32);
+
+    export const address: Codec<string> = {
+        tag: "address",
+        write(writer, value) {
+            const hex = value.startsWith("0x") ? value.slice(2) : value;
+            if (!/^[0-9a-fA-F]+$/.test(hex) || hex.length > 2 * ADDRESS_LENGTH) {
+                throw new Error(`Invalid address ${value}`);
+            }
+            const padded = hex.padStart(2 * ADDRESS_LENGTH, "0");
+            for (let i = 0; i < padded.length; i += 2) {
+                writer.push(parseInt(padded.slice(i, i + 2), 16));
+            }
+        },
+        read(reader) {
+            const bytes = reader.read(ADDRESS_LENGTH);
+            return "0x" + Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+        },
+    };
+
+    export function vector<T>(codec: Codec<T>): Codec<T[]> {
+        return {
+            tag: `vector<${codec.tag}>`,
+            write(writer, value) {
+                writer.uleb128(value.length);
+                value.forEach((elem) => codec.write(writer, elem));
+            },
+            read(reader) {
+                const length = reader.uleb128();
+                return Array.from({ length }, () => codec.read(reader));
+            },
+        };
+    }
+
+    /** Serializes the value in BCS. */
+    export function toBcs<T>(codec: Codec<T>, value: T): Uint8Array {
+        const writer = new Writer();
+        codec.write(writer, value);
+        return writer.finish();
+    }
+
+    /** Deserializes a BCS encoded value. */
+    export function fromBcs<T>(codec: Codec<T>, bytes: Uint8Array): T {
+        const reader = new Reader(bytes);
+        const value = codec.read(reader);
+        reader.finish();
+        return value;
+    }
+}
+"#;
+
+/// Generates the TypeScript bindings of the modules.
+pub fn generate(abis: &[ModuleAbi]) -> Result<String> {
+    let modules = Modules::new(abis)?;
+    let mut code = Code::default();
+    let prelude = PRELUDE.replace("{ADDRESS_LENGTH}", &AccountAddress::LENGTH.to_string());
+    for line in prelude.lines() {
+        code.line(line);
+    }
+
+    for (address, abis) in modules.by_address.iter() {
+        let namespace = address_module(address);
+        code.line("");
+        code.open(format!("export namespace {namespace} {{"));
+        code.line(format!(
+            "export const ADDRESS = {:?};",
+            address.to_hex_literal()
+        ));
+        code.close("}");
+        for abi in abis {
+            code.line("");
+            code.open(format!(
+                "export namespace {namespace}.{} {{",
+                ident(abi.id.name().as_str())
+            ));
+            generate_module(&mut code, abi);
+            code.close("}");
+        }
+    }
+    Ok(code.finish())
+}
+
+fn generate_module(code: &mut Code, abi: &ModuleAbi) {
+    code.line(format!("export const NAME = {:?};", abi.id.name().as_str()));
+    for struct_ in abi.structs.iter().filter(|s| !is_native(s)) {
+        code.line("");
+        generate_struct(code, &abi.id, struct_);
+    }
+    for func in entry_functions(abi) {
+        code.line("");
+        generate_entry_function(code, &abi.id, func);
+    }
+}
+
+fn generate_struct(code: &mut Code, module: &ModuleId, struct_: &Struct) {
+    let name = struct_.name.as_str();
+    let ty_params: Vec<_> = (0..struct_.type_parameters.len())
+        .map(|idx| format!("T{idx}"))
+        .collect();
+    let generics = generics(&ty_params);
+
+    code.line(format!(
+        "/** `{}::{}`. */",
+        module.short_str_lossless(),
+        name
+    ));
+    code.open(format!("export interface {name}{generics} {{"));
+    for field in struct_.fields.iter() {
+        code.line(format!("{}: {};", field.name, ts_type(&field.tp, module)));
+    }
+    code.close("}");
+
+    code.line("");
+    let tag = format!("{}::{}", module.short_str_lossless(), name);
+    let (tag, codec_type) = if ty_params.is_empty() {
+        code.open(format!("export const {name}: $move.Codec<{name}> = {{"));
+        (format!("{tag:?}"), name.to_string())
+    } else {
+        let params: Vec<_> = ty_params
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| format!("t{idx}: $move.Codec<{param}>"))
+            .collect();
+        let args: Vec<_> = (0..ty_params.len())
+            .map(|idx| format!("${{t{idx}.tag}}"))
+            .collect();
+        code.open(format!(
+            "export function {name}{generics}({}): $move.Codec<{name}{generics}> {{",
+            params.join(", ")
+        ));
+        code.open("return {");
+        (
+            format!("`{tag}<{}>`", args.join(", ")),
+            format!("{name}{generics}"),
+        )
+    };
+    code.line(format!("tag: {tag},"));
+    code.open(format!(
+        "write(writer: $move.Writer, value: {codec_type}): void {{"
+    ));
+    for field in struct_.fields.iter() {
+        code.line(format!(
+            "{}.write(writer, value.{});",
+            codec(&field.tp, module),
+            field.name
+        ));
+    }
+    code.close("},");
+    code.open(format!("read(reader: $move.Reader): {codec_type} {{"));
+    code.open("return {");
+    for field in struct_.fields.iter() {
+        code.line(format!(
+            "{}: {}.read(reader),",
+            field.name,
+            codec(&field.tp, module)
+        ));
+    }
+    code.close("};");
+    code.close("},");
+    code.close("};");
+    if !ty_params.is_empty() {
+        code.close("}");
+    }
+}
+
+fn generate_entry_function(code: &mut Code, module: &ModuleId, func: &Function) {
+    let ty_params: Vec<_> = (0..func.type_parameters.len())
+        .map(|idx| format!("T{idx}"))
+        .collect();
+    let params: Vec<_> = (0..func.type_parameters.len())
+        .map(|idx| format!("t{idx}: $move.Codec<T{idx}>"))
+        .chain(
+            func.parameters
+                .iter()
+                .enumerate()
+                .map(|(idx, ty)| format!("arg{idx}: {}", param_type(ty, module))),
+        )
+        .collect();
+    let type_args: Vec<_> = (0..func.type_parameters.len())
+        .map(|idx| format!("t{idx}.tag"))
+        .collect();
+    let args: Vec<_> = func
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(idx, ty)| format!("$move.toBcs({}, arg{idx})", param_codec(ty, module)))
+        .collect();
+
+    code.line(format!(
+        "/** Builds a call of the entry function `{}::{}`. */",
+        module.short_str_lossless(),
+        func.name
+    ));
+    code.open(format!(
+        "export function {}{}({}): $move.EntryFunctionCall {{",
+        ident(func.name.as_str()),
+        generics(&ty_params),
+        params.join(", ")
+    ));
+    code.open("return {");
+    code.line(format!("module: {:?},", module.short_str_lossless()));
+    code.line(format!("function: {:?},", func.name.as_str()));
+    code.line(format!("typeArgs: [{}],", type_args.join(", ")));
+    code.line(format!("args: [{}],", args.join(", ")));
+    code.close("};");
+    code.close("}");
+}
+
+fn generics(params: &[String]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn param_type(ty: &Type, module: &ModuleId) -> String {
+    if is_signer(ty) {
+        "string".to_string()
+    } else {
+        ts_type(ty, module)
+    }
+}
+
+fn param_codec(ty: &Type, module: &ModuleId) -> String {
+    if is_signer(ty) {
+        "$move.address".to_string()
+    } else {
+        codec(ty, module)
+    }
+}
+
+/// TypeScript type of the values of `ty`, used in `module`.
+fn ts_type(ty: &Type, module: &ModuleId) -> String {
+    match ty {
+        Type::Bool => "boolean".to_string(),
+        Type::U8 | Type::U16 | Type::U32 => "number".to_string(),
+        Type::U64 | Type::U128 | Type::U256 => "bigint".to_string(),
+        Type::Address | Type::Signer => "string".to_string(),
+        Type::Vector(elem) => format!("{}[]", ts_type(elem, module)),
+        Type::TypeParameter(idx) => format!("T{idx}"),
+        Type::Reference(ty) | Type::MutableReference(ty) => ts_type(ty, module),
+        Type::Struct(def) => {
+            let ty_args: Vec<_> = def.fields.iter().map(|ty| ts_type(ty, module)).collect();
+            format!(
+                "{}{}",
+                struct_path(&def.id, def.name.as_str(), module),
+                generics(&ty_args)
+            )
+        }
+    }
+}
+
+/// Expression of the codec of `ty`, used in `module`, where the codecs of the type parameters
+/// are `t0`, `t1`, etc.
+fn codec(ty: &Type, module: &ModuleId) -> String {
+    match ty {
+        Type::Bool => "$move.bool".to_string(),
+        Type::U8 => "$move.u8".to_string(),
+        Type::U16 => "$move.u16".to_string(),
+        Type::U32 => "$move.u32".to_string(),
+        Type::U64 => "$move.u64".to_string(),
+        Type::U128 => "$move.u128".to_string(),
+        Type::U256 => "$move.u256".to_string(),
+        Type::Address | Type::Signer => "$move.address".to_string(),
+        Type::Vector(elem) => format!("$move.vector({})", codec(elem, module)),
+        Type::TypeParameter(idx) => format!("t{idx}"),
+        Type::Reference(ty) | Type::MutableReference(ty) => codec(ty, module),
+        Type::Struct(def) => {
+            let path = struct_path(&def.id, def.name.as_str(), module);
+            if def.fields.is_empty() {
+                path
+            } else {
+                let ty_args: Vec<_> = def.fields.iter().map(|ty| codec(ty, module)).collect();
+                format!("{path}({})", ty_args.join(", "))
+            }
+        }
+    }
+}
+
+fn struct_path(id: &ModuleId, name: &str, module: &ModuleId) -> String {
+    if id == module {
+        name.to_string()
+    } else {
+        format!(
+            "{}.{}.{}",
+            address_module(id.address()),
+            ident(id.name().as_str()),
+            name
+        )
+    }
+}
+
+/// Escapes the Move identifiers which are reserved words in TypeScript.
+fn ident(name: &str) -> String {
+    const RESERVED: &[&str] = &[
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "debugger",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "new",
+        "null",
+        "return",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typeof",
+        "var",
+        "void",
+        "while",
+        "with",
+        "implements",
+        "interface",
+        "let",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "static",
+        "yield",
+        "arguments",
+        "eval",
+    ];
+
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
@@ -0,0 +1,14 @@
+module CafeAccount::Wallet {
+    use std::option::{Self, Option};
+    use std::string::String;
+
+    struct Wallet has key {
+        name: String,
+        limit: Option<u64>,
+        coins: vector<u128>,
+    }
+
+    public entry fun create(account: &signer, name: String, limit: u64) {
+        move_to(account, Wallet { name, limit: option::some(limit), coins: vector[] });
+    }
+}
@@ -0,0 +1,414 @@
+//! Integration tests for the generation of client bindings.
+
+use move_command_line_common::testing::{
+    add_update_baseline_fix, format_diff, read_env_update_baseline,
+};
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::Identifier;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use move_core_types::u256::U256;
+use move_core_types::value::{MoveTypeLayout, MoveValue};
+use move_vm_backend_common::abi::{
+    Field, Function, FunctionVisibility, ModuleAbi, Struct, StructDef, Type, TypeAbilities,
+    TypeLayout,
+};
+use move_vm_backend_common::layout::resolve_type_layout;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Checked against the baseline as generated.
+#[rustfmt::skip]
+#[path = "bindings/bank.rs"]
+mod bank;
+
+use bank::{EntryFunctionCall, MoveType};
+
+fn ident(name: &str) -> Identifier {
+    Identifier::new(name).unwrap()
+}
+
+fn module_id(address: &str, name: &str) -> ModuleId {
+    ModuleId::new(
+        AccountAddress::from_hex_literal(address).unwrap(),
+        ident(name),
+    )
+}
+
+fn struct_type(address: &str, module: &str, name: &str, ty_args: Vec<Type>) -> Type {
+    Type::Struct(StructDef {
+        id: module_id(address, module),
+        name: ident(name),
+        fields: ty_args,
+    })
+}
+
+fn struct_abi(name: &str, type_parameters: usize, fields: Vec<(&str, Type)>) -> Struct {
+    Struct {
+        name: ident(name),
+        type_parameters: vec![TypeAbilities { abilities: vec![] }; type_parameters],
+        abilities: TypeAbilities { abilities: vec![] },
+        fields: fields
+            .into_iter()
+            .map(|(name, tp)| Field {
+                name: ident(name),
+                tp,
+            })
+            .collect(),
+    }
+}
+
+fn function_abi(
+    name: &str,
+    visibility: FunctionVisibility,
+    is_entry: bool,
+    type_parameters: usize,
+    parameters: Vec<Type>,
+) -> Function {
+    Function {
+        name: ident(name),
+        visibility,
+        type_parameters: vec![TypeAbilities { abilities: vec![] }; type_parameters],
+        parameters,
+        returns: vec![],
+        is_entry,
+    }
+}
+
+fn signer_ref() -> Type {
+    Type::Reference(Box::new(Type::Signer))
+}
+
+/// ABIs of the modules:
+/// ```move
+/// module 0x1::tag { struct Tag { id: u32, flags: u16 } }
+/// module 0xCAFE::coin {
+///     struct Coin<phantom T> { value: u64 }
+///     struct Info { name: vector<u8>, supply: u256, admin: address, type: bool }
+///     native struct Handle;
+/// }
+/// module 0xCAFE::bank {
+///     struct Account<T> {
+///         owner: address,
+///         balance: Coin<T>,
+///         history: vector<u128>,
+///         tags: vector<Tag>,
+///         match: u8,
+///         extra: T,
+///     }
+///     public entry fun deposit<T>(account: &signer, amount: u64) { .. }
+///     entry fun open(account: &signer, name: vector<u8>, info: Info) { .. }
+///     public fun balance(owner: address): u64 { .. }
+/// }
+/// ```
+fn abis() -> Vec<ModuleAbi> {
+    let coin = |ty_arg| struct_type("0xCAFE", "coin", "Coin", vec![ty_arg]);
+    vec![
+        ModuleAbi {
+            id: module_id("0x1", "tag"),
+            friends: vec![],
+            structs: vec![struct_abi(
+                "Tag",
+                0,
+                vec![("id", Type::U32), ("flags", Type::U16)],
+            )],
+            funcs: vec![],
+        },
+        ModuleAbi {
+            id: module_id("0xCAFE", "coin"),
+            friends: vec![],
+            structs: vec![
+                struct_abi("Coin", 1, vec![("value", Type::U64)]),
+                struct_abi(
+                    "Info",
+                    0,
+                    vec![
+                        ("name", Type::Vector(Box::new(Type::U8))),
+                        ("supply", Type::U256),
+                        ("admin", Type::Address),
+                        ("type", Type::Bool),
+                    ],
+                ),
+                struct_abi("Handle", 0, vec![]),
+            ],
+            funcs: vec![],
+        },
+        ModuleAbi {
+            id: module_id("0xCAFE", "bank"),
+            friends: vec![],
+            structs: vec![struct_abi(
+                "Account",
+                1,
+                vec![
+                    ("owner", Type::Address),
+                    ("balance", coin(Type::TypeParameter(0))),
+                    ("history", Type::Vector(Box::new(Type::U128))),
+                    (
+                        "tags",
+                        Type::Vector(Box::new(struct_type("0x1", "tag", "Tag", vec![]))),
+                    ),
+                    ("match", Type::U8),
+                    ("extra", Type::TypeParameter(0)),
+                ],
+            )],
+            funcs: vec![
+                function_abi(
+                    "deposit",
+                    FunctionVisibility::Public,
+                    true,
+                    1,
+                    vec![signer_ref(), Type::U64],
+                ),
+                function_abi(
+                    "open",
+                    FunctionVisibility::Private,
+                    true,
+                    0,
+                    vec![
+                        signer_ref(),
+                        Type::Vector(Box::new(Type::U8)),
+                        struct_type("0xCAFE", "coin", "Info", vec![]),
+                    ],
+                ),
+                function_abi(
+                    "balance",
+                    FunctionVisibility::Public,
+                    false,
+                    0,
+                    vec![Type::Address],
+                ),
+            ],
+        },
+    ]
+}
+
+fn check_baseline(path: &str, output: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    if read_env_update_baseline() {
+        fs::write(&path, output).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap();
+    if output != expected {
+        let msg = format!(
+            "Generated bindings differ from {}:\n{}",
+            path.display(),
+            format_diff(expected, output),
+        );
+        panic!("{}", add_update_baseline_fix(msg));
+    }
+}
+
+#[test]
+fn rust_bindings_match_the_baseline() {
+    let bindings = move_vm_bindgen::rust::generate(&abis()).unwrap();
+    check_baseline("tests/bindings/bank.rs", &bindings);
+}
+
+#[test]
+fn typescript_bindings_match_the_baseline() {
+    let bindings = move_vm_bindgen::typescript::generate(&abis()).unwrap();
+    check_baseline("tests/bindings/bank.ts", &bindings);
+}
+
+fn address(hex: &str) -> AccountAddress {
+    AccountAddress::from_hex_literal(hex).unwrap()
+}
+
+/// Checks that the value is encoded in BCS as expected by its Move type, by decoding it with the
+/// layout resolved from the ABIs, and that the bindings decode it back.
+fn assert_round_trip<T>(value: &T, to_bcs: impl Fn(&T) -> Vec<u8>, from_bcs: fn(&[u8]) -> T)
+where
+    T: MoveType + PartialEq + std::fmt::Debug,
+{
+    let bytes = to_bcs(value);
+    let layout = resolve_type_layout(abis().as_slice(), &T::type_tag()).unwrap();
+    let move_value = MoveValue::simple_deserialize(&bytes, &MoveTypeLayout::from(&layout)).unwrap();
+    assert_eq!(move_value.undecorate().simple_serialize().unwrap(), bytes);
+    assert_eq!(&from_bcs(&bytes), value);
+}
+
+#[test]
+fn structs_round_trip_through_bcs() {
+    use bank::addr_1::tag::Tag;
+    use bank::addr_cafe::bank::Account;
+    use bank::addr_cafe::coin::{Coin, Info};
+
+    let info = Info {
+        name: b"savings".to_vec(),
+        supply: U256::from(u128::MAX) * U256::from(3u8),
+        admin: address("0xBEEF"),
+        r#type: true,
+    };
+    assert_round_trip(
+        &info,
+        |v| v.to_bcs().unwrap(),
+        |b| Info::from_bcs(b).unwrap(),
+    );
+
+    let account = Account {
+        owner: address("0xBEEF"),
+        balance: Coin {
+            value: 42,
+            _phantom: Default::default(),
+        },
+        history: vec![1, u128::MAX],
+        tags: vec![Tag {
+            id: 7,
+            flags: 0x8001,
+        }],
+        r#match: 255,
+        extra: vec![Tag { id: 1, flags: 2 }],
+    };
+    assert_round_trip(
+        &account,
+        |v| v.to_bcs().unwrap(),
+        |b| Account::from_bcs(b).unwrap(),
+    );
+}
+
+#[test]
+fn type_tags_of_generic_structs_include_their_type_arguments() {
+    use bank::addr_cafe::bank::Account;
+    use bank::addr_cafe::coin::Coin;
+
+    let coin_tag = TypeTag::Struct(Box::new(StructTag {
+        address: address("0xCAFE"),
+        module: ident("coin"),
+        name: ident("Coin"),
+        type_params: vec![TypeTag::Vector(Box::new(TypeTag::U8))],
+    }));
+    assert_eq!(<Coin<Vec<u8>>>::type_tag(), coin_tag);
+
+    let account_tag = TypeTag::Struct(Box::new(StructTag {
+        address: address("0xCAFE"),
+        module: ident("bank"),
+        name: ident("Account"),
+        type_params: vec![coin_tag],
+    }));
+    assert_eq!(<Account<Coin<Vec<u8>>>>::type_tag(), account_tag);
+}
+
+#[test]
+fn entry_function_calls_are_encoded_for_the_vm() {
+    use bank::addr_cafe::bank::{deposit, open};
+    use bank::addr_cafe::coin::{Coin, Info};
+
+    let signer = address("0xBEEF");
+    let call = deposit::<Coin<u64>>(signer, 100).unwrap();
+    assert_eq!(
+        call,
+        EntryFunctionCall {
+            module: module_id("0xCAFE", "bank"),
+            function: ident("deposit"),
+            type_args: vec![<Coin<u64>>::type_tag()],
+            args: vec![
+                bcs::to_bytes(&signer).unwrap(),
+                bcs::to_bytes(&100u64).unwrap()
+            ],
+        }
+    );
+
+    let info = Info {
+        name: vec![],
+        supply: U256::zero(),
+        admin: signer,
+        r#type: false,
+    };
+    let call = open(signer, b"main".to_vec(), info.clone()).unwrap();
+    assert_eq!(call.function, ident("open"));
+    assert!(call.type_args.is_empty());
+    assert_eq!(
+        call.args,
+        vec![
+            signer.to_vec(),
+            bcs::to_bytes(&b"main".to_vec()).unwrap(),
+            info.to_bcs().unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn missing_structs_are_rejected() {
+    // The ABIs of the dependencies are needed to bind their structs.
+    let abis = &abis()[1..];
+    let err = move_vm_bindgen::rust::generate(abis).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Missing the ABI of struct"),
+        "{err:#}"
+    );
+    assert!(move_vm_bindgen::typescript::generate(abis).is_err());
+
+    // Duplicate modules.
+    let mut abis = self::abis();
+    abis.push(abis[0].clone());
+    assert!(move_vm_bindgen::rust::generate(&abis).is_err());
+}
+
+/// Build directory of a Move package from our assets directory.
+fn package_build_dir(project: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(format!(
+        "tests/assets/move-projects/{project}/build/{project}"
+    ))
+}
+
+#[test]
+fn bindings_are_generated_for_compiled_packages() {
+    let build_dir = package_build_dir("wallet");
+    let abis = move_vm_bindgen::package_abis(&build_dir, true)
+        .unwrap_or_else(|e| panic!("Can't read {}: {e} - make sure you run move-vm-bindgen/tests/assets/move-projects/smove-build-all.sh", build_dir.display()));
+
+    // The structs of the package are bound with the ones of the dependencies they use.
+    let wallet_tag = TypeTag::Struct(Box::new(StructTag {
+        address: address("0xCAFE"),
+        module: ident("Wallet"),
+        name: ident("Wallet"),
+        type_params: vec![],
+    }));
+    let TypeLayout::Struct(layout) = resolve_type_layout(abis.as_slice(), &wallet_tag).unwrap()
+    else {
+        panic!("Wallet isn't a struct");
+    };
+    assert_eq!(
+        layout
+            .fields
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect::<Vec<_>>(),
+        ["name", "limit", "coins"]
+    );
+
+    let rust = move_vm_bindgen::rust::generate(&abis).unwrap();
+    for item in [
+        "pub mod string {",
+        "pub mod option {",
+        "pub mod Wallet {",
+        "pub struct Wallet {",
+        "pub name: super::super::addr_1::string::String,",
+        "pub limit: super::super::addr_1::option::Option<u64>,",
+        "pub fn create(arg0: ::move_core_types::account_address::AccountAddress, arg1: super::super::addr_1::string::String, arg2: u64)",
+    ] {
+        assert!(rust.contains(item), "Missing `{item}` in:\n{rust}");
+    }
+
+    let typescript = move_vm_bindgen::typescript::generate(&abis).unwrap();
+    for item in [
+        "export namespace addr_1.string {",
+        "export namespace addr_cafe.Wallet {",
+        "export interface Wallet {",
+        "export function create(arg0: string, arg1: addr_1.string.String, arg2: bigint)",
+    ] {
+        assert!(
+            typescript.contains(item),
+            "Missing `{item}` in:\n{typescript}"
+        );
+    }
+
+    // Without its dependencies, the structs they declare can't be bound.
+    let abis = move_vm_bindgen::package_abis(&build_dir, false).unwrap();
+    assert_eq!(abis.len(), 1);
+    let err = move_vm_bindgen::rust::generate(&abis).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("Missing the ABI of struct"),
+        "{err:#}"
+    );
+}
@@ -0,0 +1,217 @@
+//! Client bindings of Move modules, generated by `move-vm-bindgen`. Do not edit.
+
+#![allow(dead_code, non_camel_case_types, non_snake_case, clippy::all)]
+
+/// Call of an entry function, with the arguments encoded as expected by the MoveVM: BCS encoded,
+/// signers as their address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryFunctionCall {
+    pub module: ::move_core_types::language_storage::ModuleId,
+    pub function: ::move_core_types::identifier::Identifier,
+    pub type_args: ::std::vec::Vec<::move_core_types::language_storage::TypeTag>,
+    pub args: ::std::vec::Vec<::std::vec::Vec<u8>>,
+}
+
+/// Move type of the values.
+pub trait MoveType {
+    fn type_tag() -> ::move_core_types::language_storage::TypeTag;
+}
+
+macro_rules! move_type {
+    ($($ty:ty => $tag:ident),*) => {
+        $(impl MoveType for $ty {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                ::move_core_types::language_storage::TypeTag::$tag
+            }
+        })*
+    };
+}
+
+move_type!(
+    bool => Bool,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    ::move_core_types::u256::U256 => U256,
+    ::move_core_types::account_address::AccountAddress => Address
+);
+
+impl<T: MoveType> MoveType for ::std::vec::Vec<T> {
+    fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+        ::move_core_types::language_storage::TypeTag::Vector(::std::boxed::Box::new(T::type_tag()))
+    }
+}
+
+fn identifier(name: &str) -> ::move_core_types::identifier::Identifier {
+    ::move_core_types::identifier::Identifier::new(name).expect("Move identifier")
+}
+
+fn struct_tag(
+    address: ::move_core_types::account_address::AccountAddress,
+    module: &str,
+    name: &str,
+    type_params: ::std::vec::Vec<::move_core_types::language_storage::TypeTag>,
+) -> ::move_core_types::language_storage::TypeTag {
+    ::move_core_types::language_storage::TypeTag::Struct(::std::boxed::Box::new(
+        ::move_core_types::language_storage::StructTag {
+            address,
+            module: identifier(module),
+            name: identifier(name),
+            type_params,
+        },
+    ))
+}
+
+pub mod addr_1 {
+    /// Address `0x1`.
+    pub const ADDRESS: ::move_core_types::account_address::AccountAddress = ::move_core_types::account_address::AccountAddress::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+
+    pub mod tag {
+        /// Name of the module `0x1::tag`.
+        pub const NAME: &str = "tag";
+
+        /// `0x1::tag::Tag`.
+        #[derive(Clone, Debug, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct Tag {
+            pub id: u32,
+            pub flags: u16,
+        }
+
+        impl Tag {
+            /// Serializes the value in BCS.
+            pub fn to_bcs(&self) -> ::core::result::Result<::std::vec::Vec<u8>, ::bcs::Error> {
+                ::bcs::to_bytes(self)
+            }
+
+            /// Deserializes a BCS encoded value.
+            pub fn from_bcs(bytes: &[u8]) -> ::core::result::Result<Self, ::bcs::Error> {
+                ::bcs::from_bytes(bytes)
+            }
+        }
+
+        impl super::super::MoveType for Tag {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                super::super::struct_tag(super::ADDRESS, NAME, "Tag", ::std::vec![])
+            }
+        }
+    }
+}
+
+pub mod addr_cafe {
+    /// Address `0xcafe`.
+    pub const ADDRESS: ::move_core_types::account_address::AccountAddress = ::move_core_types::account_address::AccountAddress::new([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 202, 254]);
+
+    pub mod bank {
+        /// Name of the module `0xcafe::bank`.
+        pub const NAME: &str = "bank";
+
+        /// `0xcafe::bank::Account`.
+        #[derive(Clone, Debug, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct Account<T0> {
+            pub owner: ::move_core_types::account_address::AccountAddress,
+            pub balance: super::super::addr_cafe::coin::Coin<T0>,
+            pub history: ::std::vec::Vec<u128>,
+            pub tags: ::std::vec::Vec<super::super::addr_1::tag::Tag>,
+            pub r#match: u8,
+            pub extra: T0,
+        }
+
+        impl<T0> Account<T0> {
+            /// Serializes the value in BCS.
+            pub fn to_bcs(&self) -> ::core::result::Result<::std::vec::Vec<u8>, ::bcs::Error> where Self: ::serde::Serialize {
+                ::bcs::to_bytes(self)
+            }
+
+            /// Deserializes a BCS encoded value.
+            pub fn from_bcs(bytes: &[u8]) -> ::core::result::Result<Self, ::bcs::Error> where Self: ::serde::de::DeserializeOwned {
+                ::bcs::from_bytes(bytes)
+            }
+        }
+
+        impl<T0: super::super::MoveType> super::super::MoveType for Account<T0> {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                super::super::struct_tag(super::ADDRESS, NAME, "Account", ::std::vec![T0::type_tag()])
+            }
+        }
+
+        /// Builds a call of the entry function `0xcafe::bank::deposit`.
+        pub fn deposit<T0: super::super::MoveType + ::serde::Serialize>(arg0: ::move_core_types::account_address::AccountAddress, arg1: u64) -> ::core::result::Result<super::super::EntryFunctionCall, ::bcs::Error> {
+            ::core::result::Result::Ok(super::super::EntryFunctionCall {
+                module: ::move_core_types::language_storage::ModuleId::new(super::ADDRESS, super::super::identifier(NAME)),
+                function: super::super::identifier("deposit"),
+                type_args: ::std::vec![T0::type_tag()],
+                args: ::std::vec![::bcs::to_bytes(&arg0)?, ::bcs::to_bytes(&arg1)?],
+            })
+        }
+
+        /// Builds a call of the entry function `0xcafe::bank::open`.
+        pub fn open(arg0: ::move_core_types::account_address::AccountAddress, arg1: ::std::vec::Vec<u8>, arg2: super::super::addr_cafe::coin::Info) -> ::core::result::Result<super::super::EntryFunctionCall, ::bcs::Error> {
+            ::core::result::Result::Ok(super::super::EntryFunctionCall {
+                module: ::move_core_types::language_storage::ModuleId::new(super::ADDRESS, super::super::identifier(NAME)),
+                function: super::super::identifier("open"),
+                type_args: ::std::vec![],
+                args: ::std::vec![::bcs::to_bytes(&arg0)?, ::bcs::to_bytes(&arg1)?, ::bcs::to_bytes(&arg2)?],
+            })
+        }
+    }
+
+    pub mod coin {
+        /// Name of the module `0xcafe::coin`.
+        pub const NAME: &str = "coin";
+
+        /// `0xcafe::coin::Coin`.
+        #[derive(Clone, Debug, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct Coin<T0> {
+            pub value: u64,
+            #[serde(skip)]
+            pub _phantom: ::core::marker::PhantomData<(T0,)>,
+        }
+
+        impl<T0> Coin<T0> {
+            /// Serializes the value in BCS.
+            pub fn to_bcs(&self) -> ::core::result::Result<::std::vec::Vec<u8>, ::bcs::Error> where Self: ::serde::Serialize {
+                ::bcs::to_bytes(self)
+            }
+
+            /// Deserializes a BCS encoded value.
+            pub fn from_bcs(bytes: &[u8]) -> ::core::result::Result<Self, ::bcs::Error> where Self: ::serde::de::DeserializeOwned {
+                ::bcs::from_bytes(bytes)
+            }
+        }
+
+        impl<T0: super::super::MoveType> super::super::MoveType for Coin<T0> {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                super::super::struct_tag(super::ADDRESS, NAME, "Coin", ::std::vec![T0::type_tag()])
+            }
+        }
+
+        /// `0xcafe::coin::Info`.
+        #[derive(Clone, Debug, PartialEq, Eq, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct Info {
+            pub name: ::std::vec::Vec<u8>,
+            pub supply: ::move_core_types::u256::U256,
+            pub admin: ::move_core_types::account_address::AccountAddress,
+            pub r#type: bool,
+        }
+
+        impl Info {
+            /// Serializes the value in BCS.
+            pub fn to_bcs(&self) -> ::core::result::Result<::std::vec::Vec<u8>, ::bcs::Error> {
+                ::bcs::to_bytes(self)
+            }
+
+            /// Deserializes a BCS encoded value.
+            pub fn from_bcs(bytes: &[u8]) -> ::core::result::Result<Self, ::bcs::Error> {
+                ::bcs::from_bytes(bytes)
+            }
+        }
+
+        impl super::super::MoveType for Info {
+            fn type_tag() -> ::move_core_types::language_storage::TypeTag {
+                super::super::struct_tag(super::ADDRESS, NAME, "Info", ::std::vec![])
+            }
+        }
+    }
+}
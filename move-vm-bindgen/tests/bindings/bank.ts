@@ -0,0 +1,313 @@
+// Client bindings of Move modules, generated by `move-vm-bindgen`. Do not edit.
+
+/** Runtime of the bindings: BCS codecs of the Move types. */
+export namespace $move {
+    /** Call of an entry function, with the arguments encoded as expected by the MoveVM. */
+    export interface EntryFunctionCall {
+        module: string;
+        function: string;
+        typeArgs: string[];
+        args: Uint8Array[];
+    }
+
+    export const ADDRESS_LENGTH = 32;
+
+    export class Writer {
+        private bytes: number[] = [];
+
+        push(...bytes: number[]): void {
+            this.bytes.push(...bytes);
+        }
+
+        uleb128(value: number): void {
+            do {
+                let byte = value & 0x7f;
+                value = Math.floor(value / 0x80);
+                if (value > 0) {
+                    byte |= 0x80;
+                }
+                this.bytes.push(byte);
+            } while (value > 0);
+        }
+
+        finish(): Uint8Array {
+            return Uint8Array.from(this.bytes);
+        }
+    }
+
+    export class Reader {
+        private offset = 0;
+
+        constructor(private readonly bytes: Uint8Array) {}
+
+        read(length: number): Uint8Array {
+            if (this.offset + length > this.bytes.length) {
+                throw new Error("Unexpected end of the BCS input");
+            }
+            const bytes = this.bytes.subarray(this.offset, this.offset + length);
+            this.offset += length;
+            return bytes;
+        }
+
+        uleb128(): number {
+            let value = 0;
+            for (let shift = 0; shift < 32; shift += 7) {
+                const byte = this.read(1)[0];
+                value += (byte & 0x7f) * 2 ** shift;
+                if ((byte & 0x80) === 0) {
+                    return value;
+                }
+            }
+            throw new Error("Invalid ULEB128 length");
+        }
+
+        finish(): void {
+            if (this.offset !== this.bytes.length) {
+                throw new Error("Trailing bytes in the BCS input");
+            }
+        }
+    }
+
+    /** BCS codec of the values of a Move type. */
+    export interface Codec<T> {
+        tag: string;
+        write(writer: Writer, value: T): void;
+        read(reader: Reader): T;
+    }
+
+    function integer(tag: string, size: number): Codec<bigint> {
+        const max = 1n << BigInt(8 * size);
+        return {
+            tag,
+            write(writer, value) {
+                if (value < 0n || value >= max) {
+                    throw new Error(`${value} is out of the range of ${tag}`);
+                }
+                for (let i = 0; i < size; i++) {
+                    writer.push(Number(value & 0xffn));
+                    value >>= 8n;
+                }
+            },
+            read(reader) {
+                const bytes = reader.read(size);
+                let value = 0n;
+                for (let i = size - 1; i >= 0; i--) {
+                    value = (value << 8n) | BigInt(bytes[i]);
+                }
+                return value;
+            },
+        };
+    }
+
+    function small(codec: Codec<bigint>): Codec<number> {
+        return {
+            tag: codec.tag,
+            write: (writer, value) => codec.write(writer, BigInt(value)),
+            read: (reader) => Number(codec.read(reader)),
+        };
+    }
+
+    export const bool: Codec<boolean> = {
+        tag: "bool",
+        write: (writer, value) => writer.push(value ? 1 : 0),
+        read(reader) {
+            const byte = reader.read(1)[0];
+            if (byte > 1) {
+                throw new Error(`Invalid bool ${byte}`);
+            }
+            return byte === 1;
+        },
+    };
+    export const u8 = small(integer("u8", 1));
+    export const u16 = small(integer("u16", 2));
+    export const u32 = small(integer("u32", 4));
+    export const u64 = integer("u64", 8);
+    export const u128 = integer("u128", 16);
+    export const u256 = integer("u256", 32);
+
+    export const address: Codec<string> = {
+        tag: "address",
+        write(writer, value) {
+            const hex = value.startsWith("0x") ? value.slice(2) : value;
+            if (!/^[0-9a-fA-F]+$/.test(hex) || hex.length > 2 * ADDRESS_LENGTH) {
+                throw new Error(`Invalid address ${value}`);
+            }
+            const padded = hex.padStart(2 * ADDRESS_LENGTH, "0");
+            for (let i = 0; i < padded.length; i += 2) {
+                writer.push(parseInt(padded.slice(i, i + 2), 16));
+            }
+        },
+        read(reader) {
+            const bytes = reader.read(ADDRESS_LENGTH);
+            return "0x" + Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+        },
+    };
+
+    export function vector<T>(codec: Codec<T>): Codec<T[]> {
+        return {
+            tag: `vector<${codec.tag}>`,
+            write(writer, value) {
+                writer.uleb128(value.length);
+                value.forEach((elem) => codec.write(writer, elem));
+            },
+            read(reader) {
+                const length = reader.uleb128();
+                return Array.from({ length }, () => codec.read(reader));
+            },
+        };
+    }
+
+    /** Serializes the value in BCS. */
+    export function toBcs<T>(codec: Codec<T>, value: T): Uint8Array {
+        const writer = new Writer();
+        codec.write(writer, value);
+        return writer.finish();
+    }
+
+    /** Deserializes a BCS encoded value. */
+    export function fromBcs<T>(codec: Codec<T>, bytes: Uint8Array): T {
+        const reader = new Reader(bytes);
+        const value = codec.read(reader);
+        reader.finish();
+        return value;
+    }
+}
+
+export namespace addr_1 {
+    export const ADDRESS = "0x1";
+}
+
+export namespace addr_1.tag {
+    export const NAME = "tag";
+
+    /** `0x1::tag::Tag`. */
+    export interface Tag {
+        id: number;
+        flags: number;
+    }
+
+    export const Tag: $move.Codec<Tag> = {
+        tag: "0x1::tag::Tag",
+        write(writer: $move.Writer, value: Tag): void {
+            $move.u32.write(writer, value.id);
+            $move.u16.write(writer, value.flags);
+        },
+        read(reader: $move.Reader): Tag {
+            return {
+                id: $move.u32.read(reader),
+                flags: $move.u16.read(reader),
+            };
+        },
+    };
+}
+
+export namespace addr_cafe {
+    export const ADDRESS = "0xcafe";
+}
+
+export namespace addr_cafe.bank {
+    export const NAME = "bank";
+
+    /** `0xcafe::bank::Account`. */
+    export interface Account<T0> {
+        owner: string;
+        balance: addr_cafe.coin.Coin<T0>;
+        history: bigint[];
+        tags: addr_1.tag.Tag[];
+        match: number;
+        extra: T0;
+    }
+
+    export function Account<T0>(t0: $move.Codec<T0>): $move.Codec<Account<T0>> {
+        return {
+            tag: `0xcafe::bank::Account<${t0.tag}>`,
+            write(writer: $move.Writer, value: Account<T0>): void {
+                $move.address.write(writer, value.owner);
+                addr_cafe.coin.Coin(t0).write(writer, value.balance);
+                $move.vector($move.u128).write(writer, value.history);
+                $move.vector(addr_1.tag.Tag).write(writer, value.tags);
+                $move.u8.write(writer, value.match);
+                t0.write(writer, value.extra);
+            },
+            read(reader: $move.Reader): Account<T0> {
+                return {
+                    owner: $move.address.read(reader),
+                    balance: addr_cafe.coin.Coin(t0).read(reader),
+                    history: $move.vector($move.u128).read(reader),
+                    tags: $move.vector(addr_1.tag.Tag).read(reader),
+                    match: $move.u8.read(reader),
+                    extra: t0.read(reader),
+                };
+            },
+        };
+    }
+
+    /** Builds a call of the entry function `0xcafe::bank::deposit`. */
+    export function deposit<T0>(t0: $move.Codec<T0>, arg0: string, arg1: bigint): $move.EntryFunctionCall {
+        return {
+            module: "0xcafe::bank",
+            function: "deposit",
+            typeArgs: [t0.tag],
+            args: [$move.toBcs($move.address, arg0), $move.toBcs($move.u64, arg1)],
+        };
+    }
+
+    /** Builds a call of the entry function `0xcafe::bank::open`. */
+    export function open(arg0: string, arg1: number[], arg2: addr_cafe.coin.Info): $move.EntryFunctionCall {
+        return {
+            module: "0xcafe::bank",
+            function: "open",
+            typeArgs: [],
+            args: [$move.toBcs($move.address, arg0), $move.toBcs($move.vector($move.u8), arg1), $move.toBcs(addr_cafe.coin.Info, arg2)],
+        };
+    }
+}
+
+export namespace addr_cafe.coin {
+    export const NAME = "coin";
+
+    /** `0xcafe::coin::Coin`. */
+    export interface Coin<T0> {
+        value: bigint;
+    }
+
+    export function Coin<T0>(t0: $move.Codec<T0>): $move.Codec<Coin<T0>> {
+        return {
+            tag: `0xcafe::coin::Coin<${t0.tag}>`,
+            write(writer: $move.Writer, value: Coin<T0>): void {
+                $move.u64.write(writer, value.value);
+            },
+            read(reader: $move.Reader): Coin<T0> {
+                return {
+                    value: $move.u64.read(reader),
+                };
+            },
+        };
+    }
+
+    /** `0xcafe::coin::Info`. */
+    export interface Info {
+        name: number[];
+        supply: bigint;
+        admin: string;
+        type: boolean;
+    }
+
+    export const Info: $move.Codec<Info> = {
+        tag: "0xcafe::coin::Info",
+        write(writer: $move.Writer, value: Info): void {
+            $move.vector($move.u8).write(writer, value.name);
+            $move.u256.write(writer, value.supply);
+            $move.address.write(writer, value.admin);
+            $move.bool.write(writer, value.type);
+        },
+        read(reader: $move.Reader): Info {
+            return {
+                name: $move.vector($move.u8).read(reader),
+                supply: $move.u256.read(reader),
+                admin: $move.address.read(reader),
+                type: $move.bool.read(reader),
+            };
+        },
+    };
+}